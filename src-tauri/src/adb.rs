@@ -0,0 +1,108 @@
+use std::process::Command;
+use std::os::windows::process::CommandExt;
+use crate::error::HyperZenithError;
+
+#[derive(serde::Serialize, Clone)]
+pub struct AdbDevice {
+    pub serial: String,
+    pub state: String,
+    pub model: Option<String>,
+}
+
+/// Prefer the SDK's adb.exe (same SDK the builds use), fall back to PATH
+pub fn adb_path() -> String {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:/Users/Default/AppData/Local".to_string());
+    let sdk_adb = std::path::Path::new(&local_app_data)
+        .join("Android").join("Sdk").join("platform-tools").join("adb.exe");
+
+    if sdk_adb.exists() {
+        sdk_adb.to_string_lossy().to_string()
+    } else {
+        "adb".to_string()
+    }
+}
+
+pub fn run_adb(args: &[&str]) -> Result<String, HyperZenithError> {
+    let output = Command::new(adb_path())
+        .args(args)
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("adb", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(HyperZenithError::CommandFailed {
+            command: format!("adb {}", args.first().unwrap_or(&"")),
+            output: format!("{}{}", stdout.trim(), stderr.trim()),
+        });
+    }
+    Ok(stdout)
+}
+
+/// Parse `adb devices -l` output
+fn parse_devices(output: &str) -> Vec<AdbDevice> {
+    output
+        .lines()
+        .skip_while(|l| !l.starts_with("List of devices"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let serial = parts.next()?.to_string();
+            let state = parts.next()?.to_string();
+            let model = parts
+                .find(|p| p.starts_with("model:"))
+                .map(|p| p.trim_start_matches("model:").replace('_', " "));
+            Some(AdbDevice { serial, state, model })
+        })
+        .collect()
+}
+
+pub fn list_devices() -> Result<Vec<AdbDevice>, HyperZenithError> {
+    run_adb(&["devices", "-l"]).map(|out| parse_devices(&out))
+}
+
+/// Name of the AVD an emulator is running, `None` for physical devices
+pub fn avd_name(serial: &str) -> Option<String> {
+    if !serial.starts_with("emulator-") {
+        return None;
+    }
+    let output = run_adb(&["-s", serial, "emu", "avd", "name"]).ok()?;
+    output.lines().map(str::trim).find(|l| !l.is_empty() && *l != "OK").map(str::to_string)
+}
+
+/// Install an APK, replacing and allowing downgrades so older builds can be reinstalled
+pub fn install_apk(path: &str, serial: Option<&str>) -> Result<String, HyperZenithError> {
+    let mut args = Vec::new();
+    if let Some(s) = serial.filter(|s| !s.is_empty()) {
+        args.extend(["-s", s]);
+    }
+    args.extend(["install", "-r", "-d", path]);
+
+    println!("📲 [ADB] Installing {} on {}", path, serial.unwrap_or("default device"));
+    let output = run_adb(&args)?;
+    if output.contains("Success") {
+        Ok(output.trim().to_string())
+    } else {
+        Err(HyperZenithError::CommandFailed { command: "adb install".to_string(), output: output.trim().to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devices() {
+        let out = "* daemon started successfully\nList of devices attached\n\
+            emulator-5554          device product:sdk_gphone64 model:sdk_gphone64_x86_64 device:emu64x\n\
+            R58M123ABC             unauthorized usb:1-1 transport_id:2\n\n";
+        let devices = parse_devices(out);
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].serial, "emulator-5554");
+        assert_eq!(devices[0].model.as_deref(), Some("sdk gphone64 x86 64"));
+        assert_eq!(devices[1].state, "unauthorized");
+        assert!(devices[1].model.is_none());
+    }
+}
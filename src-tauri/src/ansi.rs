@@ -0,0 +1,106 @@
+/// Foreground colour a tool gave a line, from its SGR escape codes. Bright variants fold into
+/// the base colour; 256-colour and RGB codes outside the basic palette are dropped.
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+const PALETTE: [Color; 8] = [Color::Black, Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan, Color::White];
+
+/// Apply one `ESC [ ... m` sequence to the current foreground colour
+fn apply_sgr(params: &str, current: &mut Option<Color>) {
+    let mut codes = params.split(';').map(|p| p.parse::<u32>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        match code {
+            0 | 39 => *current = None,
+            30..=37 => *current = Some(PALETTE[(code - 30) as usize]),
+            90..=97 => *current = Some(PALETTE[(code - 90) as usize]),
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().filter(|&n| n < 16).map(|n| PALETTE[(n % 8) as usize]),
+                    Some(2) => {
+                        codes.by_ref().take(3).for_each(drop);
+                        None
+                    },
+                    _ => None,
+                };
+                if code == 38 {
+                    *current = color;
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Remove escape sequences (colours, cursor moves, window titles) from a line of tool output.
+/// Returns the plain text and the colour of its first visible character, if it had one.
+pub fn strip(text: &str) -> (String, Option<Color>) {
+    if !text.contains('\u{1b}') {
+        return (text.to_string(), None);
+    }
+    let mut plain = String::with_capacity(text.len());
+    let (mut current, mut color) = (None, None);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            if color.is_none() && !c.is_whitespace() {
+                color = current;
+            }
+            plain.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters, then a final byte in @..~
+            Some('[') => {
+                let mut params = String::new();
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        if c == 'm' {
+                            apply_sgr(&params, &mut current);
+                        }
+                        break;
+                    }
+                    params.push(c);
+                }
+            },
+            // OSC: runs to BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\u{7}' || (c == '\u{1b}' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            },
+            // Two-character escapes
+            _ => {},
+        }
+    }
+    (plain, color)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip() {
+        assert_eq!(strip("BUILD SUCCESSFUL in 42s"), ("BUILD SUCCESSFUL in 42s".to_string(), None));
+        assert_eq!(
+            strip("\u{1b}[31;1mFAILURE: \u{1b}[39;0mBuild failed with an exception."),
+            ("FAILURE: Build failed with an exception.".to_string(), Some(Color::Red))
+        );
+        assert_eq!(strip("  \u{1b}[93mwarning\u{1b}[0m: unused"), ("  warning: unused".to_string(), Some(Color::Yellow)));
+        assert_eq!(strip("\u{1b}[2K\u{1b}[1A> Task :app:mergeDebugAssets"), ("> Task :app:mergeDebugAssets".to_string(), None));
+        assert_eq!(strip("\u{1b}]0;xcodebuild\u{7}\u{1b}[38;5;10m** BUILD SUCCEEDED **"), ("** BUILD SUCCEEDED **".to_string(), Some(Color::Green)));
+        assert_eq!(strip("\u{1b}[38;2;255;0;0mrgb"), ("rgb".to_string(), None));
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use crate::error::HyperZenithError;
+
+/// Per-entry sizes of an APK/AAB: name -> (compressed, uncompressed)
+type EntrySizes = BTreeMap<String, (u64, u64)>;
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct EntryDelta {
+    pub name: String,
+    pub size_a: Option<u64>,
+    pub size_b: Option<u64>,
+    pub delta: i64,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct ArtifactDiff {
+    pub size_a: u64,
+    pub size_b: u64,
+    pub total_delta: i64,
+    pub dex_a: u64,
+    pub dex_b: u64,
+    pub dex_delta: i64,
+    pub added: Vec<EntryDelta>,
+    pub removed: Vec<EntryDelta>,
+    pub changed: Vec<EntryDelta>, // Largest absolute change first
+}
+
+/// Ends the APK Signing Block, which sits right before the central directory
+const SIGNING_BLOCK_MAGIC: &[u8; 16] = b"APK Sig Block 42";
+const EOCD_SIGNATURE: [u8; 4] = [0x50, 0x4b, 0x05, 0x06];
+/// End of central directory record plus the longest comment a zip can have
+const EOCD_SEARCH: u64 = 22 + 65535;
+/// In the subject of the certificate Android Studio signs debug builds with
+const DEBUG_SUBJECT: &[u8] = b"Android Debug";
+
+/// How an archived APK or AAB is signed
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Signing {
+    /// `v1` for JAR signing (the only kind an AAB has), `v2+` for the APK Signature Scheme
+    pub schemes: Vec<String>,
+    /// Signed with the debug keystore, so it can't go to the Play Store
+    pub debug_key: bool,
+}
+
+/// The APK Signing Block of v2 and later schemes, if the zip has one
+fn signing_block<R: Read + Seek>(file: &mut R) -> Option<Vec<u8>> {
+    let len = file.seek(SeekFrom::End(0)).ok()?;
+    let tail_start = len.saturating_sub(EOCD_SEARCH);
+    file.seek(SeekFrom::Start(tail_start)).ok()?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail).ok()?;
+    let eocd = tail.windows(4).rposition(|w| w == EOCD_SIGNATURE)?;
+    let central_directory = u32::from_le_bytes(tail.get(eocd + 16..eocd + 20)?.try_into().ok()?) as u64;
+
+    // ... | block size (u64) | magic | central directory
+    file.seek(SeekFrom::Start(central_directory.checked_sub(24)?)).ok()?;
+    let mut footer = [0u8; 24];
+    file.read_exact(&mut footer).ok()?;
+    if &footer[8..] != SIGNING_BLOCK_MAGIC {
+        return None;
+    }
+    let size = u64::from_le_bytes(footer[..8].try_into().ok()?);
+    let start = central_directory.checked_sub(size.checked_add(8)?)?;
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut block = vec![0u8; (central_directory - start) as usize];
+    file.read_exact(&mut block).ok()?;
+    Some(block)
+}
+
+fn has_debug_subject(data: &[u8]) -> bool {
+    data.windows(DEBUG_SUBJECT.len()).any(|w| w == DEBUG_SUBJECT)
+}
+
+/// Which signature schemes an artifact carries and whether the key is the debug one
+pub fn signing(path: &Path) -> Result<Signing, HyperZenithError> {
+    let mut file = std::fs::File::open(path).map_err(|e| HyperZenithError::io_at(path, e))?;
+    let mut schemes = Vec::new();
+    let mut debug_key = false;
+
+    let block = signing_block(&mut file);
+    file.seek(SeekFrom::Start(0)).map_err(|e| HyperZenithError::io_at(path, e))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| HyperZenithError::InvalidInput(format!("'{}' is not a valid APK/AAB: {}", path.display(), e)))?;
+    let certificates: Vec<String> = zip.file_names()
+        .filter_map(|name| name.ok().map(|n| n.to_string()))
+        .filter(|name| {
+            let upper = name.to_uppercase();
+            upper.starts_with("META-INF/") && [".RSA", ".DSA", ".EC"].iter().any(|ext| upper.ends_with(ext))
+        })
+        .collect();
+    if !certificates.is_empty() {
+        schemes.push("v1".to_string());
+    }
+    for name in &certificates {
+        let mut data = Vec::new();
+        if let Ok(mut entry) = zip.by_name(name) {
+            let _ = entry.read_to_end(&mut data);
+        }
+        debug_key |= has_debug_subject(&data);
+    }
+    if let Some(block) = block {
+        schemes.push("v2+".to_string());
+        debug_key |= has_debug_subject(&block);
+    }
+    Ok(Signing { schemes, debug_key })
+}
+
+pub fn read_entries(path: &Path) -> Result<EntrySizes, HyperZenithError> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| HyperZenithError::io_at(path, e))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| HyperZenithError::InvalidInput(format!("'{}' is not a valid APK/AAB: {}", path.display(), e)))?;
+
+    let mut entries = BTreeMap::new();
+    for i in 0..zip.len() {
+        let entry = zip.by_index_raw(i).map_err(|e| e.to_string())?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().map_err(|e| e.to_string())?.to_string();
+        entries.insert(name, (entry.compressed_size(), entry.size()));
+    }
+    Ok(entries)
+}
+
+/// Dex lives at the root of an APK and under `<module>/dex/` in an AAB
+fn is_dex(name: &str) -> bool {
+    let file = name.rsplit('/').next().unwrap_or(name);
+    file.starts_with("classes") && file.ends_with(".dex")
+}
+
+/// Compare two entry listings. Entry deltas use compressed (on-disk) sizes, dex totals use uncompressed
+fn diff_entries(a: &EntrySizes, b: &EntrySizes) -> (Vec<EntryDelta>, Vec<EntryDelta>, Vec<EntryDelta>, u64, u64) {
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+
+    for (name, (size_b, _)) in b {
+        match a.get(name) {
+            None => added.push(EntryDelta { name: name.clone(), size_a: None, size_b: Some(*size_b), delta: *size_b as i64 }),
+            Some((size_a, _)) if size_a != size_b => changed.push(EntryDelta {
+                name: name.clone(),
+                size_a: Some(*size_a),
+                size_b: Some(*size_b),
+                delta: *size_b as i64 - *size_a as i64,
+            }),
+            _ => {}
+        }
+    }
+    for (name, (size_a, _)) in a {
+        if !b.contains_key(name) {
+            removed.push(EntryDelta { name: name.clone(), size_a: Some(*size_a), size_b: None, delta: -(*size_a as i64) });
+        }
+    }
+
+    for list in [&mut added, &mut removed, &mut changed] {
+        list.sort_by_key(|d| std::cmp::Reverse(d.delta.abs()));
+    }
+
+    let dex_total = |m: &EntrySizes| m.iter().filter(|(n, _)| is_dex(n)).map(|(_, (_, raw))| raw).sum();
+    (added, removed, changed, dex_total(a), dex_total(b))
+}
+
+pub fn diff_artifacts(a: &Path, b: &Path) -> Result<ArtifactDiff, HyperZenithError> {
+    let entries_a = read_entries(a)?;
+    let entries_b = read_entries(b)?;
+    let (added, removed, changed, dex_a, dex_b) = diff_entries(&entries_a, &entries_b);
+
+    let size_a = std::fs::metadata(a).map(|m| m.len()).unwrap_or(0);
+    let size_b = std::fs::metadata(b).map(|m| m.len()).unwrap_or(0);
+
+    Ok(ArtifactDiff {
+        size_a,
+        size_b,
+        total_delta: size_b as i64 - size_a as i64,
+        dex_a,
+        dex_b,
+        dex_delta: dex_b as i64 - dex_a as i64,
+        added,
+        removed,
+        changed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_entries() {
+        let a: EntrySizes = [
+            ("classes.dex".to_string(), (400, 1000)),
+            ("res/old.png".to_string(), (50, 50)),
+            ("lib/arm64-v8a/libapp.so".to_string(), (300, 900)),
+        ].into_iter().collect();
+        let b: EntrySizes = [
+            ("classes.dex".to_string(), (450, 1200)),
+            ("classes2.dex".to_string(), (100, 300)),
+            ("lib/arm64-v8a/libapp.so".to_string(), (300, 900)),
+        ].into_iter().collect();
+
+        let (added, removed, changed, dex_a, dex_b) = diff_entries(&a, &b);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].name, "classes2.dex");
+        assert_eq!(removed[0].delta, -50);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].delta, 50);
+        assert_eq!((dex_a, dex_b), (1000, 1500));
+    }
+
+    #[test]
+    fn test_signing_block() {
+        // Entries, then the signing block, central directory and end of central directory
+        let block_body = b"....CN=Android Debug, O=Android....";
+        let mut apk = b"PK\x03\x04 local entries".to_vec();
+        let block_start = apk.len();
+        let size = (block_body.len() + 24) as u64;
+        apk.extend_from_slice(&size.to_le_bytes());
+        apk.extend_from_slice(block_body);
+        apk.extend_from_slice(&size.to_le_bytes());
+        apk.extend_from_slice(SIGNING_BLOCK_MAGIC);
+        let central_directory = apk.len() as u32;
+        apk.extend_from_slice(b"PK\x01\x02 central directory");
+        apk.extend_from_slice(&EOCD_SIGNATURE);
+        apk.extend_from_slice(&[0; 12]);
+        apk.extend_from_slice(&central_directory.to_le_bytes());
+        apk.extend_from_slice(&[0; 2]);
+
+        let block = signing_block(&mut std::io::Cursor::new(&apk)).unwrap();
+        assert_eq!(block, apk[block_start..central_directory as usize]);
+        assert!(has_debug_subject(&block));
+
+        // Without the magic there's no block, only JAR signing if anything
+        let mut unsigned = apk.clone();
+        unsigned[central_directory as usize - 1] = b'0';
+        assert_eq!(signing_block(&mut std::io::Cursor::new(&unsigned)), None);
+    }
+
+    #[test]
+    fn test_is_dex() {
+        assert!(is_dex("classes.dex"));
+        assert!(is_dex("base/dex/classes3.dex"));
+        assert!(!is_dex("assets/classes.json"));
+    }
+}
@@ -0,0 +1,162 @@
+use std::sync::OnceLock;
+
+/// The machine's own architecture, which an x64 build of the app running under emulation on
+/// an ARM laptop doesn't see through `cfg!(target_arch)`
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HostArch {
+    X64,
+    Arm64,
+}
+
+impl HostArch {
+    /// How release assets name the architecture
+    pub fn asset_tags(&self) -> &'static [&'static str] {
+        match self {
+            HostArch::X64 => &["x64", "amd64", "x86_64"],
+            HostArch::Arm64 => &["arm64", "aarch64"],
+        }
+    }
+
+    /// Emulator system images that run natively on this host
+    pub fn system_image_abi(&self) -> &'static str {
+        match self {
+            HostArch::X64 => "x86_64",
+            HostArch::Arm64 => "arm64-v8a",
+        }
+    }
+}
+
+/// Physical cores split into performance and efficiency cores (Intel P/E, ARM big/LITTLE)
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct CoreClasses {
+    pub performance_cores: usize,
+    /// Logical processors on the performance cores, two per core with hyper-threading
+    pub performance_threads: usize,
+    pub efficiency_cores: usize,
+}
+
+static HOST: OnceLock<HostArch> = OnceLock::new();
+static CORES: OnceLock<CoreClasses> = OnceLock::new();
+
+pub fn host() -> HostArch {
+    *HOST.get_or_init(|| {
+        use winapi::um::processthreadsapi::GetCurrentProcess;
+        use winapi::um::winnt::IMAGE_FILE_MACHINE_ARM64;
+        use winapi::um::wow64apiset::IsWow64Process2;
+
+        let (mut process_machine, mut native_machine) = (0u16, 0u16);
+        // SAFETY: the pseudo handle is always valid and both out-pointers live across the call
+        let ok = unsafe { IsWow64Process2(GetCurrentProcess(), &mut process_machine, &mut native_machine) };
+        if (ok != 0 && native_machine == IMAGE_FILE_MACHINE_ARM64) || cfg!(target_arch = "aarch64") {
+            HostArch::Arm64
+        } else {
+            HostArch::X64
+        }
+    })
+}
+
+/// `(efficiency class, logical processors)` per core from a `RelationProcessorCore` buffer
+fn parse_cores(buffer: &[u8]) -> Vec<(u8, u32)> {
+    const MASK_OFFSET: usize = 32;
+    let mask_len = std::mem::size_of::<usize>();
+    let mut cores = Vec::new();
+    let mut offset = 0;
+    while let Some(record) = buffer.get(offset..offset + 8) {
+        let size = u32::from_le_bytes([record[4], record[5], record[6], record[7]]) as usize;
+        let Some(entry) = buffer.get(offset..offset + size).filter(|e| e.len() >= MASK_OFFSET + mask_len) else { break };
+        let mut mask = [0u8; 8];
+        mask[..mask_len].copy_from_slice(&entry[MASK_OFFSET..MASK_OFFSET + mask_len]);
+        cores.push((entry[9], u64::from_le_bytes(mask).count_ones()));
+        offset += size;
+    }
+    cores
+}
+
+/// Intel's hybrid flag, CPUID leaf 7 EDX bit 15: set on 12th-gen and later P+E parts
+fn cpuid_hybrid() -> bool {
+    #[cfg(target_arch = "x86_64")]
+    {
+        use std::arch::x86_64::{__cpuid, __cpuid_count};
+        __cpuid(0).eax >= 7 && __cpuid_count(7, 0).edx & (1 << 15) != 0
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        false
+    }
+}
+
+/// Cores on the top efficiency class are performance cores, the rest efficiency cores. Windows 10
+/// reports every core of a hybrid Intel chip as one class, so there the E-cores are told apart
+/// by lacking hyper-threading. Cores all of one class, as on most desktops, are all performance.
+fn classify_cores(cores: &[(u8, u32)], hybrid: bool) -> CoreClasses {
+    let top = cores.iter().map(|(class, _)| *class).max().unwrap_or_default();
+    let one_class = cores.iter().all(|(class, _)| *class == top);
+    let smt_mixed = cores.iter().any(|(_, threads)| *threads > 1) && cores.iter().any(|(_, threads)| *threads == 1);
+    let is_performance = |&(class, threads): &(u8, u32)| match (one_class, hybrid && smt_mixed) {
+        (false, _) => class == top,
+        (true, true) => threads > 1,
+        (true, false) => true,
+    };
+    cores.iter().fold(CoreClasses::default(), |mut classes, core| {
+        if is_performance(core) {
+            classes.performance_cores += 1;
+            classes.performance_threads += core.1 as usize;
+        } else {
+            classes.efficiency_cores += 1;
+        }
+        classes
+    })
+}
+
+pub fn core_classes() -> CoreClasses {
+    *CORES.get_or_init(|| {
+        use winapi::um::sysinfoapi::GetLogicalProcessorInformationEx;
+        use winapi::um::winnt::RelationProcessorCore;
+
+        let mut length = 0u32;
+        // SAFETY: a null buffer only asks for the required length
+        unsafe { GetLogicalProcessorInformationEx(RelationProcessorCore, std::ptr::null_mut(), &mut length) };
+        let mut buffer = vec![0u8; length as usize];
+        // SAFETY: `buffer` holds `length` bytes, as the first call asked for
+        let ok = unsafe { GetLogicalProcessorInformationEx(RelationProcessorCore, buffer.as_mut_ptr().cast(), &mut length) };
+        if ok == 0 {
+            return CoreClasses::default();
+        }
+        classify_cores(&parse_cores(&buffer[..length as usize]), cpuid_hybrid())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn core_record(efficiency_class: u8, mask: u64) -> Vec<u8> {
+        let size = 32 + std::mem::size_of::<usize>() + 8;
+        let mut record = vec![0u8; size];
+        record[4..8].copy_from_slice(&(size as u32).to_le_bytes());
+        record[9] = efficiency_class;
+        record[30] = 1; // GroupCount
+        record[32..32 + std::mem::size_of::<usize>()].copy_from_slice(&mask.to_le_bytes()[..std::mem::size_of::<usize>()]);
+        record
+    }
+
+    #[test]
+    fn test_core_classes() {
+        // Two hyper-threaded P-cores and four E-cores
+        let buffer: Vec<u8> = [core_record(1, 0b11), core_record(1, 0b1100), core_record(0, 1 << 4), core_record(0, 1 << 5), core_record(0, 1 << 6), core_record(0, 1 << 7)].concat();
+        let cores = parse_cores(&buffer);
+        assert_eq!(cores, vec![(1, 2), (1, 2), (0, 1), (0, 1), (0, 1), (0, 1)]);
+        assert_eq!(classify_cores(&cores, true), CoreClasses { performance_cores: 2, performance_threads: 4, efficiency_cores: 4 });
+
+        // Windows 10 on a hybrid chip: one class, hyper-threading gives the P-cores away
+        let flat = [(0, 2), (0, 2), (0, 1), (0, 1)];
+        assert_eq!(classify_cores(&flat, true), CoreClasses { performance_cores: 2, performance_threads: 4, efficiency_cores: 2 });
+        // The same topology without the hybrid flag is a desktop with some threads parked
+        assert_eq!(classify_cores(&flat, false), CoreClasses { performance_cores: 4, performance_threads: 6, efficiency_cores: 0 });
+        assert_eq!(classify_cores(&[(0, 2), (0, 2)], true), CoreClasses { performance_cores: 2, performance_threads: 4, efficiency_cores: 0 });
+        assert_eq!(parse_cores(&[0u8; 4]), vec![]);
+
+        assert_eq!(HostArch::Arm64.system_image_abi(), "arm64-v8a");
+    }
+}
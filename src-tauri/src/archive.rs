@@ -0,0 +1,389 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::error::HyperZenithError;
+
+const ARTIFACT_EXTENSIONS: [&str; 4] = ["apk", "aab", "ipa", "app"];
+
+/// Build directories that never hold final outputs but are expensive to walk
+pub const SKIP_DIRS: [&str; 6] = [".gradle", ".cxx", "intermediates", "generated", "tmp", "node_modules"];
+
+/// Filesystem timestamps can trail the wall clock slightly
+const MTIME_SLACK: Duration = Duration::from_secs(2);
+
+/// Build details stored next to each archived artifact as `<artifact>.json`
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ArtifactMetadata {
+    pub project: String,
+    pub build_type: String,
+    pub created_at: String,
+    pub fresh: bool,
+    /// Flavor + build type, e.g. `stagingDebug`; absent for the default debug build
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// Gradle properties overridden for this build, e.g. `hermesEnabled=false`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gradle_properties: Vec<String>,
+    /// The build that produced the artifact, for attaching release notes afterwards
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_id: Option<String>,
+    /// What changed, for testers; generated from git or written by hand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_notes: Option<String>,
+    /// Folder with the build's R8 mapping and unstripped native libraries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbols: Option<String>,
+    /// Testers' feedback on this exact binary, e.g. "has the login bug"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: String,
+    pub size_bytes: u64,
+    pub modified: String,
+    pub metadata: Option<ArtifactMetadata>,
+}
+
+/// An archived artifact with what its manifest and signature say, for browsing the archive
+#[derive(serde::Serialize, Clone)]
+pub struct IndexEntry {
+    #[serde(flatten)]
+    pub entry: ArchiveEntry,
+    /// Package, versions and SDK levels; APKs and AABs only
+    pub manifest: Option<crate::manifest::ManifestInfo>,
+    pub signing: Option<crate::apk::Signing>,
+    /// Why the artifact couldn't be read, e.g. a truncated copy
+    pub error: Option<String>,
+}
+
+/// Resolve the archive folder: custom path if set, otherwise `<project>/hyperzenith_builds`
+pub fn archive_dir(working_dir: &str, custom_path: Option<String>) -> PathBuf {
+    match custom_path {
+        Some(p) if !p.is_empty() => PathBuf::from(p),
+        _ => Path::new(working_dir).join("hyperzenith_builds"),
+    }
+}
+
+pub fn is_artifact(path: &Path) -> bool {
+    path.extension()
+        .map(|e| ARTIFACT_EXTENSIONS.contains(&e.to_string_lossy().to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+pub fn sidecar_path(artifact: &Path) -> PathBuf {
+    let mut name = artifact.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+pub fn read_metadata(artifact: &Path) -> Option<ArtifactMetadata> {
+    let content = std::fs::read_to_string(sidecar_path(artifact)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn write_metadata(artifact: &Path, metadata: &ArtifactMetadata) -> Result<(), HyperZenithError> {
+    let json = serde_json::to_string_pretty(metadata).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    let path = sidecar_path(artifact);
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+/// Matches `**/build/outputs/**/*.{apk,aab}`
+fn is_build_output(path: &Path) -> bool {
+    let parts: Vec<String> = path.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    let in_outputs = parts.windows(2).any(|w| w[0] == "build" && w[1] == "outputs");
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+    in_outputs && matches!(ext.as_deref(), Some("apk") | Some("aab"))
+}
+
+/// Every APK/AAB under `android/**/build/outputs/` with its modification time.
+/// Covers flavors, ABI splits and renamed modules that a fixed path would miss.
+pub fn find_build_outputs(android_dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    walkdir::WalkDir::new(android_dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| !(e.file_type().is_dir() && SKIP_DIRS.contains(&e.file_name().to_string_lossy().as_ref())))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && is_build_output(e.path()))
+        .filter_map(|e| {
+            let modified = e.metadata().ok()?.modified().ok()?;
+            Some((e.into_path(), modified))
+        })
+        .collect()
+}
+
+/// `stagingDebug` as `staging`, `debug`
+pub(crate) fn camel_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for c in name.chars() {
+        match words.last_mut() {
+            Some(word) if !c.is_ascii_uppercase() => word.push(c),
+            _ => words.push(c.to_ascii_lowercase().to_string()),
+        }
+    }
+    words
+}
+
+/// Whether an output belongs to a variant. Gradle names the folders under `outputs/apk/`,
+/// `outputs/bundle/`, `outputs/mapping/` and `intermediates/<task>/` after it (`staging/debug/`
+/// or `stagingDebug/`); every word of the variant has to be one of theirs. The rest of the path,
+/// the project folder included, doesn't count.
+pub fn matches_variant(path: &Path, variant: &str) -> bool {
+    let segments: Vec<String> = path.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    let Some(anchor) = segments.iter().rposition(|s| s == "outputs" || s == "intermediates") else { return false };
+    let folders = segments.get(anchor + 2..segments.len().saturating_sub(1)).unwrap_or_default();
+    let words: Vec<String> = folders.iter().flat_map(|f| camel_words(f)).collect();
+    camel_words(variant).iter().all(|w| words.contains(w))
+}
+
+/// Outputs written since the build started, flagged fresh. When Gradle was fully up to date
+/// nothing gets rewritten, so fall back to the newest output of the requested kind, flagged cached.
+pub fn select_outputs(outputs: Vec<(PathBuf, SystemTime)>, since: SystemTime, kind: &str) -> (Vec<PathBuf>, bool) {
+    let since = since.checked_sub(MTIME_SLACK).unwrap_or(since);
+    let fresh: Vec<PathBuf> = outputs.iter()
+        .filter(|(_, modified)| *modified >= since)
+        .map(|(p, _)| p.clone())
+        .collect();
+    if !fresh.is_empty() {
+        return (fresh, true);
+    }
+
+    let newest = outputs.into_iter()
+        .filter(|(p, _)| p.extension().map(|e| e.to_string_lossy().eq_ignore_ascii_case(kind)).unwrap_or(false))
+        .max_by_key(|(_, modified)| *modified)
+        .map(|(p, _)| p);
+    (newest.into_iter().collect(), false)
+}
+
+/// `<stem>_<timestamp>.<ext>` in the archive, prefixed with the Gradle module if that name is taken
+pub fn archived_path(builds_dir: &Path, source: &Path, timestamp: &str) -> PathBuf {
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = source.extension().unwrap_or_default().to_string_lossy();
+    let dest = builds_dir.join(format!("{}_{}.{}", stem, timestamp, ext));
+    if !dest.exists() {
+        return dest;
+    }
+
+    // <module>/build/outputs/...
+    let module = source.ancestors()
+        .find(|a| a.file_name().map(|n| n == "build").unwrap_or(false))
+        .and_then(|b| b.parent())
+        .and_then(|m| m.file_name())
+        .map(|m| m.to_string_lossy().to_string())
+        .unwrap_or_else(|| "module".to_string());
+    builds_dir.join(format!("{}-{}_{}.{}", module, stem, timestamp, ext))
+}
+
+fn entry(path: &Path) -> ArchiveEntry {
+    let meta = std::fs::metadata(path).ok();
+    let modified = meta.as_ref()
+        .and_then(|m| m.modified().ok())
+        .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339())
+        .unwrap_or_default();
+
+    ArchiveEntry {
+        name: path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        kind: path.extension().unwrap_or_default().to_string_lossy().to_lowercase(),
+        size_bytes: meta.map(|m| m.len()).unwrap_or(0),
+        metadata: read_metadata(path),
+        path: path.to_string_lossy().to_string(),
+        modified,
+    }
+}
+
+/// All artifacts in the archive, newest first
+pub fn list_entries(dir: &Path) -> Result<Vec<ArchiveEntry>, HyperZenithError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries: Vec<ArchiveEntry> = std::fs::read_dir(dir)
+        .map_err(|e| HyperZenithError::io_at(dir, e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| is_artifact(p))
+        .map(|path| entry(&path))
+        .collect();
+
+    // RFC 3339 timestamps in the same offset sort chronologically as strings
+    entries.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(entries)
+}
+
+/// Every artifact in the archive, newest first, with its manifest and signing read from the file
+pub fn index(dir: &Path) -> Result<Vec<IndexEntry>, HyperZenithError> {
+    Ok(list_entries(dir)?
+        .into_iter()
+        .map(|entry| {
+            if entry.kind != "apk" && entry.kind != "aab" {
+                return IndexEntry { entry, manifest: None, signing: None, error: None };
+            }
+            let path = Path::new(&entry.path);
+            let manifest = crate::manifest::read(path);
+            let signing = crate::apk::signing(path);
+            let error = manifest.as_ref().err().or(signing.as_ref().err()).map(|e| e.to_string());
+            IndexEntry { manifest: manifest.ok(), signing: signing.ok(), error, entry }
+        })
+        .collect())
+}
+
+/// An artifact directly inside the archive folder; paths from the frontend go no further
+fn archived(dir: &Path, path: &str) -> Result<PathBuf, HyperZenithError> {
+    let artifact = Path::new(path);
+    let inside = match (artifact.parent().map(std::fs::canonicalize), std::fs::canonicalize(dir)) {
+        (Some(Ok(parent)), Ok(dir)) => parent == dir,
+        _ => false,
+    };
+    if !artifact.is_file() || !inside || !is_artifact(artifact) {
+        return Err(HyperZenithError::NotFound { path: path.to_string() });
+    }
+    Ok(artifact.to_path_buf())
+}
+
+/// DOS device names Windows won't create a file under, whatever the extension (`nul.apk` included)
+fn is_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or_default().trim_end().to_ascii_uppercase();
+    match stem.as_str() {
+        "CON" | "PRN" | "AUX" | "NUL" => true,
+        _ => ["COM", "LPT"].iter().any(|prefix| {
+            stem.strip_prefix(prefix).is_some_and(|n| n.len() == 1 && matches!(n.as_bytes()[0], b'1'..=b'9'))
+        }),
+    }
+}
+
+/// A file name for a renamed artifact: no folders, characters or device names Windows rejects, and the
+/// artifact's extension kept so it still lists and installs
+fn new_artifact_name(current: &Path, name: &str) -> Result<String, HyperZenithError> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.chars().any(|c| c.is_control() || "\\/:*?\"<>|".contains(c)) || is_reserved_name(name) {
+        return Err(HyperZenithError::InvalidInput(format!("'{}' can't be used as a file name", name)));
+    }
+    let ext = current.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    match Path::new(name).extension().is_some_and(|e| e.to_string_lossy().eq_ignore_ascii_case(&ext)) {
+        true => Ok(name.to_string()),
+        false => Ok(format!("{}.{}", name, ext)),
+    }
+}
+
+/// Rename an archived artifact together with its metadata sidecar
+pub fn rename(dir: &Path, path: &str, name: &str) -> Result<ArchiveEntry, HyperZenithError> {
+    let artifact = archived(dir, path)?;
+    let renamed = artifact.with_file_name(new_artifact_name(&artifact, name)?);
+    if renamed.exists() {
+        return Err(HyperZenithError::InvalidInput(format!("{} already exists in the archive", renamed.display())));
+    }
+    std::fs::rename(&artifact, &renamed).map_err(|e| HyperZenithError::io_at(&artifact, e))?;
+    let sidecar = sidecar_path(&artifact);
+    if sidecar.exists() {
+        std::fs::rename(&sidecar, sidecar_path(&renamed)).map_err(|e| HyperZenithError::io_at(&sidecar, e))?;
+    }
+    Ok(entry(&renamed))
+}
+
+/// Attach a note to an archived artifact, or remove it with an empty one. Artifacts archived
+/// before sidecars existed get one.
+pub fn annotate(dir: &Path, path: &str, note: &str) -> Result<ArchiveEntry, HyperZenithError> {
+    let artifact = archived(dir, path)?;
+    let metadata = ArtifactMetadata {
+        note: Some(note.trim().to_string()).filter(|n| !n.is_empty()),
+        ..read_metadata(&artifact).unwrap_or_default()
+    };
+    write_metadata(&artifact, &metadata)?;
+    Ok(entry(&artifact))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_build_output() {
+        assert!(is_build_output(Path::new("android/app/build/outputs/apk/release/app-release.apk")));
+        assert!(is_build_output(Path::new("android/app/build/outputs/apk/prod/debug/app-prod-arm64-v8a-debug.apk")));
+        assert!(is_build_output(Path::new("android/wear/build/outputs/bundle/debug/wear-debug.aab")));
+        assert!(!is_build_output(Path::new("android/app/build/outputs/mapping/release/mapping.txt")));
+        assert!(!is_build_output(Path::new("android/app/libs/vendor.apk")));
+    }
+
+    #[test]
+    fn test_matches_variant() {
+        let path = Path::new("android/app/build/outputs/apk/staging/debug/app-staging-debug.apk");
+        assert!(matches_variant(path, "stagingDebug"));
+        assert!(matches_variant(path, "debug"));
+        assert!(!matches_variant(path, "prodDebug"));
+        assert!(!matches_variant(path, "stagingRelease"));
+
+        let bundle = Path::new("C:/DebugTools/app/android/app/build/outputs/bundle/productionRelease/app-production-release.aab");
+        assert!(matches_variant(bundle, "productionRelease"));
+        assert!(!matches_variant(bundle, "debug"));
+        assert!(!matches_variant(bundle, "prodRelease"));
+        let libs = Path::new("intermediates/merged_native_libs/stagingRelease/out/lib/arm64-v8a/libhermes.so");
+        assert!(matches_variant(libs, "stagingRelease"));
+        assert!(!matches_variant(Path::new("C:/Debug/app-debug.apk"), "debug"));
+    }
+
+    #[test]
+    fn test_new_artifact_name() {
+        let current = Path::new("C:/app/hyperzenith_builds/app-release_2026-10-15_10-00-00.apk");
+        assert_eq!(new_artifact_name(current, " login-bug build ").unwrap(), "login-bug build.apk");
+        assert_eq!(new_artifact_name(current, "qa-1.4.2.APK").unwrap(), "qa-1.4.2.APK");
+        assert_eq!(new_artifact_name(current, "qa-1.4.2").unwrap(), "qa-1.4.2.apk");
+        assert!(new_artifact_name(current, "../outside").is_err());
+        assert!(new_artifact_name(current, "a:b").is_err());
+        assert!(new_artifact_name(current, "   ").is_err());
+        assert!(new_artifact_name(current, "CON").is_err());
+        assert!(new_artifact_name(current, "nul.apk").is_err());
+        assert!(new_artifact_name(current, "com1 .backup").is_err());
+        assert!(new_artifact_name(current, "lpt9").is_err());
+        assert_eq!(new_artifact_name(current, "console").unwrap(), "console.apk");
+        assert_eq!(new_artifact_name(current, "com10").unwrap(), "com10.apk");
+    }
+
+    #[test]
+    fn test_rename_and_annotate() {
+        let dir = std::env::temp_dir().join(format!("hz_archive_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let built = dir.join("app-release.apk");
+        std::fs::write(&built, "apk").unwrap();
+        write_metadata(&built, &ArtifactMetadata { build_type: "release".to_string(), ..Default::default() }).unwrap();
+
+        let renamed = rename(&dir, &built.to_string_lossy(), "login-bug").unwrap();
+        let moved = dir.join("login-bug.apk");
+        assert_eq!(Path::new(&renamed.path), moved);
+        assert!(!built.exists() && !sidecar_path(&built).exists());
+        assert_eq!(read_metadata(&moved).unwrap().build_type, "release");
+
+        let bare = dir.join("app-debug.apk");
+        std::fs::write(&bare, "apk").unwrap();
+        let annotated = annotate(&dir, &bare.to_string_lossy(), " crashes on launch ").unwrap();
+        assert_eq!(annotated.metadata.and_then(|m| m.note).as_deref(), Some("crashes on launch"));
+        assert!(sidecar_path(&bare).is_file());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_select_outputs() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let old = start - Duration::from_secs(3600);
+        let outputs = vec![
+            (PathBuf::from("a/app-debug.apk"), old),
+            (PathBuf::from("a/app-x86-debug.apk"), old - Duration::from_secs(60)),
+            (PathBuf::from("a/app-debug.aab"), old),
+        ];
+
+        let (cached, fresh) = select_outputs(outputs.clone(), start, "apk");
+        assert!(!fresh);
+        assert_eq!(cached, vec![PathBuf::from("a/app-debug.apk")]);
+
+        let mut rebuilt = outputs;
+        rebuilt.push((PathBuf::from("a/app-arm64-debug.apk"), start + Duration::from_secs(30)));
+        rebuilt.push((PathBuf::from("a/app-x86_64-debug.apk"), start + Duration::from_secs(31)));
+        let (selected, fresh) = select_outputs(rebuilt, start, "apk");
+        assert!(fresh);
+        assert_eq!(selected.len(), 2);
+    }
+}
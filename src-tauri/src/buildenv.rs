@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::Manager;
+use crate::error::HyperZenithError;
+
+const BUILD_ENV_FILE: &str = "build_env.json";
+
+/// One variable exported into a project's builds. Secret values live in the Windows
+/// Credential Manager and are never written to the settings file or sent to the frontend.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct EnvVar {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+fn env_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(BUILD_ENV_FILE))
+}
+
+fn load_all(app: &tauri::AppHandle) -> HashMap<String, Vec<EnvVar>> {
+    env_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Shell variable names only: they're pasted into `export` unquoted
+pub fn validate_name(name: &str) -> Result<(), HyperZenithError> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(HyperZenithError::InvalidInput(format!("'{}' is not a valid environment variable name", name)));
+    }
+    Ok(())
+}
+
+/// The project's variables, secret values blanked
+pub fn load(app: &tauri::AppHandle, working_dir: &str) -> Vec<EnvVar> {
+    load_all(app).remove(working_dir).unwrap_or_default()
+}
+
+/// Replace the project's variables. A secret sent with an empty value keeps its stored one,
+/// secrets that are gone get removed from Credential Manager.
+pub fn save(app: &tauri::AppHandle, working_dir: &str, vars: Vec<EnvVar>) -> Result<(), HyperZenithError> {
+    for var in &vars {
+        validate_name(&var.name)?;
+    }
+    let mut all = load_all(app);
+    let previous = all.remove(working_dir).unwrap_or_default();
+    for old in previous.iter().filter(|old| old.secret) {
+        if !vars.iter().any(|v| v.secret && v.name == old.name) {
+            crate::credentials::delete(&crate::credentials::target(working_dir, &old.name));
+        }
+    }
+    let mut stored = Vec::new();
+    for var in vars {
+        if var.secret {
+            if !var.value.is_empty() {
+                crate::credentials::write(&crate::credentials::target(working_dir, &var.name), &var.value)?;
+            }
+            stored.push(EnvVar { value: String::new(), ..var });
+        } else {
+            stored.push(var);
+        }
+    }
+    if !stored.is_empty() {
+        all.insert(working_dir.to_string(), stored);
+    }
+
+    let path = env_file(app).ok_or("No app config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(&all).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+/// `export A='1' B='x y'; ` for a bash prefix, empty without variables
+pub fn export_script(vars: &[(String, String)]) -> String {
+    if vars.is_empty() {
+        return String::new();
+    }
+    let assignments: Vec<String> = vars.iter().map(|(name, value)| format!("{}={}", name, crate::sh_quote(value))).collect();
+    format!("export {}; ", assignments.join(" "))
+}
+
+/// The project's variables with secrets read back from Credential Manager, as an `export`
+/// prefix. Secrets missing from the store are left out and reported through `on_missing`.
+pub fn exports(app: &tauri::AppHandle, working_dir: &str, mut on_missing: impl FnMut(&str)) -> String {
+    let resolved: Vec<(String, String)> = load(app, working_dir)
+        .into_iter()
+        .filter(|var| validate_name(&var.name).is_ok())
+        .filter_map(|var| {
+            if !var.secret {
+                return Some((var.name, var.value));
+            }
+            match crate::credentials::read(&crate::credentials::target(working_dir, &var.name)) {
+                Some(secret) => Some((var.name, secret)),
+                None => {
+                    on_missing(&var.name);
+                    None
+                },
+            }
+        })
+        .collect();
+    export_script(&resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_script() {
+        let vars = vec![
+            ("API_URL".to_string(), "https://staging.example.com".to_string()),
+            ("GREETING".to_string(), "it's on".to_string()),
+        ];
+        assert_eq!(export_script(&vars), r"export API_URL='https://staging.example.com' GREETING='it'\''s on'; ");
+        assert_eq!(export_script(&[]), "");
+
+        assert!(validate_name("_FEATURE_FLAG_2").is_ok());
+        assert!(validate_name("2FAST").is_err());
+        assert!(validate_name("API-URL").is_err());
+        assert!(validate_name("A;rm").is_err());
+    }
+}
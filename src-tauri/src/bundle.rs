@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::Emitter;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+const BUNDLE_FILE: &str = "index.android.bundle";
+const BYTECODE_FILE: &str = "index.android.hbc";
+/// Largest packages/files sent to the frontend; the rest are folded into one entry
+const MAX_MODULES: usize = 40;
+
+/// Bytes of the minified bundle attributed to one npm package or project file
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct ModuleSize {
+    pub name: String,
+    pub bytes: u64,
+    pub files: u32,
+}
+
+/// Sent on `bundle-report` (and `bundle-report-{id}`) once the release bundle is built
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BundleReport {
+    pub id: String,
+    pub bundle_bytes: u64,
+    /// Hermes bytecode, `None` when hermesc wasn't available
+    pub bytecode_bytes: Option<u64>,
+    /// Largest first; empty unless a breakdown was requested
+    pub modules: Vec<ModuleSize>,
+    pub output_dir: String,
+}
+
+/// The entry file React Native's Gradle plugin would bundle for a bare project
+fn bare_entry_file(project_dir: &Path) -> &'static str {
+    ["index.android.js", "index.js", "index.ts", "index.tsx"]
+        .into_iter()
+        .find(|f| project_dir.join(f).exists())
+        .unwrap_or("index.js")
+}
+
+/// Release bundle (plus source map) via Metro, then Hermes bytecode with the hermesc that ships
+/// with the project's react-native. Expo projects go through `expo export:embed`, which resolves
+/// their entry (e.g. expo-router) and applies Expo's Metro config the same way Gradle does.
+pub fn bundle_cmd(wsl_project_path: &str, wsl_output_dir: &str, entry: Option<&str>, pm: crate::node::PackageManager) -> String {
+    let bundle = format!("{}/{}", wsl_output_dir, BUNDLE_FILE);
+    let (cli, entry) = match entry {
+        Some(file) => ("react-native bundle", crate::sh_quote(file)),
+        None => (
+            "expo export:embed",
+            "\"$(node \"$(node -p \"require.resolve('expo/scripts/resolveAppEntry')\")\" \"$PWD\" android absolute)\"".to_string(),
+        ),
+    };
+    let metro = format!(
+        "{} --platform android --dev false --minify true --reset-cache --entry-file {} --bundle-output {} --sourcemap-output {} --assets-dest {}",
+        cli,
+        entry,
+        crate::sh_quote(&bundle),
+        crate::sh_quote(&format!("{}.map", bundle)),
+        crate::sh_quote(&format!("{}/assets", wsl_output_dir)),
+    );
+    format!(
+        "cd {project} && rm -rf {out} && mkdir -p {out} && {metro} 2>&1 && \
+         HERMESC=\"$(node -p \"require('path').dirname(require.resolve('react-native/package.json'))\")/sdks/hermesc/linux64-bin/hermesc\"; \
+         if [ -x \"$HERMESC\" ]; then echo 'Compiling Hermes bytecode...' && \"$HERMESC\" -emit-binary -O -out {hbc} {bundle} 2>&1; \
+         else echo 'hermesc not found, skipping bytecode'; fi",
+        project = crate::sh_quote(wsl_project_path),
+        out = crate::sh_quote(wsl_output_dir),
+        metro = pm.run_bin(&metro),
+        hbc = crate::sh_quote(&format!("{}/{}", wsl_output_dir, BYTECODE_FILE)),
+        bundle = crate::sh_quote(&bundle),
+    )
+}
+
+const BASE64: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// One source map segment's base64 VLQ fields
+fn decode_vlq(segment: &str) -> Option<Vec<i64>> {
+    let mut values = Vec::new();
+    let (mut value, mut shift) = (0i64, 0);
+    for c in segment.bytes() {
+        let digit = BASE64.iter().position(|&b| b == c)? as i64;
+        value += (digit & 31) << shift;
+        if digit & 32 != 0 {
+            shift += 5;
+        } else {
+            values.push(if value & 1 == 1 { -(value >> 1) } else { value >> 1 });
+            value = 0;
+            shift = 0;
+        }
+    }
+    Some(values)
+}
+
+/// `node_modules/@scope/pkg/...` → `@scope/pkg`, `node_modules/pkg/...` → `pkg`; project files keep their path
+fn module_name(source: &str) -> String {
+    let source = source.replace('\\', "/");
+    match source.rsplit_once("node_modules/") {
+        Some((_, rest)) => {
+            let mut parts = rest.split('/');
+            match (parts.next(), parts.next()) {
+                (Some(scope), Some(name)) if scope.starts_with('@') => format!("{}/{}", scope, name),
+                (Some(name), _) => name.to_string(),
+                _ => rest.to_string(),
+            }
+        },
+        None => source.trim_start_matches("./").to_string(),
+    }
+}
+
+/// Attribute every generated column of the bundle to the source it maps to, like
+/// source-map-explorer, then total per package. Sizes are in UTF-16 columns, which
+/// equal bytes for the ASCII that minified bundles are almost entirely made of.
+pub fn breakdown(bundle: &str, source_map: &serde_json::Value) -> Vec<ModuleSize> {
+    let sources: Vec<&str> = source_map["sources"]
+        .as_array()
+        .map(|s| s.iter().map(|v| v.as_str().unwrap_or("")).collect())
+        .unwrap_or_default();
+    let mappings = source_map["mappings"].as_str().unwrap_or("");
+
+    let mut per_source: HashMap<Option<usize>, u64> = HashMap::new();
+    let mut source_index = 0i64;
+    for (line, text) in mappings.split(';').zip(bundle.split('\n')) {
+        let line_len = text.encode_utf16().count() as i64;
+        let mut column = 0i64;
+        let mut segments: Vec<(i64, Option<usize>)> = Vec::new();
+        for fields in line.split(',').filter(|s| !s.is_empty()).filter_map(decode_vlq) {
+            column += fields.first().copied().unwrap_or(0);
+            let source = fields.get(1).map(|delta| {
+                source_index += delta;
+                source_index as usize
+            });
+            segments.push((column, source));
+        }
+        for (i, (start, source)) in segments.iter().enumerate() {
+            let end = segments.get(i + 1).map(|(c, _)| *c).unwrap_or(line_len);
+            *per_source.entry(*source).or_default() += (end - start).max(0) as u64;
+        }
+    }
+
+    let mut per_module: HashMap<String, ModuleSize> = HashMap::new();
+    for (source, bytes) in per_source {
+        let name = source
+            .and_then(|i| sources.get(i))
+            .map(|s| module_name(s))
+            .unwrap_or_else(|| "[unmapped]".to_string());
+        let entry = per_module.entry(name.clone()).or_insert(ModuleSize { name, bytes: 0, files: 0 });
+        entry.bytes += bytes;
+        entry.files += 1;
+    }
+
+    let mut modules: Vec<ModuleSize> = per_module.into_values().collect();
+    modules.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    if modules.len() > MAX_MODULES {
+        let rest = modules.split_off(MAX_MODULES - 1);
+        modules.push(ModuleSize {
+            name: format!("{} others", rest.len()),
+            bytes: rest.iter().map(|m| m.bytes).sum(),
+            files: rest.iter().map(|m| m.files).sum(),
+        });
+    }
+    modules
+}
+
+/// Build the release JS bundle for a project, report its sizes and emit the report
+pub async fn analyze(
+    channel: &BuildChannel,
+    working_dir: &str,
+    with_breakdown: bool,
+    toolchain: &crate::node::Toolchain,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<BundleReport, HyperZenithError> {
+    let project_dir = Path::new(working_dir);
+    let output_dir = crate::logs::logs_dir(working_dir).join("bundle");
+    let entry = (!crate::expo::is_expo_project(project_dir)).then(|| bare_entry_file(project_dir));
+
+    let distro = crate::wsl::project_distro(working_dir);
+    let script = format!(
+        "{}{}",
+        toolchain.env_script(),
+        bundle_cmd(
+            &crate::windows_to_wsl_path(working_dir),
+            &crate::windows_to_wsl_path(&output_dir.to_string_lossy()),
+            entry,
+            toolchain.package_manager
+        )
+    );
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &script);
+    if distro.is_none() {
+        cmd.current_dir(working_dir);
+    }
+
+    channel.line("📦 Bundling release JS...".to_string());
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    let log_path = crate::write_build_log(working_dir, "bundle", &outcome.log);
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+    let bundle_path = output_dir.join(BUNDLE_FILE);
+    if !outcome.success || !bundle_path.exists() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "metro bundle".to_string(),
+            output: format!("See {}", log_path.display()),
+        });
+    }
+
+    let size = |name: &str| std::fs::metadata(output_dir.join(name)).ok().map(|m| m.len());
+    let modules = if with_breakdown {
+        channel.line("🔍 Attributing bundle bytes to modules...".to_string());
+        let bundle = std::fs::read_to_string(&bundle_path).map_err(|e| HyperZenithError::io_at(&bundle_path, e))?;
+        let map_path = output_dir.join(format!("{}.map", BUNDLE_FILE));
+        let map_text = std::fs::read_to_string(&map_path).map_err(|e| HyperZenithError::io_at(&map_path, e))?;
+        let source_map = serde_json::from_str(&map_text).map_err(|e| HyperZenithError::Other(format!("Invalid source map: {}", e)))?;
+        tauri::async_runtime::spawn_blocking(move || breakdown(&bundle, &source_map))
+            .await
+            .map_err(|e| HyperZenithError::Other(e.to_string()))?
+    } else {
+        Vec::new()
+    };
+
+    let report = BundleReport {
+        id: channel.id.clone(),
+        bundle_bytes: size(BUNDLE_FILE).unwrap_or(0),
+        bytecode_bytes: size(BYTECODE_FILE),
+        modules,
+        output_dir: output_dir.display().to_string(),
+    };
+    channel.line(format!(
+        "📦 JS bundle: {:.2} MB{}",
+        report.bundle_bytes as f64 / 1_048_576.0,
+        report.bytecode_bytes.map(|b| format!(" · Hermes bytecode: {:.2} MB", b as f64 / 1_048_576.0)).unwrap_or_default()
+    ));
+    let _ = channel.app.emit(&format!("bundle-report-{}", channel.id), &report);
+    let _ = channel.app.emit("bundle-report", &report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_vlq() {
+        assert_eq!(decode_vlq("AAAA"), Some(vec![0, 0, 0, 0]));
+        assert_eq!(decode_vlq("SACF"), Some(vec![9, 0, 1, -2]));
+        assert_eq!(decode_vlq("gBAAA"), Some(vec![16, 0, 0, 0]));
+        assert_eq!(decode_vlq("A*"), None);
+    }
+
+    #[test]
+    fn test_breakdown() {
+        // Line 1: cols 0-9 from sources[0], 10-29 from sources[1]; line 2: all 15 from sources[2]
+        let bundle = format!("{}\n{}", "x".repeat(30), "y".repeat(15));
+        let map = serde_json::json!({
+            "sources": ["/app/App.tsx", "/app/node_modules/@react-navigation/native/lib/index.js", "/app/node_modules/lodash/map.js"],
+            "mappings": "AAAA,UCAA;ACAA",
+        });
+        let modules = breakdown(&bundle, &map);
+        assert_eq!(modules.iter().map(|m| (m.name.as_str(), m.bytes)).collect::<Vec<_>>(), vec![
+            ("@react-navigation/native", 20),
+            ("lodash", 15),
+            ("/app/App.tsx", 10),
+        ]);
+    }
+}
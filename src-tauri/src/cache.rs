@@ -0,0 +1,299 @@
+use std::path::{Path, PathBuf};
+use crate::error::HyperZenithError;
+
+/// Where a cache lives: inside the WSL filesystem (relative to $HOME) or on the Windows side
+enum CacheLocation {
+    WslHome(&'static str),
+    Local(PathBuf),
+}
+
+struct CacheTarget {
+    id: &'static str,
+    label: &'static str,
+    location: CacheLocation,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct CacheEntry {
+    pub id: String,
+    pub label: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub exists: bool,
+}
+
+/// Where most of a failed Android build's disk goes: dex files, merged resources and native
+/// libraries, relative to `android/`. Gradle's caches live elsewhere and survive the cleanup.
+const FAILED_BUILD_OUTPUTS: &str = "app/build/intermediates";
+
+/// What happens to the outputs a failed Android build leaves behind
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub struct FailureCleanupPolicy {
+    /// Delete `android/app/build/intermediates` after Gradle fails
+    pub clean_intermediates: bool,
+    /// Leave smaller intermediates alone, 0 to always clean
+    pub min_mb: u64,
+}
+
+impl FailureCleanupPolicy {
+    fn applies(&self, bytes: u64) -> bool {
+        self.clean_intermediates && bytes > 0 && bytes >= self.min_mb * 1_048_576
+    }
+}
+
+/// Sent on `failed-build-cleaned` (and `failed-build-cleaned-{id}`)
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct FailureCleanup {
+    pub build_id: String,
+    pub path: String,
+    pub files: u64,
+    pub freed_bytes: u64,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct PurgeReport {
+    pub purged: Vec<String>,
+    pub failed: Vec<(String, String)>, // (id, reason)
+    pub freed_bytes: u64,
+}
+
+fn cache_targets(working_dir: &str) -> Vec<CacheTarget> {
+    vec![
+        CacheTarget { id: "gradle_caches", label: "Gradle caches (WSL)", location: CacheLocation::WslHome(".gradle/caches") },
+        CacheTarget {
+            id: "project_gradle",
+            label: "Project .gradle",
+            location: CacheLocation::Local(Path::new(working_dir).join("android").join(".gradle")),
+        },
+        CacheTarget { id: "kotlin", label: "Kotlin daemon cache", location: CacheLocation::WslHome(".kotlin") },
+        CacheTarget { id: "konan", label: "Kotlin/Native (konan)", location: CacheLocation::WslHome(".konan") },
+        CacheTarget { id: "npm", label: "npm cache (WSL)", location: CacheLocation::WslHome(".npm/_cacache") },
+    ]
+}
+
+/// Total file count and size of a local directory tree
+pub fn dir_size(path: &Path) -> (u64, u64) {
+    walkdir::WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .fold((0, 0), |(files, bytes), e| {
+            (files + 1, bytes + e.metadata().map(|m| m.len()).unwrap_or(0))
+        })
+}
+
+pub fn cache_report(working_dir: &str) -> Vec<CacheEntry> {
+    let targets = cache_targets(working_dir);
+
+    // Measure every WSL-side cache in a single round trip
+    let wsl_rel: Vec<&str> = targets.iter()
+        .filter_map(|t| match t.location { CacheLocation::WslHome(rel) => Some(rel), _ => None })
+        .collect();
+    let du_cmd = format!(
+        "cd ~ && du -sb {} 2>/dev/null; true",
+        wsl_rel.join(" ")
+    );
+    let wsl_sizes = crate::wsl::run_bash(&du_cmd)
+        .map(|out| crate::wsl::parse_du(&out))
+        .unwrap_or_default();
+
+    targets.into_iter().map(|t| {
+        let (path, size, exists) = match &t.location {
+            CacheLocation::WslHome(rel) => {
+                let found = wsl_sizes.iter().find(|(p, _)| p == rel);
+                (format!("~/{}", rel), found.map(|(_, b)| *b).unwrap_or(0), found.is_some())
+            },
+            CacheLocation::Local(p) => {
+                let exists = p.exists();
+                let size = if exists { dir_size(p).1 } else { 0 };
+                (p.to_string_lossy().to_string(), size, exists)
+            }
+        };
+        CacheEntry { id: t.id.to_string(), label: t.label.to_string(), path, size_bytes: size, exists }
+    }).collect()
+}
+
+pub fn purge_caches(working_dir: &str, ids: &[String]) -> PurgeReport {
+    let sizes = cache_report(working_dir);
+    let mut report = PurgeReport { purged: Vec::new(), failed: Vec::new(), freed_bytes: 0 };
+
+    for target in cache_targets(working_dir).into_iter().filter(|t| ids.iter().any(|id| id == t.id)) {
+        println!("🧹 [CACHE] Purging {}", target.label);
+        let result = match &target.location {
+            CacheLocation::WslHome(rel) => {
+                // Daemons hold locks inside the Gradle cache, stop them before deleting
+                let stop = if target.id == "gradle_caches" { "pkill -f GradleDaemon; " } else { "" };
+                crate::wsl::run_bash(&format!("{}rm -rf ~/{}", stop, rel)).map(|_| ())
+            },
+            CacheLocation::Local(p) if p.exists() => std::fs::remove_dir_all(p).map_err(|e| HyperZenithError::io_at(p, e)),
+            CacheLocation::Local(_) => Ok(()),
+        };
+
+        match result {
+            Ok(_) => {
+                report.freed_bytes += sizes.iter().find(|s| s.id == target.id).map(|s| s.size_bytes).unwrap_or(0);
+                report.purged.push(target.id.to_string());
+            },
+            Err(e) => {
+                println!("🧹 [CACHE] ❌ Failed to purge {}: {}", target.label, e);
+                report.failed.push((target.id.to_string(), e.to_string()));
+            }
+        }
+    }
+    report
+}
+
+/// JavaScript-side caches `nuke_build` clears only when asked to, alongside the Gradle directories
+pub const JS_NUKE_TARGETS: [&str; 4] = ["node_modules/.cache", "metro", "watchman", "expo"];
+
+/// Temp-dir entries left by Metro's file map and transform cache, old and new names
+const METRO_TEMP_PREFIXES: [&str; 3] = ["metro-", "haste-map-", "react-native-packager-cache-"];
+
+fn is_metro_temp(name: &str) -> bool {
+    METRO_TEMP_PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Metro runs under WSL during the bundle step, but a Windows-side `npx expo start` caches too
+fn windows_metro_temp() -> Vec<PathBuf> {
+    std::fs::read_dir(std::env::temp_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| is_metro_temp(&e.file_name().to_string_lossy()))
+                .map(|e| e.path())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The same globs on the WSL side, expanded in `$TMPDIR`
+fn wsl_metro_globs() -> String {
+    METRO_TEMP_PREFIXES.iter().map(|p| format!("{}*", p)).collect::<Vec<_>>().join(" ")
+}
+
+fn run_project_bash(working_dir: &str, cmd: &str) -> Result<String, HyperZenithError> {
+    crate::wsl::run_bash_in(crate::wsl::project_distro(working_dir).as_deref(), cmd)
+}
+
+/// Size of a JS cache target, `None` when there's nothing to clear
+pub fn measure_js_target(working_dir: &str, id: &str) -> Option<crate::NukeTarget> {
+    let project = Path::new(working_dir);
+    let local = |dir: PathBuf| {
+        dir.exists().then(|| {
+            let (files, bytes) = dir_size(&dir);
+            crate::NukeTarget { id: id.to_string(), path: dir.display().to_string(), files, bytes }
+        })
+    };
+    match id {
+        "node_modules/.cache" => local(project.join("node_modules").join(".cache")),
+        "expo" => local(project.join(".expo")),
+        "metro" => {
+            let (mut files, mut bytes) = windows_metro_temp().iter().fold((0, 0), |(f, b), p| {
+                let (pf, pb) = if p.is_dir() { dir_size(p) } else { (1, std::fs::metadata(p).map(|m| m.len()).unwrap_or(0)) };
+                (f + pf, b + pb)
+            });
+            let script = format!(
+                "cd \"${{TMPDIR:-/tmp}}\" && shopt -s nullglob && set -- {} && [ $# -gt 0 ] && find \"$@\" -type f | wc -l && du -scb \"$@\" | tail -n1; true",
+                wsl_metro_globs()
+            );
+            let out = run_project_bash(working_dir, &script).unwrap_or_default();
+            let mut lines = out.lines();
+            if let (Some(count), Some(total)) = (lines.next(), lines.next()) {
+                files += count.trim().parse::<u64>().unwrap_or(0);
+                bytes += crate::wsl::parse_du(total).first().map(|(_, b)| *b).unwrap_or(0);
+            }
+            (files > 0).then(|| crate::NukeTarget {
+                id: id.to_string(),
+                path: format!("{}, $TMPDIR (WSL): {}", std::env::temp_dir().display(), wsl_metro_globs()),
+                files,
+                bytes,
+            })
+        },
+        // Nothing on disk worth measuring: watchman keeps the crawl state in its server
+        "watchman" => run_project_bash(working_dir, "command -v watchman")
+            .ok()
+            .filter(|out| !out.trim().is_empty())
+            .map(|_| crate::NukeTarget {
+                id: id.to_string(),
+                path: format!("watchman watch-del {}", crate::windows_to_wsl_path(working_dir)),
+                files: 0,
+                bytes: 0,
+            }),
+        _ => None,
+    }
+}
+
+/// Clear a JS cache target measured by `measure_js_target`
+pub fn clear_js_target(working_dir: &str, target: &crate::NukeTarget) -> Result<(), HyperZenithError> {
+    match target.id.as_str() {
+        "metro" => {
+            for path in windows_metro_temp() {
+                let result = if path.is_dir() { std::fs::remove_dir_all(&path) } else { std::fs::remove_file(&path) };
+                result.map_err(|e| HyperZenithError::io_at(&path, e))?;
+            }
+            run_project_bash(working_dir, &format!("cd \"${{TMPDIR:-/tmp}}\" && rm -rf {}", wsl_metro_globs())).map(|_| ())
+        },
+        // Drop the project's watch and restart the server so stale crawl results go with it
+        "watchman" => run_project_bash(working_dir, &format!(
+            "watchman watch-del {} >/dev/null 2>&1; watchman shutdown-server >/dev/null 2>&1; true",
+            crate::sh_quote(&crate::windows_to_wsl_path(working_dir))
+        ))
+        .map(|_| ()),
+        _ => std::fs::remove_dir_all(&target.path).map_err(|e| HyperZenithError::io_at(Path::new(&target.path), e)),
+    }
+}
+
+/// Delete the intermediates of a failed build when the policy asks for it, without touching
+/// `.gradle` or the rest of `app/build`. `None` when there was nothing to clean.
+pub fn clean_failed_outputs(working_dir: &str, build_id: &str, policy: &FailureCleanupPolicy) -> Result<Option<FailureCleanup>, HyperZenithError> {
+    let dir = Path::new(working_dir).join("android").join(FAILED_BUILD_OUTPUTS);
+    if !policy.clean_intermediates || !dir.is_dir() {
+        return Ok(None);
+    }
+    let (files, bytes) = dir_size(&dir);
+    if !policy.applies(bytes) {
+        return Ok(None);
+    }
+    std::fs::remove_dir_all(&dir).map_err(|e| HyperZenithError::io_at(&dir, e))?;
+    Ok(Some(FailureCleanup { build_id: build_id.to_string(), path: dir.display().to_string(), files, freed_bytes: bytes }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_metro_temp() {
+        assert!(is_metro_temp("metro-cache"));
+        assert!(is_metro_temp("haste-map-rn-4f2a1c"));
+        assert!(is_metro_temp("react-native-packager-cache-5e1f"));
+        assert!(!is_metro_temp("hyperzenith_compare"));
+        assert_eq!(wsl_metro_globs(), "metro-* haste-map-* react-native-packager-cache-*");
+    }
+
+    #[test]
+    fn test_clean_failed_outputs() {
+        let project = std::env::temp_dir().join(format!("hz_failed_outputs_{}", std::process::id()));
+        let app_build = project.join("android").join("app").join("build");
+        std::fs::create_dir_all(app_build.join("intermediates").join("dex")).unwrap();
+        std::fs::create_dir_all(project.join("android").join(".gradle")).unwrap();
+        std::fs::write(app_build.join("intermediates").join("dex").join("classes.dex"), vec![0u8; 4096]).unwrap();
+        std::fs::write(app_build.join("kotlin-cache.bin"), b"keep").unwrap();
+        let working_dir = project.display().to_string();
+
+        let off = FailureCleanupPolicy::default();
+        assert!(clean_failed_outputs(&working_dir, "b1", &off).unwrap().is_none());
+        let too_small = FailureCleanupPolicy { clean_intermediates: true, min_mb: 1 };
+        assert!(clean_failed_outputs(&working_dir, "b1", &too_small).unwrap().is_none());
+
+        let always = FailureCleanupPolicy { clean_intermediates: true, min_mb: 0 };
+        let cleaned = clean_failed_outputs(&working_dir, "b1", &always).unwrap().unwrap();
+        assert_eq!((cleaned.files, cleaned.freed_bytes), (1, 4096));
+        assert!(!app_build.join("intermediates").exists());
+        assert!(app_build.join("kotlin-cache.bin").exists() && project.join("android").join(".gradle").exists());
+        assert!(clean_failed_outputs(&working_dir, "b1", &always).unwrap().is_none());
+
+        let _ = std::fs::remove_dir_all(&project);
+    }
+}
@@ -0,0 +1,90 @@
+use std::path::Path;
+use crate::error::HyperZenithError;
+use crate::history::{BuildOutcome, BuildRecord};
+
+/// Enough for a sprint's worth of work without burying testers
+const MAX_COMMITS: usize = 50;
+
+/// When the project last built successfully on this platform
+pub fn last_success_at<'a>(records: &'a [BuildRecord], project: &str, platform: &str) -> Option<&'a str> {
+    records
+        .iter()
+        .filter(|r| r.project == project && r.platform == platform && r.outcome == BuildOutcome::Success)
+        .map(|r| r.started_at.as_str())
+        .max()
+}
+
+/// One `- subject (hash)` line per commit since `since`, or the latest commits without it
+pub fn git_log_script(wsl_dir: &str, since: Option<&str>) -> String {
+    let since = since.map(|s| format!(" --since={}", crate::sh_quote(s))).unwrap_or_default();
+    format!(
+        "cd {} && git log --no-merges -n {}{} --pretty=format:'- %s (%h)'",
+        crate::sh_quote(wsl_dir),
+        MAX_COMMITS,
+        since
+    )
+}
+
+/// Git log of the project since its last successful build, `None` when nothing was committed
+pub fn generate(app: &tauri::AppHandle, working_dir: &str, platform: &str) -> Result<Option<String>, HyperZenithError> {
+    let history = crate::history::load(app);
+    let since = last_success_at(&history, working_dir, platform);
+    let script = git_log_script(&crate::windows_to_wsl_path(working_dir), since);
+    let log = crate::wsl::run_bash_in(crate::wsl::project_distro(working_dir).as_deref(), &script)?;
+    let notes = log.trim();
+    Ok((!notes.is_empty()).then(|| notes.to_string()))
+}
+
+/// Attach notes to every artifact a build archived, returning how many were updated
+pub fn set_for_build(archive_dir: &Path, build_id: &str, text: &str) -> Result<usize, HyperZenithError> {
+    let mut updated = 0;
+    for entry in crate::archive::list_entries(archive_dir)? {
+        let Some(mut metadata) = entry.metadata else { continue };
+        if metadata.build_id.as_deref() != Some(build_id) {
+            continue;
+        }
+        metadata.release_notes = Some(text.trim().to_string()).filter(|t| !t.is_empty());
+        crate::archive::write_metadata(Path::new(&entry.path), &metadata)?;
+        updated += 1;
+    }
+    if updated == 0 {
+        return Err(HyperZenithError::InvalidInput(format!("No archived artifacts from build {}", build_id)));
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_log_script() {
+        let record = |started_at: &str, outcome| BuildRecord {
+            id: String::new(),
+            project: "C:\\app".to_string(),
+            platform: "android".to_string(),
+            build_type: "apk".to_string(),
+            variant: None,
+            started_at: started_at.to_string(),
+            duration_secs: 60.0,
+            outcome,
+            cache_hit_rate: None,
+            warnings: None,
+            phases: Vec::new(),
+        };
+        let records = vec![
+            record("2024-05-01T10:00:00+02:00", BuildOutcome::Success),
+            record("2024-05-02T09:00:00+02:00", BuildOutcome::Success),
+            record("2024-05-03T09:00:00+02:00", BuildOutcome::Failed),
+        ];
+        let since = last_success_at(&records, "C:\\app", "android");
+        assert_eq!(since, Some("2024-05-02T09:00:00+02:00"));
+        assert_eq!(last_success_at(&records, "C:\\app", "ios"), None);
+
+        assert_eq!(
+            git_log_script("/mnt/c/my app", since),
+            "cd '/mnt/c/my app' && git log --no-merges -n 50 --since='2024-05-02T09:00:00+02:00' --pretty=format:'- %s (%h)'"
+        );
+        assert_eq!(git_log_script("/mnt/c/app", None), "cd '/mnt/c/app' && git log --no-merges -n 50 --pretty=format:'- %s (%h)'");
+    }
+}
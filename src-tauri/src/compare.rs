@@ -0,0 +1,243 @@
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::Emitter;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+use crate::i18n::{tr, Msg};
+
+const WORKTREES_DIR: &str = "hyperzenith_compare";
+
+/// One side of a comparison
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CommitBuild {
+    pub commit: String,
+    pub subject: String,
+    pub success: bool,
+    pub message: String,
+    pub duration_secs: f64,
+    /// Total size of the archived artifacts
+    pub artifact_bytes: u64,
+    pub artifacts: Vec<String>,
+}
+
+/// Sent on `commit-comparison` (and `commit-comparison-{id}`) once both commits have built
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct CommitComparison {
+    pub id: String,
+    pub a: CommitBuild,
+    pub b: CommitBuild,
+    /// `b` minus `a`; `None` unless both built
+    pub duration_delta_secs: Option<f64>,
+    pub size_delta_bytes: Option<i64>,
+    pub report_path: Option<String>,
+}
+
+impl CommitComparison {
+    fn new(id: &str, a: CommitBuild, b: CommitBuild) -> Self {
+        let both = a.success && b.success;
+        CommitComparison {
+            id: id.to_string(),
+            duration_delta_secs: both.then_some(b.duration_secs - a.duration_secs),
+            size_delta_bytes: both.then_some(b.artifact_bytes as i64 - a.artifact_bytes as i64),
+            a,
+            b,
+            report_path: None,
+        }
+    }
+}
+
+/// Commit-ish as typed by the user: a hash, tag or branch, never an option
+pub fn validate_rev(rev: &str) -> Result<(), HyperZenithError> {
+    let rev = rev.trim();
+    if rev.is_empty() || rev.starts_with('-') || rev.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(HyperZenithError::InvalidInput(format!("'{}' is not a commit", rev)));
+    }
+    Ok(())
+}
+
+fn format_size_delta(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{}{:.2} MB", sign, bytes.unsigned_abs() as f64 / 1_048_576.0)
+}
+
+/// Plain-text report saved next to the build logs
+pub fn render_report(working_dir: &str, comparison: &CommitComparison) -> String {
+    let mut report = format!("{}\n\n", tr(Msg::ComparisonReport, &[("project", &working_dir)]));
+    for (label, build) in [("A", &comparison.a), ("B", &comparison.b)] {
+        report += &format!(
+            "{} {} {:.12} {}\n     {:.1}s, {:.2} MB   {}\n",
+            if build.success { "✅" } else { "❌" },
+            label,
+            build.commit,
+            build.subject,
+            build.duration_secs,
+            build.artifact_bytes as f64 / 1_048_576.0,
+            build.message
+        );
+    }
+    match (comparison.duration_delta_secs, comparison.size_delta_bytes) {
+        (Some(duration), Some(size)) => {
+            let deltas = tr(Msg::ComparisonDeltas, &[("duration", &format!("{:+.1}", duration)), ("size", &format_size_delta(size))]);
+            report += &format!("\n{}\n", deltas);
+        },
+        _ => report += &format!("\n{}\n", tr(Msg::ComparisonNoDeltas, &[])),
+    }
+    report
+}
+
+/// Where a commit's worktree lives, as (Windows path for the build, Linux path for git).
+/// Projects inside a WSL distro get theirs in that distro's /tmp to stay on the same filesystem.
+fn worktree_paths(working_dir: &str, name: &str) -> (String, String) {
+    match crate::wsl::project_distro(working_dir) {
+        Some(distro) => (
+            format!(r"\\wsl.localhost\{}\tmp\{}\{}", distro, WORKTREES_DIR, name),
+            format!("/tmp/{}/{}", WORKTREES_DIR, name),
+        ),
+        None => {
+            let dir = std::env::temp_dir().join(WORKTREES_DIR).join(name);
+            let win = dir.display().to_string();
+            let linux = crate::windows_to_wsl_path(&win);
+            (win, linux)
+        },
+    }
+}
+
+fn git(working_dir: &str, args: &str) -> Result<String, HyperZenithError> {
+    let script = format!("git -C {} {}", crate::sh_quote(&crate::windows_to_wsl_path(working_dir)), args);
+    crate::wsl::run_bash_in(crate::wsl::project_distro(working_dir).as_deref(), &script).map(|out| out.trim().to_string())
+}
+
+/// Full hash and subject line of a commit-ish
+pub fn resolve(working_dir: &str, rev: &str) -> Result<(String, String), HyperZenithError> {
+    validate_rev(rev)?;
+    let out = git(working_dir, &format!("log -1 --format='%H %s' {} --", crate::sh_quote(&format!("{}^{{commit}}", rev.trim()))))
+        .map_err(|_| HyperZenithError::InvalidInput(format!("'{}' is not a commit in this repository", rev.trim())))?;
+    let (hash, subject) = out.split_once(' ').unwrap_or((out.as_str(), ""));
+    Ok((hash.to_string(), subject.to_string()))
+}
+
+fn artifact_bytes(artifacts: &[PathBuf]) -> u64 {
+    artifacts.iter().filter_map(|p| std::fs::metadata(p).ok()).map(|m| m.len()).sum()
+}
+
+/// Build one commit in its own worktree. The user's checkout is never touched, so there's
+/// nothing to stash. Logs are copied back to the project before the worktree goes.
+async fn build_commit(
+    channel: &BuildChannel,
+    hw: crate::HardwareProfile,
+    working_dir: &str,
+    commit: &str,
+    subject: &str,
+    options: crate::AndroidBuildOptions,
+    cancel: &mut oneshot::Receiver<()>,
+) -> Result<CommitBuild, HyperZenithError> {
+    let (worktree_win, worktree_linux) = worktree_paths(working_dir, &format!("{:.8}-{:.12}", channel.id, commit));
+    channel.line(format!("🌿 Checking out {:.12} into {}", commit, worktree_win));
+    git(working_dir, &format!(
+        "worktree add --force --detach {} {}",
+        crate::sh_quote(&worktree_linux),
+        crate::sh_quote(commit)
+    ))?;
+
+    let started = Instant::now();
+    let result = crate::run_android_build(channel, hw, worktree_win.clone(), options, cancel).await;
+    let duration_secs = started.elapsed().as_secs_f64();
+
+    let logs = crate::logs::logs_dir(working_dir).join("compare");
+    if let Ok(entries) = std::fs::read_dir(crate::logs::logs_dir(&worktree_win)) {
+        let _ = std::fs::create_dir_all(&logs);
+        for entry in entries.filter_map(|e| e.ok()) {
+            let _ = std::fs::copy(entry.path(), logs.join(format!("{:.12}_{}", commit, entry.file_name().to_string_lossy())));
+        }
+    }
+    if let Err(e) = git(working_dir, &format!("worktree remove --force {}", crate::sh_quote(&worktree_linux))) {
+        channel.line(format!("⚠️ Worktree not removed: {}", e));
+    }
+
+    let build = match result {
+        Err(e @ HyperZenithError::BuildAborted { .. }) => return Err(e),
+        Ok(build) => CommitBuild {
+            commit: commit.to_string(),
+            subject: subject.to_string(),
+            success: true,
+            message: build.message,
+            duration_secs,
+            artifact_bytes: artifact_bytes(&build.archived),
+            artifacts: build.archived.iter().map(|p| p.display().to_string()).collect(),
+        },
+        Err(e) => {
+            channel.line(format!("❌ {:.12} failed: {}", commit, e));
+            CommitBuild {
+                commit: commit.to_string(),
+                subject: subject.to_string(),
+                success: false,
+                message: e.to_string(),
+                duration_secs,
+                artifact_bytes: 0,
+                artifacts: Vec::new(),
+            }
+        },
+    };
+    Ok(build)
+}
+
+/// Build `a`, then `b`, each from a clean worktree with its own `node_modules`, and report
+/// how build time and artifact size moved between them
+pub async fn run(
+    channel: &BuildChannel,
+    hw: crate::HardwareProfile,
+    working_dir: &str,
+    commits: [(String, String); 2],
+    options: crate::AndroidBuildOptions,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<CommitComparison, HyperZenithError> {
+    let mut builds = Vec::new();
+    for (i, (commit, subject)) in commits.into_iter().enumerate() {
+        channel.line(format!("🔀 [{}/2] Building {:.12} {}", i + 1, commit, subject));
+        builds.push(build_commit(channel, hw.clone(), working_dir, &commit, &subject, options.clone(), &mut cancel).await?);
+    }
+    let _ = git(working_dir, "worktree prune");
+
+    let b = builds.pop().ok_or("No build for the second commit")?;
+    let a = builds.pop().ok_or("No build for the first commit")?;
+    let mut comparison = CommitComparison::new(&channel.id, a, b);
+    let report = render_report(working_dir, &comparison);
+    let report_path = crate::write_build_log(working_dir, "compare", &report);
+    channel.line(format!("📊 Comparison saved to: {}", report_path.display()));
+    comparison.report_path = Some(report_path.display().to_string());
+
+    let _ = channel.app.emit(&format!("commit-comparison-{}", channel.id), &comparison);
+    let _ = channel.app.emit("commit-comparison", &comparison);
+    Ok(comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_report() {
+        let build = |commit: &str, duration_secs: f64, artifact_bytes: u64| CommitBuild {
+            commit: commit.to_string(),
+            subject: "Bump deps".to_string(),
+            success: true,
+            message: tr(Msg::BuildCompleted, &[]),
+            duration_secs,
+            artifact_bytes,
+            artifacts: Vec::new(),
+        };
+        let comparison = CommitComparison::new("id", build("1111111111111111", 120.0, 40 * 1_048_576), build("2222222222222222", 150.5, 38 * 1_048_576));
+        assert_eq!(comparison.size_delta_bytes, Some(-2 * 1_048_576));
+        let report = render_report("C:\\app", &comparison);
+        assert!(report.contains("✅ A 111111111111 Bump deps"));
+        assert!(report.ends_with("B vs A: +30.5s build time, -2.00 MB artifacts\n"));
+
+        let failed = CommitBuild { success: false, ..build("3333333333333333", 10.0, 0) };
+        assert_eq!(CommitComparison::new("id", build("1", 1.0, 1), failed).duration_delta_secs, None);
+
+        assert!(validate_rev("v1.4.0").is_ok());
+        assert!(validate_rev("--upload-pack=x").is_err());
+        assert!(validate_rev("a b").is_err());
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tauri::{Listener, Manager};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::Message;
+use crate::error::HyperZenithError;
+use crate::state::AppState;
+
+pub const DEFAULT_PORT: u16 = 47800;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i32 = -32700;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+#[derive(serde::Serialize, Clone)]
+pub struct ControlServerInfo {
+    pub port: u16,
+    pub token: String,
+    pub url: String,
+}
+
+/// A running control server. Dropping it stops the listener and closes every connection.
+pub struct ControlServer {
+    pub info: ControlServerInfo,
+    _shutdown: watch::Sender<()>,
+}
+
+#[derive(serde::Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(serde::Deserialize)]
+struct BuildParams {
+    working_dir: String,
+    #[serde(default = "default_build_type")]
+    build_type: String,
+    #[serde(default = "default_turbo")]
+    turbo_mode: bool,
+    #[serde(default)]
+    custom_path: Option<String>,
+    #[serde(default)]
+    verbosity: crate::events::Verbosity,
+    #[serde(default)]
+    log_level: crate::gradle::LogLevel,
+    #[serde(default)]
+    retry_with_diagnostics: bool,
+    #[serde(default)]
+    install_dependencies: bool,
+    #[serde(default)]
+    use_nvm: bool,
+    #[serde(default)]
+    gates: crate::gates::GateOptions,
+    #[serde(default)]
+    engine: crate::gradle::EngineOverrides,
+    #[serde(default)]
+    variant: Option<String>,
+    #[serde(default)]
+    release_notes: bool,
+}
+
+fn default_build_type() -> String {
+    "apk".to_string()
+}
+
+fn default_turbo() -> bool {
+    true
+}
+
+#[derive(serde::Deserialize, Default)]
+#[serde(default)]
+struct BuildIdParams {
+    build_id: Option<String>,
+}
+
+/// Bind `127.0.0.1:<port>` (0 picks a free port) and serve JSON-RPC over WebSocket.
+/// Clients authenticate with `?token=<token>` or an `Authorization: Bearer <token>` header.
+pub async fn start(app: tauri::AppHandle, port: u16) -> Result<ControlServer, HyperZenithError> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| HyperZenithError::Io(format!("Control server could not bind port {}: {}", port, e)))?;
+    let port = listener.local_addr()?.port();
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let (shutdown, stop) = watch::channel(());
+
+    let info = ControlServerInfo { port, token: token.clone(), url: format!("ws://127.0.0.1:{}/?token={}", port, token) };
+    println!("🛰️ [CONTROL] Listening on 127.0.0.1:{}", port);
+
+    let mut accept_stop = stop.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, addr)) => {
+                        println!("🛰️ [CONTROL] Connection from {}", addr);
+                        tauri::async_runtime::spawn(handle_connection(app.clone(), stream, token.clone(), stop.clone()));
+                    },
+                    Err(e) => println!("🛰️ [CONTROL] ❌ Accept failed: {}", e),
+                },
+                _ = accept_stop.changed() => break,
+            }
+        }
+        println!("🛰️ [CONTROL] Stopped");
+    });
+
+    Ok(ControlServer { info, _shutdown: shutdown })
+}
+
+/// Token from `?token=...` in the request query
+fn query_token(query: Option<&str>) -> Option<&str> {
+    query?.split('&').find_map(|pair| pair.strip_prefix("token="))
+}
+
+fn is_authorized(req: &Request, token: &str) -> bool {
+    let bearer = req.headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+    query_token(req.uri().query()).or(bearer) == Some(token)
+}
+
+// The handshake callback's error type is fixed by tungstenite
+#[allow(clippy::result_large_err)]
+async fn handle_connection(app: tauri::AppHandle, stream: TcpStream, token: String, mut stop: watch::Receiver<()>) {
+    let handshake = tokio_tungstenite::accept_hdr_async(stream, |req: &Request, resp: Response| {
+        if is_authorized(req, &token) {
+            Ok(resp)
+        } else {
+            let mut denied = ErrorResponse::new(Some("Invalid or missing token".to_string()));
+            *denied.status_mut() = StatusCode::UNAUTHORIZED;
+            Err(denied)
+        }
+    });
+    let ws = match handshake.await {
+        Ok(ws) => ws,
+        Err(e) => {
+            println!("🛰️ [CONTROL] Rejected connection: {}", e);
+            return;
+        }
+    };
+
+    let (mut sink, mut incoming) = ws.split();
+    let (notify_tx, mut notify_rx) = mpsc::unbounded_channel::<String>();
+    let mut listeners = HashMap::new();
+
+    loop {
+        tokio::select! {
+            msg = incoming.next() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = handle_request(&app, text.as_str(), &notify_tx, &mut listeners);
+                    if sink.send(Message::text(reply)).await.is_err() {
+                        break;
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {},
+            },
+            Some(notification) = notify_rx.recv() => {
+                if sink.send(Message::text(notification)).await.is_err() {
+                    break;
+                }
+            },
+            _ = stop.changed() => break,
+        }
+    }
+
+    for id in listeners.into_values() {
+        app.unlisten(id);
+    }
+    let _ = sink.close().await;
+}
+
+fn rpc_result(id: &Value, result: Value) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string()
+}
+
+fn rpc_error(id: &Value, code: i32, message: &str) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }).to_string()
+}
+
+fn parse_params<T: serde::de::DeserializeOwned + Default>(params: Value) -> Result<T, serde_json::Error> {
+    if params.is_null() {
+        Ok(T::default())
+    } else {
+        serde_json::from_value(params)
+    }
+}
+
+fn handle_request(
+    app: &tauri::AppHandle,
+    text: &str,
+    notify: &mpsc::UnboundedSender<String>,
+    listeners: &mut HashMap<String, tauri::EventId>,
+) -> String {
+    let req: RpcRequest = match serde_json::from_str(text) {
+        Ok(r) => r,
+        Err(e) => return rpc_error(&Value::Null, PARSE_ERROR, &e.to_string()),
+    };
+    let state = app.state::<AppState>();
+
+    match req.method.as_str() {
+        "build" => match serde_json::from_value::<BuildParams>(req.params) {
+            Ok(p) => {
+                let options = crate::AndroidBuildOptions {
+                    build_type: p.build_type,
+                    turbo_mode: p.turbo_mode,
+                    custom_path: p.custom_path,
+                    log_level: p.log_level,
+                    retry_with_diagnostics: p.retry_with_diagnostics,
+                    install_dependencies: p.install_dependencies,
+                    use_nvm: p.use_nvm,
+                    gates: p.gates,
+                    engine: p.engine,
+                    variant: p.variant,
+                    release_notes: p.release_notes,
+                    env_file: None,
+                    run_hooks: true,
+                };
+                let id = crate::start_android_build(app, &state, p.working_dir, options, p.verbosity);
+                rpc_result(&req.id, json!({ "build_id": id }))
+            },
+            Err(e) => rpc_error(&req.id, INVALID_PARAMS, &e.to_string()),
+        },
+        "status" => rpc_result(&req.id, json!({ "builds": state.builds.active() })),
+        "abort" => match parse_params::<BuildIdParams>(req.params) {
+            Ok(p) => {
+                let aborted = match p.build_id {
+                    Some(id) => state.builds.abort(&id),
+                    None => state.builds.abort_all() > 0,
+                };
+                rpc_result(&req.id, json!({ "aborted": aborted }))
+            },
+            Err(e) => rpc_error(&req.id, INVALID_PARAMS, &e.to_string()),
+        },
+        // Forward output and lifecycle events as notifications, for one build or all of them.
+        // Subscribing to the same events again keeps the listeners already there.
+        "subscribe" => match parse_params::<BuildIdParams>(req.params) {
+            Ok(p) => {
+                let suffix = p.build_id.map(|id| format!("-{}", id)).unwrap_or_default();
+                for event in ["build-output", "build-state"] {
+                    let name = format!("{}{}", event, suffix);
+                    if listeners.contains_key(&name) {
+                        continue;
+                    }
+                    let tx = notify.clone();
+                    let method = event.to_string();
+                    let id = app.listen_any(name.clone(), move |e| {
+                        let params: Value = serde_json::from_str(e.payload()).unwrap_or(Value::Null);
+                        let _ = tx.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string());
+                    });
+                    listeners.insert(name, id);
+                }
+                rpc_result(&req.id, json!({ "subscribed": true }))
+            },
+            Err(e) => rpc_error(&req.id, INVALID_PARAMS, &e.to_string()),
+        },
+        "unsubscribe" => {
+            for (_, id) in listeners.drain() {
+                app.unlisten(id);
+            }
+            rpc_result(&req.id, json!({ "subscribed": false }))
+        },
+        other => rpc_error(&req.id, METHOD_NOT_FOUND, &format!("Unknown method '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_token() {
+        assert_eq!(query_token(Some("token=abc123")), Some("abc123"));
+        assert_eq!(query_token(Some("client=vscode&token=abc123")), Some("abc123"));
+        assert_eq!(query_token(Some("client=vscode")), None);
+        assert_eq!(query_token(None), None);
+    }
+}
@@ -0,0 +1,59 @@
+use std::ptr;
+use crate::error::HyperZenithError;
+
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Secrets live in the Windows Credential Manager as generic credentials of this user,
+/// named `HyperZenith/<scope>/<name>`
+pub fn target(scope: &str, name: &str) -> String {
+    format!("HyperZenith/{}/{}", scope, name)
+}
+
+pub fn read(target: &str) -> Option<String> {
+    use winapi::um::wincred::{CredFree, CredReadW, CRED_TYPE_GENERIC, PCREDENTIALW};
+
+    let name = wide(target);
+    let mut credential: PCREDENTIALW = ptr::null_mut();
+    // SAFETY: `name` is NUL-terminated and outlives the call; on success Windows allocates
+    // the credential, which is only read before CredFree releases it
+    unsafe {
+        if CredReadW(name.as_ptr(), CRED_TYPE_GENERIC, 0, &mut credential) == 0 {
+            return None;
+        }
+        let blob = std::slice::from_raw_parts((*credential).CredentialBlob, (*credential).CredentialBlobSize as usize);
+        let secret = String::from_utf8(blob.to_vec()).ok();
+        CredFree(credential as *mut _);
+        secret
+    }
+}
+
+pub fn write(target: &str, secret: &str) -> Result<(), HyperZenithError> {
+    use winapi::um::wincred::{CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE, CRED_TYPE_GENERIC};
+
+    let mut name = wide(target);
+    let mut user = wide("HyperZenith");
+    let mut blob = secret.as_bytes().to_vec();
+    // SAFETY: CREDENTIALW is plain data; every pointer in it stays alive for the call
+    let mut credential: CREDENTIALW = unsafe { std::mem::zeroed() };
+    credential.Type = CRED_TYPE_GENERIC;
+    credential.TargetName = name.as_mut_ptr();
+    credential.UserName = user.as_mut_ptr();
+    credential.CredentialBlobSize = blob.len() as u32;
+    credential.CredentialBlob = blob.as_mut_ptr();
+    credential.Persist = CRED_PERSIST_LOCAL_MACHINE;
+    if unsafe { CredWriteW(&mut credential, 0) } == 0 {
+        return Err(HyperZenithError::Io(format!("Could not save '{}' in Credential Manager: {}", target, std::io::Error::last_os_error())));
+    }
+    Ok(())
+}
+
+/// Missing entries count as deleted
+pub fn delete(target: &str) {
+    use winapi::um::wincred::{CredDeleteW, CRED_TYPE_GENERIC};
+
+    let name = wide(target);
+    // SAFETY: `name` is NUL-terminated and outlives the call
+    unsafe { CredDeleteW(name.as_ptr(), CRED_TYPE_GENERIC, 0) };
+}
@@ -0,0 +1,116 @@
+use tauri::{Emitter, Manager};
+use crate::error::HyperZenithError;
+
+/// What a `hyperzenith://` link asks for
+#[derive(Debug, PartialEq)]
+pub enum DeepLinkAction {
+    /// `hyperzenith://build?dir=<project>&type=apk|aab`
+    Build { working_dir: String, build_type: String },
+    /// `hyperzenith://open?dir=<project>`
+    Open { working_dir: String },
+}
+
+/// Sent to the frontend on `deep-link` after a link was checked (or rejected). Nothing runs from
+/// the link itself: a build starts once the user confirms it in the UI.
+#[derive(serde::Serialize, Clone)]
+pub struct DeepLinkEvent {
+    pub url: String,
+    pub action: Option<String>,
+    pub working_dir: Option<String>,
+    /// `apk` or `aab`, for build links
+    pub build_type: Option<String>,
+    pub error: Option<HyperZenithError>,
+}
+
+pub fn parse(url: &tauri::Url) -> Result<DeepLinkAction, HyperZenithError> {
+    if url.scheme() != "hyperzenith" {
+        return Err(HyperZenithError::InvalidInput(format!("Unsupported link scheme '{}'", url.scheme())));
+    }
+    let param = |key: &str| url.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.trim().to_string());
+    let working_dir = param("dir")
+        .filter(|d| !d.is_empty())
+        .ok_or_else(|| HyperZenithError::InvalidInput("Link is missing the 'dir' parameter".to_string()))?;
+
+    match url.host_str() {
+        Some("build") => {
+            let build_type = param("type").unwrap_or_else(|| "apk".to_string()).to_lowercase();
+            if build_type != "apk" && build_type != "aab" {
+                return Err(HyperZenithError::InvalidInput(format!("Unsupported build type '{}'", build_type)));
+            }
+            Ok(DeepLinkAction::Build { working_dir, build_type })
+        },
+        Some("open") => Ok(DeepLinkAction::Open { working_dir }),
+        other => Err(HyperZenithError::InvalidInput(format!("Unknown link action '{}'", other.unwrap_or("")))),
+    }
+}
+
+/// Check a link's action. A build runs the project's Gradle scripts and hooks, so links may only
+/// name an Android (or Expo) project the user has already built or pinned, and get back the path
+/// as HyperZenith knows it for the confirmation.
+fn resolve(app: &tauri::AppHandle, action: DeepLinkAction) -> Result<(String, String, Option<String>), HyperZenithError> {
+    match action {
+        DeepLinkAction::Open { working_dir } => Ok(("open".to_string(), working_dir, None)),
+        DeepLinkAction::Build { working_dir, build_type } => {
+            let project = crate::recents::find(app, &working_dir).ok_or_else(|| HyperZenithError::InvalidInput(format!(
+                "Links can only build projects already built or pinned in HyperZenith, not {}", working_dir
+            )))?;
+            if !crate::expo::is_android_buildable(std::path::Path::new(&project.path)) {
+                return Err(HyperZenithError::NotFound { path: std::path::Path::new(&project.path).join("android").display().to_string() });
+            }
+            Ok(("build".to_string(), project.path, Some(build_type)))
+        },
+    }
+}
+
+pub fn handle_urls(app: &tauri::AppHandle, urls: Vec<tauri::Url>) {
+    for url in urls {
+        println!("🔗 [DEEPLINK] {}", url);
+        let event = match parse(&url).and_then(|action| resolve(app, action)) {
+            Ok((action, working_dir, build_type)) => DeepLinkEvent {
+                url: url.to_string(),
+                action: Some(action),
+                working_dir: Some(working_dir),
+                build_type,
+                error: None,
+            },
+            Err(e) => {
+                println!("🔗 [DEEPLINK] ❌ {}", e);
+                DeepLinkEvent { url: url.to_string(), action: None, working_dir: None, build_type: None, error: Some(e) }
+            },
+        };
+        let _ = app.emit("deep-link", event);
+    }
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(s: &str) -> Result<DeepLinkAction, HyperZenithError> {
+        parse(&tauri::Url::parse(s).unwrap())
+    }
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            parse_str("hyperzenith://build?dir=C:%5CDev%5CMy%20App&type=aab").unwrap(),
+            DeepLinkAction::Build { working_dir: "C:\\Dev\\My App".to_string(), build_type: "aab".to_string() }
+        );
+        assert_eq!(
+            parse_str("hyperzenith://build?dir=D:/Work/app").unwrap(),
+            DeepLinkAction::Build { working_dir: "D:/Work/app".to_string(), build_type: "apk".to_string() }
+        );
+        assert_eq!(
+            parse_str("hyperzenith://open?dir=C:/Dev/app").unwrap(),
+            DeepLinkAction::Open { working_dir: "C:/Dev/app".to_string() }
+        );
+        assert!(parse_str("hyperzenith://build").is_err());
+        assert!(parse_str("hyperzenith://build?dir=C:/a&type=ipa").is_err());
+        assert!(parse_str("hyperzenith://delete?dir=C:/a").is_err());
+    }
+}
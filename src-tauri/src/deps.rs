@@ -0,0 +1,214 @@
+use std::path::Path;
+use crate::error::HyperZenithError;
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, PartialOrd, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum VulnSeverity {
+    Info,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl VulnSeverity {
+    fn parse(value: &str) -> Self {
+        match value {
+            "low" => VulnSeverity::Low,
+            "moderate" => VulnSeverity::Moderate,
+            "high" => VulnSeverity::High,
+            "critical" => VulnSeverity::Critical,
+            _ => VulnSeverity::Info,
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct Vulnerability {
+    pub name: String,
+    pub severity: VulnSeverity,
+    /// Listed in package.json rather than pulled in by something else
+    pub is_direct: bool,
+    /// Vulnerable version range
+    pub range: String,
+    /// Advisory titles, or the packages this one is vulnerable through
+    pub via: Vec<String>,
+    pub fix_available: bool,
+    /// The only fix is a semver-major upgrade
+    pub fix_is_breaking: bool,
+}
+
+#[derive(serde::Serialize, Clone, Default, Debug)]
+pub struct AuditReport {
+    /// Most severe first
+    pub vulnerabilities: Vec<Vulnerability>,
+    pub critical: u32,
+    pub high: u32,
+    pub moderate: u32,
+    pub low: u32,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OutdatedPackage {
+    pub name: String,
+    /// `None` when the package isn't installed
+    pub current: Option<String>,
+    /// Newest version package.json's range allows
+    pub wanted: String,
+    pub latest: String,
+    /// `dependencies` or `devDependencies`
+    pub kind: String,
+    /// Latest is a new major over what's installed
+    pub major_behind: bool,
+}
+
+/// npm prints `{"error": {"summary": ...}}` on stdout when it can't produce a report
+fn npm_error(json: &serde_json::Value) -> Option<String> {
+    let error = json.get("error")?;
+    Some(error["summary"].as_str().or(error["code"].as_str()).unwrap_or("npm failed").to_string())
+}
+
+fn parse_json(output: &str, command: &str) -> Result<serde_json::Value, HyperZenithError> {
+    let json: serde_json::Value = serde_json::from_str(output.trim()).map_err(|_| HyperZenithError::CommandFailed {
+        command: command.to_string(),
+        output: output.lines().last().unwrap_or("no output").to_string(),
+    })?;
+    match npm_error(&json) {
+        Some(summary) => Err(HyperZenithError::CommandFailed { command: command.to_string(), output: summary }),
+        None => Ok(json),
+    }
+}
+
+/// `npm audit --json` (report version 2, npm 7+)
+pub fn parse_audit(json: &serde_json::Value) -> AuditReport {
+    let mut vulnerabilities: Vec<Vulnerability> = json["vulnerabilities"]
+        .as_object()
+        .map(|all| all.iter().map(|(name, v)| {
+            let fix = &v["fixAvailable"];
+            Vulnerability {
+                name: name.clone(),
+                severity: VulnSeverity::parse(v["severity"].as_str().unwrap_or("")),
+                is_direct: v["isDirect"].as_bool().unwrap_or(false),
+                range: v["range"].as_str().unwrap_or("").to_string(),
+                via: v["via"].as_array().map(|via| via.iter().filter_map(|entry| {
+                    entry.as_str().or(entry["title"].as_str()).map(str::to_string)
+                }).collect()).unwrap_or_default(),
+                fix_available: fix.as_bool().unwrap_or(fix.is_object()),
+                fix_is_breaking: fix["isSemVerMajor"].as_bool().unwrap_or(false),
+            }
+        }).collect())
+        .unwrap_or_default();
+    vulnerabilities.sort_by(|a, b| b.severity.partial_cmp(&a.severity).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.name.cmp(&b.name)));
+
+    let counts = &json["metadata"]["vulnerabilities"];
+    let count = |key: &str| counts[key].as_u64().unwrap_or(0) as u32;
+    AuditReport { vulnerabilities, critical: count("critical"), high: count("high"), moderate: count("moderate"), low: count("low") }
+}
+
+fn major(version: &str) -> Option<u64> {
+    version.trim_start_matches(['^', '~', 'v']).split('.').next()?.parse().ok()
+}
+
+/// `npm outdated --json --long`. Workspaces list a package once per location, as an array.
+pub fn parse_outdated(json: &serde_json::Value) -> Vec<OutdatedPackage> {
+    let mut packages: Vec<OutdatedPackage> = json
+        .as_object()
+        .map(|all| all.iter().filter_map(|(name, entry)| {
+            let v = entry.as_array().and_then(|a| a.first()).unwrap_or(entry);
+            let current = v["current"].as_str().map(str::to_string);
+            let latest = v["latest"].as_str()?.to_string();
+            Some(OutdatedPackage {
+                name: name.clone(),
+                major_behind: match (current.as_deref().and_then(major), major(&latest)) {
+                    (Some(c), Some(l)) => l > c,
+                    _ => false,
+                },
+                current,
+                wanted: v["wanted"].as_str().unwrap_or("").to_string(),
+                latest,
+                kind: v["type"].as_str().unwrap_or("dependencies").to_string(),
+            })
+        }).collect())
+        .unwrap_or_default();
+    packages.sort_by(|a, b| b.major_behind.cmp(&a.major_behind).then_with(|| a.name.cmp(&b.name)));
+    packages
+}
+
+/// npm exits non-zero whenever it finds something, so only the JSON on stdout counts
+fn run_npm(working_dir: &Path, args: &str, toolchain: &crate::node::Toolchain) -> Result<String, HyperZenithError> {
+    let dir = working_dir.display().to_string();
+    let script = format!(
+        "{}cd {} && npm {} 2>/dev/null; true",
+        toolchain.env_script(),
+        crate::sh_quote(&crate::windows_to_wsl_path(&dir)),
+        args
+    );
+    crate::wsl::run_bash_in(crate::wsl::project_distro(&dir).as_deref(), &script)
+}
+
+/// Known vulnerabilities in the installed tree. npm audits the lockfile, so this runs at the install root.
+pub fn audit(project_dir: &Path, toolchain: &crate::node::Toolchain) -> Result<AuditReport, HyperZenithError> {
+    let install_root = crate::node::install_root(project_dir);
+    if !install_root.join("package-lock.json").exists() {
+        return Err(HyperZenithError::InvalidInput(format!(
+            "npm audit needs a package-lock.json in {} (this project uses {:?})",
+            install_root.display(),
+            toolchain.package_manager
+        )));
+    }
+    let output = run_npm(&install_root, "audit --json", toolchain)?;
+    Ok(parse_audit(&parse_json(&output, "npm audit")?))
+}
+
+/// Dependencies with newer versions published, majors first
+pub fn outdated(project_dir: &Path, toolchain: &crate::node::Toolchain) -> Result<Vec<OutdatedPackage>, HyperZenithError> {
+    let output = run_npm(project_dir, "outdated --json --long", toolchain)?;
+    // Nothing outdated prints an empty object, or nothing at all on older npm
+    if output.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_outdated(&parse_json(&output, "npm outdated")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_audit() {
+        let json = serde_json::json!({
+            "auditReportVersion": 2,
+            "vulnerabilities": {
+                "semver": { "name": "semver", "severity": "moderate", "isDirect": false, "range": "<7.5.2",
+                            "via": [{ "title": "semver vulnerable to Regular Expression Denial of Service", "severity": "moderate" }],
+                            "fixAvailable": true },
+                "@expo/cli": { "name": "@expo/cli", "severity": "high", "isDirect": false, "range": "*",
+                               "via": ["semver", "send"], "fixAvailable": { "name": "expo", "version": "52.0.0", "isSemVerMajor": true } },
+            },
+            "metadata": { "vulnerabilities": { "info": 0, "low": 0, "moderate": 1, "high": 1, "critical": 0, "total": 2 } },
+        });
+        let report = parse_audit(&json);
+        assert_eq!((report.high, report.moderate, report.critical), (1, 1, 0));
+        assert_eq!(report.vulnerabilities[0].name, "@expo/cli");
+        assert_eq!(report.vulnerabilities[0].via, vec!["semver".to_string(), "send".to_string()]);
+        assert!(report.vulnerabilities[0].fix_available && report.vulnerabilities[0].fix_is_breaking);
+        assert_eq!(report.vulnerabilities[1].via.len(), 1);
+
+        let enolock = serde_json::json!({ "error": { "code": "ENOLOCK", "summary": "This command requires an existing lockfile." } });
+        assert_eq!(npm_error(&enolock).as_deref(), Some("This command requires an existing lockfile."));
+    }
+
+    #[test]
+    fn test_parse_outdated() {
+        let json = serde_json::json!({
+            "axios": { "current": "1.6.0", "wanted": "1.7.2", "latest": "1.7.2", "type": "dependencies" },
+            "typescript": { "current": "4.9.5", "wanted": "4.9.5", "latest": "5.4.5", "type": "devDependencies" },
+            "lodash": [{ "current": "4.17.20", "wanted": "4.17.21", "latest": "4.17.21", "location": "node_modules/lodash" }],
+        });
+        let packages = parse_outdated(&json);
+        assert_eq!(packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["typescript", "axios", "lodash"]);
+        assert!(packages[0].major_behind);
+        assert_eq!(packages[0].kind, "devDependencies");
+        assert_eq!(packages[2].current.as_deref(), Some("4.17.20"));
+    }
+}
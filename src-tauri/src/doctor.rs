@@ -0,0 +1,348 @@
+use std::path::{Path, PathBuf};
+use crate::error::HyperZenithError;
+
+const FILESYSTEM_KEY: &str = "HKLM:\\SYSTEM\\CurrentControlSet\\Control\\FileSystem";
+/// Windows' MAX_PATH, which tools without long-path support still stop at
+const MAX_PATH: usize = 260;
+/// Paths this close to the limit are a warning: builds nest their own folders under node_modules
+const LONG_PATH_MARGIN: usize = 20;
+/// Bounds on the search for the deepest path, so a huge or looping tree can't stall a build
+const WALK_MAX_DEPTH: usize = 32;
+const WALK_MAX_ENTRIES: usize = 200_000;
+
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Unknown,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct DoctorCheck {
+    pub id: String,
+    pub title: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub fix_command: Option<String>, // Name of the command that resolves this check
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct DefenderStatus {
+    pub realtime_enabled: bool,
+    pub exclusions_readable: bool,
+    pub excluded: Vec<String>,
+    pub missing: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct MpSnapshot {
+    #[serde(default)]
+    realtime: Option<bool>,
+    #[serde(default)]
+    exclusions: Option<Vec<String>>,
+}
+
+/// Directories that real-time scanning slows down the most during builds
+fn exclusion_targets(working_dir: &str) -> Vec<String> {
+    let mut targets = vec![working_dir.to_string()];
+
+    if let Some(home) = dirs::home_dir() {
+        targets.push(home.join(".gradle").to_string_lossy().to_string());
+    }
+    for vhdx in crate::wsl::vhdx_paths() {
+        targets.push(vhdx.to_string_lossy().to_string());
+    }
+    targets
+}
+
+/// Normalize a Windows path for case-insensitive prefix comparison
+fn normalize(path: &str) -> String {
+    path.replace('/', "\\").trim_end_matches('\\').to_lowercase()
+}
+
+/// A path is covered if it matches an exclusion or lives underneath one
+fn is_path_excluded(path: &str, exclusions: &[String]) -> bool {
+    let target = normalize(path);
+    exclusions.iter().any(|ex| {
+        let ex = normalize(ex);
+        !ex.is_empty() && (target == ex || target.starts_with(&format!("{}\\", ex)))
+    })
+}
+
+pub fn check_defender(working_dir: &str) -> Result<DefenderStatus, HyperZenithError> {
+    let script = "$s = Get-MpComputerStatus -ErrorAction SilentlyContinue; \
+        $p = Get-MpPreference -ErrorAction SilentlyContinue; \
+        @{ realtime = $s.RealTimeProtectionEnabled; exclusions = @($p.ExclusionPath) } | ConvertTo-Json -Compress";
+
+    let raw = crate::run_powershell(script)?;
+    let snapshot: MpSnapshot = serde_json::from_str(raw.trim()).unwrap_or_default();
+
+    let exclusions: Vec<String> = snapshot.exclusions.unwrap_or_default()
+        .into_iter()
+        .filter(|e| !e.is_empty())
+        .collect();
+
+    // Non-admin sessions on recent Windows builds get a placeholder instead of the real list
+    let exclusions_readable = !exclusions.iter().any(|e| e.starts_with("N/A"));
+
+    let (excluded, missing): (Vec<String>, Vec<String>) = exclusion_targets(working_dir)
+        .into_iter()
+        .partition(|t| exclusions_readable && is_path_excluded(t, &exclusions));
+
+    Ok(DefenderStatus {
+        realtime_enabled: snapshot.realtime.unwrap_or(false),
+        exclusions_readable,
+        excluded,
+        missing,
+    })
+}
+
+/// Adds Defender exclusions through an elevated PowerShell (triggers a UAC prompt)
+pub fn apply_defender_exclusions(paths: &[String]) -> Result<(), HyperZenithError> {
+    if paths.is_empty() {
+        return Err(HyperZenithError::InvalidInput("No paths selected for exclusion".to_string()));
+    }
+
+    let command = format!(
+        "Add-MpPreference -ExclusionPath {}",
+        paths.iter().map(|p| crate::ps_quote(p)).collect::<Vec<_>>().join(",")
+    );
+
+    println!("🛡️ [DOCTOR] Requesting elevation to add {} exclusion(s)", paths.len());
+    crate::run_powershell_elevated(&command)
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct LongPathStatus {
+    /// `LongPathsEnabled` in the registry, `None` if it couldn't be read
+    pub enabled: Option<bool>,
+    pub deepest_path: Option<String>,
+    /// In UTF-16 units, the way Windows counts
+    pub deepest_len: usize,
+    pub limit: usize,
+    pub warning: Option<String>,
+    /// The walk hit `WALK_MAX_ENTRIES` before finishing, so deeper paths may exist
+    pub truncated: bool,
+}
+
+pub fn long_paths_enabled() -> Result<bool, HyperZenithError> {
+    let script = format!("(Get-ItemProperty -Path '{}' -Name LongPathsEnabled -ErrorAction SilentlyContinue).LongPathsEnabled", FILESYSTEM_KEY);
+    Ok(crate::run_powershell(&script)?.trim() == "1")
+}
+
+fn path_len(path: &Path) -> usize {
+    path.to_string_lossy().encode_utf16().count()
+}
+
+/// Longest path under `dir`, node_modules included, within the walk's depth bound and the first `max_entries` entries.
+/// The flag is set when entries were left unvisited.
+fn deepest_path(dir: &Path, max_entries: usize) -> (Option<(PathBuf, usize)>, bool) {
+    let mut walk = walkdir::WalkDir::new(dir).max_depth(WALK_MAX_DEPTH).into_iter();
+    let deepest = walk
+        .by_ref()
+        .take(max_entries)
+        .filter_map(Result::ok)
+        .map(|entry| {
+            let len = path_len(entry.path());
+            (entry.into_path(), len)
+        })
+        .max_by_key(|(_, len)| *len);
+    (deepest, walk.next().is_some())
+}
+
+/// What to tell the user about the deepest path, if it's a problem with long paths off
+fn long_path_warning(enabled: Option<bool>, deepest: &Path, len: usize) -> Option<String> {
+    if enabled == Some(true) || len + LONG_PATH_MARGIN < MAX_PATH {
+        return None;
+    }
+    Some(format!(
+        "{} is {} characters and Windows long paths are {}; tools on Windows may fail to read it (limit {})",
+        deepest.display(),
+        len,
+        if enabled.is_some() { "off" } else { "possibly off" },
+        MAX_PATH
+    ))
+}
+
+/// The project is only walked when long paths are off (or unreadable) and there is one
+pub fn long_path_status(working_dir: Option<&str>) -> LongPathStatus {
+    let enabled = long_paths_enabled().ok();
+    let (deepest, truncated) = working_dir
+        .filter(|_| enabled != Some(true))
+        .map(|dir| deepest_path(Path::new(dir), WALK_MAX_ENTRIES))
+        .unwrap_or((None, false));
+    let deepest_len = deepest.as_ref().map(|(_, len)| *len).unwrap_or(0);
+    LongPathStatus {
+        enabled,
+        warning: deepest.as_ref().and_then(|(path, len)| long_path_warning(enabled, path, *len)),
+        deepest_path: deepest.map(|(path, _)| path.display().to_string()),
+        deepest_len,
+        limit: MAX_PATH,
+        truncated,
+    }
+}
+
+/// The warning for a build about to start, without walking the project when long paths are on
+pub fn long_path_preflight(working_dir: &str) -> Option<LongPathStatus> {
+    if long_paths_enabled().unwrap_or(false) {
+        return None;
+    }
+    Some(long_path_status(Some(working_dir))).filter(|status| status.warning.is_some())
+}
+
+/// Turns on `LongPathsEnabled` and Git's `core.longpaths` through an elevated PowerShell (triggers a UAC prompt).
+/// Programs already running keep the old limit until restarted.
+pub fn enable_long_paths() -> Result<(), HyperZenithError> {
+    let command = format!(
+        "Set-ItemProperty -Path '{}' -Name LongPathsEnabled -Value 1 -Type DWord; \
+        if (Get-Command git -ErrorAction SilentlyContinue) {{ git config --system core.longpaths true }}",
+        FILESYSTEM_KEY
+    );
+    println!("📏 [DOCTOR] Requesting elevation to enable long paths");
+    crate::run_powershell_elevated(&command)
+}
+
+fn long_path_check(working_dir: Option<&str>) -> DoctorCheck {
+    let mut check = DoctorCheck {
+        id: "long_paths".to_string(),
+        title: "Windows long paths".to_string(),
+        status: CheckStatus::Ok,
+        detail: String::new(),
+        fix_command: None,
+    };
+
+    let status = long_path_status(working_dir);
+    match (status.enabled, status.warning) {
+        (_, Some(warning)) => {
+            check.status = CheckStatus::Warn;
+            check.detail = warning;
+            check.fix_command = Some("enable_long_paths".to_string());
+        },
+        (Some(true), None) => {
+            check.detail = "Long paths are enabled".to_string();
+        },
+        (Some(false), None) if working_dir.is_none() => {
+            check.detail = "Long paths are off; open a project to check how deep its paths go".to_string();
+        },
+        (Some(false), None) => {
+            check.detail = format!("Long paths are off, the deepest project path is {} of {} characters", status.deepest_len, MAX_PATH);
+            if status.truncated {
+                check.detail.push_str(&format!(" (only the first {} entries were checked, deeper paths may exist)", WALK_MAX_ENTRIES));
+            }
+        },
+        (None, None) => {
+            check.status = CheckStatus::Unknown;
+            check.detail = "Could not read LongPathsEnabled from the registry".to_string();
+        },
+    }
+    check
+}
+
+fn defender_check(working_dir: &str) -> DoctorCheck {
+    let mut check = DoctorCheck {
+        id: "defender_exclusions".to_string(),
+        title: "Windows Defender exclusions".to_string(),
+        status: CheckStatus::Ok,
+        detail: String::new(),
+        fix_command: None,
+    };
+
+    match check_defender(working_dir) {
+        Ok(status) if !status.realtime_enabled => {
+            check.detail = "Real-time protection is off (or another antivirus is active)".to_string();
+        },
+        Ok(status) if !status.exclusions_readable => {
+            check.status = CheckStatus::Unknown;
+            check.detail = "Exclusion list requires administrator rights to read".to_string();
+            check.fix_command = Some("apply_defender_exclusions".to_string());
+        },
+        Ok(status) if status.missing.is_empty() => {
+            check.detail = format!("{} build path(s) excluded from scanning", status.excluded.len());
+        },
+        Ok(status) => {
+            check.status = CheckStatus::Warn;
+            check.detail = format!("Scanned during builds: {}", status.missing.join(", "));
+            check.fix_command = Some("apply_defender_exclusions".to_string());
+        },
+        Err(e) => {
+            check.status = CheckStatus::Unknown;
+            check.detail = format!("Could not query Defender: {}", e);
+        }
+    }
+    check
+}
+
+fn emulator_check() -> DoctorCheck {
+    let mut check = DoctorCheck {
+        id: "emulator_acceleration".to_string(),
+        title: "Emulator acceleration".to_string(),
+        status: CheckStatus::Ok,
+        detail: String::new(),
+        fix_command: None,
+    };
+
+    match crate::gpu::detect() {
+        Ok(support) if support.warnings.is_empty() => {
+            let accelerator = support.accelerator.map(|a| a.label()).unwrap_or_default();
+            let gpus: Vec<&str> = support.gpus.iter().map(|g| g.name.as_str()).collect();
+            check.detail = format!("{} acceleration, rendering on {}", accelerator, gpus.join(", "));
+        },
+        Ok(support) => {
+            check.status = CheckStatus::Warn;
+            check.detail = support.warnings.join("; ");
+        },
+        Err(e) => {
+            check.status = CheckStatus::Unknown;
+            check.detail = format!("Could not query GPU and hypervisor: {}", e);
+        }
+    }
+    check
+}
+
+/// Run every environment check against the given project. Without one (first-run setup)
+/// Defender is checked for the home folder and the long-path check doesn't walk anything.
+pub fn run_checks(working_dir: Option<&str>) -> Vec<DoctorCheck> {
+    let home = dirs::home_dir().map(|h| h.display().to_string()).unwrap_or_default();
+    vec![defender_check(working_dir.unwrap_or(&home)), emulator_check(), long_path_check(working_dir)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclusion_matching() {
+        let exclusions = vec!["C:\\Users\\Dev\\.gradle".to_string(), "d:/projects/".to_string()];
+
+        assert!(is_path_excluded("C:\\Users\\Dev\\.gradle", &exclusions));
+        assert!(is_path_excluded("c:\\users\\dev\\.gradle\\caches", &exclusions));
+        assert!(is_path_excluded("D:\\Projects\\App", &exclusions));
+        assert!(!is_path_excluded("C:\\Users\\Dev\\.gradle-old", &exclusions));
+        assert!(!is_path_excluded("E:\\Projects\\App", &exclusions));
+    }
+
+    #[test]
+    fn test_long_path_warning() {
+        let deep = Path::new("C:/Users/Dev/app/node_modules/x");
+        assert!(long_path_warning(Some(false), deep, 250).is_some_and(|w| w.contains("250 characters") && w.contains("are off")));
+        assert!(long_path_warning(None, deep, 300).is_some_and(|w| w.contains("possibly off")));
+        assert_eq!(long_path_warning(Some(true), deep, 400), None);
+        assert_eq!(long_path_warning(Some(false), deep, 200), None);
+
+        let dir = std::env::temp_dir().join(format!("hz-long-paths-{}", std::process::id()));
+        let nested = dir.join("node_modules").join("@scope").join("package");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("index.js"), "").unwrap();
+        let (deepest, truncated) = deepest_path(&dir, WALK_MAX_ENTRIES);
+        let (path, len) = deepest.unwrap();
+        assert_eq!(path, nested.join("index.js"));
+        assert_eq!(len, path_len(&path));
+        assert!(!truncated);
+        // dir, node_modules, @scope: the walk stops before package/index.js
+        let (deepest, truncated) = deepest_path(&dir, 3);
+        assert_eq!(deepest.map(|(path, _)| path), Some(dir.join("node_modules").join("@scope")));
+        assert!(truncated);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use tokio::process::Command;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum E2eRunner {
+    Maestro,
+    Detox,
+}
+
+const DETOX_CONFIGS: [&str; 4] = [".detoxrc.js", ".detoxrc.json", ".detoxrc", "detox.config.js"];
+
+/// Maestro if the project has a `.maestro/` folder, Detox if it has a Detox config
+pub fn detect_runner(project_dir: &Path) -> Option<E2eRunner> {
+    if project_dir.join(".maestro").is_dir() {
+        Some(E2eRunner::Maestro)
+    } else if DETOX_CONFIGS.iter().any(|f| project_dir.join(f).exists()) {
+        Some(E2eRunner::Detox)
+    } else {
+        None
+    }
+}
+
+/// Sent on `e2e-summary` (and `e2e-summary-{id}`) when a run ends
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct E2eSummary {
+    pub id: String,
+    pub runner: E2eRunner,
+    pub success: bool,
+    pub passed: u32,
+    pub failed: u32,
+    pub failed_flows: Vec<String>,
+    /// Screenshots/videos of failures, `None` when everything passed
+    pub artifacts_dir: Option<String>,
+}
+
+pub struct E2eRequest {
+    pub working_dir: String,
+    pub apk_path: String,
+    /// Maestro runs on it as is. Detox gets it as `--device-name`: the AVD name for emulators,
+    /// which `android.emulator` configurations expect, the serial for `android.attached` ones.
+    pub serial: String,
+    pub runner: E2eRunner,
+    /// Maestro flow file/folder or Detox configuration name
+    pub target: Option<String>,
+}
+
+/// `[Passed] login (3s)` / `[Failed] checkout (5s) (Element not found)` lines from `maestro test`
+fn parse_maestro(log: &str) -> (u32, Vec<String>) {
+    let mut passed = 0;
+    let mut failed = Vec::new();
+    for line in log.lines().map(str::trim) {
+        if line.starts_with("[Passed]") {
+            passed += 1;
+        } else if let Some(rest) = line.strip_prefix("[Failed]") {
+            let flow = rest.split(" (").next().unwrap_or(rest).trim();
+            failed.push(flow.to_string());
+        }
+    }
+    (passed, failed)
+}
+
+/// First emulator that is online, otherwise any online device
+pub fn pick_device(devices: &[crate::adb::AdbDevice]) -> Option<String> {
+    let online = || devices.iter().filter(|d| d.state == "device");
+    online().find(|d| d.serial.starts_with("emulator-")).or_else(|| online().next()).map(|d| d.serial.clone())
+}
+
+/// The newest APK in the project's archive
+pub fn latest_archived_apk(working_dir: &str, custom_path: Option<String>) -> Option<PathBuf> {
+    crate::archive::list_entries(&crate::archive::archive_dir(working_dir, custom_path))
+        .ok()?
+        .into_iter()
+        .find(|e| e.kind == "apk")
+        .map(|e| PathBuf::from(e.path))
+}
+
+/// Both runners talk to the Windows adb/emulator, so they run on the host, not in WSL.
+/// The `.bat`/`.cmd` shims maestro and npx install as are spawned directly, never through
+/// `cmd /C`, so std escapes the arguments and a path with `&` or `^` stays one argument.
+fn host_command(working_dir: &str, program: &str, args: &[&str]) -> Command {
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .current_dir(working_dir)
+        .creation_flags(crate::CREATE_NO_WINDOW);
+    cmd
+}
+
+/// `detox test` on the chosen device, reusing the APK installed on it rather than installing
+/// the configuration's own binary
+fn detox_args(configuration: &str, device_name: &str, artifacts: &str) -> Vec<String> {
+    [
+        "detox", "test", "--configuration", configuration, "--device-name", device_name, "--reuse",
+        "--take-screenshots", "failing", "--record-videos", "failing", "--artifacts-location", artifacts,
+    ]
+    .iter()
+    .map(|a| a.to_string())
+    .collect()
+}
+
+pub async fn run(channel: &BuildChannel, request: E2eRequest, mut cancel: oneshot::Receiver<()>) -> Result<E2eSummary, HyperZenithError> {
+    let E2eRequest { working_dir, apk_path, serial, runner, target } = request;
+
+    channel.line(format!("📲 Installing {} on {}...", apk_path, serial));
+    let install_path = apk_path.clone();
+    let install_serial = serial.clone();
+    tauri::async_runtime::spawn_blocking(move || crate::adb::install_apk(&install_path, Some(&install_serial)))
+        .await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))??;
+
+    let artifacts_dir = crate::logs::logs_dir(&working_dir)
+        .join(format!("e2e_{}", chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")));
+    std::fs::create_dir_all(&artifacts_dir).map_err(|e| HyperZenithError::io_at(&artifacts_dir, e))?;
+    let artifacts = artifacts_dir.to_string_lossy().to_string();
+
+    let cmd = match runner {
+        E2eRunner::Maestro => {
+            let flows = target.unwrap_or_else(|| ".maestro".to_string());
+            host_command(&working_dir, "maestro.bat", &["--device", &serial, "test", &flows, "--debug-output", &artifacts])
+        },
+        E2eRunner::Detox => {
+            let configuration = target.unwrap_or_else(|| "android.emu.debug".to_string());
+            let lookup = serial.clone();
+            let device_name = tauri::async_runtime::spawn_blocking(move || crate::adb::avd_name(&lookup))
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_else(|| serial.clone());
+            let args = detox_args(&configuration, &device_name, &artifacts);
+            host_command(&working_dir, "npx.cmd", &args.iter().map(String::as_str).collect::<Vec<_>>())
+        },
+    };
+    channel.line(format!("🎬 Running {:?} tests...", runner));
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    let _ = std::fs::write(artifacts_dir.join("e2e.log"), &outcome.log);
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(artifacts) });
+    }
+
+    let (passed, failed_flows) = match runner {
+        E2eRunner::Maestro => parse_maestro(&outcome.log),
+        E2eRunner::Detox => {
+            let summary = crate::jest::parse_summary(&outcome.log);
+            (summary.tests_passed, summary.failed_files)
+        },
+    };
+    // Only failures are worth keeping; Maestro writes debug output for every flow
+    let keep_artifacts = !outcome.success;
+    if !keep_artifacts {
+        let _ = std::fs::remove_dir_all(&artifacts_dir);
+    } else {
+        channel.line(format!("🖼️ Failure artifacts saved to: {}", artifacts));
+    }
+
+    let summary = E2eSummary {
+        id: channel.id.clone(),
+        runner,
+        success: outcome.success,
+        passed,
+        failed: failed_flows.len() as u32,
+        failed_flows,
+        artifacts_dir: keep_artifacts.then_some(artifacts),
+    };
+    let _ = channel.app.emit(&format!("e2e-summary-{}", channel.id), &summary);
+    let _ = channel.app.emit("e2e-summary", &summary);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_maestro() {
+        let log = "Running on emulator-5554\n\
+                   [Passed] login (3s)\n\
+                   [Failed] checkout (5s) (Element not found: Text matching regex: Pay)\n\
+                   [Passed] profile (2s)\n";
+        assert_eq!(parse_maestro(log), (2, vec!["checkout".to_string()]));
+    }
+
+    #[test]
+    fn test_detox_args() {
+        let args = detox_args("android.emu.release", "Pixel_7_API_34", "C:\\Dev\\R&D App\\hyperzenith_logs\\e2e");
+        let pairs: Vec<&[String]> = args.windows(2).collect();
+        assert!(pairs.iter().any(|p| p[0] == "--device-name" && p[1] == "Pixel_7_API_34"));
+        assert!(pairs.iter().any(|p| p[0] == "--artifacts-location" && p[1] == "C:\\Dev\\R&D App\\hyperzenith_logs\\e2e"));
+        assert!(args.contains(&"--reuse".to_string()));
+    }
+
+    #[test]
+    fn test_pick_device() {
+        let device = |serial: &str, state: &str| crate::adb::AdbDevice { serial: serial.into(), state: state.into(), model: None };
+        let devices = vec![device("R58M123ABC", "device"), device("emulator-5556", "offline"), device("emulator-5554", "device")];
+        assert_eq!(pick_device(&devices).as_deref(), Some("emulator-5554"));
+        assert_eq!(pick_device(&devices[..2]).as_deref(), Some("R58M123ABC"));
+        assert_eq!(pick_device(&[]), None);
+    }
+}
@@ -0,0 +1,183 @@
+use serde::ser::SerializeMap;
+use crate::i18n::{tr, Msg};
+
+/// Error returned by every command. Serialized as `{ kind, message, ...details }`
+/// so the frontend can branch on `kind` and offer a targeted recovery action.
+#[derive(Debug, Clone)]
+pub enum HyperZenithError {
+    WslMissing,
+    ToolMissing { tool: String },
+    GradleFailed { exit_code: Option<i32>, log_path: String },
+    BuildAborted { log_path: Option<String> },
+    DiskFull { path: String },
+    NotFound { path: String },
+    InvalidInput(String),
+    SshConnectFailed { host: String, reason: String },
+    HostNotFound { host: String, reason: String },
+    SshAuthFailed { user: String, reason: String },
+    RemoteCommandFailed { exit_code: i32 },
+    XcodebuildMissing,
+    LowDiskSpace { host: String, path: String, free_bytes: u64, required_bytes: u64 },
+    SyncFailed { reason: String },
+    ElevationDeclined,
+    CommandFailed { command: String, output: String },
+    Io(String),
+    Other(String),
+}
+
+impl HyperZenithError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::WslMissing => "wsl_missing",
+            Self::ToolMissing { .. } => "tool_missing",
+            Self::GradleFailed { .. } => "gradle_failed",
+            Self::BuildAborted { .. } => "build_aborted",
+            Self::DiskFull { .. } => "disk_full",
+            Self::NotFound { .. } => "not_found",
+            Self::InvalidInput(_) => "invalid_input",
+            Self::SshConnectFailed { .. } => "ssh_connect_failed",
+            Self::HostNotFound { .. } => "host_not_found",
+            Self::SshAuthFailed { .. } => "ssh_auth_failed",
+            Self::RemoteCommandFailed { .. } => "remote_command_failed",
+            Self::XcodebuildMissing => "xcodebuild_missing",
+            Self::LowDiskSpace { .. } => "low_disk_space",
+            Self::SyncFailed { .. } => "sync_failed",
+            Self::ElevationDeclined => "elevation_declined",
+            Self::CommandFailed { .. } => "command_failed",
+            Self::Io(_) => "io",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// Classify a failure to launch an external program
+    pub fn spawn(program: &str, err: std::io::Error) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound if program == "wsl" => Self::WslMissing,
+            std::io::ErrorKind::NotFound => Self::ToolMissing { tool: program.to_string() },
+            _ => Self::Io(format!("Failed to launch {}: {}", program, err)),
+        }
+    }
+
+    /// Classify a filesystem error for a specific path (full disks get their own kind)
+    pub fn io_at(path: &std::path::Path, err: std::io::Error) -> Self {
+        // ERROR_DISK_FULL / ERROR_HANDLE_DISK_FULL
+        if matches!(err.raw_os_error(), Some(112) | Some(39)) {
+            return Self::DiskFull { path: path.display().to_string() };
+        }
+        match err.kind() {
+            std::io::ErrorKind::NotFound => Self::NotFound { path: path.display().to_string() },
+            _ => Self::Io(format!("{}: {}", path.display(), err)),
+        }
+    }
+}
+
+/// Follows the language set with `set_locale`, see `crate::i18n`
+impl std::fmt::Display for HyperZenithError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::WslMissing => tr(Msg::WslMissing, &[]),
+            Self::ToolMissing { tool } => tr(Msg::ToolMissing, &[("tool", tool)]),
+            Self::GradleFailed { exit_code, log_path } => match exit_code {
+                Some(code) => tr(Msg::GradleFailed, &[("code", code), ("log", log_path)]),
+                None => tr(Msg::GradleFailedNoCode, &[("log", log_path)]),
+            },
+            Self::BuildAborted { log_path: Some(p) } => tr(Msg::BuildAborted, &[("log", p)]),
+            Self::BuildAborted { log_path: None } => tr(Msg::BuildAbortedNoLog, &[]),
+            Self::DiskFull { path } => tr(Msg::DiskFull, &[("path", path)]),
+            Self::NotFound { path } => tr(Msg::NotFound, &[("path", path)]),
+            Self::InvalidInput(msg) => return write!(f, "{}", msg),
+            Self::SshConnectFailed { host, reason } => tr(Msg::SshConnectFailed, &[("host", host), ("reason", reason)]),
+            Self::HostNotFound { host, reason } => tr(Msg::HostNotFound, &[("host", host), ("reason", reason)]),
+            Self::SshAuthFailed { user, reason } => tr(Msg::SshAuthFailed, &[("user", user), ("reason", reason)]),
+            Self::RemoteCommandFailed { exit_code } => tr(Msg::RemoteCommandFailed, &[("code", exit_code)]),
+            Self::XcodebuildMissing => tr(Msg::XcodebuildMissing, &[]),
+            Self::LowDiskSpace { host, path, free_bytes, required_bytes } => tr(Msg::LowDiskSpace, &[
+                ("free", &format!("{:.1}", *free_bytes as f64 / 1e9)),
+                ("host", host),
+                ("path", path),
+                ("required", &(required_bytes / 1_000_000_000)),
+            ]),
+            Self::SyncFailed { reason } => tr(Msg::SyncFailed, &[("reason", reason)]),
+            Self::ElevationDeclined => tr(Msg::ElevationDeclined, &[]),
+            Self::CommandFailed { command, output } => tr(Msg::CommandFailed, &[("command", command), ("output", output)]),
+            Self::Io(msg) | Self::Other(msg) => return write!(f, "{}", msg),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for HyperZenithError {}
+
+impl serde::Serialize for HyperZenithError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            Self::ToolMissing { tool } => map.serialize_entry("tool", tool)?,
+            Self::GradleFailed { exit_code, log_path } => {
+                map.serialize_entry("exit_code", exit_code)?;
+                map.serialize_entry("log_path", log_path)?;
+            },
+            Self::BuildAborted { log_path } => map.serialize_entry("log_path", log_path)?,
+            Self::DiskFull { path } | Self::NotFound { path } => map.serialize_entry("path", path)?,
+            Self::SshConnectFailed { host, .. } | Self::HostNotFound { host, .. } => map.serialize_entry("host", host)?,
+            Self::SshAuthFailed { user, .. } => map.serialize_entry("user", user)?,
+            Self::RemoteCommandFailed { exit_code } => map.serialize_entry("exit_code", exit_code)?,
+            Self::CommandFailed { command, .. } => map.serialize_entry("command", command)?,
+            Self::LowDiskSpace { host, path, free_bytes, required_bytes } => {
+                map.serialize_entry("host", host)?;
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("free_bytes", free_bytes)?;
+                map.serialize_entry("required_bytes", required_bytes)?;
+            },
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+impl From<std::io::Error> for HyperZenithError {
+    fn from(err: std::io::Error) -> Self {
+        if matches!(err.raw_os_error(), Some(112) | Some(39)) {
+            return Self::DiskFull { path: String::new() };
+        }
+        Self::Io(err.to_string())
+    }
+}
+
+impl From<String> for HyperZenithError {
+    fn from(msg: String) -> Self {
+        Self::Other(msg)
+    }
+}
+
+impl From<&str> for HyperZenithError {
+    fn from(msg: &str) -> Self {
+        Self::Other(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialized_shape() {
+        let err = HyperZenithError::GradleFailed { exit_code: Some(1), log_path: "C:\\logs\\fail.log".to_string() };
+        let json = serde_json::to_value(&err).unwrap();
+
+        assert_eq!(json["kind"], "gradle_failed");
+        assert_eq!(json["exit_code"], 1);
+        assert!(json["message"].as_str().unwrap().contains("exit code 1"));
+    }
+
+    #[test]
+    fn test_spawn_classification() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+        assert_eq!(HyperZenithError::spawn("wsl", missing).kind(), "wsl_missing");
+
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+        assert_eq!(HyperZenithError::spawn("adb", missing).kind(), "tool_missing");
+    }
+}
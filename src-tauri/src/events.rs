@@ -0,0 +1,414 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::{Emitter, Manager};
+use crate::error::HyperZenithError;
+
+/// Output is emitted in chunks: whichever comes first of this many lines or this much time
+const FLUSH_LINES: usize = 100;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lines without a recognisable phase still nudge progress forward, up to this cap
+const CREEP_PER_LINE: f64 = 0.08;
+const CREEP_CAP: f64 = 95.0;
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildState {
+    Started,
+    Finished,
+    Failed,
+    Aborted,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct BuildStateEvent {
+    pub build_id: String,
+    pub state: BuildState,
+    pub message: Option<String>,
+    pub error: Option<HyperZenithError>,
+}
+
+/// Milestones of a build, each emitted as its own event next to the human-readable line
+/// that announces it, so the UI can follow along without reading log text
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Lifecycle {
+    /// Project files are on their way to the Mac
+    SyncStarted,
+    SyncComplete,
+    /// The compile itself, after any queueing, syncing and pre-steps
+    BuildStarted,
+    /// Sent for every terminal state, whether or not the build got as far as starting
+    BuildFinished(BuildState),
+}
+
+impl Lifecycle {
+    pub fn event_name(self) -> &'static str {
+        match self {
+            Lifecycle::SyncStarted => "sync-started",
+            Lifecycle::SyncComplete => "sync-complete",
+            Lifecycle::BuildStarted => "build-started",
+            Lifecycle::BuildFinished(_) => "build-finished",
+        }
+    }
+}
+
+/// Payload of the lifecycle events; `status`, `message` and `error` are only set on `build-finished`
+#[derive(serde::Serialize, Clone)]
+pub struct LifecycleEvent {
+    pub build_id: String,
+    pub status: Option<BuildState>,
+    pub message: Option<String>,
+    pub error: Option<HyperZenithError>,
+}
+
+/// Which tool output lines reach the UI. The saved log always keeps everything.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    #[default]
+    All,
+    /// Drops blank lines, up-to-date/skipped tasks and download progress
+    Compact,
+    /// Only errors, warnings and the final build result
+    Problems,
+}
+
+impl Verbosity {
+    pub fn keeps(self, line: &str) -> bool {
+        let trimmed = line.trim();
+        match self {
+            Verbosity::All => true,
+            Verbosity::Compact => {
+                let idle_task = trimmed.starts_with("> Task")
+                    && ["UP-TO-DATE", "NO-SOURCE", "SKIPPED", "FROM-CACHE"].iter().any(|s| trimmed.ends_with(s));
+                !trimmed.is_empty() && !idle_task && !trimmed.starts_with("Download")
+            },
+            Verbosity::Problems => {
+                let lower = trimmed.to_lowercase();
+                trimmed.starts_with("e: ") || trimmed.starts_with("w: ") || trimmed.starts_with("BUILD ")
+                    || ["error", "warning", "failure", "failed", "exception"].iter().any(|k| lower.contains(k))
+            },
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    /// HyperZenith's own status messages
+    System,
+}
+
+/// One emitted line of build output
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OutputLine {
+    pub timestamp: String,
+    /// Seconds since the build started
+    pub elapsed: f64,
+    pub stream: Stream,
+    /// Plain text, escape codes removed
+    pub text: String,
+    /// Colour the tool printed the line in, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<crate::ansi::Color>,
+}
+
+impl OutputLine {
+    fn new(started: Instant, stream: Stream, text: String) -> Self {
+        let (text, color) = crate::ansi::strip(&text);
+        OutputLine {
+            timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+            elapsed: started.elapsed().as_secs_f64(),
+            stream,
+            text,
+            color,
+        }
+    }
+}
+
+/// Saved-log form: `[12:03:44 +12.3s] text`
+impl std::fmt::Display for OutputLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let clock = self.timestamp.get(11..19).unwrap_or(&self.timestamp);
+        write!(f, "[{} +{:.1}s] {}", clock, self.elapsed, self.text)
+    }
+}
+
+struct PendingLines {
+    lines: Vec<OutputLine>,
+    issues: Vec<crate::issues::BuildIssue>,
+    last_flush: Instant,
+    progress: f64,
+    progress_changed: bool,
+}
+
+/// Payload of the global `build-output`: lines from every build arrive there, so they say whose they are
+#[derive(serde::Serialize, Clone)]
+pub struct BuildOutputEvent {
+    pub build_id: String,
+    pub lines: Vec<OutputLine>,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct BuildProgressEvent {
+    pub build_id: String,
+    pub percent: f64,
+}
+
+/// Emits a build's output on `build-output-{id}`, the problems in it on `build-issues-{id}`
+/// and its state on `build-state-{id}`, with
+/// milestones on `sync-started-{id}`, `sync-complete-{id}`, `build-started-{id}` and `build-finished-{id}`.
+/// Everything is mirrored on the global `build-output`/`build-state` events so a listener
+/// attached before the id is known doesn't miss early lines; listen to one or the other, not both.
+/// Output payloads are arrays of lines, batched so a chatty Gradle run can't flood the webview,
+/// wrapped with the build id on the global event.
+#[derive(Clone)]
+pub struct BuildChannel {
+    pub app: tauri::AppHandle,
+    pub id: String,
+    started: Instant,
+    verbosity: Verbosity,
+    pending: Arc<Mutex<PendingLines>>,
+    sources: Arc<Mutex<crate::issues::SourceRoots>>,
+    phases: Arc<Mutex<crate::phases::PhaseTracker>>,
+}
+
+impl BuildChannel {
+    pub fn new(app: &tauri::AppHandle, id: &str) -> Self {
+        BuildChannel {
+            app: app.clone(),
+            id: id.to_string(),
+            started: Instant::now(),
+            verbosity: Verbosity::All,
+            pending: Arc::new(Mutex::new(PendingLines { lines: Vec::new(), issues: Vec::new(), last_flush: Instant::now(), progress: 0.0, progress_changed: false })),
+            sources: Arc::default(),
+            phases: Arc::default(),
+        }
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    /// The project being built, so file references in problems resolve to its files
+    pub fn with_project(self, working_dir: &str) -> Self {
+        if let Ok(mut sources) = self.sources.lock() {
+            sources.project = Some(working_dir.to_string());
+        }
+        self
+    }
+
+    /// Output from here on may reference `remote` on the Mac, a copy of `local`
+    pub fn map_remote(&self, remote: &str, local: &str) {
+        if let Ok(mut sources) = self.sources.lock() {
+            sources.remote = Some((remote.to_string(), local.to_string()));
+        }
+    }
+
+    /// Mark the start of a phase HyperZenith runs itself, rather than one spotted in tool output
+    pub fn phase(&self, phase: crate::phases::Phase) {
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.enter(phase, self.started.elapsed().as_secs_f64());
+        }
+    }
+
+    /// The tool driving the current phase has exited
+    pub fn end_phase(&self) {
+        if let Ok(mut phases) = self.phases.lock() {
+            phases.end(self.started.elapsed().as_secs_f64());
+        }
+    }
+
+    /// Where the time went since the last call, for the history entry of the build that just finished
+    pub fn take_phases(&self) -> Vec<crate::phases::PhaseTiming> {
+        self.phases.lock().map(|mut phases| phases.take(self.started.elapsed().as_secs_f64())).unwrap_or_default()
+    }
+
+    /// A HyperZenith status line, always shown
+    pub fn line(&self, text: impl Into<String>) {
+        let line = OutputLine::new(self.started, Stream::System, text.into());
+        self.heard(&line.text);
+        self.push(line);
+    }
+
+    /// A line of tool output, subject to the verbosity filter. Returns it stamped for the saved log.
+    pub fn output(&self, stream: Stream, text: impl Into<String>) -> OutputLine {
+        let line = OutputLine::new(self.started, stream, text.into());
+        self.heard(&line.text);
+        self.advance_progress(&line.text);
+        if let Some(phase) = crate::phases::detect(&line.text) {
+            self.phase(phase);
+        }
+        // Problems are collected whatever the verbosity, they're what the filter is there to surface
+        if let Some(issue) = crate::issues::classify(&line.text) {
+            let issue = match self.sources.lock() {
+                Ok(sources) => issue.locate(&sources),
+                Err(_) => issue,
+            };
+            if let Ok(mut pending) = self.pending.lock() {
+                pending.issues.push(issue);
+            }
+        }
+        if self.verbosity.keeps(&line.text) {
+            self.push(line.clone());
+        }
+        line
+    }
+
+    /// Keep the stall watchdog from firing while there's output, hidden by the verbosity filter or not
+    fn heard(&self, text: &str) {
+        if let Some(state) = self.app.try_state::<crate::state::AppState>() {
+            state.builds.heard(&self.id, text);
+        }
+    }
+
+    fn push(&self, line: OutputLine) {
+        let ready = {
+            let Ok(mut pending) = self.pending.lock() else { return };
+            pending.lines.push(line);
+            pending.lines.len() >= FLUSH_LINES || pending.last_flush.elapsed() >= FLUSH_INTERVAL
+        };
+        if ready {
+            self.flush();
+        }
+    }
+
+    /// Progress never goes backwards: phases jump it forward, other lines creep it
+    fn advance_progress(&self, text: &str) {
+        let Ok(mut pending) = self.pending.lock() else { return };
+        let next = match crate::gradle::estimate_progress(text) {
+            Some(percent) => pending.progress.max(percent),
+            None if text.trim().is_empty() => return,
+            None => (pending.progress + CREEP_PER_LINE).min(CREEP_CAP).max(pending.progress),
+        };
+        if next != pending.progress {
+            pending.progress = next;
+            pending.progress_changed = true;
+        }
+    }
+
+    /// Emit whatever is buffered. Streaming loops call this on a timer so quiet periods don't strand lines.
+    pub fn flush(&self) {
+        let (lines, issues, progress) = {
+            let Ok(mut pending) = self.pending.lock() else { return };
+            pending.last_flush = Instant::now();
+            let progress = std::mem::take(&mut pending.progress_changed).then_some(pending.progress);
+            (std::mem::take(&mut pending.lines), std::mem::take(&mut pending.issues), progress)
+        };
+        if !lines.is_empty() {
+            let _ = self.app.emit(&format!("build-output-{}", self.id), &lines);
+            let _ = self.app.emit("build-output", BuildOutputEvent { build_id: self.id.clone(), lines });
+        }
+        if !issues.is_empty() {
+            let payload = crate::issues::BuildIssuesEvent { build_id: self.id.clone(), issues };
+            let _ = self.app.emit(&format!("build-issues-{}", self.id), &payload);
+            let _ = self.app.emit("build-issues", &payload);
+        }
+        if let Some(percent) = progress {
+            let payload = BuildProgressEvent { build_id: self.id.clone(), percent };
+            let _ = self.app.emit(&format!("build-progress-{}", self.id), &payload);
+            let _ = self.app.emit("build-progress", &payload);
+            self.taskbar(ProgressBarStatus::Normal, Some(percent));
+        }
+    }
+
+    /// Mirror progress on the main window's taskbar button
+    fn taskbar(&self, status: ProgressBarStatus, percent: Option<f64>) {
+        if let Some(window) = self.app.get_webview_window("main") {
+            let _ = window.set_progress_bar(ProgressBarState { status: Some(status), progress: percent.map(|p| p.round() as u64) });
+        }
+    }
+
+    /// Announce a milestone other than the end, which `state` and `fail` announce themselves
+    pub fn lifecycle(&self, stage: Lifecycle) {
+        match stage {
+            Lifecycle::SyncStarted => self.phase(crate::phases::Phase::Sync),
+            Lifecycle::SyncComplete => self.end_phase(),
+            _ => {},
+        }
+        self.emit_lifecycle(stage, LifecycleEvent { build_id: self.id.clone(), status: None, message: None, error: None });
+    }
+
+    fn emit_lifecycle(&self, stage: Lifecycle, payload: LifecycleEvent) {
+        // Lines announcing the milestone go out first
+        self.flush();
+        let _ = self.app.emit(&format!("{}-{}", stage.event_name(), self.id), &payload);
+        let _ = self.app.emit(stage.event_name(), &payload);
+    }
+
+    pub fn state(&self, state: BuildState, message: Option<String>) {
+        self.emit_state(BuildStateEvent { build_id: self.id.clone(), state, message, error: None });
+    }
+
+    /// Terminal failure: `BuildAborted` maps to the aborted state, anything else to failed
+    pub fn fail(&self, error: HyperZenithError) {
+        let state = match error {
+            HyperZenithError::BuildAborted { .. } => BuildState::Aborted,
+            _ => BuildState::Failed,
+        };
+        self.emit_state(BuildStateEvent { build_id: self.id.clone(), state, message: Some(error.to_string()), error: Some(error) });
+    }
+
+    fn emit_state(&self, payload: BuildStateEvent) {
+        // Output must land before the state change that ends it
+        self.flush();
+        let current = self.pending.lock().map(|p| p.progress).unwrap_or(0.0);
+        match payload.state {
+            BuildState::Started => self.taskbar(ProgressBarStatus::Normal, Some(0.0)),
+            BuildState::Finished => self.taskbar(ProgressBarStatus::None, None),
+            BuildState::Failed | BuildState::Aborted => self.taskbar(ProgressBarStatus::Error, Some(current.max(1.0))),
+        }
+        let _ = self.app.emit(&format!("build-state-{}", self.id), &payload);
+        let _ = self.app.emit("build-state", &payload);
+        if payload.state != BuildState::Started {
+            let BuildStateEvent { build_id, state, message, error } = payload;
+            self.emit_lifecycle(Lifecycle::BuildFinished(state), LifecycleEvent { build_id, status: Some(state), message, error });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_filter() {
+        let lines = [
+            "> Task :app:compileDebugKotlin UP-TO-DATE",
+            "> Task :app:mergeDebugResources",
+            "",
+            "e: file:///app/src/Main.kt:12:5 Unresolved reference: foo",
+            "BUILD SUCCESSFUL in 42s",
+        ];
+        let kept = |v: Verbosity| lines.iter().filter(|l| v.keeps(l)).count();
+
+        assert_eq!(kept(Verbosity::All), 5);
+        assert_eq!(kept(Verbosity::Compact), 3);
+        assert_eq!(kept(Verbosity::Problems), 2);
+    }
+
+    #[test]
+    fn test_output_line_log_format() {
+        let line = OutputLine {
+            timestamp: "2026-01-05T14:03:09.250+01:00".to_string(),
+            elapsed: 12.34,
+            stream: Stream::Stdout,
+            text: "> Task :app:assembleDebug".to_string(),
+            color: None,
+        };
+        assert_eq!(line.to_string(), "[14:03:09 +12.3s] > Task :app:assembleDebug");
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        assert_eq!(Lifecycle::SyncComplete.event_name(), "sync-complete");
+        assert_eq!(Lifecycle::BuildFinished(BuildState::Aborted).event_name(), "build-finished");
+
+        let finished = LifecycleEvent { build_id: "id".to_string(), status: Some(BuildState::Failed), message: None, error: None };
+        assert_eq!(serde_json::to_value(&finished).unwrap()["status"], "failed");
+    }
+}
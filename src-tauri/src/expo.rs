@@ -0,0 +1,65 @@
+use std::path::Path;
+
+/// True if package.json lists `expo` as a dependency
+pub fn is_expo_project(project_dir: &Path) -> bool {
+    depends_on(project_dir, "expo")
+}
+
+/// True if package.json lists `expo-updates`, without which an app never fetches OTA updates
+pub fn has_updates(project_dir: &Path) -> bool {
+    depends_on(project_dir, "expo-updates")
+}
+
+fn depends_on(project_dir: &Path, name: &str) -> bool {
+    std::fs::read_to_string(project_dir.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .map(|pkg| has_dependency(&pkg, name))
+        .unwrap_or(false)
+}
+
+fn has_dependency(pkg: &serde_json::Value, name: &str) -> bool {
+    ["dependencies", "devDependencies"].iter().any(|key| pkg[key].get(name).is_some())
+}
+
+/// Managed Expo project whose native android/ folder hasn't been generated yet
+pub fn needs_prebuild(project_dir: &Path) -> bool {
+    !project_dir.join("android").is_dir() && is_expo_project(project_dir)
+}
+
+/// Something the Android engine can build, now or after a prebuild
+pub fn is_android_buildable(project_dir: &Path) -> bool {
+    project_dir.join("android").is_dir() || is_expo_project(project_dir)
+}
+
+/// Generate android/ inside WSL, installing dependencies first since prebuild needs the project's expo.
+/// Installs run in `wsl_install_root`, the workspace root for monorepo apps.
+/// CI=1 makes prebuild fail instead of prompting for missing config.
+pub fn prebuild_cmd(wsl_project_path: &str, wsl_install_root: &str, pm: crate::node::PackageManager) -> String {
+    format!(
+        "export NODE_ENV=development CI=1 && cd {} && {{ {}; }} 2>&1 && cd {} && {} 2>&1",
+        crate::sh_quote(wsl_install_root),
+        crate::node::hydrate_script(pm),
+        crate::sh_quote(wsl_project_path),
+        pm.exec_cmd("expo prebuild --platform android")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_dependency() {
+        let managed: serde_json::Value = serde_json::from_str(r#"{"dependencies": {"expo": "~51.0.0", "expo-updates": "~0.25.0"}}"#).unwrap();
+        let dev: serde_json::Value = serde_json::from_str(r#"{"devDependencies": {"expo": "^50.0.0"}}"#).unwrap();
+        let bare: serde_json::Value = serde_json::from_str(r#"{"dependencies": {"react-native": "0.74.0"}}"#).unwrap();
+
+        assert!(has_dependency(&managed, "expo"));
+        assert!(has_dependency(&managed, "expo-updates"));
+        assert!(has_dependency(&dev, "expo"));
+        assert!(!has_dependency(&dev, "expo-updates"));
+        assert!(!has_dependency(&bare, "expo"));
+        assert!(!has_dependency(&serde_json::Value::Null, "expo"));
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::Path;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+/// Fastlane complains about anything but UTF-8, and its update check just slows every run
+const FASTLANE_ENV: &str = "export LANG=en_US.UTF-8 LC_ALL=en_US.UTF-8 FASTLANE_SKIP_UPDATE_CHECK=1; ";
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct FastlaneLane {
+    /// `ios` for lanes inside `platform :ios do`
+    pub platform: Option<String>,
+    pub name: String,
+}
+
+/// Public lanes of a Fastfile. Blocks are followed by their `do`/`end` pairs, which is enough
+/// for the `platform :x do ... lane :y do ... end ... end` layout every Fastfile uses.
+pub fn parse_lanes(fastfile: &str) -> Vec<FastlaneLane> {
+    let symbol = |rest: &str| {
+        rest.trim_start_matches([' ', '(', ':'])
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .next()
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    let mut lanes = Vec::new();
+    let mut depth = 0usize;
+    let mut platform: Option<(String, usize)> = None;
+
+    for line in fastfile.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let opens = line.ends_with(" do") || line.contains(" do |")
+            || ["if ", "unless ", "def ", "begin", "case ", "while ", "until "].iter().any(|k| line.starts_with(k));
+        if let Some(rest) = line.strip_prefix("platform ") {
+            platform = symbol(rest).map(|p| (p, depth));
+        } else if let Some(rest) = line.strip_prefix("lane ") {
+            if let Some(name) = symbol(rest) {
+                lanes.push(FastlaneLane { platform: platform.as_ref().map(|(p, _)| p.clone()), name });
+            }
+        }
+        if opens {
+            depth += 1;
+        } else if line == "end" || line.starts_with("end ") {
+            depth = depth.saturating_sub(1);
+            if platform.as_ref().is_some_and(|(_, at)| *at == depth) {
+                platform = None;
+            }
+        }
+    }
+    lanes
+}
+
+/// Folder holding `fastlane/Fastfile`, relative to the project: `ios/` or `android/` first
+/// (the usual React Native layout), then the project root
+pub fn find_fastlane_dir(project_dir: &Path, platform: &str) -> Option<String> {
+    [platform, ""].into_iter().find(|dir| project_dir.join(dir).join("fastlane").join("Fastfile").is_file()).map(str::to_string)
+}
+
+pub fn lanes(project_dir: &Path, platform: &str) -> Result<(String, Vec<FastlaneLane>), HyperZenithError> {
+    let dir = find_fastlane_dir(project_dir, platform)
+        .ok_or_else(|| HyperZenithError::NotFound { path: project_dir.join("fastlane").join("Fastfile").display().to_string() })?;
+    let fastfile = project_dir.join(&dir).join("fastlane").join("Fastfile");
+    let content = std::fs::read_to_string(&fastfile).map_err(|e| HyperZenithError::io_at(&fastfile, e))?;
+    let lanes = parse_lanes(&content)
+        .into_iter()
+        .filter(|l| l.platform.as_deref().is_none_or(|p| p == platform))
+        .collect();
+    Ok((dir, lanes))
+}
+
+/// `[bundle exec] fastlane [platform] <lane>`, after checking the lane exists
+pub fn fastlane_cmd(lanes: &[FastlaneLane], lane: &str, bundler: bool) -> Result<String, HyperZenithError> {
+    let found = lanes.iter().find(|l| l.name == lane).ok_or_else(|| {
+        let names: Vec<&str> = lanes.iter().map(|l| l.name.as_str()).collect();
+        HyperZenithError::InvalidInput(format!("No lane '{}' in the Fastfile (available: {})", lane, names.join(", ")))
+    })?;
+    Ok(format!(
+        "{}fastlane {}{} 2>&1",
+        if bundler { "bundle exec " } else { "" },
+        found.platform.as_ref().map(|p| format!("{} ", p)).unwrap_or_default(),
+        found.name
+    ))
+}
+
+/// Android lanes run in WSL next to the Android SDK the builds use
+pub async fn run_android(
+    channel: &BuildChannel,
+    working_dir: &str,
+    lane: &str,
+    toolchain: &crate::node::Toolchain,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<String, HyperZenithError> {
+    let project_dir = Path::new(working_dir);
+    let (dir, lanes) = lanes(project_dir, "android")?;
+    let command = fastlane_cmd(&lanes, lane, project_dir.join(&dir).join("Gemfile").is_file())?;
+    let wsl_dir = crate::windows_to_wsl_path(&project_dir.join(&dir).to_string_lossy());
+    let script = format!("{}{}cd {} && {}", toolchain.env_script(), FASTLANE_ENV, crate::sh_quote(&wsl_dir), command);
+    let distro = crate::wsl::project_distro(working_dir);
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &script);
+    if distro.is_none() {
+        cmd.current_dir(working_dir);
+    }
+
+    channel.line(format!("🏁 fastlane {} (in {})", lane, if dir.is_empty() { "." } else { &dir }));
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    let log_path = crate::write_build_log(working_dir, "fastlane", &outcome.log);
+    channel.line(format!("📄 Log saved to: {}", log_path.display()));
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+    if !outcome.success {
+        return Err(HyperZenithError::CommandFailed { command: format!("fastlane {}", lane), output: format!("See {}", log_path.display()) });
+    }
+    Ok(format!("Lane '{}' finished", lane))
+}
+
+/// iOS lanes run on the Mac in the synced copy, where Xcode and the signing keychain live
+pub fn run_ios(
+    channel: &BuildChannel,
+    config: &crate::ios::MacConfig,
+    working_dir: &str,
+    remote_app: &str,
+    lane: &str,
+    toolchain: &crate::node::Toolchain,
+    pipeline: &crate::state::IosPipeline,
+) -> Result<String, HyperZenithError> {
+    let project_dir = Path::new(working_dir);
+    let (dir, lanes) = lanes(project_dir, "ios")?;
+    let command = fastlane_cmd(&lanes, lane, project_dir.join(&dir).join("Gemfile").is_file())?;
+    let remote_dir = if dir.is_empty() { remote_app.to_string() } else { format!("{}/{}", remote_app, dir) };
+    let script = format!("{}{}cd {} && {}", toolchain.env_script(), FASTLANE_ENV, crate::sh_quote_path(&remote_dir), command);
+
+    let (sess, _) = crate::ios::tracked_session(config, pipeline)?;
+    channel.line(format!("🏁 fastlane {} on {} (in {})", lane, config.ip, remote_dir));
+    let log_buffer = std::sync::Arc::new(std::sync::Mutex::new(String::new()));
+    let result = crate::ios::run_remote_command(&sess, &crate::ios::keep_awake(&script), channel, Some(&log_buffer));
+    let log = log_buffer.lock().map(|l| l.clone()).unwrap_or_default();
+    let log_path = crate::write_build_log(working_dir, "fastlane", &log);
+    channel.line(format!("📄 Log saved to: {}", log_path.display()));
+    if pipeline.cancelled() {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+    result?;
+    Ok(format!("Lane '{}' finished", lane))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lanes() {
+        let fastfile = r#"
+default_platform(:ios)
+
+# lane :commented do
+platform :ios do
+  desc "Push a new beta build to TestFlight"
+  lane :beta do
+    if is_ci
+      setup_ci
+    end
+    build_app(scheme: "App")
+    upload_to_testflight
+  end
+
+  private_lane :bump do
+    increment_build_number
+  end
+end
+
+platform :android do
+  lane :internal do |options|
+    gradle(task: "bundle", build_type: "Release")
+  end
+end
+
+lane :lint do
+  sh("yarn lint")
+end
+"#;
+        let lane = |platform: Option<&str>, name: &str| FastlaneLane { platform: platform.map(str::to_string), name: name.to_string() };
+        let lanes = parse_lanes(fastfile);
+        assert_eq!(lanes, vec![lane(Some("ios"), "beta"), lane(Some("android"), "internal"), lane(None, "lint")]);
+
+        assert_eq!(fastlane_cmd(&lanes, "beta", true).unwrap(), "bundle exec fastlane ios beta 2>&1");
+        assert_eq!(fastlane_cmd(&lanes, "lint", false).unwrap(), "fastlane lint 2>&1");
+        assert!(fastlane_cmd(&lanes, "bump", false).is_err());
+    }
+}
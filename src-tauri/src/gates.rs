@@ -0,0 +1,98 @@
+use std::path::Path;
+use crate::node::PackageManager;
+
+/// Checks run in WSL before Gradle, which only finds these problems minutes later while bundling
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default, Debug)]
+#[serde(default)]
+pub struct GateOptions {
+    /// `tsc --noEmit`
+    pub typecheck: bool,
+    /// ESLint over the project
+    pub lint: bool,
+    /// Abort the build when a gate reports errors, instead of warning and carrying on
+    pub block_on_errors: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Gate {
+    Typecheck,
+    Lint,
+}
+
+const LEGACY_ESLINT_CONFIGS: [&str; 5] = [".eslintrc", ".eslintrc.js", ".eslintrc.cjs", ".eslintrc.json", ".eslintrc.yml"];
+const FLAT_ESLINT_CONFIGS: [&str; 3] = ["eslint.config.js", "eslint.config.mjs", "eslint.config.cjs"];
+
+impl Gate {
+    pub fn label(self) -> &'static str {
+        match self {
+            Gate::Typecheck => "typecheck",
+            Gate::Lint => "lint",
+        }
+    }
+
+    /// Whether the project is set up for this gate at all
+    fn configured(self, project_dir: &Path) -> bool {
+        match self {
+            Gate::Typecheck => project_dir.join("tsconfig.json").exists(),
+            Gate::Lint => LEGACY_ESLINT_CONFIGS.iter().chain(FLAT_ESLINT_CONFIGS.iter()).any(|f| project_dir.join(f).exists()),
+        }
+    }
+
+    /// WSL script run from the project directory
+    pub fn script(self, wsl_project_path: &str, project_dir: &Path, pm: PackageManager) -> String {
+        let command = match self {
+            Gate::Typecheck => "tsc --noEmit --pretty false",
+            // Legacy configs only lint .js unless told otherwise; flat configs list their own files
+            Gate::Lint if LEGACY_ESLINT_CONFIGS.iter().any(|f| project_dir.join(f).exists()) => "eslint . --ext .js,.jsx,.ts,.tsx",
+            Gate::Lint => "eslint .",
+        };
+        format!("cd {} && {} 2>&1", crate::sh_quote(wsl_project_path), pm.run_bin(command))
+    }
+
+    /// Errors reported in the gate's output
+    pub fn count_errors(self, log: &str) -> usize {
+        match self {
+            Gate::Typecheck => log.lines().filter(|l| l.contains("error TS")).count(),
+            // Summary line: "✖ 12 problems (3 errors, 9 warnings)"
+            Gate::Lint => log.lines()
+                .rev()
+                .find_map(|l| {
+                    let inner = l.split_once('(')?.1;
+                    inner.split_whitespace().next()?.parse().ok()
+                })
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Gates to run for this project; skipped ones come back with the reason
+pub fn plan(options: &GateOptions, project_dir: &Path) -> Vec<Result<Gate, String>> {
+    [(options.typecheck, Gate::Typecheck), (options.lint, Gate::Lint)]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, gate)| {
+            if gate.configured(project_dir) {
+                Ok(gate)
+            } else {
+                Err(format!("No {} config found, skipping {}", if gate == Gate::Typecheck { "tsconfig.json" } else { "ESLint" }, gate.label()))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_errors() {
+        let tsc = "App.tsx(12,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                   src/api.ts(3,1): error TS2304: Cannot find name 'fetchUser'.\n";
+        assert_eq!(Gate::Typecheck.count_errors(tsc), 2);
+        assert_eq!(Gate::Typecheck.count_errors(""), 0);
+
+        let eslint = "/app/App.tsx\n  4:10  error  'x' is defined but never used  no-unused-vars\n\n✖ 12 problems (3 errors, 9 warnings)\n";
+        assert_eq!(Gate::Lint.count_errors(eslint), 3);
+        assert_eq!(Gate::Lint.count_errors("All clean"), 0);
+    }
+}
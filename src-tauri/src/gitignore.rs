@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+/// One `.gitignore` line
+#[derive(Debug, Clone, PartialEq)]
+struct Rule {
+    /// `/`-separated glob segments; `**` spans any number of them
+    segments: Vec<String>,
+    /// Containing a slash ties the pattern to the file's folder; otherwise it matches a name at any depth
+    anchored: bool,
+    negated: bool,
+}
+
+/// The parts of a `.gitignore` that decide which folders a walk can skip: comments, `!`
+/// negation, anchoring and `*`/`?`/`**`. Character classes are taken literally.
+#[derive(Debug, Clone)]
+pub struct Gitignore {
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+/// `*` and `?` within one path segment
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => glob(&pattern[1..], text) || (!text.is_empty() && glob(pattern, &text[1..])),
+        (Some(b'?'), Some(_)) => glob(&pattern[1..], &text[1..]),
+        (Some(p), Some(t)) if p == t => glob(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+fn match_segments(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => (0..=path.len()).any(|i| match_segments(rest, &path[i..])),
+        Some((first, rest)) => path.first().is_some_and(|segment| glob(first.as_bytes(), segment.as_bytes())) && match_segments(rest, &path[1..]),
+    }
+}
+
+impl Gitignore {
+    pub fn parse(base: &Path, text: &str) -> Self {
+        let rules = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| {
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let line = line.trim_end_matches('/');
+                let anchored = line.contains('/');
+                let segments = line.trim_start_matches('/').split('/').map(str::to_string).collect();
+                Rule { segments, anchored, negated }
+            })
+            .filter(|rule| rule.segments.iter().all(|s| !s.is_empty()))
+            .collect();
+        Gitignore { base: base.to_path_buf(), rules }
+    }
+
+    pub fn load(dir: &Path) -> Option<Self> {
+        std::fs::read_to_string(dir.join(".gitignore")).ok().map(|text| Gitignore::parse(dir, &text))
+    }
+
+    /// Whether a folder below this file's folder is ignored, `None` if no rule mentions it.
+    /// The last matching rule wins, as in git.
+    pub fn ignores(&self, dir: &Path) -> Option<bool> {
+        let relative = dir.strip_prefix(&self.base).ok()?;
+        let segments: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+        let name = *segments.last()?;
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| match rule.anchored {
+                true => match_segments(&rule.segments, &segments),
+                false => rule.segments.len() == 1 && glob(rule.segments[0].as_bytes(), name.as_bytes()),
+            })
+            .map(|rule| !rule.negated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignores() {
+        let base = Path::new("repo");
+        let ignore = Gitignore::parse(base, "# deps\nnode_modules/\n/out\n*.tmp\ndocs/**/generated\nvendor*\n!vendor-keep\n\n");
+        let check = |rel: &str| ignore.ignores(&base.join(rel));
+
+        assert_eq!(check("node_modules"), Some(true));
+        assert_eq!(check("apps/mobile/node_modules"), Some(true));
+        assert_eq!(check("out"), Some(true));
+        assert_eq!(check("apps/out"), None);
+        assert_eq!(check("cache.tmp"), Some(true));
+        assert_eq!(check("docs/api/v2/generated"), Some(true));
+        assert_eq!(check("docs/generated"), Some(true));
+        assert_eq!(check("vendored"), Some(true));
+        assert_eq!(check("vendor-keep"), Some(false));
+        assert_eq!(check("apps/mobile"), None);
+        assert_eq!(ignore.ignores(Path::new("elsewhere/node_modules")), None);
+    }
+}
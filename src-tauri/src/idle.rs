@@ -0,0 +1,160 @@
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Consecutive quiet CPU samples needed, so one lull between bursts doesn't count as idle
+const LOW_CPU_SAMPLES: u32 = 4;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum IdleJobKind {
+    Prewarm,
+    Build,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug)]
+pub struct IdleSettings {
+    /// Minutes without keyboard/mouse input
+    pub idle_minutes: u64,
+    /// Average CPU usage (all cores) that still counts as idle
+    pub max_cpu_percent: f32,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        IdleSettings { idle_minutes: 10, max_cpu_percent: 20.0 }
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct IdleJobInfo {
+    pub working_dir: String,
+    pub kind: IdleJobKind,
+    pub build_type: Option<String>,
+    pub settings: IdleSettings,
+    pub queued_at: String,
+}
+
+/// Work waiting for the machine to go idle. At most one is queued; queueing again replaces it.
+pub struct IdleJob {
+    pub info: IdleJobInfo,
+    pub options: Option<crate::AndroidBuildOptions>,
+    pub verbosity: crate::events::Verbosity,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct IdleJobStarted {
+    pub working_dir: String,
+    pub kind: IdleJobKind,
+    pub build_id: String,
+}
+
+/// Decides when the system has been idle long enough
+#[derive(Default)]
+struct IdleMonitor {
+    low_cpu_streak: u32,
+}
+
+impl IdleMonitor {
+    fn observe(&mut self, input_idle: Duration, cpu_percent: f32, settings: &IdleSettings) -> bool {
+        if cpu_percent <= settings.max_cpu_percent {
+            self.low_cpu_streak += 1;
+        } else {
+            self.low_cpu_streak = 0;
+        }
+        input_idle >= Duration::from_secs(settings.idle_minutes * 60) && self.low_cpu_streak >= LOW_CPU_SAMPLES
+    }
+}
+
+/// Time since the last keyboard or mouse input in this session
+fn input_idle() -> Duration {
+    use winapi::um::sysinfoapi::GetTickCount;
+    use winapi::um::winuser::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    // SAFETY: `info` is a properly sized LASTINPUTINFO that outlives the call
+    let ok = unsafe { GetLastInputInfo(&mut info) } != 0;
+    if !ok {
+        return Duration::ZERO;
+    }
+    // Tick counts wrap every ~49 days; wrapping_sub keeps the difference right across the wrap
+    let now = unsafe { GetTickCount() };
+    Duration::from_millis(now.wrapping_sub(info.dwTime) as u64)
+}
+
+fn cpu_percent(state: &AppState) -> f32 {
+    let Ok(mut sys) = state.system.lock() else { return 100.0 };
+    sys.refresh_cpu();
+    sys.global_cpu_info().cpu_usage()
+}
+
+/// Start the queued job if nothing else is building; returns the new build id
+fn run_job(app: &tauri::AppHandle, state: &AppState, job: IdleJob) -> Option<String> {
+    let working_dir = job.info.working_dir.clone();
+    match (job.info.kind, job.options) {
+        (IdleJobKind::Build, Some(options)) => Some(crate::start_android_build(app, state, working_dir, options, job.verbosity)),
+        (IdleJobKind::Prewarm, _) => Some(crate::start_prewarm(app, state, working_dir)),
+        (IdleJobKind::Build, None) => None,
+    }
+}
+
+/// Poll for idle in the background for the lifetime of the app
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut monitor = IdleMonitor::default();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+
+            let settings = match state.idle_job.lock().ok().and_then(|j| j.as_ref().map(|j| j.info.settings)) {
+                Some(settings) => settings,
+                None => {
+                    monitor = IdleMonitor::default();
+                    continue;
+                }
+            };
+            let idle = monitor.observe(input_idle(), cpu_percent(&state), &settings);
+            // Never stack a background build on top of one the user started
+            if !idle || !state.builds.active().is_empty() {
+                continue;
+            }
+
+            let Some(job) = state.idle_job.lock().ok().and_then(|mut j| j.take()) else { continue };
+            let (working_dir, kind) = (job.info.working_dir.clone(), job.info.kind);
+            println!("💤 [IDLE] System idle, starting queued {:?} for {}", kind, working_dir);
+            if let Some(build_id) = run_job(&app, &state, job) {
+                let _ = app.emit("idle-job-started", IdleJobStarted { working_dir, kind, build_id });
+            }
+            monitor = IdleMonitor::default();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idle_requires_sustained_quiet() {
+        let settings = IdleSettings { idle_minutes: 5, max_cpu_percent: 20.0 };
+        let away = Duration::from_secs(600);
+        let mut monitor = IdleMonitor::default();
+
+        for _ in 0..LOW_CPU_SAMPLES - 1 {
+            assert!(!monitor.observe(away, 5.0, &settings));
+        }
+        assert!(monitor.observe(away, 5.0, &settings));
+
+        // A CPU spike resets the streak
+        assert!(!monitor.observe(away, 80.0, &settings));
+        assert!(!monitor.observe(away, 5.0, &settings));
+
+        // Recent input is never idle
+        let mut monitor = IdleMonitor::default();
+        for _ in 0..LOW_CPU_SAMPLES {
+            assert!(!monitor.observe(Duration::from_secs(30), 1.0, &settings));
+        }
+    }
+}
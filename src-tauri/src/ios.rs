@@ -1,11 +1,12 @@
 use ssh2::Session;
-use std::io::Read;
-use std::net::TcpStream;
-use std::process::Command;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
 use std::path::Path;
-use tauri::Emitter;
+use crate::events::{BuildChannel, Stream};
 use std::sync::{Arc, Mutex};
-use chrono::Local;
+use crate::error::HyperZenithError;
+use crate::state::IosPipeline;
 
 #[derive(serde::Deserialize, Clone)]
 pub struct MacConfig {
@@ -13,19 +14,144 @@ pub struct MacConfig {
     pub username: String,
     pub password: Option<String>,
     pub ssh_key_path: Option<String>,  // For MacinCloud 2FA or key-based auth
+    #[serde(default)]
+    pub sync: SyncSettings,
+    /// Gateway the Mac is only reachable through
+    #[serde(default)]
+    pub jump: Option<JumpHost>,
 }
 
-/// Helper to parse IP:PORT from the ip field. Defaults to port 22.
-fn parse_ip_and_port(input: &str) -> (&str, &str) {
-    if let Some((ip, port)) = input.split_once(':') {
-        (ip, port)
+/// Sent on `ios-queue` while a build waits for its Mac; `ahead` reaches 0 when it starts
+#[derive(serde::Serialize, Clone)]
+pub struct QueuePosition {
+    pub build_id: String,
+    pub mac: String,
+    pub ahead: usize,
+}
+
+/// Bastion box in front of the Mac, same address formats and credentials as the Mac itself
+#[derive(serde::Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct JumpHost {
+    pub ip: String,
+    pub username: String,
+    pub password: Option<String>,
+    pub ssh_key_path: Option<String>,
+}
+
+impl MacConfig {
+    /// Which Mac this is, for queueing builds on it
+    pub fn queue_key(&self) -> String {
+        format!("{}@{}", self.username.trim(), self.ip.trim().to_lowercase())
+    }
+
+    /// The jump host as a config of its own, if one is filled in
+    fn jump_host(&self) -> Option<MacConfig> {
+        self.jump.as_ref().filter(|j| !j.ip.trim().is_empty()).map(|j| MacConfig {
+            ip: j.ip.clone(),
+            username: j.username.clone(),
+            password: j.password.clone(),
+            ssh_key_path: j.ssh_key_path.clone(),
+            sync: SyncSettings::default(),
+            jump: None,
+        })
+    }
+}
+
+/// rsync tuning for this Mac's connection
+#[derive(serde::Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// Compare file contents instead of size + mtime, for trees whose timestamps can't be trusted
+    pub checksum: bool,
+    /// Remove files from the remote tree that were deleted locally (excluded folders are kept)
+    pub delete: bool,
+    /// KiB/s, for slow or metered connections
+    pub bwlimit_kbps: Option<u32>,
+}
+
+impl SyncSettings {
+    fn flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if self.checksum {
+            flags.push("--checksum".to_string());
+        }
+        if self.delete {
+            flags.push("--delete".to_string());
+        }
+        if let Some(limit) = self.bwlimit_kbps.filter(|l| *l > 0) {
+            flags.push(format!("--bwlimit={}", limit));
+        }
+        flags
+    }
+}
+
+/// Split the Mac address field into host and port, defaulting to 22. The host may be an IP,
+/// a hostname (`.local` Bonjour and Tailscale MagicDNS names included) or an IPv6 literal,
+/// which needs brackets when a port follows: `[fd7a::1]:2222`.
+fn parse_host_and_port(input: &str) -> Result<(&str, u16), HyperZenithError> {
+    let input = input.trim();
+    let port = |p: &str| p.parse::<u16>().map_err(|_| HyperZenithError::InvalidInput(format!("'{}' is not a valid SSH port", p)));
+
+    let (host, port) = if let Some(rest) = input.strip_prefix('[') {
+        let (host, after) = rest.split_once(']').ok_or_else(|| HyperZenithError::InvalidInput(format!("Missing ']' in '{}'", input)))?;
+        match after.strip_prefix(':') {
+            Some(p) => (host, port(p)?),
+            None if after.is_empty() => (host, 22),
+            None => return Err(HyperZenithError::InvalidInput(format!("Unexpected '{}' after ']' in '{}'", after, input))),
+        }
+    } else if input.matches(':').count() > 1 {
+        // Bare IPv6, there's no telling a port apart
+        if input.parse::<std::net::Ipv6Addr>().is_err() {
+            return Err(HyperZenithError::InvalidInput(format!("'{}' is not a valid address. Write IPv6 with a port as [address]:port", input)));
+        }
+        (input, 22)
     } else {
-        (input, "22")
+        match input.split_once(':') {
+            Some((host, p)) => (host, port(p)?),
+            None => (input, 22),
+        }
+    };
+
+    if host.is_empty() {
+        return Err(HyperZenithError::InvalidInput("Connection failed: Mac address is empty".to_string()));
+    }
+    Ok((host, port))
+}
+
+/// Why a name might not resolve, by the kind of name
+fn resolve_hint(host: &str) -> &'static str {
+    if host.ends_with(".local") {
+        "Bonjour names need mDNS to reach this network, try the Mac's IP instead"
+    } else if host.ends_with(".ts.net") || !host.contains('.') {
+        "For Tailscale MagicDNS names make sure Tailscale is connected on this PC"
+    } else {
+        "Check the hostname and your DNS settings"
+    }
+}
+
+/// Resolve the Mac's address once, here, so WSL's resolver (which can't see `.local` or
+/// MagicDNS names) only ever gets an IP
+fn resolve(config: &MacConfig) -> Result<SocketAddr, HyperZenithError> {
+    let (host, port) = parse_host_and_port(&config.ip)?;
+    let not_found = |reason: String| HyperZenithError::HostNotFound { host: host.to_string(), reason };
+    (host, port)
+        .to_socket_addrs()
+        .map_err(|e| not_found(format!("{} ({})", e, resolve_hint(host))))?
+        .next()
+        .ok_or_else(|| not_found(resolve_hint(host).to_string()))
+}
+
+/// Host part of an rsync `user@host:path`, IPv6 in brackets
+fn rsync_host(addr: &SocketAddr) -> String {
+    match addr {
+        SocketAddr::V4(v4) => v4.ip().to_string(),
+        SocketAddr::V6(v6) => format!("[{}]", v6.ip()),
     }
 }
 
 /// Helper to construct SSH options string for Command-based operations
-fn get_ssh_options(port: &str, key_path: &Option<String>) -> String {
+fn get_ssh_options(port: u16, key_path: &Option<String>) -> String {
     let mut opts = format!(
         "-p {} -o StrictHostKeyChecking=no -o UserKnownHostsFile=/dev/null -o ConnectTimeout=30",
         port
@@ -38,30 +164,142 @@ fn get_ssh_options(port: &str, key_path: &Option<String>) -> String {
     opts
 }
 
-/// Helper to establish SSH connection with detailed error reporting
-fn create_session(config: &MacConfig) -> Result<Session, String> {
-    let (ip, port) = parse_ip_and_port(&config.ip);
-    
-    // Validate IP early
-    if ip.is_empty() {
-        return Err("Connection failed: IP address is empty".to_string());
+/// A jump host without a key: rsync's ssh has no way to type its password, ssh2 does
+fn keyless_jump(config: &MacConfig) -> bool {
+    config.jump_host().is_some_and(|jump| jump.ssh_key_path.as_deref().is_none_or(|k| k.trim().is_empty()))
+}
+
+/// `user@host` for rsync and the `-e` ssh command that reaches it, through the jump host's
+/// `ssh -W` when there is one. Behind a jump host the Mac's name is resolved by the jump host.
+fn rsync_remote(config: &MacConfig) -> Result<(String, String), HyperZenithError> {
+    match config.jump_host() {
+        Some(_) if keyless_jump(config) => Err(HyperZenithError::InvalidInput(
+            "rsync can't log in to a jump host with a password, set an SSH key for the jump host".to_string(),
+        )),
+        Some(jump) => {
+            let (host, port) = parse_host_and_port(&config.ip)?;
+            let jump_addr = resolve(&jump)?;
+            // ssh runs the ProxyCommand through the shell
+            let proxy = format!(
+                "ssh {} -i {} -W %h:%p {}@{}",
+                get_ssh_options(jump_addr.port(), &None),
+                crate::sh_quote(jump.ssh_key_path.as_deref().unwrap_or_default()),
+                jump.username,
+                jump_addr.ip()
+            );
+            let host = if host.contains(':') { format!("[{}]", host) } else { host.to_string() };
+            Ok((
+                format!("{}@{}", config.username, host),
+                // rsync splits `-e` itself, a doubled quote is a literal one inside its quotes
+                format!("ssh {} -o ProxyCommand='{}'", get_ssh_options(port, &config.ssh_key_path), proxy.replace('\'', "''")),
+            ))
+        },
+        None => {
+            let addr = resolve(config)?;
+            Ok((
+                format!("{}@{}", config.username, rsync_host(&addr)),
+                format!("ssh {}", get_ssh_options(addr.port(), &config.ssh_key_path)),
+            ))
+        },
+    }
+}
+
+/// Write everything to a non-blocking stream, waiting out `WouldBlock`
+fn write_all_retrying(w: &mut impl Write, mut data: &[u8]) -> std::io::Result<()> {
+    while !data.is_empty() {
+        match w.write(data) {
+            Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+            Ok(n) => data = &data[n..],
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(std::time::Duration::from_millis(1)),
+            Err(e) => return Err(e),
+        }
     }
+    Ok(())
+}
+
+/// Open a forwarded channel from the jump host to the Mac. ssh2 sessions need a real socket, so
+/// the channel is bridged to a loopback connection that a background thread pumps for as long
+/// as the session using it stays open.
+fn tunnel(jump: Session, host: &str, port: u16) -> Result<TcpStream, HyperZenithError> {
+    let mut channel = jump.channel_direct_tcpip(host, port, None).map_err(|e| HyperZenithError::SshConnectFailed {
+        host: format!("{}:{} (via jump host)", host, port),
+        reason: e.to_string(),
+    })?;
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let local = TcpStream::connect(listener.local_addr()?)?;
+    let (mut bridged, _) = listener.accept()?;
+
+    std::thread::spawn(move || {
+        jump.set_blocking(false);
+        if bridged.set_nonblocking(true).is_err() {
+            return;
+        }
+        let mut buffer = [0u8; 16 * 1024];
+        loop {
+            let mut idle = true;
+            match bridged.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => {
+                    idle = false;
+                    if write_all_retrying(&mut channel, &buffer[..n]).is_err() {
+                        break;
+                    }
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {},
+                Err(_) => break,
+            }
+            match channel.read(&mut buffer) {
+                Ok(0) if channel.eof() => break,
+                Ok(0) => {},
+                Ok(n) => {
+                    idle = false;
+                    if write_all_retrying(&mut bridged, &buffer[..n]).is_err() {
+                        break;
+                    }
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {},
+                Err(_) => break,
+            }
+            if idle {
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            }
+        }
+    });
+    Ok(local)
+}
+
+/// Helper to establish SSH connection with detailed error reporting
+pub(crate) fn create_session(config: &MacConfig) -> Result<Session, HyperZenithError> {
+    connect(config).map(|(sess, _)| sess)
+}
+
+/// An authenticated session plus a handle on its socket, which can end the session from
+/// another thread even while a read is blocked on it
+fn connect(config: &MacConfig) -> Result<(Session, TcpStream), HyperZenithError> {
+    // Validate the address early
+    let (host, port) = parse_host_and_port(&config.ip)?;
     if config.username.is_empty() {
-        return Err("Connection failed: Username is empty".to_string());
+        return Err(HyperZenithError::InvalidInput("Connection failed: Username is empty".to_string()));
     }
-    
-    // Set connection timeout for cloud connections
-    let addr = format!("{}:{}", ip, port);
-    let tcp = TcpStream::connect(&addr)
-        .map_err(|e| format!("Connection failed: Cannot reach '{}' - {} (Check IP/Port)", addr, e))?;
+
+    let tcp = match config.jump_host() {
+        Some(jump) => tunnel(create_session(&jump)?, host, port)?,
+        None => {
+            let addr = resolve(config)?;
+            // Set connection timeout for cloud connections
+            TcpStream::connect_timeout(&addr, std::time::Duration::from_secs(30))
+                .map_err(|e| HyperZenithError::SshConnectFailed { host: format!("{} ({})", config.ip.trim(), addr), reason: e.to_string() })?
+        },
+    };
     
     // Set read/write timeout to prevent hanging (Increased to 10m for slow cloud builds)
     tcp.set_read_timeout(Some(std::time::Duration::from_secs(600))).ok();
     tcp.set_write_timeout(Some(std::time::Duration::from_secs(600))).ok();
     
+    let socket = tcp.try_clone().map_err(|e| HyperZenithError::SshConnectFailed { host: host.to_string(), reason: e.to_string() })?;
     let mut sess = Session::new().unwrap();
     sess.set_tcp_stream(tcp);
-    sess.handshake().map_err(|e| format!("SSH Handshake failed with '{}' - {}", ip, e))?;
+    sess.handshake().map_err(|e| HyperZenithError::SshConnectFailed { host: host.to_string(), reason: format!("SSH handshake failed - {}", e) })?;
 
     // AUTHENTICATION LOGIC - Support both Key and Password auth
     // Robust checks: treat empty strings as "not provided"
@@ -72,109 +310,594 @@ fn create_session(config: &MacConfig) -> Result<Session, String> {
         let key_path = config.ssh_key_path.as_ref().unwrap();
         // Validate key file exists before attempting auth
         if !Path::new(key_path).exists() {
-            return Err(format!("SSH Key file not found: '{}' (Check path)", key_path));
+            return Err(HyperZenithError::NotFound { path: key_path.clone() });
         }
         sess.userauth_pubkey_file(&config.username, None, Path::new(key_path), None)
-            .map_err(|e| format!("SSH Key auth failed for user '{}': {} (Check username, key path, and permissions)", config.username, e))?;
+            .map_err(|e| HyperZenithError::SshAuthFailed {
+                user: config.username.clone(),
+                reason: format!("SSH key rejected: {} (Check username, key path, and permissions)", e),
+            })?;
     } else if has_password {
         let pwd = config.password.as_ref().unwrap();
         sess.userauth_password(&config.username, pwd)
-            .map_err(|e| format!("Password auth failed for user '{}': {} (Check username and password)", config.username, e))?;
+            .map_err(|e| HyperZenithError::SshAuthFailed {
+                user: config.username.clone(),
+                reason: format!("Password rejected: {} (Check username and password)", e),
+            })?;
     } else {
-        return Err("No credentials provided: Enter either SSH Key Path OR Password".to_string());
+        return Err(HyperZenithError::InvalidInput("No credentials provided: Enter either SSH Key Path OR Password".to_string()));
     }
 
     if !sess.authenticated() {
-        return Err(format!("Authentication failed for user '{}' at '{}' (Credentials rejected)", config.username, ip));
+        return Err(HyperZenithError::SshAuthFailed {
+            user: config.username.clone(),
+            reason: format!("Credentials rejected by '{}'", host),
+        });
     }
 
-    Ok(sess)
+    Ok((sess, socket))
+}
+
+/// What a running iOS build is busy with, enough to stop it from another thread
+pub enum IosStage {
+    /// Waiting for the Mac, by its queue key
+    Queued { mac: String },
+    /// rsync in WSL, by the pid of its `wsl` process
+    Syncing { pid: u32 },
+    /// Between steps, with nothing running that a cancel would have to stop
+    Idle,
+    /// Talking to the Mac over SSH
+    Connected { socket: TcpStream },
+    /// The detached build is running on the Mac, which outlives the connection and has to be
+    /// stopped there too
+    Building { socket: TcpStream, config: MacConfig },
+}
+
+impl IosStage {
+    /// Stop whatever this stage is running. A queued build only needs to leave its queue.
+    pub fn stop(self) -> Result<(), HyperZenithError> {
+        match self {
+            IosStage::Queued { .. } | IosStage::Idle => Ok(()),
+            IosStage::Syncing { pid } => {
+                use std::os::windows::process::CommandExt;
+                let output = Command::new("taskkill")
+                    .args(["/PID", &pid.to_string(), "/T", "/F"])
+                    .creation_flags(crate::CREATE_NO_WINDOW)
+                    .output()
+                    .map_err(|e| HyperZenithError::spawn("taskkill", e))?;
+                if !output.status.success() {
+                    return Err(HyperZenithError::CommandFailed {
+                        command: format!("taskkill /PID {}", pid),
+                        output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                    });
+                }
+                Ok(())
+            },
+            IosStage::Connected { socket } => {
+                let _ = socket.shutdown(std::net::Shutdown::Both);
+                Ok(())
+            },
+            IosStage::Building { socket, config } => {
+                let _ = socket.shutdown(std::net::Shutdown::Both);
+                let sess = create_session(&config)?;
+                run_remote_capture(&sess, &stop_detached_cmd()).map(|_| ())
+            },
+        }
+    }
 }
 
-/// Executing a remote command and streaming stdout/stderr to the frontend
-fn run_remote_command(
+/// Connect for a tracked build, letting a cancel cut the connection. Also returns a spare
+/// handle on the socket for moving to a later stage.
+pub(crate) fn tracked_session(config: &MacConfig, pipeline: &IosPipeline) -> Result<(Session, TcpStream), HyperZenithError> {
+    let (sess, socket) = connect(config)?;
+    let spare = socket.try_clone()?;
+    pipeline.enter(IosStage::Connected { socket })?;
+    Ok((sess, spare))
+}
+
+/// Hold an idle-sleep assertion for as long as the command's shell lives, so a long archive
+/// build isn't cut off by the Mac dozing. Macs without `caffeinate` just run the command.
+pub(crate) fn keep_awake(command: &str) -> String {
+    format!("(command -v caffeinate >/dev/null && caffeinate -i -w $$ >/dev/null 2>&1 &); {}", command)
+}
+
+/// Where a detached build keeps its script, output and exit code on the Mac
+pub(crate) const DETACHED_DIR: &str = "~/.hyperzenith";
+const DETACHED_SESSION: &str = "hyperzenith-ios";
+/// Bracketed so `pgrep -f` doesn't match the shell whose command line contains the pattern
+const DETACHED_PATTERN: &str = "hyperzenith/[i]os_build.sh";
+/// Exit code when another detached build is still running
+const BUILD_RUNNING_EXIT: i32 = 75;
+
+/// Stream the detached build's log until its exit code appears, then exit with it.
+/// Gives up if the build process is gone without writing one (e.g. the Mac rebooted).
+fn follow_detached_cmd() -> String {
+    format!(
+        "[ -f {dir}/ios_build.log ] || {{ echo '>> No iOS build has run on this Mac'; exit 1; }}; \
+        tail -n +1 -f {dir}/ios_build.log & t=$!; \
+        while [ ! -f {dir}/ios_build.exit ] && pgrep -f '{pattern}' >/dev/null; do sleep 2; done; \
+        sleep 1; kill $t; \
+        [ -f {dir}/ios_build.exit ] || {{ echo '>> The build stopped without finishing'; exit 1; }}; \
+        exit $(cat {dir}/ios_build.exit)",
+        dir = DETACHED_DIR,
+        pattern = DETACHED_PATTERN
+    )
+}
+
+/// End the detached build wherever it runs: its tmux or screen session, or the bare script.
+/// Killing the script's process group takes xcodebuild and CocoaPods down with it. The
+/// cancelled exit code is only recorded when a build was actually stopped and left none.
+fn stop_detached_cmd() -> String {
+    format!(
+        "hz_killed=; tmux kill-session -t {session} 2>/dev/null && hz_killed=1; screen -S {session} -X quit 2>/dev/null && hz_killed=1; \
+        for pid in $(pgrep -f '{pattern}'); do kill -TERM -- -$(ps -o pgid= -p $pid | tr -d ' ') 2>/dev/null; kill $pid 2>/dev/null; hz_killed=1; done; \
+        rm -f {dir}/ios_build.env; \
+        if [ -z \"$hz_killed\" ]; then echo 'not running'; else [ -f {dir}/ios_build.exit ] || echo 130 > {dir}/ios_build.exit; echo stopped; fi",
+        session = DETACHED_SESSION,
+        pattern = DETACHED_PATTERN,
+        dir = DETACHED_DIR
+    )
+}
+
+/// Run the build inside a tmux (or screen) session that survives the SSH connection,
+/// writing to a log on the Mac, and follow that log. The subshell keeps an `exit` in the
+/// build from skipping the exit code. Plain `nohup` when neither is installed.
+/// `env_exports` (which may hold secrets) never enter the script: they go to an owner-only
+/// file that the script sources and deletes before the build starts.
+fn detached_build_cmd(command: &str, env_exports: &str) -> String {
+    // Output lands next to the script itself, `~` may not be ours inside an already running tmux server
+    let script = format!(
+        "hz_dir=$(dirname \"$0\"); . \"$hz_dir/ios_build.env\"; rm -f \"$hz_dir/ios_build.env\"; \
+        ( {} ) > \"$hz_dir/ios_build.log\" 2>&1; echo $? > \"$hz_dir/ios_build.exit\"",
+        keep_awake(command)
+    );
+    format!(
+        "if pgrep -f '{pattern}' >/dev/null; then echo '>> Another iOS build is still running on this Mac, reattach to follow it'; exit {busy}; fi; \
+        mkdir -p {dir} && rm -f {dir}/ios_build.exit {dir}/ios_build.env && : > {dir}/ios_build.log && \
+        (umask 077 && printf '%s\\n' {env} > {dir}/ios_build.env) && \
+        printf '%s\\n' {script} > {dir}/ios_build.sh || exit 1; \
+        if command -v tmux >/dev/null; then tmux new-session -d -s {session} sh {dir}/ios_build.sh; \
+        elif command -v screen >/dev/null; then screen -dmS {session} sh {dir}/ios_build.sh; \
+        else nohup sh {dir}/ios_build.sh >/dev/null 2>&1 & fi; \
+        sleep 1; {follow}",
+        pattern = DETACHED_PATTERN,
+        busy = BUILD_RUNNING_EXIT,
+        dir = DETACHED_DIR,
+        env = crate::sh_quote(env_exports),
+        script = crate::sh_quote(&script),
+        session = DETACHED_SESSION,
+        follow = follow_detached_cmd()
+    )
+}
+
+/// Detached `caffeinate` that outlives the SSH session; a duration of 0 ends any running one.
+/// Only the one started here is ended, by the pid it left behind: the `caffeinate` a build runs
+/// under keeps the Mac awake until that build is done.
+fn keepalive_cmd(duration_secs: u64) -> String {
+    let pidfile = format!("{}/keepalive.pid", DETACHED_DIR);
+    let stop = format!(
+        "hz_pid=$(cat {f} 2>/dev/null); [ -n \"$hz_pid\" ] && ps -p \"$hz_pid\" -o comm= | grep -q caffeinate && kill \"$hz_pid\"; rm -f {f}",
+        f = pidfile
+    );
+    if duration_secs == 0 {
+        format!("{}; echo stopped", stop)
+    } else {
+        format!(
+            "{}; mkdir -p {}; nohup caffeinate -i -t {} >/dev/null 2>&1 & echo $! > {}; echo started",
+            stop, DETACHED_DIR, duration_secs, pidfile
+        )
+    }
+}
+
+/// Executing a remote command and streaming stdout/stderr to the frontend. Both are read on this
+/// thread in turn, so lines reach the log in the order they arrived, each tagged with its stream.
+pub(crate) fn run_remote_command(
     sess: &Session, 
     command: &str, 
-    app: &tauri::AppHandle, 
-    event_name: &str,
+    build: &BuildChannel,
     log_buffer: Option<&Arc<Mutex<String>>>
-) -> Result<(), String> {
+) -> Result<(), HyperZenithError> {
     let mut channel = sess.channel_session()
         .map_err(|e| format!("Failed to open channel: {}", e))?;
     
     channel.exec(command)
         .map_err(|e| format!("Failed to exec command: {}", e))?;
 
-    let mut buffer = [0u8; 1024];
-    loop {
-        let bytes_read = channel.read(&mut buffer).unwrap_or(0);
-        if bytes_read == 0 { break; }
-        
-        let output = String::from_utf8_lossy(&buffer[..bytes_read]);
-        let _ = app.emit(event_name, output.to_string());
-        
+    let emit = |stream: Stream, text: &str| {
+        let line = build.output(stream, text);
+
         // Capture log if buffer is provided
         if let Some(buf) = log_buffer {
             if let Ok(mut lock) = buf.lock() {
-                lock.push_str(&output);
+                lock.push_str(&format!("{}\n", line));
             }
         }
+    };
+
+    // Non-blocking so a quiet stdout doesn't hold up stderr and the other way round
+    sess.set_blocking(false);
+    let mut splitter = crate::process::LineSplitter::default();
+    let mut buffer = [0u8; 16 * 1024];
+    'read: loop {
+        let mut idle = true;
+        for stream in [Stream::Stdout, Stream::Stderr] {
+            let read = match stream {
+                Stream::Stderr => channel.stderr().read(&mut buffer),
+                _ => channel.read(&mut buffer),
+            };
+            match read {
+                Ok(0) => {},
+                Ok(n) => {
+                    idle = false;
+                    for text in splitter.push(stream, &buffer[..n]) {
+                        emit(stream, &text);
+                    }
+                },
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {},
+                Err(_) => break 'read,
+            }
+        }
+        if idle {
+            if channel.eof() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+    }
+    sess.set_blocking(true);
+    for (stream, text) in splitter.finish() {
+        emit(stream, &text);
     }
 
+    build.flush();
     channel.wait_close().ok();
     let exit_status = channel.exit_status().unwrap_or(-1);
 
     if exit_status != 0 {
-        return Err(format!("Command failed with exit code: {}", exit_status));
+        return Err(HyperZenithError::RemoteCommandFailed { exit_code: exit_status });
     }
     Ok(())
 }
 
-/// Synchronize files using rsync (Expects rsync in Windows PATH)
-pub fn sync_files(local_path: &str, config: &MacConfig, remote_path: &str) -> Result<(), String> {
-    let (ip, port) = parse_ip_and_port(&config.ip);
+/// rsync in WSL. Remote paths are quoted for the Mac's shell here, so the escaping rsync 3.2.4+
+/// adds on its own is turned off; older versions ignore the variable and never escape.
+fn wsl_rsync() -> Command {
+    let mut cmd = Command::new("wsl");
+    cmd.args(["env", "RSYNC_OLD_ARGS=1", "rsync"]);
+    cmd
+}
+
+/// `user@host:path` with the path quoted for the Mac's shell, `~/` left for it to expand
+fn rsync_destination(remote: &str, remote_path: &str) -> String {
+    format!("{}:{}", remote, crate::sh_quote_path(remote_path))
+}
+
+/// Push the contents of the Windows folder `local_path` into `remote_path` with rsync in WSL,
+/// returning its stdout. `extra` flags go ahead of the shared ones, e.g. `--dry-run` for a preview.
+/// A tracked build can have the transfer killed from under it.
+fn rsync_upload(local_path: &str, config: &MacConfig, remote_path: &str, extra: &[&str], pipeline: Option<&IosPipeline>) -> Result<String, HyperZenithError> {
+    // Reported like a missing rsync so callers fall back to SFTP, which can use the password
+    if keyless_jump(config) {
+        return Err(HyperZenithError::ToolMissing { tool: "rsync".to_string() });
+    }
+    // Trailing slash: copy the folder's contents, not the folder itself
+    let source = format!("{}/", crate::windows_to_wsl_path(local_path).trim_end_matches('/'));
     
     // SSH options string with optional key support
-    let ssh_opts_str = format!("ssh {}", get_ssh_options(port, &config.ssh_key_path));
-    let destination = format!("{}@{}:{}", config.username, ip, remote_path);
+    let (remote, ssh_opts_str) = rsync_remote(config)?;
+    let destination = rsync_destination(&remote, remote_path);
 
-    let output = Command::new("wsl")
-        .args(&[
-            "rsync",
+    let child = wsl_rsync()
+        .args(extra)
+        .args(config.sync.flags())
+        .args([
             "-avz",
             "--timeout=120",  // Fail if transfer stalls for 2 minutes
             "-e", &ssh_opts_str,
-            "--exclude", "node_modules", 
-            "--exclude", ".git", 
-            "--exclude", "android",
-            "--exclude", "ios/Pods",        // Save bandwidth: let remote 'pod install' handle this
-            "--exclude", "ios/build",       // Don't sync local build artifacts
-            "--exclude", "ios/DerivedData", // Don't sync intermediate build files
-            "--exclude", "ios/.xcode.env.local", // Machine-specific config
-            local_path, 
-            &destination
         ])
-        .output()
-        .map_err(|e| format!("Rsync (via WSL) failed: {}", e))?;
+        .args(crate::sftp::SYNC_EXCLUDES.iter().map(|pattern| format!("--exclude={}", pattern)))
+        .args([&source, &destination])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| HyperZenithError::spawn("wsl", e))?;
+    if let Some(pipeline) = pipeline {
+        pipeline.enter(IosStage::Syncing { pid: child.id() })?;
+    }
+    let output = child.wait_with_output();
+    // The pid is gone now and may be reused, a cancel from here on must not kill it
+    if let Some(pipeline) = pipeline {
+        pipeline.enter(IosStage::Idle)?;
+    }
+    let output = output.map_err(|e| HyperZenithError::spawn("wsl", e))?;
 
+    // WSL is there but its distro has no rsync, or there's no distro at all
+    if output.status.code() == Some(127) || (!output.status.success() && crate::wsl::installed_distros().is_ok_and(|d| d.is_empty())) {
+        return Err(HyperZenithError::ToolMissing { tool: "rsync".to_string() });
+    }
     if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+        return Err(HyperZenithError::SyncFailed { reason: String::from_utf8_lossy(&output.stderr).to_string() });
     }
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SyncMethod {
+    Rsync,
+    /// Over the SSH session itself, for machines without WSL or without rsync in it
+    Sftp,
+}
+
+/// Synchronize files using rsync in WSL, falling back to SFTP when that isn't available.
+/// The contents of `local_path` land directly in `remote_path`, which the build then `cd`s into.
+pub fn sync_files(local_path: &str, config: &MacConfig, remote_path: &str, pipeline: &IosPipeline) -> Result<SyncMethod, HyperZenithError> {
+    match rsync_upload(local_path, config, remote_path, &[], Some(pipeline)) {
+        Ok(_) => Ok(SyncMethod::Rsync),
+        Err(HyperZenithError::WslMissing | HyperZenithError::ToolMissing { .. }) => {
+            let (sess, _) = tracked_session(config, pipeline)?;
+            let plan = crate::sftp::plan(&sess, Path::new(local_path), remote_path, config.sync.delete)?;
+            crate::sftp::apply(&sess, &plan, remote_path, &config.sync)?;
+            Ok(SyncMethod::Sftp)
+        },
+        Err(e) => Err(e),
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct SyncFile {
+    pub path: String,
+    pub bytes: u64,
+    /// Not on the Mac yet, as opposed to changed since the last sync
+    pub is_new: bool,
+}
+
+/// Bytes to send per top-level folder, where an accidentally included asset dump shows up
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct SyncFolder {
+    pub path: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SyncPreview {
+    /// Largest first
+    pub files: Vec<SyncFile>,
+    pub folders: Vec<SyncFolder>,
+    pub total_bytes: u64,
+    /// Remote files `--delete` would remove
+    pub deleted: Vec<String>,
+}
+
+/// Largest files first, totalled per top-level folder
+fn summarize(mut files: Vec<SyncFile>, deleted: Vec<String>) -> SyncPreview {
+    files.sort_by_key(|f| std::cmp::Reverse(f.bytes));
+
+    let mut folders: Vec<SyncFolder> = Vec::new();
+    for file in &files {
+        let top = match file.path.split_once('/') {
+            Some((dir, _)) => format!("{}/", dir),
+            None => file.path.clone(),
+        };
+        match folders.iter_mut().find(|f| f.path == top) {
+            Some(folder) => {
+                folder.bytes += file.bytes;
+                folder.files += 1;
+            },
+            None => folders.push(SyncFolder { path: top, bytes: file.bytes, files: 1 }),
+        }
+    }
+    folders.sort_by_key(|f| std::cmp::Reverse(f.bytes));
+
+    SyncPreview { total_bytes: files.iter().map(|f| f.bytes).sum(), files, folders, deleted }
+}
+
+/// Parse `%i %l %n` lines from `rsync --itemize-changes`; only files being sent count
+fn parse_itemized(output: &str) -> SyncPreview {
+    let files: Vec<SyncFile> = output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let changes = parts.next()?;
+            let bytes = parts.next()?.parse().ok()?;
+            let path = parts.next()?;
+            changes.starts_with("<f").then(|| SyncFile { path: path.to_string(), bytes, is_new: changes.contains("+++") })
+        })
+        .collect();
+
+    // Deletions come through as `*deleting` in place of the change flags
+    let deleted = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("*deleting"))
+        .map(|rest| {
+            let rest = rest.trim_start();
+            match rest.split_once(' ') {
+                Some((len, path)) if len.parse::<u64>().is_ok() => path.to_string(),
+                _ => rest.to_string(),
+            }
+        })
+        .collect();
+
+    summarize(files, deleted)
+}
+
+/// What `sync_files` would upload, without sending anything
+pub fn preview_sync(local_path: &str, config: &MacConfig, remote_path: &str) -> Result<SyncPreview, HyperZenithError> {
+    match rsync_upload(local_path, config, remote_path, &["--dry-run", "--itemize-changes", "--out-format=%i %l %n"], None) {
+        Ok(output) => Ok(parse_itemized(&output)),
+        Err(HyperZenithError::WslMissing | HyperZenithError::ToolMissing { .. }) => {
+            let sess = create_session(config)?;
+            let plan = crate::sftp::plan(&sess, Path::new(local_path), remote_path, config.sync.delete)?;
+            let files = plan.upload.into_iter().map(|(f, is_new)| SyncFile { path: f.rel, bytes: f.bytes, is_new }).collect();
+            Ok(summarize(files, plan.delete))
+        },
+        Err(e) => Err(e),
+    }
+}
+
+/// Free space an Xcode build needs on the Mac: DerivedData, Pods and the synced sources add up fast
+pub const MIN_FREE_BYTES: u64 = 10_000_000_000;
+
+/// Free space on the volume holding a path, from `df -Pk`
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct VolumeSpace {
+    pub path: String,
+    pub mount: String,
+    pub free_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Parse `@path` markers each followed by the data line of `df -Pk`
+fn parse_df(output: &str) -> Vec<VolumeSpace> {
+    let mut volumes = Vec::new();
+    let mut path = None;
+    for line in output.lines() {
+        if let Some(p) = line.strip_prefix('@') {
+            path = Some(p.trim().to_string());
+            continue;
+        }
+        // Filesystem names and mount points may contain spaces, the capacity column anchors the rest
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let Some(cap) = fields.iter().rposition(|f| f.ends_with('%')) else { continue };
+        if cap < 4 {
+            continue;
+        }
+        let (Ok(total_kb), Ok(free_kb)) = (fields[cap - 3].parse::<u64>(), fields[cap - 1].parse::<u64>()) else { continue };
+        if let Some(path) = path.take() {
+            volumes.push(VolumeSpace {
+                path,
+                mount: fields[cap + 1..].join(" "),
+                free_bytes: free_kb * 1024,
+                total_bytes: total_kb * 1024,
+            });
+        }
+    }
+    volumes
+}
+
+/// Check the volumes holding the project and DerivedData before spending minutes on a sync.
+/// Paths that don't exist yet are measured at their nearest existing parent.
+pub fn check_disk_space(build: &BuildChannel, config: &MacConfig, remote_path: &str) -> Result<Vec<VolumeSpace>, HyperZenithError> {
+    let sess = create_session(config)?;
+    let script = format!(
+        "for p in {} ~/Library/Developer/Xcode/DerivedData; do \
+            d=\"$p\"; while [ ! -d \"$d\" ]; do d=$(dirname \"$d\"); done; \
+            echo \"@$p\"; df -Pk \"$d\" | tail -1; \
+        done",
+        crate::sh_quote_path(remote_path)
+    );
+    let mut volumes = parse_df(&run_remote_capture(&sess, &script)?);
+    volumes.dedup_by(|b, a| a.mount == b.mount);
+
+    for volume in &volumes {
+        build.line(format!(
+            "💽 {:.1} GB free of {:.0} GB on {} ({})",
+            volume.free_bytes as f64 / 1e9,
+            volume.total_bytes as f64 / 1e9,
+            volume.mount,
+            volume.path
+        ));
+    }
+    match volumes.iter().find(|v| v.free_bytes < MIN_FREE_BYTES) {
+        Some(low) => Err(HyperZenithError::LowDiskSpace {
+            host: config.ip.clone(),
+            path: low.path.clone(),
+            free_bytes: low.free_bytes,
+            required_bytes: MIN_FREE_BYTES,
+        }),
+        None => Ok(volumes),
+    }
+}
+
+/// Files the Mac regenerates during a build that belong in the local tree, relative to the app
+pub const DEFAULT_PULL_BACK: [&str; 1] = ["ios/Podfile.lock"];
+
+/// Names rsync reports with `--out-format=%n`, minus the directories `--relative` implies
+fn parse_pulled(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.ends_with('/'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Reverse sync: copy selected files the Mac generated (`pod install`, prebuild) back into the
+/// local app folder so they can be committed. Files missing on the Mac are skipped.
+pub fn pull_generated_files(local_path: &str, config: &MacConfig, remote_path: &str, files: &[String]) -> Result<Vec<String>, HyperZenithError> {
+    if let Some(bad) = files.iter().find(|f| f.is_empty() || f.starts_with('/') || f.split(['/', '\\']).any(|part| part == "..")) {
+        return Err(HyperZenithError::InvalidInput(format!("'{}' must be a path inside the project", bad)));
+    }
+    let (remote, ssh_opts_str) = rsync_remote(config)?;
+    // `/./` marks where the relative path starts, later sources reuse the host with a leading `:`
+    let sources: Vec<String> = files
+        .iter()
+        .enumerate()
+        .map(|(i, file)| {
+            let host = if i == 0 { remote.clone() } else { String::new() };
+            format!("{}:{}/./{}", host, crate::sh_quote_path(remote_path.trim_end_matches('/')), crate::sh_quote(&file.replace('\\', "/")))
+        })
+        .collect();
+    let destination = format!("{}/", local_path.trim_end_matches('/'));
+
+    let output = wsl_rsync()
+        .args(["-az", "--relative", "--timeout=120", "--out-format=%n", "-e", &ssh_opts_str])
+        .args(&sources)
+        .arg(&destination)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("wsl", e))?;
+
+    // 23: some files didn't exist on the Mac, the rest still came across
+    if !output.status.success() && output.status.code() != Some(23) {
+        return Err(HyperZenithError::SyncFailed { reason: String::from_utf8_lossy(&output.stderr).to_string() });
+    }
+    Ok(parse_pulled(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Shell prefix that installs dependencies on the Mac and leaves it in the app's `ios` folder.
+/// 1. Lockfile present: strict install with the project's package manager (npm ci, --frozen-lockfile, ...)
+/// 2. No lockfile: plain install (Compatible with "simple" hacking)
+/// 3. EXPLICIT POD INSTALL: Ensure native bindings are linked before Xcode build
+///
+/// Installs run at the synced root (the workspace root in a monorepo), pods in the app itself
+pub(crate) fn prepare_cmd(remote_path: &str, app_subdir: &str, toolchain: &crate::node::Toolchain) -> String {
+    let hydration_cmd = format!("{}; \
+    cd {} || exit 1; \
+    if [ -d 'ios' ]; then \
+        cd ios; \
+        echo '>> verifying pods...'; \
+        if [ ! -d 'Pods' ]; then \
+           echo '>> Initializing Pods...'; \
+           pod install; \
+        fi; \
+        cd ..; \
+    fi", crate::node::hydrate_script(toolchain.package_manager), crate::sh_quote(if app_subdir.is_empty() { "." } else { app_subdir }));
+
+    format!(
+        "{node_env}cd {path} && {hydration} && cd ios",
+        node_env = toolchain.env_script(),
+        path = crate::sh_quote_path(remote_path),
+        hydration = hydration_cmd
+    )
 }
 
 /// The "Turbo" Build Logic for iOS with Pre-flight Checks & Resilient Install
+#[allow(clippy::too_many_arguments)]
 pub fn execute_turbo_ios(
-    app: tauri::AppHandle, 
+    build: &BuildChannel, 
+    working_dir: &str,
     config: MacConfig, 
     remote_path: String,
+    app_subdir: &str,
     scheme: String,
-    build_type: String
-) -> Result<String, String> {
-    let sess = create_session(&config)?;
+    build_type: String,
+    toolchain: &crate::node::Toolchain,
+    pipeline: &IosPipeline
+) -> Result<String, HyperZenithError> {
+    let (sess, socket) = tracked_session(&config, pipeline)?;
 
     // --- FEATURE 2: RESTRICTED SHELL DETECTION (Pre-flight Check) ---
-    let _ = app.emit("build-output", "🔍 Running pre-flight environment check...".to_string());
+    build.line("🔍 Running pre-flight environment check...".to_string());
     
     let pre_flight_cmd = "which xcodebuild || echo 'XCODE_NOT_FOUND'";
     let mut channel = sess.channel_session()
@@ -187,10 +910,18 @@ pub fn execute_turbo_ios(
     channel.wait_close().ok();
     
     if pre_flight_output.contains("XCODE_NOT_FOUND") {
-        let _ = app.emit("build-output", "❌ Pre-flight FAILED: 'xcodebuild' not found in PATH".to_string());
-        return Err("Remote environment invalid: 'xcodebuild' not found in PATH. Check if Xcode is installed and CLI tools are configured.".to_string());
+        build.line("❌ Pre-flight FAILED: 'xcodebuild' not found in PATH".to_string());
+        return Err(HyperZenithError::XcodebuildMissing);
+    }
+    build.line("✅ Pre-flight passed: xcodebuild found".to_string());
+
+    let node_env = toolchain.env_script();
+    if toolchain.node.is_some() {
+        let reported = run_remote_capture(&sess, &format!("{}node --version", node_env)).ok();
+        if let Some(message) = toolchain.check_message(reported.as_deref(), "the Mac") {
+            build.line(message);
+        }
     }
-    let _ = app.emit("build-output", "✅ Pre-flight passed: xcodebuild found".to_string());
 
     // Set destination based on build type
     let destination = if build_type == "device" {
@@ -199,32 +930,11 @@ pub fn execute_turbo_ios(
         "platform=iOS Simulator,name=iPhone 15"
     };
 
-    // --- FEATURE 3: RESILIENT NPM INSTALL (SMART FALLBACK) ---
-    // 1. If package-lock.json exists: Use 'npm ci --prefer-offline' (Best for CI/speed/stability)
-    // 2. If NO package-lock.json: Fallback to 'npm install' (Compatible with "simple" hacking)
-    // 3. EXPLICIT POD INSTALL: Ensure native bindings are linked before Xcode build
-    let hydration_cmd = "if [ ! -d 'node_modules' ]; then \
-        if [ -f 'package-lock.json' ]; then \
-            echo '>> Hydrating with npm ci (Strict)...'; \
-            npm ci --prefer-offline; \
-        else \
-            echo '>> Hydrating with npm install (Fallback)...'; \
-            npm install; \
-        fi \
-    fi; \
-    if [ -d 'ios' ]; then \
-        cd ios; \
-        echo '>> verifying pods...'; \
-        if [ ! -d 'Pods' ]; then \
-           echo '>> Initializing Pods...'; \
-           pod install; \
-        fi; \
-        cd ..; \
-    fi";
-
-    // Construct the "Turbo" Command with Pre-Hydration & High-Performance Flags
+    // Construct the "Turbo" Command with Pre-Hydration & High-Performance Flags.
+    // The project's variables reach it through the detached build's env file, not the script.
+    let script_toolchain = toolchain.clone().with_env(String::new());
     let build_cmd = format!(
-        "cd {path} && {hydration} && cd ios && \
+        "{prepare} && \
         xcodebuild -workspace {scheme}.xcworkspace \
         -scheme {scheme} \
         -configuration Debug \
@@ -232,76 +942,394 @@ pub fn execute_turbo_ios(
         COMPILER_INDEX_STORE_ENABLE=NO \
         DEBUG_INFORMATION_FORMAT=dwarf \
         RCT_NO_LAUNCH_PACKAGER=1",
-        path = remote_path,
-        hydration = hydration_cmd,
+        prepare = prepare_cmd(&remote_path, app_subdir, &script_toolchain),
         scheme = scheme,
         destination = destination
     );
 
-    let _ = app.emit("build-output", format!("🚀 Initializing Resilient Turbo Build on Remote Mac: {}\n", config.ip));
+    build.line("☕ Keeping the Mac awake until the build finishes".to_string());
+    build.line(format!("🖥️ Building in the '{}' session on the Mac, reattach if the connection drops", DETACHED_SESSION));
+    build.line(format!("🚀 Initializing Resilient Turbo Build on Remote Mac: {}\n", config.ip));
     
     let log_buffer = Arc::new(Mutex::new(String::new()));
-    
-    let result = run_remote_command(&sess, &build_cmd, &app, "build-output", Some(&log_buffer));
-
-    // ALWAYS write logs, regardless of success or failure
-    if let Some(home_dir) = dirs::home_dir() {
-        let log_dir = home_dir.join(".hyperzenith").join("ios_logs");
-        let _ = std::fs::create_dir_all(&log_dir);
-        
-        let prefix = if result.is_ok() { "ios_build_success" } else { "ios_build_fail" };
-        let log_path = log_dir.join(format!("{}_{}.log", prefix, Local::now().format("%Y-%m-%d_%H-%M-%S")));
-        
-        if let Ok(content) = log_buffer.lock() {
-            let _ = std::fs::write(&log_path, content.clone());
-            let _ = app.emit("build-output", format!("📄 Log saved to: {}", log_path.display()));
-        }
+    pipeline.enter(IosStage::Building { socket, config: config.clone() })?;
+
+    let result = run_remote_command(&sess, &detached_build_cmd(&build_cmd, &toolchain.env_exports), build, Some(&log_buffer))
+        .map_err(|e| match e {
+            HyperZenithError::RemoteCommandFailed { exit_code: BUILD_RUNNING_EXIT } => HyperZenithError::InvalidInput(
+                "Another iOS build is still running on the Mac. Reattach to follow it".to_string(),
+            ),
+            e => e,
+        });
+
+    // ALWAYS write logs, regardless of success or failure, next to the project's Android ones
+    let prefix = if result.is_ok() { "ios_build_success" } else { "ios_build_fail" };
+    if let Ok(content) = log_buffer.lock() {
+        let log_path = crate::write_build_log(working_dir, prefix, &content);
+        build.line(format!("📄 Log saved to: {}", log_path.display()));
     }
 
     match result {
-        Ok(_) => Ok("iOS Build Completed Successfully via Satellite".to_string()),
+        Ok(_) => Ok(crate::i18n::tr(crate::i18n::Msg::IosBuildCompleted, &[])),
         Err(e) => Err(e),
     }
 }
 
+/// Cleanup targets of the iOS nuke that can be previewed and individually deselected
+pub const NUKE_TARGETS: [&str; 5] = ["derived_data", "cocoapods_cache", "pods", "simulators", "rn_temp"];
+
+/// Remote location(s) freed by each nuke target, as shown to the user
+fn nuke_target_path(id: &str, remote_path: &str) -> Option<String> {
+    match id {
+        "derived_data" => Some("~/Library/Developer/Xcode/DerivedData".to_string()),
+        "cocoapods_cache" => Some("~/Library/Caches/CocoaPods".to_string()),
+        "pods" => Some(format!("{}/ios/Pods", remote_path)),
+        "simulators" => Some("~/Library/Developer/CoreSimulator/Devices".to_string()),
+        "rn_temp" => Some("$TMPDIR/react-* $TMPDIR/metro-*".to_string()),
+        _ => None,
+    }
+}
+
+/// Run a remote command and collect its stdout instead of streaming it
+pub(crate) fn run_remote_capture(sess: &Session, command: &str) -> Result<String, HyperZenithError> {
+    let mut channel = sess.channel_session()
+        .map_err(|e| format!("Failed to open channel: {}", e))?;
+    channel.exec(command)
+        .map_err(|e| format!("Failed to exec command: {}", e))?;
+
+    let mut output = String::new();
+    channel.read_to_string(&mut output).ok();
+    channel.wait_close().ok();
+    Ok(output)
+}
+
+/// Save what a remote command writes to stdout (`cat`, `tar -c`) as the local file `target`
+pub(crate) fn download_remote(sess: &Session, command: &str, target: &Path) -> Result<(), HyperZenithError> {
+    let mut channel = sess.channel_session()
+        .map_err(|e| format!("Failed to open channel: {}", e))?;
+    channel.exec(command)
+        .map_err(|e| format!("Failed to exec command: {}", e))?;
+
+    let mut bytes = Vec::new();
+    channel.read_to_end(&mut bytes)?;
+    channel.wait_close().ok();
+    let exit_code = channel.exit_status().unwrap_or(-1);
+    if exit_code != 0 || bytes.is_empty() {
+        return Err(HyperZenithError::RemoteCommandFailed { exit_code });
+    }
+    std::fs::write(target, bytes).map_err(|e| HyperZenithError::io_at(target, e))
+}
+
+/// Parse "id|files|kilobytes" lines produced by the nuke preview script
+fn parse_nuke_preview(output: &str, remote_path: &str) -> Vec<crate::NukeTarget> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().split('|');
+            let id = parts.next()?;
+            let files: u64 = parts.next()?.trim().parse().ok()?;
+            let kb: u64 = parts.next()?.trim().parse().unwrap_or(0);
+            Some(crate::NukeTarget {
+                id: id.to_string(),
+                path: nuke_target_path(id, remote_path)?,
+                files,
+                bytes: kb * 1024,
+            })
+        })
+        .filter(|t| t.files > 0 || t.bytes > 0)
+        .collect()
+}
+
+/// Measure what the nuke would delete on the Mac without touching anything
+/// One `id|files|kilobytes` line per target. The project's Pods path is quoted; the fixed
+/// `~` and `$TMPDIR` locations stay bare on purpose so the shell expands them and their globs.
+fn nuke_preview_script(remote_path: &str, targets: &[String]) -> String {
+    targets
+        .iter()
+        .filter_map(|id| {
+            let p = match id.as_str() {
+                "pods" => crate::sh_quote_path(&format!("{}/ios/Pods", remote_path)),
+                _ => nuke_target_path(id, remote_path)?,
+            };
+            Some(format!(
+                "echo \"{id}|$(find {p} -type f 2>/dev/null | wc -l)|$(du -skc {p} 2>/dev/null | tail -1 | cut -f1)\";",
+                id = id,
+                p = p
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn preview_nuke_ios(config: &MacConfig, remote_path: &str, targets: &[String]) -> Result<Vec<crate::NukeTarget>, HyperZenithError> {
+    let sess = create_session(config)?;
+    let output = run_remote_capture(&sess, &nuke_preview_script(remote_path, targets))?;
+    Ok(parse_nuke_preview(&output, remote_path))
+}
+
+/// Xcode and CocoaPods caches measured by the Mac cache report: (id, label, path).
+/// Ids shared with `NUKE_TARGETS` can be cleaned selectively from the report.
+const MAC_CACHES: [(&str, &str, &str); 6] = [
+    ("derived_data", "DerivedData", "~/Library/Developer/Xcode/DerivedData"),
+    ("simulators", "Simulator devices", "~/Library/Developer/CoreSimulator/Devices"),
+    ("simulator_runtimes", "Simulator runtime images", "/Library/Developer/CoreSimulator/Images"),
+    ("archives", "Xcode archives", "~/Library/Developer/Xcode/Archives"),
+    ("device_support", "iOS DeviceSupport", "~/Library/Developer/Xcode/iOS DeviceSupport"),
+    ("cocoapods_cache", "CocoaPods cache", "~/Library/Caches/CocoaPods"),
+];
+
+/// Parse "id|kilobytes" lines, missing folders print nothing
+fn parse_cache_sizes(output: &str) -> Vec<crate::cache::CacheEntry> {
+    MAC_CACHES
+        .iter()
+        .map(|(id, label, path)| {
+            let kb = output
+                .lines()
+                .filter_map(|line| line.trim().split_once('|'))
+                .find(|(found, _)| found == id)
+                .and_then(|(_, kb)| kb.trim().parse::<u64>().ok());
+            crate::cache::CacheEntry {
+                id: id.to_string(),
+                label: label.to_string(),
+                path: path.to_string(),
+                size_bytes: kb.unwrap_or(0) * 1024,
+                exists: kb.is_some(),
+            }
+        })
+        .collect()
+}
+
+/// Size up the Mac's Xcode caches in one round trip, largest first, so cleaning can be selective
+pub fn mac_cache_report(config: &MacConfig) -> Result<Vec<crate::cache::CacheEntry>, HyperZenithError> {
+    let sess = create_session(config)?;
+    let script = MAC_CACHES
+        .iter()
+        .map(|(id, _, path)| {
+            let path = crate::sh_quote_path(path);
+            format!("[ -d {p} ] && echo \"{id}|$(du -sk {p} 2>/dev/null | cut -f1)\";", id = id, p = path)
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut entries = parse_cache_sizes(&run_remote_capture(&sess, &format!("{} true", script))?);
+    entries.sort_by_key(|e| std::cmp::Reverse(e.size_bytes));
+    Ok(entries)
+}
+
 /// The "Nuclear" Recovery Sequence for iOS
 pub fn nuke_ios_remote(
-    app: tauri::AppHandle, 
+    build: &BuildChannel, 
     config: MacConfig,
-    remote_path: String
-) -> Result<String, String> {
+    remote_path: String,
+    targets: Vec<String>
+) -> Result<String, HyperZenithError> {
     let sess = create_session(&config)?;
-    let _ = app.emit("build-output", "☢️ Initiating NUCLEAR iOS Recovery Sequence...\n".to_string());
-
-    let nuke_cmd = format!(
-        "set -e; \
-        echo 'Step 1: Killing Processes...'; \
-        killall Xcode xcodebuild CoreSimulatorBridge || true; \
-        
-        echo 'Step 2: Cleaning Project...'; \
-        cd {path}/ios && xcodebuild clean; \
-        
-        echo 'Step 3: Purging DerivedData...'; \
-        rm -rf ~/Library/Developer/Xcode/DerivedData/*; \
-        
-        echo 'Step 4: Purging CocoaPods Caches (Global & Local)...'; \
-        rm -rf ~/Library/Caches/CocoaPods; \
-        rm -rf Pods Podfile.lock; \
-        
-        echo 'Step 5: Resetting Simulators...'; \
-        xcrun simctl erase all; \
-        
-        echo 'Step 6: Cleaning React Native Temp...'; \
-        rm -rf $TMPDIR/react-* $TMPDIR/metro-*; \
-        watchman watch-del-all || true; \
-
-        echo 'Step 7: Re-Hydrating...'; \
-        pod install --repo-update; \
-        echo '✅ NUKE COMPLETE';",
-        path = remote_path
-    );
+    build.line("☢️ Initiating NUCLEAR iOS Recovery Sequence...\n".to_string());
+
+    let selected = |id: &str| targets.iter().any(|t| t == id);
+    let mut steps = vec![
+        ("Killing Processes...", "killall Xcode xcodebuild CoreSimulatorBridge || true;".to_string()),
+        ("Cleaning Project...", format!("cd {} && xcodebuild clean;", crate::sh_quote_path(&format!("{}/ios", remote_path)))),
+    ];
+
+    if selected("derived_data") {
+        steps.push(("Purging DerivedData...", "rm -rf ~/Library/Developer/Xcode/DerivedData/*;".to_string()));
+    }
+    if selected("cocoapods_cache") {
+        steps.push(("Purging CocoaPods Cache (Global)...", "rm -rf ~/Library/Caches/CocoaPods;".to_string()));
+    }
+    if selected("pods") {
+        steps.push(("Purging Pods (Local)...", "rm -rf Pods Podfile.lock;".to_string()));
+    }
+    if selected("simulators") {
+        steps.push(("Resetting Simulators...", "xcrun simctl erase all;".to_string()));
+    }
+    if selected("rn_temp") {
+        steps.push(("Cleaning React Native Temp...", "rm -rf $TMPDIR/react-* $TMPDIR/metro-*; watchman watch-del-all || true;".to_string()));
+    }
+    if selected("pods") || selected("cocoapods_cache") {
+        steps.push(("Re-Hydrating...", "pod install --repo-update;".to_string()));
+    }
+
+    let mut nuke_cmd = String::from("set -e; ");
+    for (i, (label, cmd)) in steps.iter().enumerate() {
+        nuke_cmd.push_str(&format!("echo 'Step {}: {}'; {} ", i + 1, label, cmd));
+    }
+    nuke_cmd.push_str("echo '✅ NUKE COMPLETE';");
 
-    run_remote_command(&sess, &nuke_cmd, &app, "build-output", None)?;
+    run_remote_command(&sess, &keep_awake(&nuke_cmd), build, None)?;
 
     Ok("Recovery Sequence Finished".to_string())
 }
+
+/// Follow a detached build from its log, e.g. after HyperZenith was closed or the Wi-Fi dropped.
+/// Replays the output so far, then streams the rest until the build finishes.
+pub fn reattach_ios_build(build: &BuildChannel, config: MacConfig) -> Result<String, HyperZenithError> {
+    let sess = create_session(&config)?;
+    build.line(format!("🔌 Reattaching to the iOS build on {}...", config.ip));
+    run_remote_command(&sess, &follow_detached_cmd(), build, None)?;
+    Ok(crate::i18n::tr(crate::i18n::Msg::IosBuildCompleted, &[]))
+}
+
+/// Stop the Mac from idle-sleeping for a while, e.g. before a long archive build driven from elsewhere
+pub fn keep_mac_awake(config: &MacConfig, duration_secs: u64) -> Result<String, HyperZenithError> {
+    let sess = create_session(config)?;
+    let output = run_remote_capture(&sess, &keepalive_cmd(duration_secs))?;
+    if !output.contains("started") && !output.contains("stopped") {
+        return Err(HyperZenithError::CommandFailed { command: "caffeinate".to_string(), output: output.trim().to_string() });
+    }
+    Ok(if duration_secs == 0 {
+        format!("{} may sleep again", config.ip)
+    } else {
+        format!("{} will stay awake for {} min", config.ip, duration_secs.div_ceil(60))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_host_and_port() {
+        assert_eq!(parse_host_and_port("192.168.1.20").unwrap(), ("192.168.1.20", 22));
+        assert_eq!(parse_host_and_port(" studio.local:2222 ").unwrap(), ("studio.local", 2222));
+        assert_eq!(parse_host_and_port("mac-mini.tailnet-1234.ts.net").unwrap(), ("mac-mini.tailnet-1234.ts.net", 22));
+        assert_eq!(parse_host_and_port("[fd7a:115c:a1e0::1]:2200").unwrap(), ("fd7a:115c:a1e0::1", 2200));
+        assert_eq!(parse_host_and_port("fe80::1").unwrap(), ("fe80::1", 22));
+        assert!(parse_host_and_port("mac:ssh").is_err());
+        assert!(parse_host_and_port("fd7a::1:zz").is_err());
+        assert!(parse_host_and_port(":22").is_err());
+
+        let v6: SocketAddr = "[fd7a::1]:22".parse().unwrap();
+        assert_eq!(rsync_host(&v6), "[fd7a::1]");
+    }
+
+    #[test]
+    fn test_rsync_remote_via_jump() {
+        let mut config = MacConfig {
+            ip: "studio.office.lan:2200".to_string(),
+            username: "builder".to_string(),
+            password: None,
+            ssh_key_path: None,
+            sync: SyncSettings::default(),
+            jump: Some(JumpHost { ip: "203.0.113.7".to_string(), username: "gate".to_string(), password: Some("pw".to_string()), ssh_key_path: None }),
+        };
+        assert!(keyless_jump(&config));
+        assert!(matches!(rsync_remote(&config), Err(HyperZenithError::InvalidInput(_))));
+
+        config.jump.as_mut().unwrap().ssh_key_path = Some("/home/me/.ssh/o'neil key".to_string());
+        let (remote, ssh) = rsync_remote(&config).unwrap();
+        assert_eq!(remote, "builder@studio.office.lan");
+        assert!(ssh.starts_with("ssh -p 2200 "));
+        assert!(ssh.ends_with(
+            r#" -o ProxyCommand='ssh -p 22 -o StrictHostKeyChecking=no -o UserKnownHostsFile=/dev/null -o ConnectTimeout=30 -i ''/home/me/.ssh/o''\''''neil key'' -W %h:%p gate@203.0.113.7'"#
+        ));
+
+        // An empty jump address means no jump host
+        config.ip = "10.0.0.5".to_string();
+        config.jump.as_mut().unwrap().ip = " ".to_string();
+        assert_eq!(rsync_remote(&config).unwrap(), ("builder@10.0.0.5".to_string(), format!("ssh {}", get_ssh_options(22, &None))));
+    }
+
+    #[test]
+    fn test_parse_nuke_preview() {
+        let out = "derived_data|1200|524288\npods|0|0\nbogus line\nrn_temp|3|12\n";
+        let targets = parse_nuke_preview(out, "~/proj");
+
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].id, "derived_data");
+        assert_eq!(targets[0].bytes, 524288 * 1024);
+        assert_eq!(targets[1].files, 3);
+
+        let script = nuke_preview_script("~/my proj;rm -rf ~", &["pods".to_string(), "rn_temp".to_string()]);
+        assert!(script.contains("$(find ~/'my proj;rm -rf ~/ios/Pods' -type f"));
+        assert!(script.contains("$(du -skc ~/'my proj;rm -rf ~/ios/Pods' 2>/dev/null"));
+        assert!(script.contains("$(find $TMPDIR/react-* $TMPDIR/metro-* -type f"));
+        assert_eq!(rsync_destination("me@mac", "~/my proj;x"), "me@mac:~/'my proj;x'");
+    }
+
+    #[test]
+    fn test_parse_cache_sizes() {
+        let entries = parse_cache_sizes("derived_data|2097152\narchives|\ncocoapods_cache|512\n");
+
+        assert_eq!(entries.len(), MAC_CACHES.len());
+        assert_eq!(entries[0].size_bytes, 2097152 * 1024);
+        let archives = entries.iter().find(|e| e.id == "archives").unwrap();
+        assert!(!archives.exists && archives.size_bytes == 0);
+        assert!(!entries.iter().find(|e| e.id == "simulators").unwrap().exists);
+    }
+
+    #[test]
+    fn test_parse_pulled() {
+        let out = "ios/\nios/Podfile.lock\nios/App.xcworkspace/\nios/App.xcworkspace/contents.xcworkspacedata\n\n";
+        assert_eq!(parse_pulled(out), vec!["ios/Podfile.lock".to_string(), "ios/App.xcworkspace/contents.xcworkspacedata".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_itemized() {
+        let out = "sending incremental file list\n\
+            <f+++++++++ 4200000000 assets/raw/video.mov\n\
+            cd+++++++++ 4096 assets/raw/\n\
+            <f.st...... 1500 src/App.tsx\n\
+            <f+++++++++ 900 assets/icon copy.png\n\
+            <f+++++++++ 120 package.json\n\
+            *deleting   0 src/OldScreen.tsx\n\
+            \n\
+            sent 1,234 bytes  received 56 bytes  2,580.00 bytes/sec\n\
+            total size is 4,200,002,520  speedup is 3,255,815.91 (DRY RUN)\n";
+        let preview = parse_itemized(out);
+
+        assert_eq!(preview.files.len(), 4);
+        assert_eq!(preview.total_bytes, 4200002520);
+        assert_eq!(preview.files[0], SyncFile { path: "assets/raw/video.mov".to_string(), bytes: 4200000000, is_new: true });
+        assert!(!preview.files[1].is_new);
+        assert_eq!(preview.folders[0], SyncFolder { path: "assets/".to_string(), bytes: 4200000900, files: 2 });
+        assert_eq!(preview.folders.last().unwrap().path, "package.json");
+        assert_eq!(preview.deleted, vec!["src/OldScreen.tsx".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_df() {
+        let out = "@~/hyperzenith_builds/project\n\
+            /dev/disk3s5  488245288 421000000  4100000    99%    /System/Volumes/Data\n\
+            @~/Library/Developer/Xcode/DerivedData\n\
+            map auto_home  0 0 0   100%    /Volumes/Build Disk\n";
+        let volumes = parse_df(out);
+
+        assert_eq!(volumes.len(), 2);
+        assert_eq!(volumes[0].path, "~/hyperzenith_builds/project");
+        assert_eq!(volumes[0].free_bytes, 4100000 * 1024);
+        assert_eq!(volumes[0].mount, "/System/Volumes/Data");
+        assert_eq!(volumes[1].mount, "/Volumes/Build Disk");
+    }
+
+    #[test]
+    fn test_detached_build_cmd() {
+        let cmd = detached_build_cmd("cd ~/proj && xcodebuild -destination 'generic/platform=iOS'", "export API_KEY='s3cret'; ");
+
+        assert!(cmd.starts_with("if pgrep -f 'hyperzenith/[i]os_build.sh'"));
+        // Variables go to an owner-only file the script sources and removes, never into the script
+        assert!(cmd.contains("(umask 077 && printf '%s\\n' 'export API_KEY='\\''s3cret'\\''; ' > ~/.hyperzenith/ios_build.env)"));
+        assert!(cmd.contains("printf '%s\\n' 'hz_dir=$(dirname \"$0\"); . \"$hz_dir/ios_build.env\"; rm -f \"$hz_dir/ios_build.env\"; "));
+        assert_eq!(cmd.matches("s3cret").count(), 1);
+        // The build script is written out single-quoted, its own quotes escaped
+        assert!(cmd.contains("( (command -v caffeinate"));
+        assert!(cmd.contains("-destination '\\''generic/platform=iOS'\\'' ) > \"$hz_dir/ios_build.log\" 2>&1; echo $? > \"$hz_dir/ios_build.exit\"'"));
+        assert!(cmd.contains("tmux new-session -d -s hyperzenith-ios sh ~/.hyperzenith/ios_build.sh"));
+        assert!(cmd.ends_with(&follow_detached_cmd()));
+
+        // Stopping nothing leaves the last build's exit code alone
+        assert!(stop_detached_cmd().contains("if [ -z \"$hz_killed\" ]; then echo 'not running'; else [ -f ~/.hyperzenith/ios_build.exit ] || echo 130"));
+    }
+
+    #[test]
+    fn test_keep_awake() {
+        let wrapped = keep_awake("cd ~/proj && xcodebuild");
+        assert!(wrapped.starts_with("(command -v caffeinate >/dev/null && caffeinate -i -w $$"));
+        assert!(wrapped.ends_with("; cd ~/proj && xcodebuild"));
+
+        assert!(keepalive_cmd(2400).contains("nohup caffeinate -i -t 2400 >/dev/null 2>&1 & echo $! > ~/.hyperzenith/keepalive.pid"));
+        assert!(!keepalive_cmd(0).contains("nohup"));
+        assert!(!keepalive_cmd(0).contains("pkill"));
+    }
+}
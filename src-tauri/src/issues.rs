@@ -0,0 +1,240 @@
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+/// A problem picked out of the build output
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct BuildIssue {
+    pub severity: Severity,
+    pub message: String,
+    /// Where the tool says the problem is, as printed, e.g. `/mnt/c/app/src/App.tsx:12:7`
+    pub location: Option<String>,
+    /// `location` as a Windows path that can be opened in an editor
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    /// The whole output line
+    pub text: String,
+}
+
+/// Sent on `build-issues` (and `build-issues-{id}`) in batches, alongside the output they came from
+#[derive(serde::Serialize, Clone)]
+pub struct BuildIssuesEvent {
+    pub build_id: String,
+    pub issues: Vec<BuildIssue>,
+}
+
+/// What paths in a build's output are relative to, to turn them into files on this machine
+#[derive(Clone, Default, Debug)]
+pub struct SourceRoots {
+    /// The Windows project folder: relative paths resolve against it, and a `\\wsl$` project's
+    /// distro addresses Linux paths outside `/mnt`
+    pub project: Option<String>,
+    /// The folder synced to on the Mac and the local folder it mirrors
+    pub remote: Option<(String, String)>,
+}
+
+impl SourceRoots {
+    /// A path as printed by the build, as a Windows path
+    pub fn resolve(&self, path: &str) -> String {
+        if let Some((remote, local)) = &self.remote {
+            // `~/app` on the Mac prints as `/Users/<name>/app`
+            let root = remote.trim_end_matches('/');
+            let root = root.strip_prefix('~').unwrap_or(root);
+            let rest = if remote.starts_with('~') {
+                path.find(&format!("{}/", root)).map(|index| &path[index + root.len()..])
+            } else {
+                path.strip_prefix(root).filter(|rest| rest.starts_with('/'))
+            };
+            if let Some(rest) = rest {
+                return format!("{}{}", local.trim_end_matches(['\\', '/']), rest.replace('/', "\\"));
+            }
+        }
+        let is_windows = path.get(1..3).is_some_and(|p| p == ":\\" || p == ":/") || path.starts_with("\\\\");
+        match &self.project {
+            _ if is_windows => path.replace('/', "\\"),
+            Some(project) if path.starts_with('/') => crate::wsl_to_windows_path(path, crate::wsl::project_distro(project).as_deref()),
+            Some(project) => format!("{}\\{}", project.trim_end_matches(['\\', '/']), path.trim_start_matches("./").replace('/', "\\")),
+            None => crate::wsl_to_windows_path(path, None),
+        }
+    }
+}
+
+/// Split a location into path, line and column: `file:///a/B.kt:12:5`, `a/B.java:7` or `src/App.tsx(12,7)`
+pub fn parse_location(location: &str) -> (String, Option<u32>, Option<u32>) {
+    let location = match location.strip_prefix("file://") {
+        // `file:///C:/app/...` on Windows
+        Some(url) => url.strip_prefix('/').filter(|p| p.get(1..2) == Some(":")).unwrap_or(url).replace("%20", " "),
+        None => location.to_string(),
+    };
+    if let Some((path, numbers)) = location.strip_suffix(')').and_then(|l| l.rsplit_once('(')) {
+        let mut numbers = numbers.split(',').map(|n| n.trim().parse().ok());
+        if let Some(line @ Some(_)) = numbers.next() {
+            return (path.to_string(), line, numbers.next().flatten());
+        }
+    }
+    // Numbers trail the path; a drive letter's colon is never followed by one
+    let mut path = location.as_str();
+    let mut numbers = Vec::new();
+    while numbers.len() < 2 {
+        match path.rsplit_once(':').and_then(|(rest, n)| Some((rest, n.trim().parse::<u32>().ok()?))) {
+            Some((rest, n)) => {
+                numbers.insert(0, n);
+                path = rest;
+            },
+            None => break,
+        }
+    }
+    (path.to_string(), numbers.first().copied(), numbers.get(1).copied())
+}
+
+impl BuildIssue {
+    /// Fill in `file`, `line` and `column` from the printed location
+    pub fn locate(mut self, roots: &SourceRoots) -> Self {
+        if let Some(location) = &self.location {
+            let (path, line, column) = parse_location(location);
+            self.file = Some(roots.resolve(&path));
+            self.line = line;
+            self.column = column;
+        }
+        self
+    }
+}
+
+/// `error`, `warning` or `note` as compilers spell them
+fn severity_word(word: &str) -> Option<Severity> {
+    match word.trim().to_lowercase().as_str() {
+        "error" | "fatal error" => Some(Severity::Error),
+        "warning" => Some(Severity::Warning),
+        "note" => Some(Severity::Note),
+        _ => None,
+    }
+}
+
+/// A location-looking prefix: a path with a line number after it
+fn is_location(text: &str) -> bool {
+    (text.contains('/') || text.contains('\\') || text.contains('.')) && text.chars().any(|c| c.is_ascii_digit())
+}
+
+/// `<location>: error: <message>`, as clang, swiftc, javac and ld print it
+fn after_location(line: &str) -> Option<(Severity, String, String)> {
+    for word in ["fatal error", "error", "warning", "note"] {
+        let marker = format!(": {}: ", word);
+        if let Some(index) = line.find(&marker) {
+            let location = &line[..index];
+            if is_location(location) {
+                return Some((severity_word(word)?, line[index + marker.len()..].to_string(), location.to_string()));
+            }
+        }
+    }
+    None
+}
+
+/// `src/App.tsx(12,7): error TS2322: ...` or `src/App.tsx:12:7 - error TS2322: ...`
+fn typescript(line: &str) -> Option<(Severity, String, String)> {
+    let (location, rest) = line.split_once("): ").map(|(l, r)| (format!("{})", l), r)).or_else(|| line.split_once(" - ").map(|(l, r)| (l.to_string(), r)))?;
+    let (word, _) = rest.split_once(" TS")?;
+    let severity = severity_word(word)?;
+    is_location(&location).then(|| (severity, rest.to_string(), location))
+}
+
+/// Errors, warnings and notes from Kotlin, Java, clang/Swift, TypeScript and Gradle itself.
+/// Lines that merely mention "error" aren't issues, only the formats tools print problems in.
+pub fn classify(text: &str) -> Option<BuildIssue> {
+    let line = text.trim();
+    let issue = |severity, message: &str, location: Option<String>| BuildIssue {
+        severity,
+        message: message.trim().to_string(),
+        location,
+        file: None,
+        line: None,
+        column: None,
+        text: line.to_string(),
+    };
+
+    // Kotlin: `e: file:///app/src/Main.kt:12:5 Unresolved reference: foo`
+    for (prefix, severity) in [("e: ", Severity::Error), ("w: ", Severity::Warning)] {
+        if let Some(rest) = line.strip_prefix(prefix) {
+            return Some(match rest.split_once(' ') {
+                Some((location, message)) if is_location(location) => issue(severity, message, Some(location.trim_end_matches(':').to_string())),
+                _ => issue(severity, rest, None),
+            });
+        }
+    }
+    if let Some((severity, message, location)) = after_location(line).or_else(|| typescript(line)) {
+        return Some(issue(severity, &message, Some(location)));
+    }
+    // Gradle and tools that print a bare prefix
+    for (prefix, severity) in [
+        ("FAILURE: ", Severity::Error),
+        ("ERROR: ", Severity::Error),
+        ("error: ", Severity::Error),
+        ("fatal error: ", Severity::Error),
+        ("warning: ", Severity::Warning),
+        ("WARNING: ", Severity::Warning),
+        ("Note: ", Severity::Note),
+    ] {
+        if let Some(message) = line.strip_prefix(prefix) {
+            return Some(issue(severity, message, None));
+        }
+    }
+    if line.starts_with("> Task ") && line.ends_with(" FAILED") {
+        return Some(issue(Severity::Error, line.trim_start_matches("> "), None));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let kotlin = classify("e: file:///mnt/c/app/android/app/src/main/java/com/app/MainActivity.kt:12:5 Unresolved reference: foo").unwrap();
+        assert_eq!(kotlin.severity, Severity::Error);
+        assert_eq!(kotlin.message, "Unresolved reference: foo");
+        assert_eq!(kotlin.location.as_deref(), Some("file:///mnt/c/app/android/app/src/main/java/com/app/MainActivity.kt:12:5"));
+
+        let javac = classify("/mnt/c/app/android/app/src/main/java/com/app/Foo.java:7: warning: [deprecation] bar() has been deprecated").unwrap();
+        assert_eq!((javac.severity, javac.location.as_deref()), (Severity::Warning, Some("/mnt/c/app/android/app/src/main/java/com/app/Foo.java:7")));
+
+        let clang = classify("/Users/dev/app/ios/App/AppDelegate.mm:21:3: error: use of undeclared identifier 'foo'").unwrap();
+        assert_eq!((clang.severity, clang.message.as_str()), (Severity::Error, "use of undeclared identifier 'foo'"));
+
+        let tsc = classify("src/App.tsx(12,7): error TS2322: Type 'string' is not assignable to type 'number'.").unwrap();
+        assert_eq!((tsc.severity, tsc.location.as_deref()), (Severity::Error, Some("src/App.tsx(12,7)")));
+        assert_eq!(classify("src/api.ts:3:1 - warning TS6133: 'x' is declared but never used.").unwrap().severity, Severity::Warning);
+
+        assert_eq!(classify("FAILURE: Build failed with an exception.").unwrap().location, None);
+        assert_eq!(classify("> Task :app:compileDebugKotlin FAILED").unwrap().severity, Severity::Error);
+        assert_eq!(classify("Note: Some input files use unchecked or unsafe operations.").unwrap().severity, Severity::Note);
+
+        assert_eq!(classify("> Task :app:mergeDebugResources"), None);
+        assert_eq!(classify("Downloading error-prone-2.18.0.jar"), None);
+        assert_eq!(classify("BUILD SUCCESSFUL in 42s"), None);
+    }
+
+    #[test]
+    fn test_locate() {
+        assert_eq!(parse_location("file:///mnt/c/My%20App/Main.kt:12:5"), ("/mnt/c/My App/Main.kt".to_string(), Some(12), Some(5)));
+        assert_eq!(parse_location("file:///C:/app/Main.kt:3:1"), ("C:/app/Main.kt".to_string(), Some(3), Some(1)));
+        assert_eq!(parse_location("src/App.tsx(12,7)"), ("src/App.tsx".to_string(), Some(12), Some(7)));
+        assert_eq!(parse_location("C:\\app\\Foo.java:7"), ("C:\\app\\Foo.java".to_string(), Some(7), None));
+
+        let roots = SourceRoots { project: Some("C:\\Dev\\App".to_string()), remote: Some(("~/builds/app".to_string(), "C:\\Dev\\App".to_string())) };
+        let locate = |text: &str| {
+            let issue = classify(text).unwrap().locate(&roots);
+            (issue.file.unwrap(), issue.line, issue.column)
+        };
+        assert_eq!(locate("e: file:///mnt/c/Dev/App/android/Main.kt:12:5 Unresolved reference: foo"), ("C:\\Dev\\App\\android\\Main.kt".to_string(), Some(12), Some(5)));
+        assert_eq!(locate("src/App.tsx(3,1): error TS2322: nope"), ("C:\\Dev\\App\\src\\App.tsx".to_string(), Some(3), Some(1)));
+        assert_eq!(locate("/Users/dev/builds/app/ios/App/AppDelegate.mm:21:3: error: oops"), ("C:\\Dev\\App\\ios\\App\\AppDelegate.mm".to_string(), Some(21), Some(3)));
+
+        let wsl = SourceRoots { project: Some("\\\\wsl$\\Ubuntu\\home\\dev\\app".to_string()), remote: None };
+        assert_eq!(wsl.resolve("/home/dev/app/src/a.ts"), "\\\\wsl$\\Ubuntu\\home\\dev\\app\\src\\a.ts");
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::Instant;
+use tauri::Emitter;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+/// Sent on `test-summary` (and `test-summary-{id}`) once a Jest run ends
+#[derive(serde::Serialize, Clone, Default, Debug, PartialEq)]
+pub struct TestSummary {
+    pub id: String,
+    pub success: bool,
+    pub suites_passed: u32,
+    pub suites_failed: u32,
+    pub suites_total: u32,
+    pub tests_passed: u32,
+    pub tests_failed: u32,
+    pub tests_skipped: u32,
+    pub tests_total: u32,
+    /// Test files reported as `FAIL`
+    pub failed_files: Vec<String>,
+    pub elapsed_secs: f64,
+    pub log_path: Option<String>,
+}
+
+/// Counts from a Jest summary line such as `Tests:       2 failed, 1 skipped, 10 passed, 13 total`
+fn parse_counts(rest: &str) -> Vec<(String, u32)> {
+    rest.split(',')
+        .filter_map(|part| {
+            let mut words = part.split_whitespace();
+            let count = words.next()?.parse().ok()?;
+            Some((words.next()?.to_string(), count))
+        })
+        .collect()
+}
+
+pub fn parse_summary(log: &str) -> TestSummary {
+    let mut summary = TestSummary::default();
+    for line in log.lines().map(str::trim) {
+        if let Some(file) = line.strip_prefix("FAIL ") {
+            let file = file.trim().to_string();
+            if !summary.failed_files.contains(&file) {
+                summary.failed_files.push(file);
+            }
+        } else if let Some(rest) = line.strip_prefix("Test Suites:") {
+            for (kind, count) in parse_counts(rest) {
+                match kind.as_str() {
+                    "passed" => summary.suites_passed = count,
+                    "failed" => summary.suites_failed = count,
+                    "total" => summary.suites_total = count,
+                    _ => {},
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Tests:") {
+            for (kind, count) in parse_counts(rest) {
+                match kind.as_str() {
+                    "passed" => summary.tests_passed = count,
+                    "failed" => summary.tests_failed = count,
+                    "skipped" | "todo" => summary.tests_skipped += count,
+                    "total" => summary.tests_total = count,
+                    _ => {},
+                }
+            }
+        }
+    }
+    summary
+}
+
+/// Jest inside WSL, filtered to test paths matching `pattern` when given
+pub fn jest_cmd(wsl_project_path: &str, pattern: Option<&str>, pm: crate::node::PackageManager) -> String {
+    let filter = pattern.filter(|p| !p.trim().is_empty()).map(|p| format!(" {}", crate::sh_quote(p.trim()))).unwrap_or_default();
+    format!(
+        "export CI=true && cd {} && {} 2>&1",
+        crate::sh_quote(wsl_project_path),
+        pm.run_bin(&format!("jest --ci{}", filter))
+    )
+}
+
+/// Run Jest for a project, streaming output on the channel, and emit the parsed summary
+pub async fn run(
+    channel: &BuildChannel,
+    working_dir: &str,
+    pattern: Option<&str>,
+    toolchain: &crate::node::Toolchain,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<TestSummary, HyperZenithError> {
+    let started = Instant::now();
+    let distro = crate::wsl::project_distro(working_dir);
+    let script = format!(
+        "{}{}",
+        toolchain.env_script(),
+        jest_cmd(&crate::windows_to_wsl_path(working_dir), pattern, toolchain.package_manager)
+    );
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &script);
+    if distro.is_none() {
+        cmd.current_dir(working_dir);
+    }
+
+    channel.line(format!("🧪 Running Jest{}...", pattern.map(|p| format!(" ({})", p)).unwrap_or_default()));
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    let log_path = crate::write_build_log(working_dir, "jest", &outcome.log);
+    channel.line(format!("📄 Log saved to: {}", log_path.display()));
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+
+    let summary = TestSummary {
+        id: channel.id.clone(),
+        success: outcome.success,
+        elapsed_secs: started.elapsed().as_secs_f64(),
+        log_path: Some(log_path.display().to_string()),
+        ..parse_summary(&outcome.log)
+    };
+    channel.line(format!(
+        "🧪 Suites: {} passed, {} failed · Tests: {} passed, {} failed, {} skipped",
+        summary.suites_passed, summary.suites_failed, summary.tests_passed, summary.tests_failed, summary.tests_skipped
+    ));
+    let _ = channel.app.emit(&format!("test-summary-{}", channel.id), &summary);
+    let _ = channel.app.emit("test-summary", &summary);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_summary() {
+        let log = "PASS src/utils.test.ts\n\
+                   FAIL src/api.test.ts\n  ● fetchUser › returns the user\n\n\
+                   Test Suites: 1 failed, 3 passed, 4 total\n\
+                   Tests:       2 failed, 1 skipped, 10 passed, 13 total\n\
+                   Snapshots:   0 total\n\
+                   Time:        3.21 s\n";
+        let summary = parse_summary(log);
+
+        assert_eq!((summary.suites_passed, summary.suites_failed, summary.suites_total), (3, 1, 4));
+        assert_eq!((summary.tests_passed, summary.tests_failed, summary.tests_skipped, summary.tests_total), (10, 2, 1, 13));
+        assert_eq!(summary.failed_files, vec!["src/api.test.ts".to_string()]);
+        assert_eq!(parse_summary("No tests found"), TestSummary::default());
+    }
+}
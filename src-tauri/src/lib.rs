@@ -1,18 +1,76 @@
-use std::sync::{Mutex, Arc};
-use std::process::{Command, Child, Stdio};
+use std::process::Command;
 mod ios;
+mod doctor;
+mod cache;
+mod archive;
+mod adb;
+mod apk;
+mod wsl;
+mod process;
+mod state;
+mod events;
+mod error;
+mod gradle;
+mod control;
+mod deeplink;
+mod tray;
+mod idle;
+mod power;
+mod expo;
+mod node;
+mod project;
+mod gates;
+mod jest;
+mod e2e;
+mod bundle;
+mod matrix;
+mod ota;
+mod scaffold;
+mod deps;
+mod history;
+mod sftp;
+mod xctest;
+mod simulator;
+mod fastlane;
+mod hooks;
+mod updates;
+mod settings;
+mod i18n;
+mod credentials;
+mod buildenv;
+mod changelog;
+mod compare;
+mod storage;
+mod symbols;
+mod symbolicate;
+mod memory;
+mod ports;
+mod push;
+mod recents;
+mod scan;
+mod gitignore;
+mod logs;
+mod ansi;
+mod issues;
+mod gpu;
+mod arch;
+mod resources;
+mod phases;
+mod watchdog;
+mod onboarding;
+mod presets;
+mod scripts;
+mod support;
+mod manifest;
 use std::os::windows::process::CommandExt;
-use tauri::Emitter;
-use lazy_static::lazy_static;
+use tauri::{Emitter, Manager};
 use chrono::Local;
+use state::AppState;
+use events::{BuildChannel, BuildState, Lifecycle, Verbosity};
+use error::HyperZenithError;
 
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-lazy_static! {
-    static ref ACTIVE_BUILD_HANDLE: Mutex<Option<Child>> = Mutex::new(None);
-    static ref SYSTEM_MONITOR: Mutex<sysinfo::System> = Mutex::new(sysinfo::System::new_all());
-}
-
 #[derive(serde::Serialize, Clone)]
 pub struct SystemStats {
     pub cpu_usage: Vec<f32>,
@@ -26,13 +84,44 @@ pub struct SystemStats {
 pub struct HardwareProfile {
     pub max_workers: usize,
     pub jvm_heap_gb: usize,
+    /// Logical processors, hyper-threads included
     pub cpu_cores: usize,
+    /// Physical cores by kind on hybrid chips; on others every core is a performance core
+    pub performance_cores: usize,
+    pub efficiency_cores: usize,
     pub total_ram_gb: usize,
+    pub arch: arch::HostArch,
+    /// Cores and memory other programs weren't using when the profile was taken
+    pub idle_cores: usize,
+    pub available_ram_gb: usize,
+    /// Why workers or heap were scaled back from what the machine could give an idle build
+    pub constrained_by: Option<String>,
+    /// GPU and hypervisor as the Android emulator sees them; only `get_hardware_profile` looks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emulator: Option<gpu::EmulatorSupport>,
+}
+
+impl HardwareProfile {
+    /// For the build log: what was there and what the build gets
+    pub fn summary(&self) -> String {
+        let efficiency = match self.efficiency_cores {
+            0 => String::new(),
+            n => format!("{}P+{}E, ", self.performance_cores, n),
+        };
+        let mut summary = format!(
+            "🖥️ {} cores ({}{} idle), {}GB RAM ({}GB free) → {} workers, {}GB heap",
+            self.cpu_cores, efficiency, self.idle_cores, self.total_ram_gb, self.available_ram_gb, self.max_workers, self.jvm_heap_gb
+        );
+        if let Some(reason) = &self.constrained_by {
+            summary.push_str(&format!(", scaled down: {}", reason));
+        }
+        summary
+    }
 }
 
 #[tauri::command]
-fn get_system_stats() -> SystemStats {
-    let mut sys = SYSTEM_MONITOR.lock().unwrap();
+fn get_system_stats(state: tauri::State<'_, AppState>) -> SystemStats {
+    let mut sys = state.system.lock().unwrap();
     sys.refresh_cpu();
     sys.refresh_memory();
     
@@ -45,484 +134,2607 @@ fn get_system_stats() -> SystemStats {
     }
 }
 
+/// The profile a build would get now, with emulator acceleration. The GPU query takes a moment,
+/// so it stays off the main thread and out of build startup.
 #[tauri::command]
-fn get_hardware_profile() -> HardwareProfile {
-    // Only needs static info, no refresh needed really but we'll use the shared one
-    let sys = SYSTEM_MONITOR.lock().unwrap();
-    calculate_profile(sys.cpus().len(), sys.total_memory())
+async fn get_hardware_profile(state: tauri::State<'_, AppState>) -> Result<HardwareProfile, HyperZenithError> {
+    let mut hw = hardware_profile(&state);
+    hw.emulator = tauri::async_runtime::spawn_blocking(gpu::detect).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
+        .map_err(|e| println!("🎮 [HARDWARE] GPU detection failed: {}", e))
+        .ok();
+    Ok(hw)
+}
+
+/// The profile for a build starting now, sized to what other programs leave free.
+/// CPU usage is measured between two refreshes, sysinfo's minimum interval apart; a single
+/// refresh would report the average since whenever the last one happened.
+fn hardware_profile(state: &AppState) -> HardwareProfile {
+    state.system.lock().unwrap().refresh_cpu();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    let mut sys = state.system.lock().unwrap();
+    sys.refresh_cpu();
+    sys.refresh_memory();
+    let usage: Vec<f32> = sys.cpus().iter().map(|c| c.cpu_usage()).collect();
+    let mut hw = calculate_profile(sys.cpus().len(), arch::core_classes(), sys.total_memory(), sys.available_memory(), &usage);
+    hw.arch = arch::host();
+    hw
 }
 
 /// Pure logic for resource allocation (Separate for testing)
-fn calculate_profile(cpu_cores: usize, total_ram_bytes: u64) -> HardwareProfile {
-    let total_ram_gb = (total_ram_bytes / 1024 / 1024 / 1024) as usize;
-    
-    // Use 90% of cores
-    let max_workers = ((cpu_cores as f64) * 0.9).floor() as usize;
-    // Use 50% of RAM for JVM
-    let jvm_heap_gb = ((total_ram_gb as f64) * 0.5).floor() as usize; 
-    
+fn calculate_profile(cpu_cores: usize, cores: arch::CoreClasses, total_ram_bytes: u64, available_ram_bytes: u64, cpu_usage: &[f32]) -> HardwareProfile {
+    let gigabyte = 1024.0 * 1024.0 * 1024.0;
+    let total_ram_gb = (total_ram_bytes as f64 / gigabyte) as usize;
+    let available_ram_gb = (available_ram_bytes as f64 / gigabyte) as usize;
+    let busy: f64 = cpu_usage.iter().map(|u| u.clamp(0.0, 100.0) as f64 / 100.0).sum();
+    let idle_cores = (cpu_cores as f64 - busy).round().max(0.0) as usize;
+
+    // Use 90% of cores, less whatever other programs are keeping busy beyond the 10% left over.
+    // On hybrid chips that's a worker per P-core thread and one per two E-cores: an E-core gets
+    // through Gradle work at about half the pace, and a worker each oversubscribes the P-cores.
+    let reserved = cpu_cores - ((cpu_cores as f64) * 0.9).floor() as usize;
+    let busy_cores = cpu_cores - idle_cores;
+    let capacity = match cores.efficiency_cores {
+        0 => cpu_cores,
+        efficiency => (cores.performance_threads + efficiency / 2).min(cpu_cores),
+    };
+    let max_workers = (((capacity as f64) * 0.9).floor() as usize).max(4);
+    let effective_workers = max_workers.saturating_sub(busy_cores.saturating_sub(reserved)).max(2);
+    // Use 50% of RAM for JVM, but no more than three quarters of what's free so nothing swaps
+    let jvm_heap_gb = (((total_ram_gb as f64) * 0.5).floor() as usize).clamp(4, 16);
+    let free_heap_gb = ((available_ram_gb as f64) * 0.75).floor() as usize;
+
+    // Below 4GB a React Native build runs out of heap, so that floor stays even under load
+    let effective_heap_gb = jvm_heap_gb.min(free_heap_gb).max(4);
+
+    let mut reasons = Vec::new();
+    if effective_workers < max_workers {
+        reasons.push(format!("{} cores busy", busy_cores));
+    }
+    if effective_heap_gb < jvm_heap_gb {
+        reasons.push(format!("only {}GB RAM free", available_ram_gb));
+    }
     HardwareProfile {
-        max_workers: max_workers.max(4),
-        jvm_heap_gb: jvm_heap_gb.clamp(4, 16),
+        max_workers: effective_workers,
+        jvm_heap_gb: effective_heap_gb,
         cpu_cores,
+        performance_cores: if cores.performance_cores > 0 { cores.performance_cores } else { cpu_cores },
+        efficiency_cores: cores.efficiency_cores,
         total_ram_gb,
+        arch: arch::HostArch::X64,
+        idle_cores,
+        available_ram_gb,
+        constrained_by: (!reasons.is_empty()).then(|| reasons.join(", ")),
+        emulator: None,
+    }
+}
+
+/// Expo SDK / React Native / Gradle versions of a project plus known-issue advisories
+#[tauri::command]
+fn get_project_info(working_dir: String) -> Result<project::ProjectInfo, HyperZenithError> {
+    let dir = std::path::Path::new(&working_dir);
+    if !dir.is_dir() {
+        return Err(HyperZenithError::NotFound { path: working_dir });
+    }
+    Ok(project::inspect(dir))
+}
+
+/// `npm audit` for the project's install root, most severe vulnerabilities first
+#[tauri::command]
+async fn audit_dependencies(working_dir: String, use_nvm: Option<bool>) -> Result<deps::AuditReport, HyperZenithError> {
+    let dir = std::path::Path::new(&working_dir);
+    if !dir.join("package.json").exists() {
+        return Err(HyperZenithError::NotFound { path: dir.join("package.json").display().to_string() });
+    }
+    let toolchain = node::Toolchain::detect(dir, &node::install_root(dir), use_nvm.unwrap_or(false));
+    deps::audit(dir, &toolchain)
+}
+
+/// `npm outdated` for the project, packages a major version behind first
+#[tauri::command]
+async fn outdated_dependencies(working_dir: String, use_nvm: Option<bool>) -> Result<Vec<deps::OutdatedPackage>, HyperZenithError> {
+    let dir = std::path::Path::new(&working_dir);
+    if !dir.join("package.json").exists() {
+        return Err(HyperZenithError::NotFound { path: dir.join("package.json").display().to_string() });
+    }
+    let toolchain = node::Toolchain::detect(dir, &node::install_root(dir), use_nvm.unwrap_or(false));
+    deps::outdated(dir, &toolchain)
+}
+
+/// Success rate, durations and cache-hit trends from the build history, per project
+/// (or for `working_dir` only)
+#[tauri::command]
+fn get_build_analytics(app: tauri::AppHandle, working_dir: Option<String>) -> Vec<history::ProjectAnalytics> {
+    history::analytics(&history::load(&app), working_dir.as_deref())
+}
+
+#[tauri::command]
+fn get_power_status() -> Option<power::PowerStatus> {
+    power::status()
+}
+
+/// Language for build statuses and error messages from now on (`en`, `de-DE`, ...).
+/// Returns the language actually used.
+#[tauri::command]
+fn set_locale(locale: String) -> i18n::Locale {
+    let locale = i18n::Locale::from_tag(&locale);
+    i18n::set_locale(locale);
+    locale
+}
+
+/// How Android builds react to running on battery, applied to every build from now on
+#[tauri::command]
+fn set_battery_policy(state: tauri::State<'_, AppState>, policy: power::BatteryPolicy) -> Result<(), HyperZenithError> {
+    *state.battery_policy.lock().map_err(|_| "Failed to acquire lock")? = policy;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_push_config(app: tauri::AppHandle) -> push::PushConfig {
+    push::load(&app)
+}
+
+/// Save where finished builds are pushed. `token` is stored in Credential Manager: `None`
+/// keeps the current one, an empty string removes it.
+#[tauri::command]
+fn set_push_config(app: tauri::AppHandle, config: push::PushConfig, token: Option<String>) -> Result<push::PushConfig, HyperZenithError> {
+    push::save(&app, config, token)
+}
+
+/// Send a test notification with the saved config
+#[tauri::command]
+async fn test_push_notification(app: tauri::AppHandle) -> Result<(), HyperZenithError> {
+    let message = push::PushMessage {
+        title: "HyperZenith test notification".to_string(),
+        body: "Build notifications reach this device".to_string(),
+        failed: false,
+    };
+    push::send(&push::load(&app), &message)
+}
+
+/// Processes listening on Metro, Flipper and the first emulator's ports
+#[tauri::command]
+async fn check_ports() -> Vec<ports::PortConflict> {
+    ports::check()
+}
+
+/// Kill whatever listens on `port`, on Windows or inside WSL
+#[tauri::command]
+async fn kill_port_process(port: u16) -> Result<Vec<ports::PortConflict>, HyperZenithError> {
+    ports::kill(port)
+}
+
+/// Whether to drop WSL's page cache after builds and shut it down when idle, from now on
+#[tauri::command]
+fn set_memory_policy(state: tauri::State<'_, AppState>, policy: memory::MemoryPolicy) -> Result<(), HyperZenithError> {
+    *state.memory_policy.lock().map_err(|_| "Failed to acquire lock")? = policy;
+    Ok(())
+}
+
+/// When quiet builds are warned about and aborted, from now on
+#[tauri::command]
+fn set_stall_policy(state: tauri::State<'_, AppState>, policy: watchdog::StallPolicy) -> Result<(), HyperZenithError> {
+    *state.stall_policy.lock().map_err(|_| "Failed to acquire lock")? = policy;
+    Ok(())
+}
+
+/// Whether failed Android builds get their intermediates deleted, from now on
+#[tauri::command]
+fn set_failure_cleanup_policy(state: tauri::State<'_, AppState>, policy: cache::FailureCleanupPolicy) -> Result<(), HyperZenithError> {
+    *state.failure_cleanup.lock().map_err(|_| "Failed to acquire lock")? = policy;
+    Ok(())
+}
+
+/// Drop WSL's page cache right away, in the distro `working_dir` builds in
+#[tauri::command]
+async fn reclaim_wsl_memory(app: tauri::AppHandle, working_dir: Option<String>) -> Result<memory::MemoryReclaimed, HyperZenithError> {
+    memory::reclaim(&app, memory::ReclaimAction::DropCaches, working_dir.as_deref()).await
+}
+
+#[tauri::command]
+fn abort_build(state: tauri::State<'_, AppState>, build_id: Option<String>) -> Result<String, HyperZenithError> {
+    let aborted = match build_id {
+        Some(id) => state.builds.abort(&id),
+        None => state.builds.abort_all() > 0,
+    };
+    if aborted {
+        Ok(i18n::tr(i18n::Msg::AbortStatus, &[]))
+    } else {
+        Ok(i18n::tr(i18n::Msg::NoActiveBuild, &[]))
+    }
+}
+
+#[tauri::command]
+fn get_active_builds(state: tauri::State<'_, AppState>) -> Vec<state::BuildInfo> {
+    state.builds.active()
+}
+
+/// Start the localhost WebSocket/JSON-RPC control API (restarting it if already running)
+#[tauri::command]
+async fn start_control_server(app: tauri::AppHandle, state: tauri::State<'_, AppState>, port: Option<u16>) -> Result<control::ControlServerInfo, HyperZenithError> {
+    // Release the old listener first so the same port can be reused
+    state.control.lock().map_err(|_| "Failed to acquire lock")?.take();
+    let server = control::start(app, port.unwrap_or(control::DEFAULT_PORT)).await?;
+    let info = server.info.clone();
+    *state.control.lock().map_err(|_| "Failed to acquire lock")? = Some(server);
+    Ok(info)
+}
+
+#[tauri::command]
+fn stop_control_server(state: tauri::State<'_, AppState>) -> Result<String, HyperZenithError> {
+    match state.control.lock().map_err(|_| "Failed to acquire lock")?.take() {
+        Some(_) => Ok("Control server stopped".to_string()),
+        None => Ok("Control server was not running".to_string()),
+    }
+}
+
+#[tauri::command]
+fn get_control_server(state: tauri::State<'_, AppState>) -> Option<control::ControlServerInfo> {
+    state.control.lock().ok()?.as_ref().map(|s| s.info.clone())
+}
+
+/// What purging WSL would stop, for the user to confirm first: Docker Desktop and anything
+/// else running in WSL goes down with a shutdown
+#[tauri::command]
+async fn purge_wsl_impact(app: tauri::AppHandle) -> Result<wsl::PurgeImpact, HyperZenithError> {
+    let distros = tauri::async_runtime::spawn_blocking(wsl::running_distros).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))??;
+    let state = app.state::<AppState>();
+    Ok(wsl::PurgeImpact { distros, vmmem_bytes: memory::vmmem_bytes(&state), active_builds: state.builds.active().len() })
+}
+
+/// Terminate just `distros`, or shut the whole VM down when none are given
+#[tauri::command]
+fn purge_wsl(distros: Option<Vec<String>>) -> Result<String, HyperZenithError> {
+    match distros {
+        Some(distros) => {
+            for distro in &distros {
+                wsl::terminate(distro)?;
+            }
+            Ok(format!("Terminated {}", distros.join(", ")))
+        },
+        None => {
+            wsl::shutdown()?;
+            Ok("WSL Purged".to_string())
+        },
+    }
+}
+
+/// Run a PowerShell snippet without a console window and return its stdout
+fn run_powershell(script: &str) -> Result<String, HyperZenithError> {
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("powershell", e))?;
+
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "PowerShell".to_string(),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Quote a string as a PowerShell single-quoted literal
+fn ps_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Inverse of `windows_to_wsl_path`: `/mnt/c/...` becomes `C:\...`, anything else
+/// is addressed through `\\wsl$\<distro>` when the distro is known
+fn wsl_to_windows_path(linux_path: &str, distro: Option<&str>) -> String {
+    if let Some(rest) = linux_path.strip_prefix("/mnt/") {
+        let mut chars = rest.chars();
+        if let (Some(drive), None | Some('/')) = (chars.next(), chars.next()) {
+            if drive.is_ascii_alphabetic() {
+                return format!("{}:\\{}", drive.to_ascii_uppercase(), rest.get(2..).unwrap_or("").replace('/', "\\"));
+            }
+        }
+    }
+    match distro {
+        Some(d) => format!("\\\\wsl$\\{}{}", d, linux_path.replace('/', "\\")),
+        None => linux_path.to_string(),
+    }
+}
+
+/// Quote a value for bash. Embedded single quotes are closed, escaped and reopened
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Like `sh_quote`, but a leading `~` stays outside the quotes so the shell still expands it
+fn sh_quote_path(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("~/{}", sh_quote(rest)),
+        None if path == "~" => path.to_string(),
+        None => sh_quote(path),
+    }
+}
+
+/// Run a PowerShell command elevated via UAC and wait for it to finish
+fn run_powershell_elevated(command: &str) -> Result<(), HyperZenithError> {
+    // The command is embedded in a double-quoted argument, so escape its specials
+    let escaped = command.replace('`', "``").replace('$', "`$").replace('"', "`\"");
+    // Start-Process doesn't fail when the elevated command does, so pass its exit code on
+    let script = format!(
+        "$p = Start-Process powershell -Verb RunAs -Wait -PassThru -WindowStyle Hidden -ArgumentList '-NoProfile','-Command',\"{}\"; \
+         if ($p.ExitCode -ne 0) {{ [Console]::Error.WriteLine(\"The elevated command exited with code $($p.ExitCode)\"); exit $p.ExitCode }}",
+        escaped
+    );
+    run_powershell(&script).map(|_| ()).map_err(|e| {
+        if e.to_string().to_lowercase().contains("canceled by the user") {
+            HyperZenithError::ElevationDeclined
+        } else {
+            e
+        }
+    })
+}
+
+/// Convert Windows path to WSL path (handles any drive letter)
+fn windows_to_wsl_path(win_path: &str) -> String {
+    // Strip the extended-length prefix (\\?\C:\... or \\?\UNC\server\share)
+    let path = match win_path.strip_prefix(r"\\?\UNC\") {
+        Some(unc) => format!(r"\\{}", unc),
+        None => win_path.strip_prefix(r"\\?\").unwrap_or(win_path).to_string(),
+    };
+    let normalized = path.replace('\\', "/");
+
+    // \\wsl$\<distro>\... and \\wsl.localhost\<distro>\... already live on the Linux side
+    for host in ["//wsl$/", "//wsl.localhost/"] {
+        if normalized.get(..host.len()).is_some_and(|h| h.eq_ignore_ascii_case(host)) {
+            let inside = normalized[host.len()..].split_once('/').map(|(_, p)| p).unwrap_or("");
+            return format!("/{}", inside);
+        }
+    }
+
+    // Handle drive letters like C:\, D:\, E:\ etc.
+    let mut chars = normalized.chars();
+    match (chars.next(), chars.next()) {
+        (Some(drive), Some(':')) if drive.is_ascii_alphabetic() => {
+            format!("/mnt/{}{}", drive.to_ascii_lowercase(), &normalized[2..])
+        },
+        _ => normalized,
+    }
+}
+
+/// Warms the Gradle daemon and dependency cache in the background and returns the prewarm id.
+/// Progress arrives on `prewarm-progress`, the result on `prewarm-complete`; `abort_build` cancels it.
+#[tauri::command]
+fn prewarm_engine(app: tauri::AppHandle, state: tauri::State<'_, AppState>, working_dir: String) -> Result<String, HyperZenithError> {
+    Ok(start_prewarm(&app, &state, working_dir))
+}
+
+/// Register and spawn a prewarm, returning its id. Shared by `prewarm_engine` and idle jobs.
+fn start_prewarm(app: &tauri::AppHandle, state: &AppState, working_dir: String) -> String {
+    let (id, cancel_rx) = state.builds.register(&working_dir, "prewarm");
+
+    let app = app.clone();
+    let prewarm_id = id.clone();
+    tauri::async_runtime::spawn(async move {
+        gradle::prewarm(app.clone(), prewarm_id.clone(), working_dir, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&prewarm_id);
+    });
+    id
+}
+
+/// Runs `./gradlew --refresh-dependencies help` in the direct engine's environment, e.g. after
+/// publishing a new snapshot of an internal library. Returns the run id; output streams on
+/// `build-output-{id}` and the result arrives on `dependencies-refreshed`.
+#[tauri::command]
+fn refresh_dependencies(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    if !expo::is_android_buildable(std::path::Path::new(&working_dir)) {
+        return Err(HyperZenithError::NotFound { path: std::path::Path::new(&working_dir).join("android").display().to_string() });
+    }
+    let hw = hardware_profile(&state);
+    let (id, mut cancel_rx) = state.builds.register(&working_dir, "refresh");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+    channel.line(hw.summary());
+
+    tauri::async_runtime::spawn(async move {
+        let started = std::time::Instant::now();
+        channel.line("🔄 Refreshing Gradle dependencies...");
+        let project_dir = std::path::Path::new(&working_dir);
+        let install_root = node::install_root(project_dir);
+        let node_env = node::Toolchain::detect(project_dir, &install_root, use_nvm.unwrap_or(false))
+            .with_env(project_env(&channel, &working_dir))
+            .env_script();
+        let script = gradle::refresh_dependencies_cmd(hw.jvm_heap_gb, &format!("{}/android", windows_to_wsl_path(&working_dir)), hw.max_workers);
+        let distro = wsl::project_distro(&working_dir);
+        let cmd = process::wsl_command_in(distro.as_deref(), &format!("{}{}", node_env, script));
+
+        let result = match process::run_streamed(&channel, cmd, Some(&mut cancel_rx)).await {
+            Ok(outcome) => {
+                let prefix = if outcome.success { "gradle_refresh_success" } else { "gradle_refresh_fail" };
+                let log_path = write_build_log(&working_dir, prefix, &outcome.log);
+                channel.line(format!("📄 Log saved to: {}", log_path.display()));
+                if outcome.cancelled {
+                    Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) })
+                } else if outcome.success {
+                    Ok(log_path)
+                } else {
+                    Err(HyperZenithError::GradleFailed { exit_code: outcome.exit_code, log_path: log_path.display().to_string() })
+                }
+            },
+            Err(e) => Err(e),
+        };
+        app.state::<AppState>().builds.finish(&channel.id);
+
+        let complete = gradle::RefreshComplete {
+            id: channel.id.clone(),
+            success: result.is_ok(),
+            elapsed_secs: started.elapsed().as_secs_f64(),
+            message: match &result {
+                Ok(_) => "Dependencies refreshed".to_string(),
+                Err(e) => e.to_string(),
+            },
+            log_path: match &result {
+                Ok(path) => Some(path.display().to_string()),
+                Err(HyperZenithError::BuildAborted { log_path }) => log_path.clone(),
+                Err(HyperZenithError::GradleFailed { log_path, .. }) => Some(log_path.clone()),
+                Err(_) => None,
+            },
+        };
+        let _ = app.emit("dependencies-refreshed", &complete);
+        match result {
+            Ok(_) => channel.state(BuildState::Finished, Some(complete.message)),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Queue a prewarm or full build to run once the machine is idle (no input, low CPU).
+/// Replaces any job already queued; `idle-job-started` fires when it kicks off.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn queue_idle_job(
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    kind: idle::IdleJobKind,
+    build_type: Option<String>,
+    turbo_mode: Option<bool>,
+    custom_path: Option<String>,
+    verbosity: Option<Verbosity>,
+    log_level: Option<gradle::LogLevel>,
+    install_dependencies: Option<bool>,
+    use_nvm: Option<bool>,
+    gates: Option<gates::GateOptions>,
+    settings: Option<idle::IdleSettings>
+) -> Result<idle::IdleJobInfo, HyperZenithError> {
+    let android_dir = std::path::Path::new(&working_dir).join("android");
+    if !expo::is_android_buildable(std::path::Path::new(&working_dir)) {
+        return Err(HyperZenithError::NotFound { path: android_dir.display().to_string() });
+    }
+    let options = (kind == idle::IdleJobKind::Build).then(|| AndroidBuildOptions {
+        build_type: build_type.clone().unwrap_or_else(|| "apk".to_string()),
+        turbo_mode: turbo_mode.unwrap_or(true),
+        custom_path,
+        log_level: log_level.unwrap_or_default(),
+        retry_with_diagnostics: false,
+        install_dependencies: install_dependencies.unwrap_or(false),
+        use_nvm: use_nvm.unwrap_or(false),
+        gates: gates.unwrap_or_default(),
+        engine: gradle::EngineOverrides::default(),
+        variant: None,
+        release_notes: false,
+        env_file: None,
+        run_hooks: true,
+    });
+    let info = idle::IdleJobInfo {
+        working_dir,
+        kind,
+        build_type: options.as_ref().map(|o| o.build_type.clone()),
+        settings: settings.unwrap_or_default(),
+        queued_at: Local::now().to_rfc3339(),
+    };
+    println!("💤 [IDLE] Queued {:?} for {} (after {} min idle)", kind, info.working_dir, info.settings.idle_minutes);
+    *state.idle_job.lock().map_err(|_| "Failed to acquire lock")? = Some(idle::IdleJob {
+        info: info.clone(),
+        options,
+        verbosity: verbosity.unwrap_or_default(),
+    });
+    Ok(info)
+}
+
+#[tauri::command]
+fn cancel_idle_job(state: tauri::State<'_, AppState>) -> Result<bool, HyperZenithError> {
+    Ok(state.idle_job.lock().map_err(|_| "Failed to acquire lock")?.take().is_some())
+}
+
+#[tauri::command]
+fn get_idle_job(state: tauri::State<'_, AppState>) -> Option<idle::IdleJobInfo> {
+    state.idle_job.lock().ok()?.as_ref().map(|j| j.info.clone())
+}
+
+/// Runs Jest in WSL in the background and returns the run's id. Output streams like a build's;
+/// the parsed counts arrive on `test-summary`.
+#[tauri::command]
+fn run_tests(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    pattern: Option<String>,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let project_dir = std::path::Path::new(&working_dir);
+    if !project_dir.join("package.json").exists() {
+        return Err(HyperZenithError::NotFound { path: project_dir.join("package.json").display().to_string() });
+    }
+    let toolchain = node::Toolchain::detect(project_dir, &node::install_root(project_dir), use_nvm.unwrap_or(false));
+    let (id, cancel_rx) = state.builds.register(&working_dir, "tests");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+
+    tauri::async_runtime::spawn(async move {
+        let result = jest::run(&channel, &working_dir, pattern.as_deref(), &toolchain, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(summary) if summary.success => {
+                channel.state(BuildState::Finished, Some(format!("{} test(s) passed", summary.tests_passed)));
+            },
+            Ok(summary) => channel.fail(HyperZenithError::CommandFailed {
+                command: "jest".to_string(),
+                output: format!("{} test(s) failed in {} suite(s)", summary.tests_failed, summary.suites_failed),
+            }),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Builds the release JS bundle (and Hermes bytecode) in WSL and reports their sizes, with a
+/// per-package breakdown from the source map when `breakdown` is set. Returns the run id;
+/// the report arrives on `bundle-report`.
+#[tauri::command]
+fn analyze_bundle(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    breakdown: Option<bool>,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let project_dir = std::path::Path::new(&working_dir);
+    if !project_dir.join("package.json").exists() {
+        return Err(HyperZenithError::NotFound { path: project_dir.join("package.json").display().to_string() });
+    }
+    let toolchain = node::Toolchain::detect(project_dir, &node::install_root(project_dir), use_nvm.unwrap_or(false));
+    let (id, cancel_rx) = state.builds.register(&working_dir, "bundle");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+
+    tauri::async_runtime::spawn(async move {
+        let result = bundle::analyze(&channel, &working_dir, breakdown.unwrap_or(true), &toolchain, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(report) => channel.state(
+                BuildState::Finished,
+                Some(format!("JS bundle is {:.2} MB", report.bundle_bytes as f64 / 1_048_576.0))
+            ),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Installs an APK (the newest archived one by default) on an emulator and runs the project's
+/// Maestro flows or Detox suite against it. Returns the run id; results arrive on `e2e-summary`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn run_e2e(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    apk_path: Option<String>,
+    serial: Option<String>,
+    runner: Option<e2e::E2eRunner>,
+    target: Option<String>,
+    custom_path: Option<String>,
+    verbosity: Option<Verbosity>
+) -> Result<String, HyperZenithError> {
+    let runner = runner.or_else(|| e2e::detect_runner(std::path::Path::new(&working_dir)))
+        .ok_or_else(|| HyperZenithError::InvalidInput("No .maestro/ folder or Detox config found in the project".to_string()))?;
+    let apk_path = match apk_path {
+        Some(path) => path,
+        None => e2e::latest_archived_apk(&working_dir, custom_path)
+            .ok_or_else(|| HyperZenithError::InvalidInput("No archived APK to test. Build an APK first.".to_string()))?
+            .to_string_lossy()
+            .to_string(),
+    };
+    let serial = match serial {
+        Some(s) => s,
+        None => e2e::pick_device(&adb::list_devices()?)
+            .ok_or_else(|| HyperZenithError::InvalidInput("No emulator or device connected".to_string()))?,
+    };
+
+    let (id, cancel_rx) = state.builds.register(&working_dir, "e2e");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+
+    let request = e2e::E2eRequest { working_dir, apk_path, serial, runner, target };
+    tauri::async_runtime::spawn(async move {
+        let result = e2e::run(&channel, request, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(summary) if summary.success => {
+                channel.state(BuildState::Finished, Some(format!("{} E2E test(s) passed", summary.passed)));
+            },
+            Ok(summary) => channel.fail(HyperZenithError::CommandFailed {
+                command: format!("{:?}", summary.runner).to_lowercase(),
+                output: format!("{} failed: {}", summary.failed, summary.failed_flows.join(", ")),
+            }),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Starts an Android build in the background and returns its id.
+/// Output streams on `build-output-{id}`, completion arrives on `build-state-{id}`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn execute_build(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String, 
+    build_type: String,
+    turbo_mode: bool,
+    custom_path: Option<String>,
+    verbosity: Option<Verbosity>,
+    log_level: Option<gradle::LogLevel>,
+    retry_with_diagnostics: Option<bool>,
+    install_dependencies: Option<bool>,
+    use_nvm: Option<bool>,
+    gates: Option<gates::GateOptions>,
+    engine: Option<gradle::EngineOverrides>,
+    variant: Option<String>,
+    release_notes: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let options = AndroidBuildOptions {
+        build_type,
+        turbo_mode,
+        custom_path,
+        log_level: log_level.unwrap_or_default(),
+        retry_with_diagnostics: retry_with_diagnostics.unwrap_or(false),
+        install_dependencies: install_dependencies.unwrap_or(false),
+        use_nvm: use_nvm.unwrap_or(false),
+        gates: gates.unwrap_or_default(),
+        engine: engine.unwrap_or_default(),
+        variant,
+        release_notes: release_notes.unwrap_or(false),
+        env_file: None,
+        run_hooks: true,
+    };
+    Ok(start_android_build(&app, &state, working_dir, options, verbosity.unwrap_or_default()))
+}
+
+/// Scaffolds a new Expo or React Native app in WSL under `parent_dir`, then adds it to the
+/// scanner's list. Returns the run id; the new project's path arrives on `project-created`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn create_project(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    parent_dir: String,
+    name: String,
+    kind: scaffold::ProjectKind,
+    template: Option<String>,
+    install_dependencies: Option<bool>,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let name = name.trim().to_string();
+    scaffold::validate_name(kind, &name).map_err(HyperZenithError::InvalidInput)?;
+    let parent = std::path::Path::new(&parent_dir);
+    if !parent.is_dir() {
+        return Err(HyperZenithError::NotFound { path: parent_dir });
+    }
+    if parent.join(&name).exists() {
+        return Err(HyperZenithError::InvalidInput(format!("{} already exists", parent.join(&name).display())));
+    }
+
+    let toolchain = node::Toolchain::detect(parent, parent, use_nvm.unwrap_or(false));
+    let (id, cancel_rx) = state.builds.register(&parent.join(&name).display().to_string(), "create");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default());
+    channel.state(BuildState::Started, None);
+
+    let request = scaffold::ScaffoldRequest { parent_dir, name, kind, template, install_dependencies: install_dependencies.unwrap_or(true) };
+    tauri::async_runtime::spawn(async move {
+        let result = scaffold::create(&channel, request, &toolchain, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(created) => channel.state(BuildState::Finished, Some(format!("Created {}", created.path))),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Clones a Git repository in WSL, installs dependencies, prebuilds managed Expo apps and adds
+/// the result to the scanner's list. An existing `dest` folder gets the repo cloned inside it
+/// under the repo's name; otherwise `dest` is the clone's path. Returns the run id; the
+/// buildable project's path arrives on `project-created`.
+#[tauri::command]
+fn clone_project(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    url: String,
+    dest: String,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let url = url.trim().to_string();
+    let name = scaffold::repo_name(&url).ok_or_else(|| HyperZenithError::InvalidInput(format!("Not a Git URL: {}", url)))?;
+    let dest_path = std::path::Path::new(&dest);
+    let repo_dir = if dest_path.is_dir() { dest_path.join(&name) } else { dest_path.to_path_buf() };
+    if repo_dir.exists() {
+        return Err(HyperZenithError::InvalidInput(format!("{} already exists", repo_dir.display())));
+    }
+    if !repo_dir.parent().is_some_and(|p| p.is_dir()) {
+        return Err(HyperZenithError::NotFound { path: repo_dir.parent().unwrap_or(dest_path).display().to_string() });
+    }
+
+    let (id, cancel_rx) = state.builds.register(&repo_dir.display().to_string(), "clone");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default());
+    channel.state(BuildState::Started, None);
+
+    tauri::async_runtime::spawn(async move {
+        let result = scaffold::clone(&channel, &url, repo_dir, use_nvm.unwrap_or(false), cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(created) => channel.state(BuildState::Finished, Some(format!("Ready to build {}", created.path))),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Publishes a JS-only update for an Expo project with `eas update` (or `expo export` plus a
+/// self-hosted upload), skipping the native build. Returns the run id; the recorded update
+/// arrives on `ota-published`.
+#[tauri::command]
+fn publish_ota(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    options: Option<ota::OtaOptions>,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let project_dir = std::path::Path::new(&working_dir);
+    if !expo::is_expo_project(project_dir) {
+        return Err(HyperZenithError::InvalidInput("OTA updates need an Expo project".to_string()));
+    }
+    if !expo::has_updates(project_dir) {
+        return Err(HyperZenithError::InvalidInput("OTA updates need expo-updates, run `npx expo install expo-updates` first".to_string()));
+    }
+    let toolchain = node::Toolchain::detect(project_dir, &node::install_root(project_dir), use_nvm.unwrap_or(false));
+    let (id, cancel_rx) = state.builds.register(&working_dir, "ota");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+
+    tauri::async_runtime::spawn(async move {
+        let result = ota::publish(&channel, &working_dir, options.unwrap_or_default(), &toolchain, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(record) => channel.state(
+                BuildState::Finished,
+                Some(match record.group_id {
+                    Some(group) => format!("Update published ({})", group),
+                    None => "Update exported".to_string(),
+                })
+            ),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Builds several variants/build types back to back in one run (e.g. stagingDebug APK, then
+/// prodRelease APK + AAB), archiving each. Returns the run id; the consolidated results arrive
+/// on `matrix-summary` and are saved as a `matrix_*.log` report.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn build_matrix(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    entries: Vec<matrix::MatrixEntry>,
+    custom_path: Option<String>,
+    verbosity: Option<Verbosity>,
+    log_level: Option<gradle::LogLevel>,
+    install_dependencies: Option<bool>,
+    use_nvm: Option<bool>,
+    gates: Option<gates::GateOptions>,
+    stop_on_failure: Option<bool>
+) -> Result<String, HyperZenithError> {
+    if entries.is_empty() {
+        return Err(HyperZenithError::InvalidInput("The build matrix has no entries".to_string()));
+    }
+    if let Some(bad) = entries.iter().find(|e| e.build_type != "apk" && e.build_type != "aab") {
+        return Err(HyperZenithError::InvalidInput(format!("Unsupported build type in matrix: {}", bad.build_type)));
+    }
+    if !expo::is_android_buildable(std::path::Path::new(&working_dir)) {
+        return Err(HyperZenithError::NotFound { path: std::path::Path::new(&working_dir).join("android").display().to_string() });
     }
+    // Variants are Gradle tasks, so the matrix always uses the direct engine
+    let base = AndroidBuildOptions {
+        build_type: "apk".to_string(),
+        turbo_mode: true,
+        custom_path,
+        log_level: log_level.unwrap_or_default(),
+        retry_with_diagnostics: false,
+        install_dependencies: install_dependencies.unwrap_or(false),
+        use_nvm: use_nvm.unwrap_or(false),
+        gates: gates.unwrap_or_default(),
+        engine: gradle::EngineOverrides::default(),
+        variant: None,
+        release_notes: false,
+        env_file: None,
+        run_hooks: true,
+    };
+
+    let hw = hardware_profile(&state);
+    let (id, cancel_rx) = state.builds.register(&working_dir, "android");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+    let policy = state.battery_policy.lock().map(|p| *p).unwrap_or_default();
+    let hw = power::adjust_profile(&channel, hw, policy);
+
+    tauri::async_runtime::spawn(async move {
+        let result = matrix::run(&channel, hw, &working_dir, entries, base, stop_on_failure.unwrap_or(false), cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(summary) if summary.success => {
+                channel.state(BuildState::Finished, Some(format!("{} matrix build(s) completed", summary.results.len())));
+            },
+            Ok(summary) => channel.fail(HyperZenithError::CommandFailed {
+                command: "build matrix".to_string(),
+                output: format!(
+                    "{} of {} build(s) failed, see {}",
+                    summary.results.iter().filter(|r| !r.success).count(),
+                    summary.results.len(),
+                    summary.report_path.unwrap_or_default()
+                ),
+            }),
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Register and spawn an Android build, returning its id. Shared by `execute_build` and the control API.
+fn start_android_build(app: &tauri::AppHandle, state: &AppState, working_dir: String, options: AndroidBuildOptions, verbosity: Verbosity) -> String {
+    // Auto-detect hardware for optimal settings
+    let hw = hardware_profile(state);
+
+    if let Ok(mut last) = state.last_build.lock() {
+        *last = Some(state::LastBuild { working_dir: working_dir.clone(), options: options.clone(), verbosity });
+    }
+
+    // Registering kills orphans: a running build of this project gets cancelled
+    let (build_id, mut cancel_rx) = state.builds.register(&working_dir, "android");
+    let channel = BuildChannel::new(app, &build_id).with_verbosity(verbosity).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+    let policy = state.battery_policy.lock().map(|p| *p).unwrap_or_default();
+    let hw = power::adjust_profile(&channel, hw, policy);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let timer = history::BuildTimer::start();
+        let (project, build_type, variant) = (working_dir.clone(), options.build_type.clone(), options.variant.clone());
+        let result = run_android_build(&channel, hw, working_dir, options, &mut cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        let record = history::BuildRecord {
+            cache_hit_rate: result.as_ref().ok().and_then(|r| r.cache_hit_rate),
+            warnings: result.as_ref().ok().map(|r| r.warnings),
+            phases: channel.take_phases(),
+            ..timer.record(&channel.id, &project, "android", &build_type, variant, &result)
+        };
+        history::record(&app, &record);
+        push::notify_build(&app, &record, &match &result {
+            Ok(build) => build.message.clone(),
+            Err(e) => e.to_string(),
+        });
+        match result {
+            Ok(result) => channel.state(BuildState::Finished, Some(result.message)),
+            Err(e) => channel.fail(e),
+        }
+    });
+
+    build_id
+}
+
+#[derive(Clone)]
+struct AndroidBuildOptions {
+    build_type: String,
+    turbo_mode: bool,
+    custom_path: Option<String>,
+    log_level: gradle::LogLevel,
+    /// Re-run a failed turbo build with `--stacktrace --info`
+    retry_with_diagnostics: bool,
+    /// Install node_modules in WSL first when missing or older than the lockfile
+    install_dependencies: bool,
+    /// Switch to the project's Node version (`.nvmrc`/`engines.node`) through nvm
+    use_nvm: bool,
+    /// Typecheck/lint before Gradle
+    gates: gates::GateOptions,
+    /// One-off hermesEnabled/newArchEnabled overrides
+    engine: gradle::EngineOverrides,
+    /// Flavor + build type such as `stagingRelease`; `None` builds `debug`
+    variant: Option<String>,
+    /// Attach the git log since the last successful build to the archived artifacts
+    release_notes: bool,
+    /// Dotenv file in the project whose variables are exported into the build
+    env_file: Option<String>,
+    /// Run the project's `.hyperzenith/hooks` scripts around the build
+    run_hooks: bool,
+}
+
+/// What a finished Android build reports back: the status message and the archived artifacts
+struct AndroidBuildResult {
+    message: String,
+    archived: Vec<std::path::PathBuf>,
+    release_notes: Option<String>,
+    /// Share of Gradle tasks that were up to date or from the build cache
+    cache_hit_rate: Option<f64>,
+    warnings: gradle::WarningCounts,
+}
+
+/// Run a step before Gradle starts, returning its log. When `required`, a failure saves the
+/// log on its own and ends the build there; otherwise it is reported and the build carries on.
+async fn run_pre_step(
+    channel: &BuildChannel,
+    cmd: tokio::process::Command,
+    cancel_rx: &mut tokio::sync::oneshot::Receiver<()>,
+    working_dir: &str,
+    step: &str,
+    required: bool,
+) -> Result<String, HyperZenithError> {
+    let outcome = process::run_streamed(channel, cmd, Some(cancel_rx)).await?;
+    if outcome.success || (!required && !outcome.cancelled) {
+        if !outcome.success {
+            channel.line(format!("⚠️ {} failed, continuing", step));
+        }
+        return Ok(format!("{}\n===== End of {} =====\n", outcome.log, step));
+    }
+    let log_path = write_build_log(working_dir, &format!("android_{}_fail", step.replace(' ', "_")), &outcome.log);
+    channel.line(format!("📄 Log saved to: {}", log_path.display()));
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+    Err(HyperZenithError::CommandFailed {
+        command: step.to_string(),
+        output: format!("exit code {}, see {}", outcome.exit_code.unwrap_or(-1), log_path.display()),
+    })
+}
+
+async fn run_android_build(
+    channel: &BuildChannel,
+    hw: HardwareProfile,
+    working_dir: String,
+    options: AndroidBuildOptions,
+    cancel_rx: &mut tokio::sync::oneshot::Receiver<()>
+) -> Result<AndroidBuildResult, HyperZenithError> {
+    // The project's .hyperzenith/hooks scripts wrap the build
+    let run_hooks = options.run_hooks;
+    let payload = hooks::HookPayload {
+        build_id: channel.id.clone(),
+        project: working_dir.clone(),
+        platform: "android".to_string(),
+        build_type: options.build_type.clone(),
+        variant: options.variant.clone(),
+        ..Default::default()
+    };
+    if run_hooks {
+        hooks::run(channel, &working_dir, hooks::Hook::PreBuild, &payload).await?;
+    }
+    let result = android_build_steps(channel, hw, working_dir.clone(), options, cancel_rx).await;
+
+    let payload = hooks::HookPayload {
+        success: Some(result.is_ok()),
+        message: Some(match &result {
+            Ok(build) => build.message.clone(),
+            Err(e) => e.to_string(),
+        }),
+        artifacts: result.as_ref().map(|b| b.archived.iter().map(|p| p.display().to_string()).collect()).unwrap_or_default(),
+        release_notes: result.as_ref().ok().and_then(|b| b.release_notes.clone()),
+        ..payload
+    };
+    match &result {
+        _ if !run_hooks => {},
+        Ok(build) => {
+            if !build.archived.is_empty() {
+                hooks::run_quietly(channel, &working_dir, hooks::Hook::PostArchive, &payload).await;
+            }
+            hooks::run_quietly(channel, &working_dir, hooks::Hook::PostBuild, &payload).await;
+        },
+        // An abort isn't a failure worth reacting to
+        Err(HyperZenithError::BuildAborted { .. }) => {},
+        Err(_) => {
+            hooks::run_quietly(channel, &working_dir, hooks::Hook::PostBuild, &payload).await;
+            hooks::run_quietly(channel, &working_dir, hooks::Hook::OnFailure, &payload).await;
+        },
+    }
+
+    // After the hooks, which may want to look at them: a failed build's intermediates run to
+    // gigabytes and the next build redoes them anyway
+    if let Err(HyperZenithError::GradleFailed { .. }) = &result {
+        let policy = channel.app.state::<AppState>().failure_cleanup.lock().map(|p| *p).unwrap_or_default();
+        let (dir, id) = (working_dir.clone(), channel.id.clone());
+        let cleaned = tauri::async_runtime::spawn_blocking(move || cache::clean_failed_outputs(&dir, &id, &policy)).await
+            .map_err(|e| HyperZenithError::Other(e.to_string()))
+            .and_then(|cleaned| cleaned);
+        match cleaned {
+            Ok(Some(cleaned)) => {
+                channel.line(format!("🧹 Cleaned {} ({:.1} MB recovered, caches kept)", cleaned.path, cleaned.freed_bytes as f64 / 1_048_576.0));
+                let _ = channel.app.emit(&format!("failed-build-cleaned-{}", channel.id), &cleaned);
+                let _ = channel.app.emit("failed-build-cleaned", &cleaned);
+            },
+            Ok(None) => {},
+            Err(e) => channel.line(format!("⚠️ Intermediates not cleaned: {}", e)),
+        }
+    }
+    result
+}
+
+async fn android_build_steps(
+    channel: &BuildChannel,
+    hw: HardwareProfile,
+    working_dir: String,
+    options: AndroidBuildOptions,
+    cancel_rx: &mut tokio::sync::oneshot::Receiver<()>
+) -> Result<AndroidBuildResult, HyperZenithError> {
+    let AndroidBuildOptions { build_type, turbo_mode, custom_path, log_level, retry_with_diagnostics, install_dependencies, use_nvm, gates: gate_options, engine, variant, release_notes, env_file, run_hooks: _ } = options;
+
+    channel.line(hw.summary());
+    
+    let wsl_path = windows_to_wsl_path(&working_dir);
+    // Projects opened through \\wsl$ are built in place, inside their own distro
+    let distro = wsl::project_distro(&working_dir);
+    if let Some(d) = &distro {
+        channel.line(format!("🐧 Project lives in WSL ({}), building in place", d));
+    }
+
+    // Projects on the Windows side reach node_modules through Windows paths too. The registry
+    // query and the walk block, so they run off the async runtime.
+    if distro.is_none() {
+        let dir = working_dir.clone();
+        let preflight = tauri::async_runtime::spawn_blocking(move || doctor::long_path_preflight(&dir)).await.ok().flatten();
+        if let Some(status) = preflight {
+            channel.line(format!("📏 {}", status.warning.as_deref().unwrap_or_default()));
+            let _ = channel.app.emit(&format!("long-paths-{}", channel.id), &status);
+            let _ = channel.app.emit("long-paths", &status);
+        }
+    }
+
+    let task = gradle::task_name(&build_type, variant.as_deref());
+    // Debug builds load their JS from Metro; a leftover server or emulator on the dev ports is
+    // behind most first runs that hang
+    if task.ends_with("Debug") {
+        let conflicts = ports::check();
+        for c in &conflicts {
+            channel.line(format!(
+                "🔌 Port {} ({}) is in use by {} (pid {}, {:?})",
+                c.port, c.purpose, c.process, c.pid.map(|p| p.to_string()).unwrap_or_else(|| "?".to_string()), c.side
+            ));
+        }
+        if !conflicts.is_empty() {
+            let _ = channel.app.emit(&format!("port-conflicts-{}", channel.id), &conflicts);
+            let _ = channel.app.emit("port-conflicts", &conflicts);
+        }
+    }
+
+    let build_cmd = |diagnostic: bool| if turbo_mode {
+        // V1.2 SUPER-SONIC EDITION: Configuration Cache + Parallel GC + High Throughput
+        format!(
+            r#"{} && \
+             cd {} && chmod +x ./gradlew && \
+             ./gradlew {} {} \
+               --console=plain \
+               --parallel \
+               --build-cache \
+               --configuration-cache \
+               --configuration-cache-problems=warn \
+               --max-workers={} \
+               -Dorg.gradle.caching=true \
+               -Dorg.gradle.parallel=true \
+               -Dorg.gradle.vfs.watch=true \
+               -Dkotlin.incremental=true \
+               -x lint -x test {} \
+               2>&1"#,
+            gradle::turbo_env(hw.jvm_heap_gb), sh_quote(&format!("{}/android", wsl_path)), task,
+            gradle::build_flags(log_level, diagnostic), hw.max_workers, engine.flags()
+        )
+
+
+    } else {
+        format!(
+            "export NODE_ENV=development && cd {} && npx eas build --platform android --local --profile preview --non-interactive 2>&1",
+            sh_quote(&wsl_path)
+        )
+    };
+
+    let install_root = node::install_root(std::path::Path::new(&working_dir));
+    let wsl_install_root = windows_to_wsl_path(&install_root.to_string_lossy());
+    let mut env = project_env(channel, &working_dir);
+    if let Some(file) = &env_file {
+        channel.line(format!("🌱 Exporting {} into the build", file));
+        env += &presets::env_file_exports(&working_dir, file)?;
+    }
+    let toolchain = node::Toolchain::detect(std::path::Path::new(&working_dir), &install_root, use_nvm)
+        .with_env(env);
+    let package_manager = toolchain.package_manager;
+    let node_env = toolchain.env_script();
+    if toolchain.node.is_some() {
+        let reported = wsl::run_bash_in(distro.as_deref(), &format!("{}node --version", node_env)).ok();
+        if let Some(message) = toolchain.check_message(reported.as_deref(), "WSL") {
+            channel.line(message);
+        }
+    }
+
+    let wsl_in_project = |script: &str| {
+        let mut cmd = process::wsl_command_in(distro.as_deref(), &format!("{}{}", node_env, script));
+        // A UNC working directory is useless to wsl.exe, the script cds on the Linux side anyway
+        if distro.is_none() {
+            cmd.current_dir(&working_dir);
+        }
+        cmd
+    };
+    let wsl_build = |diagnostic: bool| wsl_in_project(&build_cmd(diagnostic));
+    let mut pre_log = String::new();
+
+    // Fresh clones and lockfile bumps get their dependencies before anything needs them
+    if install_dependencies {
+        match node::install_reason(&install_root) {
+            Some(reason) => {
+                channel.line(format!("📦 {}, installing dependencies with {:?}...", reason, package_manager));
+                let cmd = wsl_in_project(&node::install_cmd(&wsl_install_root, package_manager));
+                pre_log += &run_pre_step(channel, cmd, cancel_rx, &working_dir, "dependency install", true).await?;
+            },
+            None => channel.line("📦 node_modules up to date"),
+        }
+    }
+
+    // Managed Expo projects have no android/ until prebuild generates it (EAS does this itself)
+    if turbo_mode && expo::needs_prebuild(std::path::Path::new(&working_dir)) {
+        channel.line("📐 Managed Expo project without android/, running expo prebuild...");
+        let cmd = wsl_in_project(&expo::prebuild_cmd(&wsl_path, &wsl_install_root, package_manager));
+        pre_log += &run_pre_step(channel, cmd, cancel_rx, &working_dir, "expo prebuild", true).await?;
+    }
+
+    // Cheap checks first: a typecheck takes seconds, a Gradle bundling failure takes minutes
+    for planned in gates::plan(&gate_options, std::path::Path::new(&working_dir)) {
+        let gate = match planned {
+            Ok(gate) => gate,
+            Err(skipped) => { channel.line(format!("⏭️ {}", skipped)); continue; },
+        };
+        channel.line(format!("🔎 Running {} gate...", gate.label()));
+        let cmd = wsl_in_project(&gate.script(&wsl_path, std::path::Path::new(&working_dir), package_manager));
+        let log = run_pre_step(channel, cmd, cancel_rx, &working_dir, gate.label(), gate_options.block_on_errors).await?;
+        let errors = gate.count_errors(&log);
+        if errors > 0 {
+            channel.line(format!("🔎 {}: {} error(s)", gate.label(), errors));
+        }
+        pre_log += &log;
+    }
+
+    if engine != gradle::EngineOverrides::default() {
+        if turbo_mode {
+            channel.line(format!("🧪 One-off engine override: {}", engine.properties().join(", ")));
+        } else {
+            channel.line("⚠️ Engine overrides only apply to the direct Gradle engine, EAS uses the project's settings");
+        }
+    }
+
+    let build_started = std::time::SystemTime::now();
+    let cmd = wsl_build(false);
+    channel.lifecycle(Lifecycle::BuildStarted);
+    let mut outcome = process::run_streamed(channel, cmd, Some(&mut *cancel_rx)).await?;
+
+    // A crashed or corrupted daemon fails every build until it's gone; clearing it out used to
+    // take a manual WSL purge
+    if !outcome.success && !outcome.cancelled && turbo_mode {
+        if let Some(signature) = gradle::daemon_failure(&outcome.log).map(str::to_string) {
+            channel.line(format!("🩹 Gradle/Kotlin daemon failure ({}), restarting the daemons and retrying once...", signature.chars().take(100).collect::<String>()));
+            let reset = wsl_in_project(&gradle::daemon_reset_cmd(hw.jvm_heap_gb, &format!("{}/android", wsl_path)));
+            let reset = process::run_streamed(channel, reset, Some(&mut *cancel_rx)).await?;
+            let log = format!("{}\n===== Retry after daemon restart =====\n{}", outcome.log, reset.log);
+            outcome = if reset.cancelled {
+                process::StreamOutcome { log, ..reset }
+            } else {
+                let rerun = process::run_streamed(channel, wsl_build(false), Some(&mut *cancel_rx)).await?;
+                process::StreamOutcome { log: format!("{}{}", log, rerun.log), ..rerun }
+            };
+        }
+    }
+
+    // EAS drives Gradle itself, so only the direct engine can be re-run with diagnostics
+    if !outcome.success && !outcome.cancelled && turbo_mode && retry_with_diagnostics {
+        channel.line(format!("🔁 Build failed, re-running with {} for the log...", gradle::DIAGNOSTIC_FLAGS));
+        let cmd = wsl_build(true);
+        let rerun = process::run_streamed(channel, cmd, Some(&mut *cancel_rx)).await?;
+        outcome = process::StreamOutcome {
+            log: format!("{}\n===== Diagnostic re-run ({}) =====\n{}", outcome.log, gradle::DIAGNOSTIC_FLAGS, rerun.log),
+            ..rerun
+        };
+    }
+
+    channel.end_phase();
+
+    // ALWAYS write logs
+    let prefix = if outcome.success { "android_build_success" } else { "android_build_fail" };
+    let log_path = write_build_log(&working_dir, prefix, &format!("{}{}", pre_log, outcome.log));
+    channel.line(format!("📄 Log saved to: {}", log_path.display()));
+
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+
+    if outcome.success {
+        // Archive every APK/AAB the build produced, with timestamp
+        let kind = if build_type == "aab" { "aab" } else { "apk" };
+        let android_dir = std::path::Path::new(&working_dir).join("android");
+        let found = match &distro {
+            Some(d) => wsl::find_build_outputs(d, &format!("{}/android", wsl_path)),
+            None => archive::find_build_outputs(&android_dir),
+        };
+        // Other variants' outputs sit in the same tree; keep the cached fallback to this one
+        let found = match &variant {
+            Some(v) => found.into_iter().filter(|(p, _)| archive::matches_variant(p, v)).collect(),
+            None => found,
+        };
+        let (outputs, recent) = archive::select_outputs(found, build_started, kind);
+
+        // Trust Gradle's own task outcomes over file times; mtime is only the fallback
+        let task_outcomes = gradle::parse_task_outcomes(&outcome.log);
+        let cache_hit_rate = gradle::cache_hit_rate(&task_outcomes);
+        let warnings = gradle::count_warnings(&outcome.log);
+        if warnings.total() > 0 {
+            channel.line(format!(
+                "⚠️ {} warning(s): {} Gradle deprecation(s), {} Kotlin, {} Java",
+                warnings.total(), warnings.deprecations, warnings.kotlin, warnings.java
+            ));
+        }
+        let is_fresh = match gradle::artifact_freshness(&task_outcomes) {
+            Some(fresh) => {
+                channel.line(if fresh { "🔎 Packaging tasks executed" } else { "🔎 Packaging tasks UP-TO-DATE" });
+                fresh
+            },
+            None => recent,
+        };
+
+        if outputs.is_empty() {
+            return Ok(AndroidBuildResult { message: i18n::tr(i18n::Msg::BuildCompleted, &[]), archived: Vec::new(), release_notes: None, cache_hit_rate, warnings });
+        }
+
+        channel.phase(phases::Phase::Archive);
+        // Copies to a share retry with pauses and can take a while, so archiving runs off the async runtime
+        let output_count = outputs.len();
+        let archive_channel = channel.clone();
+        let (archived, release_notes) = tauri::async_runtime::spawn_blocking(move || {
+            let channel = &archive_channel;
+            let storage = storage::ArchiveStorage::resolve(&working_dir, custom_path);
+            let builds_dir = storage.prepare(channel);
+            let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+            let mut archived = Vec::new();
+            let release_notes = if release_notes {
+                match changelog::generate(&channel.app, &working_dir, "android") {
+                    Ok(Some(notes)) => {
+                        channel.line(format!("📝 Release notes: {} commit(s) since the last successful build", notes.lines().count()));
+                        Some(notes)
+                    },
+                    Ok(None) => {
+                        channel.line("📝 No commits since the last successful build");
+                        None
+                    },
+                    Err(e) => {
+                        channel.line(format!("⚠️ Release notes not generated: {}", e));
+                        None
+                    },
+                }
+            } else {
+                None
+            };
+
+            // Crash symbols for release or minified builds, so a build that reached testers can be symbolicated.
+            // Outputs Gradle didn't rebuild may not match the symbols left in the tree, so those get none.
+            let symbol_variant = variant.as_deref().unwrap_or("debug");
+            let symbol_files = if is_fresh && symbols::wanted(symbol_variant, &task_outcomes) {
+                symbols::find(&android_dir, symbol_variant)
+            } else {
+                Vec::new()
+            };
+            let symbols_dir = if symbol_files.is_empty() {
+                None
+            } else {
+                match symbols::archive(&storage, &builds_dir, &channel.id, &symbol_files) {
+                    Ok(dir) => {
+                        channel.line(format!("🔣 {} symbol file(s) saved to: {}", symbol_files.len(), dir.display()));
+                        Some(dir.display().to_string())
+                    },
+                    Err(e) => {
+                        channel.line(format!("⚠️ Symbols not archived: {}", e));
+                        None
+                    },
+                }
+            };
+
+            for source_path in &outputs {
+                let dest_path = archive::archived_path(&builds_dir, source_path, &timestamp);
+                match storage.store(source_path, &dest_path) {
+                    Ok(dest_path) => {
+                        let metadata = archive::ArtifactMetadata {
+                            project: working_dir.clone(),
+                            build_type: build_type.clone(),
+                            created_at: Local::now().to_rfc3339(),
+                            fresh: is_fresh,
+                            variant: variant.clone(),
+                            gradle_properties: if turbo_mode { engine.properties() } else { Vec::new() },
+                            build_id: Some(channel.id.clone()),
+                            release_notes: release_notes.clone(),
+                            symbols: symbols_dir.clone(),
+                            note: None,
+                        };
+                        if let Err(e) = archive::write_metadata(&dest_path, &metadata) {
+                            println!("📦 [ARCHIVE] ⚠️ Metadata sidecar not written: {}", e);
+                        }
+                        channel.line(format!("📂 Saved to: {}", dest_path.display()));
+                        archived.push(dest_path);
+                    },
+                    Err(e) => println!("📦 [ARCHIVE] ❌ Copy of {} failed: {}", source_path.display(), e),
+                }
+            }
+            (archived, release_notes)
+        })
+        .await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?;
+
+        let message = if is_fresh {
+            channel.line(format!("📦 {} new artifact(s) archived!", output_count));
+            i18n::tr(i18n::Msg::BuildCompletedFresh, &[("kind", &kind.to_uppercase())])
+        } else {
+            channel.line(format!("♻️ Cached {} (code unchanged)", kind.to_uppercase()));
+            i18n::tr(i18n::Msg::BuildCompletedCached, &[])
+        };
+        Ok(AndroidBuildResult { message, archived, release_notes, cache_hit_rate, warnings })
+    } else {
+        Err(HyperZenithError::GradleFailed { exit_code: outcome.exit_code, log_path: log_path.display().to_string() })
+    }
+}
+
+/// Builds two commits of the project (hash, tag or branch) in temporary git worktrees and
+/// compares build time and artifact size. Returns the run id; the result arrives on
+/// `commit-comparison` and is saved as a `compare_*.log` report.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn compare_commits(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    sha_a: String,
+    sha_b: String,
+    custom_path: Option<String>,
+    verbosity: Option<Verbosity>,
+    log_level: Option<gradle::LogLevel>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    if !expo::is_android_buildable(std::path::Path::new(&working_dir)) {
+        return Err(HyperZenithError::NotFound { path: std::path::Path::new(&working_dir).join("android").display().to_string() });
+    }
+    let commits = [compare::resolve(&working_dir, &sha_a)?, compare::resolve(&working_dir, &sha_b)?];
+    // Worktrees start without node_modules, and the archive must outlive them
+    let options = AndroidBuildOptions {
+        build_type: "apk".to_string(),
+        turbo_mode: true,
+        custom_path: Some(archive::archive_dir(&working_dir, custom_path).display().to_string()),
+        log_level: log_level.unwrap_or_default(),
+        retry_with_diagnostics: false,
+        install_dependencies: true,
+        use_nvm: use_nvm.unwrap_or(false),
+        gates: gates::GateOptions::default(),
+        engine: gradle::EngineOverrides::default(),
+        variant: None,
+        release_notes: false,
+        env_file: None,
+        run_hooks: true,
+    };
+
+    let hw = hardware_profile(&state);
+    let (id, cancel_rx) = state.builds.register(&working_dir, "android");
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
+    let policy = state.battery_policy.lock().map(|p| *p).unwrap_or_default();
+    let hw = power::adjust_profile(&channel, hw, policy);
+
+    tauri::async_runtime::spawn(async move {
+        let result = compare::run(&channel, hw, &working_dir, commits, options, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(comparison) => {
+                let summary = match (comparison.duration_delta_secs, comparison.size_delta_bytes) {
+                    (Some(duration), Some(size)) => format!("Compared: {:+.1}s, {:+.2} MB", duration, size as f64 / 1_048_576.0),
+                    _ => "Compared: at least one commit failed to build".to_string(),
+                };
+                channel.state(BuildState::Finished, Some(summary));
+            },
+            Err(e) => channel.fail(e),
+        }
+    });
+    Ok(id)
+}
+
+/// Save a build log as `<prefix>_<timestamp>.log` in the project's logs folder,
+/// `<project>/hyperzenith_logs` unless one is configured
+fn write_build_log(working_dir: &str, prefix: &str, log: &str) -> std::path::PathBuf {
+    let logs_dir = logs::logs_dir(working_dir);
+    let _ = std::fs::create_dir_all(&logs_dir);
+    let log_path = logs_dir.join(format!("{}_{}.log", prefix, Local::now().format("%Y-%m-%d_%H-%M-%S")));
+    let _ = std::fs::write(&log_path, log);
+    log_path
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct NukeTarget {
+    pub id: String,
+    pub path: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct NukeReport {
+    pub dry_run: bool,
+    pub targets: Vec<NukeTarget>,
+    pub message: String,
+}
+
+#[tauri::command]
+fn nuke_build(working_dir: String, dry_run: Option<bool>, targets: Option<Vec<String>>) -> Result<NukeReport, HyperZenithError> {
+    println!("🧨 [NUKE] Target Working Dir: {}", working_dir);
+    let dry_run = dry_run.unwrap_or(false);
+    let android_dir = std::path::Path::new(&working_dir).join("android");
+    // Identified by their path relative to android/ (both build dirs share a file name)
+    let all_targets = ["app/build", "build", ".gradle"];
+
+    let selected: Vec<&str> = all_targets.iter()
+        .copied()
+        .filter(|id| targets.as_ref().map(|t| t.iter().any(|s| s == id)).unwrap_or(true))
+        .collect();
+
+    let mut measured = Vec::new();
+    for id in selected {
+        let dir = android_dir.join(id);
+        if dir.exists() {
+            let (files, bytes) = cache::dir_size(&dir);
+            measured.push(NukeTarget { id: id.to_string(), path: dir.display().to_string(), files, bytes });
+        }
+    }
+    // JS-side caches only go when named explicitly
+    let js_targets = cache::JS_NUKE_TARGETS.iter().filter(|id| targets.as_ref().is_some_and(|t| t.iter().any(|s| s == *id)));
+    measured.extend(js_targets.filter_map(|id| cache::measure_js_target(&working_dir, id)));
+
+    if dry_run {
+        let total: u64 = measured.iter().map(|t| t.bytes).sum();
+        let message = format!("Would free {:.1} MB across {} item(s)", total as f64 / 1_048_576.0, measured.len());
+        return Ok(NukeReport { dry_run, targets: measured, message });
+    }
+    
+    let mut report = String::from("Nuked: ");
+    let mut deleted = Vec::new();
+    
+    for target in measured {
+        println!("🧨 NUKING: {}", target.path);
+        let result = if cache::JS_NUKE_TARGETS.contains(&target.id.as_str()) {
+            cache::clear_js_target(&working_dir, &target)
+        } else {
+            std::fs::remove_dir_all(&target.path).map_err(|e| HyperZenithError::io_at(std::path::Path::new(&target.path), e))
+        };
+        match result {
+            Ok(_) => {
+                report.push_str(&format!("{}, ", target.id));
+                deleted.push(target);
+            },
+            Err(e) => {
+                println!("❌ Failed to nuke {}: {}", target.path, e);
+                report.push_str(&format!("(Fail: {}) ", target.id));
+            }
+        }
+    }
+    
+    let message = if deleted.is_empty() {
+        "Nothing to nuke! (Clean)".to_string()
+    } else {
+        let freed: u64 = deleted.iter().map(|t| t.bytes).sum();
+        format!("{} ({} items, {:.1} MB freed)", report.trim_end_matches(", "), deleted.len(), freed as f64 / 1_048_576.0)
+    };
+    Ok(NukeReport { dry_run, targets: deleted, message })
+}
+
+#[tauri::command]
+fn open_build_archive(working_dir: String, custom_path: Option<String>) -> Result<String, HyperZenithError> {
+    let builds_dir = archive::archive_dir(&working_dir, custom_path);
+    
+    let _ = std::fs::create_dir_all(&builds_dir);
+    println!("📂 [SYSTEM] Opening archive: {}", builds_dir.display());
+
+    if builds_dir.exists() {
+        Command::new("explorer")
+            .arg(builds_dir.to_str().unwrap())
+            .spawn()
+            .map_err(|e| HyperZenithError::spawn("explorer", e))?;
+        Ok("Opened Archive".to_string())
+    } else {
+        Err(HyperZenithError::NotFound { path: builds_dir.display().to_string() })
+    }
+}
+
+#[tauri::command]
+fn open_logs_folder(working_dir: String) -> Result<String, HyperZenithError> {
+    let logs_dir = logs::logs_dir(&working_dir);
+    let _ = std::fs::create_dir_all(&logs_dir);
+    
+    println!("📂 [SYSTEM] Opening logs: {}", logs_dir.display());
+
+    Command::new("explorer")
+        .arg(logs_dir.to_str().unwrap())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok("Opened Logs".to_string())
+}
+
+/// Open a file from a build problem in VS Code, at the line and column when known
+#[tauri::command]
+fn open_in_editor(path: String, line: Option<u32>, column: Option<u32>) -> Result<(), HyperZenithError> {
+    if !std::path::Path::new(&path).is_file() {
+        return Err(HyperZenithError::NotFound { path });
+    }
+    let target = match (line, column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", path, line, column),
+        (Some(line), None) => format!("{}:{}", path, line),
+        _ => path.clone(),
+    };
+    println!("📝 [SYSTEM] Opening in VS Code: {}", target);
+
+    // `code` is a .cmd shim. Spawned directly rather than through `cmd /C`, std escapes the
+    // path, so a file name with `&` or `%` can't run anything.
+    let output = Command::new("code.cmd")
+        .args(["--goto", &target])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("code", e))?;
+    if !output.status.success() {
+        return Err(HyperZenithError::ToolMissing { tool: "code".to_string() });
+    }
+    Ok(())
+}
+
+/// Android and iOS logs saved for the project, newest first; `platform` narrows to one
+#[tauri::command]
+fn list_logs(working_dir: String, platform: Option<String>) -> Vec<logs::LogFile> {
+    logs::list(&working_dir, platform.as_deref())
+}
+
+#[tauri::command]
+fn get_logs_folder(working_dir: String) -> String {
+    logs::logs_dir(&working_dir).display().to_string()
+}
+
+/// Keep the project's logs in `folder`, or back in `<project>/hyperzenith_logs` without one
+#[tauri::command]
+fn set_logs_folder(app: tauri::AppHandle, working_dir: String, folder: Option<String>) -> Result<String, HyperZenithError> {
+    logs::set_folder(&app, &working_dir, folder).map(|dir| dir.display().to_string())
+}
+
+#[tauri::command]
+fn clear_archive(state: tauri::State<'_, AppState>, working_dir: String, custom_path: Option<String>) -> Result<String, HyperZenithError> {
+    let builds_dir = archive::archive_dir(&working_dir, custom_path);
+    
+    println!("🗑️ [CLEAR] Target Dir: {}", builds_dir.display());
+    
+    if !builds_dir.exists() {
+        println!("🗑️ [CLEAR] ⚠️ Directory does not exist!");
+        return Ok("Archive folder doesn't exist.".to_string());
+    }
+    
+    let mut trashed = Vec::new();
+    match std::fs::read_dir(&builds_dir) {
+        Ok(entries) => {
+            println!("🗑️ [CLEAR] Reading directory entries...");
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                println!("🗑️ [CLEAR] Found item: {}", path.display());
+                
+                if let Some(ext) = path.extension() {
+                    let ext_str = ext.to_string_lossy().to_lowercase();
+                    // Case-insensitive check for APK, AAB, IPA, APP
+                    if ext_str == "apk" || ext_str == "aab" || ext_str == "ipa" || ext_str == "app" {
+                        println!("🗑️ [CLEAR] >> Recycling {}...", ext_str.to_uppercase());
+                        match trash::delete(&path) {
+                            Ok(_) => { 
+                                println!("🗑️ [CLEAR] >> ✅ Moved to Recycle Bin.");
+                                let sidecar = archive::sidecar_path(&path);
+                                if sidecar.exists() && trash::delete(&sidecar).is_ok() {
+                                    trashed.push(sidecar);
+                                }
+                                trashed.push(path);
+                            },
+                            Err(e) => println!("🗑️ [CLEAR] >> ❌ Failed to recycle: {}", e),
+                        }
+                    } else {
+                         println!("🗑️ [CLEAR] >> Skipping non-artifact (ext: {:?})", ext);
+                    }
+                } else {
+                    println!("🗑️ [CLEAR] >> Skipping (no extension)");
+                }
+            }
+        },
+        Err(e) => {
+            println!("🗑️ [CLEAR] ❌ Failed to read directory: {}", e);
+            return Err(HyperZenithError::io_at(&builds_dir, e));
+        }
+    }
+    
+    if trashed.is_empty() {
+        Ok("No APKs to clear.".to_string())
+    } else {
+        let count = trashed.iter().filter(|p| archive::is_artifact(p)).count();
+        if let Ok(mut last) = state.last_cleared.lock() {
+            *last = trashed;
+        }
+        Ok(format!("Moved {} APK(s) to Recycle Bin", count))
+    }
+}
+
+#[tauri::command]
+fn undo_last_clear(state: tauri::State<'_, AppState>) -> Result<String, HyperZenithError> {
+    let cleared = std::mem::take(&mut *state.last_cleared.lock().map_err(|_| "Failed to acquire lock")?);
+    if cleared.is_empty() {
+        return Ok("Nothing to restore.".to_string());
+    }
+
+    let mut items = trash::os_limited::list().map_err(|e| format!("Failed to read Recycle Bin: {}", e))?;
+    // Newest first, so a re-cleared file restores its latest copy
+    items.sort_by_key(|i| std::cmp::Reverse(i.time_deleted));
+
+    let mut to_restore: Vec<trash::TrashItem> = Vec::new();
+    for item in items {
+        let original = item.original_path();
+        if cleared.contains(&original) && !to_restore.iter().any(|r| r.original_path() == original) {
+            to_restore.push(item);
+        }
+    }
+
+    if to_restore.is_empty() {
+        return Err(HyperZenithError::Other("Cleared artifacts are no longer in the Recycle Bin".to_string()));
+    }
+
+    let count = to_restore.len();
+    println!("♻️ [CLEAR] Restoring {} artifact(s) from Recycle Bin", count);
+    trash::os_limited::restore_all(to_restore).map_err(|e| format!("Restore failed: {}", e))?;
+    Ok(format!("Restored {} artifact(s)", count))
+}
+
+#[tauri::command]
+fn list_archive(working_dir: String, custom_path: Option<String>) -> Result<Vec<archive::ArchiveEntry>, HyperZenithError> {
+    archive::list_entries(&archive::archive_dir(&working_dir, custom_path))
+}
+
+/// The archive with each APK's and AAB's package, versions, SDK levels and signing read from the file
+#[tauri::command]
+async fn get_archive_index(working_dir: String, custom_path: Option<String>) -> Result<Vec<archive::IndexEntry>, HyperZenithError> {
+    archive::index(&archive::archive_dir(&working_dir, custom_path))
+}
+
+/// Rename an archived APK or AAB, keeping its metadata sidecar alongside
+#[tauri::command]
+async fn rename_artifact(working_dir: String, custom_path: Option<String>, path: String, new_name: String) -> Result<archive::ArchiveEntry, HyperZenithError> {
+    archive::rename(&archive::archive_dir(&working_dir, custom_path), &path, &new_name)
+}
+
+/// Store testers' feedback with an archived artifact; an empty note removes it
+#[tauri::command]
+async fn annotate_artifact(working_dir: String, custom_path: Option<String>, path: String, note: String) -> Result<archive::ArchiveEntry, HyperZenithError> {
+    archive::annotate(&archive::archive_dir(&working_dir, custom_path), &path, &note)
+}
+
+/// Where the project's artifacts are archived, whether that's reachable right now and how many
+/// artifacts are waiting locally for it
+#[tauri::command]
+async fn get_archive_storage(working_dir: String, custom_path: Option<String>) -> storage::StorageStatus {
+    storage::ArchiveStorage::resolve(&working_dir, custom_path).status()
+}
+
+/// Move artifacts held while the archive target was offline into it, returning how many moved
+#[tauri::command]
+async fn flush_archive_pending(working_dir: String, custom_path: Option<String>) -> Result<String, HyperZenithError> {
+    let storage = storage::ArchiveStorage::resolve(&working_dir, custom_path);
+    if !storage.available() {
+        return Err(HyperZenithError::NotFound { path: storage.root.display().to_string() });
+    }
+    let moved = storage.flush_pending();
+    Ok(format!("Moved {} pending artifact(s), {} still waiting", moved, storage.pending().len()))
+}
+
+/// De-obfuscate a pasted crash: Android stack traces through the mapping.txt archived for
+/// `build_id`, Apple crash reports through atos on the Mac with the dSYMs in `dsym_path`.
+/// iOS builds made here are Debug builds without dSYMs, so nothing is archived for them.
+#[tauri::command]
+async fn symbolicate(
+    working_dir: String,
+    custom_path: Option<String>,
+    build_id: String,
+    stacktrace_text: String,
+    mac_config: Option<ios::MacConfig>,
+    dsym_path: Option<String>
+) -> Result<symbolicate::Symbolication, HyperZenithError> {
+    if build_id.is_empty() || build_id.contains(['/', '\\']) || build_id.contains("..") {
+        return Err(HyperZenithError::InvalidInput(format!("Invalid build id: {}", build_id)));
+    }
+    let symbols_dir = symbols::symbols_dir(&archive::archive_dir(&working_dir, custom_path), &build_id);
+    if symbolicate::parse_ios_frames(&stacktrace_text).is_empty() {
+        return tauri::async_runtime::spawn_blocking(move || symbolicate::android(&symbols_dir, &stacktrace_text)).await
+            .map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    }
+    let mac_config = mac_config.ok_or_else(|| HyperZenithError::InvalidInput("Symbolicating an iOS crash needs the Mac".to_string()))?;
+    let dsym_dir = dsym_path.filter(|p| !p.trim().is_empty()).map(std::path::PathBuf::from).ok_or_else(|| {
+        HyperZenithError::InvalidInput("Symbolicating an iOS crash needs the folder with the build's dSYMs, e.g. the dSYMs folder of its .xcarchive".to_string())
+    })?;
+    tauri::async_runtime::spawn_blocking(move || symbolicate::ios(&mac_config, &dsym_dir, &build_id, &stacktrace_text)).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
+}
+
+/// Attach release notes to the artifacts a build archived; empty text removes them
+#[tauri::command]
+fn set_release_notes(working_dir: String, custom_path: Option<String>, build_id: String, text: String) -> Result<String, HyperZenithError> {
+    let count = changelog::set_for_build(&archive::archive_dir(&working_dir, custom_path), &build_id, &text)?;
+    Ok(format!("Release notes attached to {} artifact(s)", count))
+}
+
+/// Commits since the project's last successful build on `platform`, one `- subject (hash)` per line
+#[tauri::command]
+async fn generate_release_notes(app: tauri::AppHandle, working_dir: String, platform: Option<String>) -> Result<String, HyperZenithError> {
+    let notes = changelog::generate(&app, &working_dir, platform.as_deref().unwrap_or("android"))?;
+    Ok(notes.unwrap_or_default())
+}
+
+#[tauri::command]
+async fn diff_artifacts(a: String, b: String) -> Result<apk::ArtifactDiff, HyperZenithError> {
+    apk::diff_artifacts(std::path::Path::new(&a), std::path::Path::new(&b))
+}
+
+#[tauri::command]
+async fn list_adb_devices() -> Result<Vec<adb::AdbDevice>, HyperZenithError> {
+    adb::list_devices()
+}
+
+#[tauri::command]
+async fn install_archived(path: String, serial: Option<String>) -> Result<String, HyperZenithError> {
+    let artifact = std::path::Path::new(&path);
+    if !artifact.exists() {
+        return Err(HyperZenithError::NotFound { path });
+    }
+    match artifact.extension().map(|e| e.to_string_lossy().to_lowercase()).as_deref() {
+        Some("apk") => adb::install_apk(&path, serial.as_deref()),
+        Some("aab") => Err(HyperZenithError::InvalidInput("AAB bundles can't be installed directly. Build an APK or use bundletool.".to_string())),
+        _ => Err(HyperZenithError::InvalidInput("Only Android APKs can be installed from the archive".to_string())),
+    }
+}
+
+#[tauri::command]
+async fn run_doctor(working_dir: String) -> Vec<doctor::DoctorCheck> {
+    doctor::run_checks(Some(&working_dir))
+}
+
+/// First-run setup: WSL, JDK, Node, Android SDK and the doctor in turn, with progress on
+/// `onboarding-progress`. Steps listed in `install` install what they find missing.
+#[tauri::command]
+async fn initialize_environment(app: tauri::AppHandle, working_dir: Option<String>, install: Option<Vec<onboarding::StepId>>) -> Result<onboarding::OnboardingReport, HyperZenithError> {
+    tauri::async_runtime::spawn_blocking(move || onboarding::run(&app, working_dir.as_deref(), &install.unwrap_or_default())).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))
+}
+
+#[tauri::command]
+async fn check_defender_exclusions(working_dir: String) -> Result<doctor::DefenderStatus, HyperZenithError> {
+    doctor::check_defender(&working_dir)
+}
+
+#[tauri::command]
+async fn apply_defender_exclusions(working_dir: String, paths: Vec<String>) -> Result<doctor::DefenderStatus, HyperZenithError> {
+    doctor::apply_defender_exclusions(&paths)?;
+    // Re-check so the UI reflects what actually got applied
+    doctor::check_defender(&working_dir)
+}
+
+/// Zip a build's log, a doctor run, the settings without secrets and system details for a
+/// bug report, optionally pasting a text version online. `settings` are the frontend's.
+#[tauri::command]
+async fn create_support_bundle(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    build_id: String,
+    settings: Option<serde_json::Map<String, serde_json::Value>>,
+    upload: Option<bool>
+) -> Result<support::SupportBundle, HyperZenithError> {
+    let info = support::SupportInfo {
+        app_version: app.package_info().version.to_string(),
+        hardware: hardware_profile(&state).summary(),
+        settings: settings.unwrap_or_default(),
+    };
+    tauri::async_runtime::spawn_blocking(move || support::create(&app, &build_id, info, upload.unwrap_or(false))).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
+}
+
+#[tauri::command]
+async fn check_long_paths(working_dir: String) -> doctor::LongPathStatus {
+    doctor::long_path_status(Some(&working_dir))
+}
+
+#[tauri::command]
+async fn enable_long_paths(working_dir: String) -> Result<doctor::LongPathStatus, HyperZenithError> {
+    doctor::enable_long_paths()?;
+    // Re-check so the UI reflects what actually got applied
+    Ok(doctor::long_path_status(Some(&working_dir)))
+}
+
+#[tauri::command]
+async fn get_wsl_disk_report() -> wsl::WslDiskReport {
+    wsl::disk_report()
+}
+
+#[tauri::command]
+async fn compact_wsl_disk(vhdx_path: String) -> Result<wsl::CompactionResult, HyperZenithError> {
+    wsl::compact_disk(&vhdx_path)
 }
 
 #[tauri::command]
-fn abort_build() -> Result<String, String> {
-    let mut handle = ACTIVE_BUILD_HANDLE.lock().map_err(|_| "Failed to acquire lock")?;
-    if let Some(mut child) = handle.take() {
-        let _ = child.kill();
-        Ok("Build Aborted".to_string())
-    } else {
-        Ok("No active build".to_string())
-    }
+async fn get_cache_report(working_dir: String) -> Vec<cache::CacheEntry> {
+    cache::cache_report(&working_dir)
 }
 
 #[tauri::command]
-fn purge_wsl() -> Result<String, String> {
-    Command::new("wsl").args(["--shutdown"]).output()
-        .map_err(|e| format!("Failed: {}", e))?;
-    Ok("WSL Purged".to_string())
+async fn purge_caches(working_dir: String, cache_ids: Vec<String>) -> cache::PurgeReport {
+    cache::purge_caches(&working_dir, &cache_ids)
 }
 
-/// Convert Windows path to WSL path (handles any drive letter)
-fn windows_to_wsl_path(win_path: &str) -> String {
-    // Handle drive letters like C:\, D:\, E:\ etc.
-    if win_path.len() >= 2 && win_path.chars().nth(1) == Some(':') {
-        let drive = win_path.chars().next().unwrap().to_lowercase().next().unwrap();
-        let rest = &win_path[2..].replace("\\", "/");
-        format!("/mnt/{}{}", drive, rest)
-    } else {
-        win_path.replace("\\", "/")
-    }
+/// `export` prefix with the project's build environment variables
+fn project_env(channel: &BuildChannel, working_dir: &str) -> String {
+    buildenv::exports(&channel.app, working_dir, |name| {
+        channel.line(format!("⚠️ Secret {} is missing from Credential Manager, building without it", name));
+    })
 }
 
-#[tauri::command]
-fn prewarm_engine(working_dir: String) -> Result<String, String> {
-    let wsl_path = windows_to_wsl_path(&working_dir);
+/// Queue behind other builds for the same Mac, reporting the position on `ios-queue`.
+/// The build is tracked for `cancel_ios_pipeline` from here on; `None` if it was cancelled while queued.
+fn wait_for_mac<'a>(app: &tauri::AppHandle, state: &'a AppState, channel: &BuildChannel, mac_config: &ios::MacConfig) -> Option<(state::IosPipeline<'a>, state::MacTurn<'a>)> {
+    let pipeline = state.ios_pipelines.track(&state.builds, &channel.id, &mac_config.queue_key());
+    let mut waited = false;
+    let position = |ahead: usize| ios::QueuePosition { build_id: channel.id.clone(), mac: mac_config.ip.clone(), ahead };
+    let turn = state.mac_queues.wait_turn(&mac_config.queue_key(), &channel.id, |ahead| {
+        waited = true;
+        channel.line(format!("⏳ {} build(s) ahead on {}, waiting for the Mac...", ahead, mac_config.ip));
+        let _ = app.emit("ios-queue", position(ahead));
+    });
+    let Some(turn) = turn.filter(|_| !pipeline.cancelled()) else {
+        channel.line("🛑 Cancelled while waiting for the Mac");
+        return None;
+    };
+    if waited {
+        channel.line("▶️ The Mac is free, starting");
+        let _ = app.emit("ios-queue", position(0));
+    }
+    Some((pipeline, turn))
+}
 
-    std::thread::spawn(move || {
-        println!("🔥 [SYSTEM] PRE-WARMING GRADLE DAEMON...");
-        if let Ok(mut child) = Command::new("wsl")
-            .args(["-e", "bash", "-c", &format!("cd '{}/android' && ./gradlew --version", wsl_path)])
-            .stdout(Stdio::null()).stderr(Stdio::null())
-            .creation_flags(CREATE_NO_WINDOW)
-            .spawn() 
-        {
-            let _ = child.wait();
-            println!("✅ [SYSTEM] ENGINE WARMED.");
+/// Register an iOS build, test run or lane with the other builds, so `abort_build`, the stall
+/// watchdog and resource sampling see it. An abort from there cancels it like `cancel_ios_pipeline`.
+fn register_ios(app: &tauri::AppHandle, working_dir: &str, kind: &str) -> String {
+    let (id, cancel_rx) = app.state::<AppState>().builds.register(working_dir, kind);
+    let (app, build_id) = (app.clone(), id.clone());
+    tauri::async_runtime::spawn(async move {
+        // The sender is dropped without a send once the pipeline ends
+        if cancel_rx.await.is_ok() {
+            if let Err(e) = cancel_ios_pipeline(app.state::<AppState>(), build_id).await {
+                println!("🍎 [iOS] ⚠️ Abort failed: {}", e);
+            }
         }
     });
-    Ok("Pre-heating...".to_string())
+    id
 }
 
+/// Get the project onto the Mac, returns the synced root and the app's folder inside it
+fn sync_to_mac(channel: &BuildChannel, working_dir: &str, mac_config: &ios::MacConfig, remote_path: &str, pipeline: &state::IosPipeline) -> Result<(String, String), HyperZenithError> {
+    // Monorepo apps need the whole workspace on the Mac, hoisted dependencies live at its root
+    let (sync_root, app_subdir) = match node::workspace(std::path::Path::new(working_dir)) {
+        Some(w) => {
+            channel.line(format!("🧩 Workspace root: {} (app at {})", w.root.display(), w.app_subdir));
+            (w.root.to_string_lossy().to_string(), w.app_subdir)
+        },
+        None => (working_dir.to_string(), String::new()),
+    };
+    // Fail fast rather than halfway through the sync or the build
+    if let Err(e) = ios::check_disk_space(channel, mac_config, remote_path) {
+        channel.line(format!("❌ {}", e));
+        return Err(e);
+    }
+    channel.line("🔄 Syncing files to Mac...");
+    channel.lifecycle(Lifecycle::SyncStarted);
+    match ios::sync_files(&sync_root, mac_config, remote_path, pipeline) {
+        Ok(ios::SyncMethod::Rsync) => channel.line("✅ Sync Complete."),
+        Ok(ios::SyncMethod::Sftp) => channel.line("✅ Sync Complete (over SFTP, rsync in WSL is unavailable)."),
+        Err(_) if pipeline.cancelled() => {
+            channel.line("🛑 Sync cancelled");
+            return Err(HyperZenithError::BuildAborted { log_path: None });
+        },
+        Err(e) => {
+            channel.line(format!("❌ Sync Failed: {}", e));
+            return Err(e);
+        }
+    }
+    channel.lifecycle(Lifecycle::SyncComplete);
+    channel.map_remote(remote_path, &sync_root);
+    Ok((sync_root, app_subdir))
+}
+
+/// Starts the iOS sync & build pipeline in the background and returns its build id
 #[tauri::command]
-async fn execute_build(
+#[allow(clippy::too_many_arguments)]
+async fn start_ios_build(
     app: tauri::AppHandle,
-    working_dir: String, 
+    working_dir: String,
+    mac_config: ios::MacConfig,
+    remote_path: String,
+    scheme: String,
     build_type: String,
-    turbo_mode: bool,
-    custom_path: Option<String>
-) -> Result<String, String> {
-    use std::io::{BufRead, BufReader};
-    
-    // Auto-detect hardware for optimal settings
-    let hw = get_hardware_profile();
-    println!("🖥️ [HARDWARE] {} cores, {}GB RAM → {} workers, {}GB heap", 
-             hw.cpu_cores, hw.total_ram_gb, hw.max_workers, hw.jvm_heap_gb);
-    
-    let wsl_path = windows_to_wsl_path(&working_dir);
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>,
+    pull_back: Option<Vec<String>>
+) -> Result<String, HyperZenithError> {
+    let build_id = register_ios(&app, &working_dir, "ios");
+    let channel = BuildChannel::new(&app, &build_id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
 
-    // Get LOCALAPPDATA for dynamic Android SDK path (Failsafe included)
-    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:/Users/Default/AppData/Local".to_string());
-    let win_sdk_path = format!("{}/Android/Sdk", local_app_data.replace("\\", "/"));
-    let android_sdk_path = windows_to_wsl_path(&win_sdk_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        // 0. One build per Mac at a time, later ones wait their turn
+        let state = app.state::<AppState>();
+        let Some((pipeline, _turn)) = wait_for_mac(&app, &state, &channel, &mac_config) else {
+            channel.fail(HyperZenithError::BuildAborted { log_path: None });
+            return;
+        };
 
+        // Pre-build hooks run locally before the sync, so whatever they generate reaches the Mac
+        let payload = hooks::HookPayload {
+            build_id: channel.id.clone(),
+            project: working_dir.clone(),
+            platform: "ios".to_string(),
+            build_type: build_type.clone(),
+            ..Default::default()
+        };
+        if let Err(e) = tauri::async_runtime::block_on(hooks::run(&channel, &working_dir, hooks::Hook::PreBuild, &payload)) {
+            channel.line(format!("❌ {}", e));
+            channel.fail(e);
+            return;
+        }
 
-    let task = match build_type.as_str() {
-        "aab" => "bundleDebug",
-        _ => "assembleDebug",
-    };
+        // 1-2. Workspace detection, disk check and sync
+        let (sync_root, app_subdir) = match sync_to_mac(&channel, &working_dir, &mac_config, &remote_path, &pipeline) {
+            Ok(synced) => synced,
+            Err(e) => {
+                channel.fail(e);
+                return; // Abort build if sync fails
+            }
+        };
 
-    let wsl_cmd = if turbo_mode {
-        // V1.2 SUPER-SONIC EDITION: Configuration Cache + Parallel GC + High Throughput
-        format!(
-            r#"export NODE_ENV=development && \
-             export ANDROID_HOME={} && \
-             export PATH=$ANDROID_HOME/platform-tools:$ANDROID_HOME/cmdline-tools/latest/bin:$PATH && \
-             export GRADLE_OPTS="-Xmx{}g -XX:+UseParallelGC -XX:MaxMetaspaceSize=1g -Dorg.gradle.daemon.idletimeout=3600000" && \
-             cd '{}/android' && chmod +x ./gradlew && \
-             ./gradlew {} \
-               --parallel \
-               --build-cache \
-               --configuration-cache \
-               --configuration-cache-problems=warn \
-               --max-workers={} \
-               -Dorg.gradle.caching=true \
-               -Dorg.gradle.parallel=true \
-               -Dorg.gradle.vfs.watch=true \
-               -Dkotlin.incremental=true \
-               -x lint -x test \
-               2>&1"#,
-            android_sdk_path, hw.jvm_heap_gb, wsl_path, task, hw.max_workers
-        )
+        // 3. Ignite Build
+        let toolchain = node::Toolchain::detect(std::path::Path::new(&working_dir), std::path::Path::new(&sync_root), use_nvm.unwrap_or(false))
+            .with_env(project_env(&channel, &working_dir));
+        channel.line(format!("📦 Package manager: {:?}", toolchain.package_manager));
+        let timer = history::BuildTimer::start();
+        channel.lifecycle(Lifecycle::BuildStarted);
+        let result = ios::execute_turbo_ios(&channel, &working_dir, mac_config.clone(), remote_path.clone(), &app_subdir, scheme, build_type.clone(), &toolchain, &pipeline)
+            .map_err(|e| if pipeline.cancelled() { HyperZenithError::BuildAborted { log_path: None } } else { e });
+        let record = history::BuildRecord { phases: channel.take_phases(), ..timer.record(&channel.id, &working_dir, "ios", &build_type, None, &result) };
+        history::record(&app, &record);
+        let payload = hooks::HookPayload {
+            success: Some(result.is_ok()),
+            message: Some(match &result {
+                Ok(msg) => msg.clone(),
+                Err(e) => e.to_string(),
+            }),
+            ..payload
+        };
+        push::notify_build(&app, &record, payload.message.as_deref().unwrap_or_default());
+        let cancelled = matches!(result, Err(HyperZenithError::BuildAborted { .. }));
+        tauri::async_runtime::block_on(async {
+            // An abort isn't a failure worth reacting to
+            if cancelled {
+                return;
+            }
+            hooks::run_quietly(&channel, &working_dir, hooks::Hook::PostBuild, &payload).await;
+            if result.is_err() {
+                hooks::run_quietly(&channel, &working_dir, hooks::Hook::OnFailure, &payload).await;
+            }
+        });
 
+        // 4. Pods were installed even if xcodebuild then failed, bring what they changed home
+        let files = pull_back.unwrap_or_else(|| ios::DEFAULT_PULL_BACK.iter().map(|f| f.to_string()).collect());
+        if !files.is_empty() && !cancelled {
+            let remote_app = if app_subdir.is_empty() { remote_path } else { format!("{}/{}", remote_path, app_subdir) };
+            match ios::pull_generated_files(&windows_to_wsl_path(&working_dir), &mac_config, &remote_app, &files) {
+                Ok(pulled) if pulled.is_empty() => channel.line("⬅️ Generated files already match the Mac"),
+                Ok(pulled) => channel.line(format!("⬅️ Pulled back from the Mac: {}", pulled.join(", "))),
+                Err(e) => channel.line(format!("⚠️ Could not pull generated files back: {}", e)),
+            }
+        }
+        match result {
+            Ok(msg) => {
+                channel.line(format!("✅ {}", msg));
+                channel.state(BuildState::Finished, Some(msg));
+            },
+            Err(e) if cancelled => {
+                channel.line("🛑 iOS build cancelled");
+                channel.fail(e);
+            },
+            Err(e) => {
+                channel.line(format!("❌ iOS Build Failed: {}", e));
+                channel.fail(e);
+            },
+        }
+    });
+    Ok(build_id)
+}
 
-    } else {
-        format!(
-            "export NODE_ENV=development && cd '{}' && npx eas build --platform android --local --profile preview --non-interactive 2>&1",
-            wsl_path
-        )
+/// Stop an iOS build, test run or lane wherever it is: waiting for the Mac, syncing, or
+/// building on the Mac, where the detached build is ended too
+#[tauri::command]
+async fn cancel_ios_pipeline(state: tauri::State<'_, AppState>, build_id: String) -> Result<String, HyperZenithError> {
+    let Some(stage) = state.ios_pipelines.cancel(&build_id) else {
+        return Ok(i18n::tr(i18n::Msg::NoActiveBuild, &[]));
     };
-
-    // Kill orphans
-    if let Ok(mut handle) = ACTIVE_BUILD_HANDLE.lock() {
-        if let Some(mut existing) = handle.take() { let _ = existing.kill(); }
+    if let ios::IosStage::Queued { mac } = &stage {
+        state.mac_queues.leave(mac, &build_id);
     }
+    tauri::async_runtime::spawn_blocking(move || stage.stop())
+        .await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))??;
+    Ok(i18n::tr(i18n::Msg::AbortStatus, &[]))
+}
 
-    let mut child = Command::new("wsl")
-        .args(["-e", "bash", "-c", &wsl_cmd])
-        .current_dir(&working_dir)
-        .stdout(Stdio::piped()).stderr(Stdio::piped())
-        .creation_flags(CREATE_NO_WINDOW)
-        .spawn().map_err(|e| e.to_string())?;
+/// Run the scheme's XCTest/XCUITest targets on a simulator on the Mac, returns the build id.
+/// The summary arrives on `ios-test-summary`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn run_ios_tests(
+    app: tauri::AppHandle,
+    working_dir: String,
+    mac_config: ios::MacConfig,
+    remote_path: String,
+    scheme: String,
+    simulator: Option<String>,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let build_id = register_ios(&app, &working_dir, "xctest");
+    let channel = BuildChannel::new(&app, &build_id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
 
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
-    let log_buffer = Arc::new(Mutex::new(String::new()));
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let Some((pipeline, _turn)) = wait_for_mac(&app, &state, &channel, &mac_config) else {
+            channel.fail(HyperZenithError::BuildAborted { log_path: None });
+            return;
+        };
+        let (sync_root, app_subdir) = match sync_to_mac(&channel, &working_dir, &mac_config, &remote_path, &pipeline) {
+            Ok(synced) => synced,
+            Err(e) => {
+                channel.fail(e);
+                return;
+            }
+        };
 
-    let app1 = app.clone();
-    let buf1 = Arc::clone(&log_buffer);
-    let t1 = std::thread::spawn(move || {
-        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
-            let _ = app1.emit("build-output", &line);
-            buf1.lock().unwrap().push_str(&format!("{}\n", line));
+        let toolchain = node::Toolchain::detect(std::path::Path::new(&working_dir), std::path::Path::new(&sync_root), use_nvm.unwrap_or(false))
+            .with_env(project_env(&channel, &working_dir));
+        channel.lifecycle(Lifecycle::BuildStarted);
+        match xctest::run(&channel, &mac_config, &working_dir, &remote_path, &app_subdir, &scheme, simulator, &toolchain, &pipeline) {
+            Ok(summary) if summary.success => {
+                channel.state(BuildState::Finished, Some(format!("{} test(s) passed", summary.tests_passed)));
+            },
+            Ok(summary) => channel.fail(HyperZenithError::CommandFailed {
+                command: "xcodebuild test".to_string(),
+                output: format!("{} of {} test(s) failed", summary.tests_failed, summary.tests_total),
+            }),
+            Err(e) => {
+                channel.line(format!("❌ iOS Tests Failed: {}", e));
+                channel.fail(e);
+            },
         }
     });
+    Ok(build_id)
+}
+
+/// Scripts from the project's package.json
+#[tauri::command]
+fn list_npm_scripts(working_dir: String) -> Result<Vec<scripts::NpmScript>, HyperZenithError> {
+    scripts::scripts(std::path::Path::new(&working_dir))
+}
+
+/// Run one of the project's package.json scripts in WSL and stream its output like a build,
+/// returns the run's id
+#[tauri::command]
+fn run_npm_script(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    name: String,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let project_dir = std::path::Path::new(&working_dir);
+    let script = scripts::find(project_dir, &name)?;
+    let toolchain = node::Toolchain::detect(project_dir, &node::install_root(project_dir), use_nvm.unwrap_or(false));
+    // One run per script: starting `lint` mustn't cancel the `start` that's serving Metro
+    let platform = format!("script:{}", name);
+    let (id, cancel_rx) = if script.long_running {
+        state.builds.register_service(&working_dir, &platform)
+    } else {
+        state.builds.register(&working_dir, &platform)
+    };
+    let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+    channel.state(BuildState::Started, None);
 
-    let app2 = app.clone();
-    let buf2 = Arc::clone(&log_buffer);
-    let t2 = std::thread::spawn(move || {
-        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
-            let _ = app2.emit("build-output", &line);
-            buf2.lock().unwrap().push_str(&format!("{}\n", line));
+    tauri::async_runtime::spawn(async move {
+        let toolchain = toolchain.with_env(project_env(&channel, &working_dir));
+        let result = scripts::run(&channel, &working_dir, &script, &toolchain, cancel_rx).await;
+        app.state::<AppState>().builds.finish(&channel.id);
+        match result {
+            Ok(msg) => channel.state(BuildState::Finished, Some(msg)),
+            Err(e) => channel.fail(e),
         }
     });
+    Ok(id)
+}
 
-    t1.join().ok(); t2.join().ok();
-    let status = child.wait().map_err(|e| e.to_string())?;
+/// Public lanes of the project's Fastfile for `platform` (`android` or `ios`)
+#[tauri::command]
+fn list_fastlane_lanes(working_dir: String, platform: String) -> Result<Vec<fastlane::FastlaneLane>, HyperZenithError> {
+    fastlane::lanes(std::path::Path::new(&working_dir), &platform).map(|(_, lanes)| lanes)
+}
 
-    // ALWAYS write logs
-    let logs_dir = std::path::Path::new(&working_dir).join("hyperzenith_logs");
-    let _ = std::fs::create_dir_all(&logs_dir);
-    let prefix = if status.success() { "android_build_success" } else { "android_build_fail" };
-    let log_path = logs_dir.join(format!("{}_{}.log", prefix, Local::now().format("%Y-%m-%d_%H-%M-%S")));
-    
-    if let Ok(content) = log_buffer.lock() {
-        let _ = std::fs::write(&log_path, content.clone());
-        let _ = app.emit("build-output", format!("📄 Log saved to: {}", log_path.display()));
-    }
+/// Run an existing Fastlane lane and stream its output like a build, returns the run's id.
+/// Android lanes run in WSL, iOS lanes on the Mac after syncing the project.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+fn run_fastlane(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    working_dir: String,
+    lane: String,
+    platform: String,
+    mac_config: Option<ios::MacConfig>,
+    remote_path: Option<String>,
+    verbosity: Option<Verbosity>,
+    use_nvm: Option<bool>
+) -> Result<String, HyperZenithError> {
+    let project_dir = std::path::Path::new(&working_dir);
+    // Fail before starting anything when the lane doesn't exist
+    let (_, lanes) = fastlane::lanes(project_dir, &platform)?;
+    fastlane::fastlane_cmd(&lanes, &lane, false)?;
+    let use_nvm = use_nvm.unwrap_or(false);
 
-    if status.success() {
-        // Archive the Artifact with timestamp
-        let (output_subpath, ext) = match build_type.as_str() {
-            "aab" => ("android/app/build/outputs/bundle/debug/app-debug.aab", "aab"),
-            _ => ("android/app/build/outputs/apk/debug/app-debug.apk", "apk"),
-        };
+    match platform.as_str() {
+        "android" => {
+            let toolchain = node::Toolchain::detect(project_dir, &node::install_root(project_dir), use_nvm);
+            let (id, cancel_rx) = state.builds.register(&working_dir, "fastlane");
+            let channel = BuildChannel::new(&app, &id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+            channel.state(BuildState::Started, None);
 
-        let source_path = std::path::Path::new(&working_dir).join(output_subpath);
-        
-        let builds_dir = match custom_path {
-            Some(p) if !p.is_empty() => std::path::PathBuf::from(p),
-            _ => std::path::Path::new(&working_dir).join("hyperzenith_builds"),
-        };
-        
-        let _ = std::fs::create_dir_all(&builds_dir);
-        
-        if source_path.exists() {
-            // Check if Artifact is fresh or cached by looking at modification time
-            let modified = source_path.metadata()
-                .and_then(|m| m.modified())
-                .ok();
-            
-            let is_fresh = modified.map(|m| {
-                let age = std::time::SystemTime::now().duration_since(m).unwrap_or_default();
-                age.as_secs() < 120 // Modified within last 2 minutes = fresh
-            }).unwrap_or(false);
-            
-            let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S");
-            let dest_name = format!("app-debug_{}.{}", timestamp, ext);
-            let dest_path = builds_dir.join(&dest_name);
-            
-            match std::fs::copy(&source_path, &dest_path) {
-                Ok(_) => {
-                    let _ = app.emit("build-output", format!("📂 Saved to: {}", dest_path.display()));
-                    if is_fresh {
-                        let _ = app.emit("build-output", format!("📦 New {} archived!", ext.to_uppercase()));
-                    } else {
-                        let _ = app.emit("build-output", format!("♻️ Cached {} (code unchanged)", ext.to_uppercase()));
+            tauri::async_runtime::spawn(async move {
+                let result = fastlane::run_android(&channel, &working_dir, &lane, &toolchain, cancel_rx).await;
+                app.state::<AppState>().builds.finish(&channel.id);
+                match result {
+                    Ok(msg) => channel.state(BuildState::Finished, Some(msg)),
+                    Err(e) => channel.fail(e),
+                }
+            });
+            Ok(id)
+        },
+        "ios" => {
+            let mac_config = mac_config.ok_or_else(|| HyperZenithError::InvalidInput("iOS lanes need a Mac configured".to_string()))?;
+            let remote_path = remote_path.ok_or_else(|| HyperZenithError::InvalidInput("iOS lanes need the remote path on the Mac".to_string()))?;
+            let build_id = register_ios(&app, &working_dir, "fastlane-ios");
+            let channel = BuildChannel::new(&app, &build_id).with_verbosity(verbosity.unwrap_or_default()).with_project(&working_dir);
+            channel.state(BuildState::Started, None);
+
+            tauri::async_runtime::spawn_blocking(move || {
+                let state = app.state::<AppState>();
+                let Some((pipeline, _turn)) = wait_for_mac(&app, &state, &channel, &mac_config) else {
+                    channel.fail(HyperZenithError::BuildAborted { log_path: None });
+                    return;
+                };
+                let (sync_root, app_subdir) = match sync_to_mac(&channel, &working_dir, &mac_config, &remote_path, &pipeline) {
+                    Ok(synced) => synced,
+                    Err(e) => {
+                        channel.fail(e);
+                        return;
                     }
-                },
-                Err(e) => println!("📦 [ARCHIVE] ❌ Copy failed: {}", e),
-            }
-            
-            if is_fresh {
-                Ok("Build completed! (Fresh APK)".to_string())
-            } else {
-                Ok("Build completed! (Cached - no code changes)".to_string())
-            }
-        } else {
-            Ok("Build completed!".to_string())
-        }
-    } else {
-        Err(format!("Build failed. Log: {}", log_path.display()))
+                };
+                let toolchain = node::Toolchain::detect(std::path::Path::new(&working_dir), std::path::Path::new(&sync_root), use_nvm);
+                let remote_app = if app_subdir.is_empty() { remote_path } else { format!("{}/{}", remote_path, app_subdir) };
+                channel.lifecycle(Lifecycle::BuildStarted);
+                match fastlane::run_ios(&channel, &mac_config, &working_dir, &remote_app, &lane, &toolchain, &pipeline) {
+                    Ok(msg) => channel.state(BuildState::Finished, Some(msg)),
+                    Err(e) => {
+                        channel.line(format!("❌ fastlane {} failed: {}", lane, e));
+                        channel.fail(e);
+                    },
+                }
+            });
+            Ok(build_id)
+        },
+        other => Err(HyperZenithError::InvalidInput(format!("Unknown platform '{}', expected android or ios", other))),
     }
 }
 
+/// What the next iOS build would upload to the Mac, without sending anything
 #[tauri::command]
-fn nuke_build(working_dir: String) -> Result<String, String> {
-    println!("🧨 [NUKE] Target Working Dir: {}", working_dir);
-    let android_dir = std::path::Path::new(&working_dir).join("android");
-    let targets = vec![
-        android_dir.join("app").join("build"),
-        android_dir.join("build"),
-        android_dir.join(".gradle"),
-    ];
-    
-    let mut report = String::from("Nuked: ");
-    let mut deleted_count = 0;
-    
-    for dir in targets {
-        if dir.exists() {
-            println!("🧨 NUKING: {}", dir.display());
-            let name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
-            match std::fs::remove_dir_all(&dir) {
-                Ok(_) => {
-                    deleted_count += 1;
-                    report.push_str(&format!("{}, ", name));
-                },
-                Err(e) => {
-                    println!("❌ Failed to nuke {}: {}", dir.display(), e);
-                    report.push_str(&format!("(Fail: {}) ", name));
-                }
-            }
-        }
-    }
-    
-    if deleted_count == 0 {
-        Ok("Nothing to nuke! (Clean)".to_string())
-    } else {
-        Ok(format!("{} ({} items)", report.trim_end_matches(", "), deleted_count))
-    }
+async fn preview_sync(working_dir: String, mac_config: ios::MacConfig, remote_path: String) -> Result<ios::SyncPreview, HyperZenithError> {
+    let sync_root = match node::workspace(std::path::Path::new(&working_dir)) {
+        Some(w) => w.root.to_string_lossy().to_string(),
+        None => working_dir,
+    };
+    tauri::async_runtime::spawn_blocking(move || ios::preview_sync(&sync_root, &mac_config, &remote_path)).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
 }
 
+/// Copy files generated on the Mac (Podfile.lock by default) back into the local project
 #[tauri::command]
-fn open_build_archive(working_dir: String, custom_path: Option<String>) -> Result<String, String> {
-    let builds_dir = match custom_path {
-        Some(p) if !p.is_empty() => std::path::PathBuf::from(p),
-        _ => std::path::Path::new(&working_dir).join("hyperzenith_builds"),
+async fn pull_generated_files(
+    working_dir: String,
+    mac_config: ios::MacConfig,
+    remote_path: String,
+    files: Option<Vec<String>>
+) -> Result<Vec<String>, HyperZenithError> {
+    let files = files.unwrap_or_else(|| ios::DEFAULT_PULL_BACK.iter().map(|f| f.to_string()).collect());
+    let remote_app = match node::workspace(std::path::Path::new(&working_dir)) {
+        Some(w) => format!("{}/{}", remote_path, w.app_subdir),
+        None => remote_path,
     };
-    
-    let _ = std::fs::create_dir_all(&builds_dir);
-    println!("📂 [SYSTEM] Opening archive: {}", builds_dir.display());
-
-    if builds_dir.exists() {
-        Command::new("explorer")
-            .arg(builds_dir.to_str().unwrap())
-            .spawn()
-            .map_err(|e| e.to_string())?;
-        Ok("Opened Archive".to_string())
-    } else {
-        Err("Archive folder missing. Run a build first!".to_string())
-    }
+    tauri::async_runtime::spawn_blocking(move || ios::pull_generated_files(&windows_to_wsl_path(&working_dir), &mac_config, &remote_app, &files)).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
 }
 
+/// Follow the iOS build still running on the Mac, returns its build id
 #[tauri::command]
-fn open_logs_folder(working_dir: String) -> Result<String, String> {
-    let logs_dir = std::path::Path::new(&working_dir).join("hyperzenith_logs");
-    let _ = std::fs::create_dir_all(&logs_dir);
-    
-    println!("📂 [SYSTEM] Opening logs: {}", logs_dir.display());
+async fn reattach_ios_build(app: tauri::AppHandle, mac_config: ios::MacConfig, verbosity: Option<Verbosity>) -> Result<String, HyperZenithError> {
+    let build_id = uuid::Uuid::new_v4().to_string();
+    let channel = BuildChannel::new(&app, &build_id).with_verbosity(verbosity.unwrap_or_default());
+    channel.state(BuildState::Started, None);
 
-    Command::new("explorer")
-        .arg(logs_dir.to_str().unwrap())
-        .spawn()
-        .map_err(|e| e.to_string())?;
-    Ok("Opened Logs".to_string())
+    tauri::async_runtime::spawn_blocking(move || {
+        match ios::reattach_ios_build(&channel, mac_config) {
+            Ok(msg) => {
+                channel.line(format!("✅ {}", msg));
+                channel.state(BuildState::Finished, Some(msg));
+            },
+            Err(e) => {
+                channel.line(format!("❌ iOS Build Failed: {}", e));
+                channel.fail(e);
+            },
+        }
+    });
+    Ok(build_id)
 }
 
 #[tauri::command]
-fn clear_archive(working_dir: String, custom_path: Option<String>) -> Result<String, String> {
-    let builds_dir = match custom_path {
-        Some(p) if !p.is_empty() => std::path::PathBuf::from(p),
-        _ => std::path::Path::new(&working_dir).join("hyperzenith_builds"),
-    };
-    
-    println!("🗑️ [CLEAR] Target Dir: {}", builds_dir.display());
-    
-    if !builds_dir.exists() {
-        println!("🗑️ [CLEAR] ⚠️ Directory does not exist!");
-        return Ok("Archive folder doesn't exist.".to_string());
+async fn trigger_nuke_ios(
+    app: tauri::AppHandle,
+    mac_config: ios::MacConfig,
+    remote_path: String,
+    dry_run: Option<bool>,
+    targets: Option<Vec<String>>
+) -> Result<NukeReport, HyperZenithError> {
+    let selected = targets.unwrap_or_else(|| ios::NUKE_TARGETS.iter().map(|t| t.to_string()).collect());
+
+    if dry_run.unwrap_or(false) {
+        let measured = ios::preview_nuke_ios(&mac_config, &remote_path, &selected)?;
+        let total: u64 = measured.iter().map(|t| t.bytes).sum();
+        let message = format!("Would free {:.1} MB across {} item(s)", total as f64 / 1_048_576.0, measured.len());
+        return Ok(NukeReport { dry_run: true, targets: measured, message });
     }
-    
-    let mut deleted = 0;
-    match std::fs::read_dir(&builds_dir) {
-        Ok(entries) => {
-            println!("🗑️ [CLEAR] Reading directory entries...");
-            for entry in entries.filter_map(|e| e.ok()) {
-                let path = entry.path();
-                println!("🗑️ [CLEAR] Found item: {}", path.display());
-                
-                if let Some(ext) = path.extension() {
-                    let ext_str = ext.to_string_lossy().to_lowercase();
-                    // Case-insensitive check for APK, AAB, IPA, APP
-                    if ext_str == "apk" || ext_str == "aab" || ext_str == "ipa" || ext_str == "app" {
-                        println!("🗑️ [CLEAR] >> Deleting {}...", ext_str.to_uppercase());
-                        match std::fs::remove_file(&path) {
-                            Ok(_) => { 
-                                println!("🗑️ [CLEAR] >> ✅ Deleted.");
-                                deleted += 1 
-                            },
-                            Err(e) => println!("🗑️ [CLEAR] >> ❌ Failed to delete: {}", e),
-                        }
-                    } else {
-                         println!("🗑️ [CLEAR] >> Skipping non-artifact (ext: {:?})", ext);
-                    }
-                } else {
-                    println!("🗑️ [CLEAR] >> Skipping (no extension)");
-                }
-            }
-        },
-        Err(e) => {
-            println!("🗑️ [CLEAR] ❌ Failed to read directory: {}", e);
-            return Err(format!("Failed to read archive: {}", e));
+
+    let channel = BuildChannel::new(&app, &uuid::Uuid::new_v4().to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        match ios::nuke_ios_remote(&channel, mac_config, remote_path, selected) {
+            Ok(msg) => channel.line(format!("✅ {}", msg)),
+            Err(e) => channel.line(format!("❌ iOS Nuke Failed: {}", e)),
         }
-    }
-    
-    if deleted == 0 {
-        Ok("No APKs to clear.".to_string())
-    } else {
-        Ok(format!("Cleared {} APK(s)", deleted))
-    }
+    });
+    Ok(NukeReport { dry_run: false, targets: Vec::new(), message: "Nuke Ignited".into() })
 }
 
+/// Sizes of DerivedData, simulators, archives and CocoaPods caches on the Mac
 #[tauri::command]
-async fn start_ios_build(app: tauri::AppHandle, working_dir: String, mac_config: ios::MacConfig, remote_path: String, scheme: String, build_type: String) -> Result<String, String> {
-    let app_handle = app.clone();
-    std::thread::spawn(move || {
-        // 1. Convert Windows path to WSL path for rsync
-        let wsl_local_path = windows_to_wsl_path(&working_dir);
-        let _ = app_handle.emit("build-output", "🔄 Syncing files to Mac...".to_string());
+async fn get_mac_cache_report(mac_config: ios::MacConfig) -> Result<Vec<cache::CacheEntry>, HyperZenithError> {
+    tauri::async_runtime::spawn_blocking(move || ios::mac_cache_report(&mac_config)).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
+}
 
-        // 2. Sync Files
-        match ios::sync_files(&wsl_local_path, &mac_config, &remote_path) {
-            Ok(_) => { let _ = app_handle.emit("build-output", "✅ Sync Complete.".to_string()); },
-            Err(e) => { 
-                let _ = app_handle.emit("build-output", format!("❌ Sync Failed: {}", e)); 
-                return; // Abort build if sync fails
-            }
-        }
+/// Screenshot (`kind: "screenshot"`) or record (`"recording"`) the simulator on the Mac and
+/// save it under the project's logs folder. Also sent on `simulator-capture`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn capture_simulator(
+    app: tauri::AppHandle,
+    working_dir: String,
+    mac_config: ios::MacConfig,
+    scheme: String,
+    simulator: Option<String>,
+    kind: simulator::CaptureKind,
+    duration_secs: Option<u64>,
+    launch: Option<bool>
+) -> Result<simulator::SimulatorCapture, HyperZenithError> {
+    let simulator = simulator.filter(|s| !s.trim().is_empty()).unwrap_or_else(|| xctest::DEFAULT_SIMULATOR.to_string());
+    tauri::async_runtime::spawn_blocking(move || {
+        simulator::capture(&app, &mac_config, &working_dir, &scheme, &simulator, kind, duration_secs.unwrap_or(10), launch.unwrap_or(true))
+    }).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
+}
 
-        // 3. Ignite Build
-        match ios::execute_turbo_ios(app_handle.clone(), mac_config, remote_path, scheme, build_type) {
-            Ok(msg) => { let _ = app_handle.emit("build-output", format!("✅ {}", msg)); },
-            Err(e) => { let _ = app_handle.emit("build-output", format!("❌ iOS Build Failed: {}", e)); },
-        }
-    });
-    Ok("Sync & Build Ignited".into())
+#[tauri::command]
+fn list_build_presets(app: tauri::AppHandle, working_dir: String) -> Vec<presets::BuildPreset> {
+    presets::load(&app, &working_dir)
 }
 
+/// Save a named build configuration for the project, replacing one with the same name
 #[tauri::command]
-async fn trigger_nuke_ios(app: tauri::AppHandle, mac_config: ios::MacConfig, remote_path: String) -> Result<String, String> {
-    let app_handle = app.clone();
-    std::thread::spawn(move || {
-        match ios::nuke_ios_remote(app_handle.clone(), mac_config, remote_path) {
-            Ok(msg) => { let _ = app_handle.emit("build-output", format!("✅ {}", msg)); },
-            Err(e) => { let _ = app_handle.emit("build-output", format!("❌ iOS Nuke Failed: {}", e)); },
-        }
-    });
-    Ok("Nuke Ignited".into())
+fn save_build_preset(app: tauri::AppHandle, working_dir: String, preset: presets::BuildPreset) -> Result<Vec<presets::BuildPreset>, HyperZenithError> {
+    presets::save(&app, &working_dir, preset)
 }
 
 #[tauri::command]
-async fn scan_for_projects(start_path: String) -> Vec<String> {
+fn delete_build_preset(app: tauri::AppHandle, working_dir: String, name: String) -> Result<Vec<presets::BuildPreset>, HyperZenithError> {
+    presets::delete(&app, &working_dir, &name)
+}
 
-    use std::collections::HashSet;
-    let mut projects = HashSet::new(); // Use Set to avoid duplicates
-    
-    // 1. Determine directories to scan
-    let mut scan_roots = Vec::new();
-    
-    // If user provided a path, check it + its parent
-    let p_path = std::path::Path::new(&start_path);
-    if p_path.exists() {
-        scan_roots.push(p_path.to_path_buf());
-        if let Some(parent) = p_path.parent() {
-            scan_roots.push(parent.to_path_buf());
-        }
-    }
-
-    // Always check the "Scratch" folder (Default workspace)
-    if let Ok(home) = std::env::var("USERPROFILE") {
-        let scratch = std::path::Path::new(&home)
-            .join(".gemini")
-            .join("antigravity")
-            .join("scratch");
-        if scratch.exists() { scan_roots.push(scratch); }
-        
-        let docs = std::path::Path::new(&home).join("Documents");
-        if docs.exists() { scan_roots.push(docs); }
+/// Approve the hook script version a build skipped (from `hook-untrusted`) so later builds run it
+#[tauri::command]
+fn trust_hook(app: tauri::AppHandle, working_dir: String, name: String, sha256: String) -> Result<(), HyperZenithError> {
+    hooks::trust(&app, &working_dir, &name, &sha256)
+}
+
+/// Start an Android build with a saved preset's configuration and return its id
+#[tauri::command]
+fn build_with_preset(app: tauri::AppHandle, state: tauri::State<'_, AppState>, working_dir: String, name: String) -> Result<String, HyperZenithError> {
+    let preset = presets::find(&app, &working_dir, &name)?;
+    if !expo::is_android_buildable(std::path::Path::new(&working_dir)) {
+        return Err(HyperZenithError::NotFound { path: std::path::Path::new(&working_dir).join("android").display().to_string() });
     }
+    Ok(start_android_build(&app, &state, working_dir, preset.options(), preset.verbosity))
+}
 
-    // 2. Helper to check if a folder is a Project
-    let is_android_project = |path: &std::path::Path| -> bool {
-        path.join("android").join("build.gradle").exists() || // Standard
-        path.join("android").join("settings.gradle").exists() // Alternative
-    };
+/// Environment variables exported into the project's builds, secret values left out
+#[tauri::command]
+fn get_build_env(app: tauri::AppHandle, working_dir: String) -> Vec<buildenv::EnvVar> {
+    buildenv::load(&app, &working_dir)
+}
 
-    // 3. Scan logic (Depth 2 recursion)
-    for root in scan_roots {
-        let walker = walkdir::WalkDir::new(&root)
-            .max_depth(3) // Look 3 levels deep
-            .follow_links(false)
-            .into_iter();
-
-        for entry in walker.filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if path.is_dir() && is_android_project(path) {
-                if let Some(s) = path.to_str() {
-                    projects.insert(s.to_string());
-                }
-            }
-        }
+/// Replace the project's build environment variables. Secret values go to Credential Manager.
+#[tauri::command]
+fn set_build_env(app: tauri::AppHandle, working_dir: String, vars: Vec<buildenv::EnvVar>) -> Result<(), HyperZenithError> {
+    buildenv::save(&app, &working_dir, vars)
+}
+
+/// Save the frontend's settings (minus passwords) and the known projects to `path`.
+/// Returns how many settings were written.
+#[tauri::command]
+fn export_settings(app: tauri::AppHandle, path: String, settings: serde_json::Map<String, serde_json::Value>) -> Result<usize, HyperZenithError> {
+    settings::export(std::path::Path::new(&path), &app.package_info().version.to_string(), settings, scaffold::known_projects(&app))
+}
+
+/// Read a settings export, adding its projects to the scanner's list. The frontend applies
+/// the returned settings.
+#[tauri::command]
+fn import_settings(app: tauri::AppHandle, path: String) -> Result<settings::SettingsBundle, HyperZenithError> {
+    let bundle = settings::import(std::path::Path::new(&path))?;
+    for project in bundle.projects.iter().filter(|p| std::path::Path::new(p).is_dir()) {
+        scaffold::remember_project(&app, project)?;
     }
+    Ok(bundle)
+}
 
-    projects.into_iter().collect()
+/// Compare this build with the latest GitHub release
+#[tauri::command]
+async fn check_for_updates(app: tauri::AppHandle) -> Result<updates::UpdateInfo, HyperZenithError> {
+    updates::check(&app.package_info().version.to_string())
+}
+
+/// Download the release installer, start it and quit so it can replace this executable
+#[tauri::command]
+async fn install_update(app: tauri::AppHandle, installer_name: String) -> Result<(), HyperZenithError> {
+    let installer = updates::download_and_launch(&app.package_info().version.to_string(), &installer_name)?;
+    println!("⬆️ [UPDATE] Started {}", installer.display());
+    app.exit(0);
+    Ok(())
+}
+
+/// Keep the Mac from idle-sleeping for `duration_secs` (0 lets it sleep again)
+#[tauri::command]
+async fn keep_mac_awake(mac_config: ios::MacConfig, duration_secs: u64) -> Result<String, HyperZenithError> {
+    tauri::async_runtime::spawn_blocking(move || ios::keep_mac_awake(&mac_config, duration_secs)).await
+        .map_err(|e| HyperZenithError::Other(e.to_string()))?
+}
+
+/// Pinned favorites first, then recently built projects with their last build
+#[tauri::command]
+fn get_recent_projects(app: tauri::AppHandle) -> Vec<recents::RecentProject> {
+    recents::list(&app)
+}
+
+#[tauri::command]
+fn pin_project(app: tauri::AppHandle, path: String, pinned: Option<bool>) -> Result<Vec<recents::RecentProject>, HyperZenithError> {
+    recents::pin(&app, &path, pinned.unwrap_or(true))
+}
+
+/// Android and iOS projects the last scan found, right away. A new scan of the start path, its parent
+/// and Documents runs in the background and reports on `projects-discovered`.
+#[tauri::command]
+fn scan_for_projects(app: tauri::AppHandle, start_path: String) -> Vec<scan::DiscoveredProject> {
+    scan::start(&app, &start_path)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
+        .manage(AppState::default())
+        // Must be first: a second launch (e.g. from a hyperzenith:// link) hands its URL to this instance
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             get_system_stats,
             get_hardware_profile,
+            get_power_status,
+            get_project_info,
+            audit_dependencies,
+            outdated_dependencies,
+            get_build_analytics,
+            set_battery_policy,
+            set_memory_policy,
+            set_stall_policy,
+            set_failure_cleanup_policy,
+            reclaim_wsl_memory,
+            check_ports,
+            get_push_config,
+            set_push_config,
+            test_push_notification,
+            kill_port_process,
+            set_locale,
             abort_build,
+            cancel_ios_pipeline,
+            get_active_builds,
+            start_control_server,
+            stop_control_server,
+            get_control_server,
             execute_build,
+            run_tests,
+            run_e2e,
+            analyze_bundle,
+            build_matrix,
+            publish_ota,
+            create_project,
+            clone_project,
+            purge_wsl_impact,
             purge_wsl,
             prewarm_engine,
+            refresh_dependencies,
+            queue_idle_job,
+            cancel_idle_job,
+            get_idle_job,
             nuke_build,
             open_build_archive,
             open_logs_folder,
+            open_in_editor,
+            list_logs,
+            get_logs_folder,
+            set_logs_folder,
             clear_archive,
+            undo_last_clear,
+            list_archive,
+            get_archive_index,
+            rename_artifact,
+            annotate_artifact,
+            symbolicate,
+            get_archive_storage,
+            flush_archive_pending,
+            compare_commits,
+            set_release_notes,
+            generate_release_notes,
+            list_adb_devices,
+            install_archived,
+            diff_artifacts,
             scan_for_projects,
+            get_recent_projects,
+            pin_project,
             start_ios_build,
-            trigger_nuke_ios
+            run_ios_tests,
+            list_fastlane_lanes,
+            run_fastlane,
+            list_npm_scripts,
+            run_npm_script,
+            reattach_ios_build,
+            pull_generated_files,
+            preview_sync,
+            trigger_nuke_ios,
+            keep_mac_awake,
+            capture_simulator,
+            check_for_updates,
+            get_build_env,
+            set_build_env,
+            list_build_presets,
+            save_build_preset,
+            delete_build_preset,
+            trust_hook,
+            build_with_preset,
+            export_settings,
+            import_settings,
+            install_update,
+            get_mac_cache_report,
+            run_doctor,
+            initialize_environment,
+            check_defender_exclusions,
+            apply_defender_exclusions,
+            create_support_bundle,
+            check_long_paths,
+            enable_long_paths,
+            get_wsl_disk_report,
+            compact_wsl_disk,
+            get_cache_report,
+            purge_caches
         ])
+        .setup(|app| {
+            use tauri_plugin_deep_link::DeepLinkExt;
+            // Installers register the scheme; dev builds register it at runtime
+            #[cfg(debug_assertions)]
+            let _ = app.deep_link().register_all();
+
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| deeplink::handle_urls(&handle, event.urls()));
+            // Cold start from a link
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                deeplink::handle_urls(app.handle(), urls);
+            }
+
+            logs::load(app.handle());
+            tray::init(app.handle())?;
+            idle::spawn_watcher(app.handle().clone());
+            memory::spawn_watcher(app.handle().clone());
+            watchdog::spawn_watcher(app.handle().clone());
+            resources::spawn_watcher(app.handle().clone());
+            Ok(())
+        })
+        // Closing the window hides it to the tray so running builds keep going; quit from the tray menu
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                api.prevent_close();
+                let _ = window.hide();
+            }
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -536,6 +2748,35 @@ mod tests {
         assert_eq!(windows_to_wsl_path("C:\\Users\\Game"), "/mnt/c/Users/Game");
         assert_eq!(windows_to_wsl_path("D:/Projects/App"), "/mnt/d/Projects/App");
         assert_eq!(windows_to_wsl_path("E:\\Work\\Dev"), "/mnt/e/Work/Dev");
+        assert_eq!(windows_to_wsl_path("C:\\Users\\Zoë\\My Apps\\Bob's App"), "/mnt/c/Users/Zoë/My Apps/Bob's App");
+        assert_eq!(windows_to_wsl_path("\\\\?\\D:\\Long\\Path"), "/mnt/d/Long/Path");
+        assert_eq!(windows_to_wsl_path("\\\\wsl$\\Ubuntu\\home\\dev\\app"), "/home/dev/app");
+        assert_eq!(windows_to_wsl_path("\\\\wsl.localhost\\Ubuntu-22.04\\home\\dev"), "/home/dev");
+        assert_eq!(windows_to_wsl_path("\\\\WSL$\\Debian"), "/");
+    }
+
+    #[test]
+    fn test_wsl_to_windows_path() {
+        assert_eq!(wsl_to_windows_path("/mnt/c/Users/Game/app", None), "C:\\Users\\Game\\app");
+        assert_eq!(wsl_to_windows_path("/mnt/d", None), "D:\\");
+        assert_eq!(wsl_to_windows_path("/home/dev/app/x.apk", Some("Ubuntu")), "\\\\wsl$\\Ubuntu\\home\\dev\\app\\x.apk");
+        assert_eq!(wsl_to_windows_path("/mnt/wsl/shared", Some("Ubuntu")), "\\\\wsl$\\Ubuntu\\mnt\\wsl\\shared");
+        assert_eq!(wsl_to_windows_path("/home/dev", None), "/home/dev");
+    }
+
+    #[test]
+    fn test_sh_quote() {
+        assert_eq!(sh_quote("/mnt/c/My Apps"), "'/mnt/c/My Apps'");
+        assert_eq!(sh_quote("/mnt/c/Bob's \"App\""), "'/mnt/c/Bob'\\''s \"App\"'");
+        assert_eq!(sh_quote("/mnt/c/アプリ/$HOME"), "'/mnt/c/アプリ/$HOME'");
+        assert_eq!(sh_quote_path("~/builds/Bob's App"), "~/'builds/Bob'\\''s App'");
+        assert_eq!(sh_quote_path("/Users/dev/app"), "'/Users/dev/app'");
+    }
+
+    #[test]
+    fn test_ps_quote() {
+        assert_eq!(ps_quote("C:\\Dev\\App"), "'C:\\Dev\\App'");
+        assert_eq!(ps_quote("C:\\Bob's Apps"), "'C:\\Bob''s Apps'");
     }
 
     #[test]
@@ -543,14 +2784,28 @@ mod tests {
         let gigabyte = 1024 * 1024 * 1024;
 
         // Test high-end system (Clamped at 16GB)
-        let hw_high = calculate_profile(32, 256 * gigabyte);
+        let hw_high = calculate_profile(32, arch::CoreClasses::default(), 256 * gigabyte, 256 * gigabyte, &[0.0; 32]);
         assert_eq!(hw_high.jvm_heap_gb, 16); 
         assert!(hw_high.max_workers >= 4);
         
         // Test low-spec system (Minimum 4GB)
-        let hw_low = calculate_profile(2, 4 * gigabyte);
+        let hw_low = calculate_profile(2, arch::CoreClasses::default(), 4 * gigabyte, 4 * gigabyte, &[0.0; 2]);
         assert_eq!(hw_low.jvm_heap_gb, 4); 
         assert_eq!(hw_low.max_workers, 4); 
+        assert_eq!(hw_low.constrained_by, None);
+
+        // Chrome and Docker holding 20GB and most of the CPU
+        let hw_busy = calculate_profile(16, arch::CoreClasses::default(), 32 * gigabyte, 8 * gigabyte, &[75.0; 16]);
+        assert_eq!((hw_busy.idle_cores, hw_busy.available_ram_gb), (4, 8));
+        assert_eq!(hw_busy.max_workers, 4);
+        assert_eq!(hw_busy.jvm_heap_gb, 6);
+        assert_eq!(hw_busy.constrained_by.as_deref(), Some("12 cores busy, only 8GB RAM free"));
+
+        // 8 P-cores with hyper-threading and 8 E-cores: 20 workers would oversubscribe
+        let hybrid = arch::CoreClasses { performance_cores: 8, performance_threads: 16, efficiency_cores: 8 };
+        let hw_hybrid = calculate_profile(24, hybrid, 64 * gigabyte, 48 * gigabyte, &[0.0; 24]);
+        assert_eq!((hw_hybrid.max_workers, hw_hybrid.performance_cores, hw_hybrid.efficiency_cores), (18, 8, 8));
+        assert!(hw_hybrid.summary().starts_with("🖥️ 24 cores (8P+8E, 24 idle)"));
     }
 
     #[test]
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Manager;
+use crate::error::HyperZenithError;
+
+/// Logs folder inside each project unless one is configured
+pub const LOGS_DIR: &str = "hyperzenith_logs";
+const FOLDERS_FILE: &str = "log_folders.json";
+
+/// Projects whose logs go somewhere else, by lowercased path. Loaded at startup so log writers
+/// don't need the app handle.
+static FOLDERS: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// A saved log, as listed by `list_logs`
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct LogFile {
+    pub path: String,
+    pub name: String,
+    /// `android` or `ios`; `None` for tests, OTA updates and other tooling
+    pub platform: Option<String>,
+    /// From the `_success` / `_fail` suffix, where the log has one
+    pub success: Option<bool>,
+    pub modified: Option<String>,
+    pub bytes: u64,
+}
+
+fn key(working_dir: &str) -> String {
+    working_dir.trim_end_matches(['\\', '/']).to_lowercase()
+}
+
+fn folders_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(FOLDERS_FILE))
+}
+
+/// Read the configured folders, once at startup
+pub fn load(app: &tauri::AppHandle) {
+    let folders: BTreeMap<String, String> = folders_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    *FOLDERS.lock().unwrap() = folders;
+}
+
+/// Where a project's logs go, Android and iOS alike
+pub fn logs_dir(working_dir: &str) -> PathBuf {
+    match FOLDERS.lock().unwrap().get(&key(working_dir)) {
+        Some(folder) => PathBuf::from(folder),
+        None => Path::new(working_dir).join(LOGS_DIR),
+    }
+}
+
+/// Send a project's logs to `folder`, or back into the project with `None`. Returns the folder now in use.
+pub fn set_folder(app: &tauri::AppHandle, working_dir: &str, folder: Option<String>) -> Result<PathBuf, HyperZenithError> {
+    let folder = folder.map(|f| f.trim().to_string()).filter(|f| !f.is_empty());
+    if let Some(folder) = &folder {
+        if !Path::new(folder).is_absolute() {
+            return Err(HyperZenithError::InvalidInput(format!("Logs folder must be a full path, got '{}'", folder)));
+        }
+        std::fs::create_dir_all(folder).map_err(|e| HyperZenithError::io_at(Path::new(folder), e))?;
+    }
+
+    let mut folders = FOLDERS.lock().unwrap();
+    match folder {
+        Some(folder) => folders.insert(key(working_dir), folder),
+        None => folders.remove(&key(working_dir)),
+    };
+    let path = folders_file(app).ok_or("No app config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(&*folders).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    drop(folders);
+    Ok(logs_dir(working_dir))
+}
+
+/// Platform and outcome from a log's name, e.g. `ios_build_fail_<timestamp>.log`
+fn describe(name: &str) -> (Option<&'static str>, Option<bool>) {
+    let platform = if name.starts_with("ios_") {
+        Some("ios")
+    } else if name.starts_with("android_") || name.starts_with("gradle_") {
+        Some("android")
+    } else {
+        None
+    };
+    let success = if name.contains("_success_") {
+        Some(true)
+    } else if name.contains("_fail_") {
+        Some(false)
+    } else {
+        None
+    };
+    (platform, success)
+}
+
+/// Logs saved for the project, newest first, optionally only one platform's
+pub fn list(working_dir: &str, platform: Option<&str>) -> Vec<LogFile> {
+    let Ok(entries) = std::fs::read_dir(logs_dir(working_dir)) else { return Vec::new() };
+    let mut logs: Vec<(std::time::SystemTime, LogFile)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|e| {
+            let metadata = e.metadata().ok()?;
+            let name = e.file_name().to_string_lossy().to_string();
+            let (log_platform, success) = describe(&name);
+            if platform.is_some() && log_platform != platform {
+                return None;
+            }
+            let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+            Some((modified, LogFile {
+                path: e.path().display().to_string(),
+                name,
+                platform: log_platform.map(str::to_string),
+                success,
+                modified: metadata.modified().ok().map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339()),
+                bytes: metadata.len(),
+            }))
+        })
+        .collect();
+    logs.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+    logs.into_iter().map(|(_, log)| log).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe("ios_build_fail_2026-01-04_10-00-00.log"), (Some("ios"), Some(false)));
+        assert_eq!(describe("android_build_success_2026-01-04_10-00-00.log"), (Some("android"), Some(true)));
+        assert_eq!(describe("gradle_refresh_fail_2026-01-04_10-00-00.log"), (Some("android"), Some(false)));
+        assert_eq!(describe("ios_tests_2026-01-04_10-00-00.log"), (Some("ios"), None));
+        assert_eq!(describe("jest_2026-01-04_10-00-00.log"), (None, None));
+
+        assert_eq!(logs_dir("C:\\Dev\\App"), Path::new("C:\\Dev\\App").join(LOGS_DIR));
+    }
+}
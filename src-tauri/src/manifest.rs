@@ -0,0 +1,358 @@
+use std::io::Read;
+use std::path::Path;
+use crate::error::HyperZenithError;
+
+/// Binary XML chunk types from the Android framework's ResourceTypes.h
+const RES_STRING_POOL: u16 = 0x0001;
+const RES_XML_START_ELEMENT: u16 = 0x0102;
+const RES_XML_RESOURCE_MAP: u16 = 0x0180;
+const UTF8_FLAG: u32 = 0x100;
+const NO_STRING: u32 = u32::MAX;
+
+/// Attribute resource ids, for manifests whose attribute names were stripped by an obfuscator
+const ATTRIBUTE_IDS: [(u32, &str); 4] = [
+    (0x0101_021b, "versionCode"),
+    (0x0101_021c, "versionName"),
+    (0x0101_020c, "minSdkVersion"),
+    (0x0101_0270, "targetSdkVersion"),
+];
+
+/// What the manifest of an APK or AAB says about the app
+#[derive(serde::Serialize, Clone, Default, Debug, PartialEq)]
+pub struct ManifestInfo {
+    pub package: Option<String>,
+    pub version_code: Option<u64>,
+    pub version_name: Option<String>,
+    pub min_sdk: Option<u32>,
+    pub target_sdk: Option<u32>,
+}
+
+/// An element's name and its attributes as text, whichever format the manifest came in
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+}
+
+impl ManifestInfo {
+    fn from_elements(elements: &[Element]) -> Self {
+        let attribute = |element: &str, name: &str| {
+            elements.iter()
+                .filter(|e| e.name == element)
+                .find_map(|e| e.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.clone()))
+        };
+        ManifestInfo {
+            package: attribute("manifest", "package"),
+            version_code: attribute("manifest", "versionCode").and_then(|v| v.parse().ok()),
+            version_name: attribute("manifest", "versionName"),
+            min_sdk: attribute("uses-sdk", "minSdkVersion").and_then(|v| v.parse().ok()),
+            target_sdk: attribute("uses-sdk", "targetSdkVersion").and_then(|v| v.parse().ok()),
+        }
+    }
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Lengths in UTF-8 pools take one byte, or two with the high bit set
+fn utf8_len(data: &[u8], at: &mut usize) -> Option<usize> {
+    let first = *data.get(*at)? as usize;
+    *at += 1;
+    if first & 0x80 == 0 {
+        return Some(first);
+    }
+    let second = *data.get(*at)? as usize;
+    *at += 1;
+    Some(((first & 0x7f) << 8) | second)
+}
+
+fn string_pool(chunk: &[u8]) -> Option<Vec<String>> {
+    let header_size = u16_at(chunk, 2)? as usize;
+    let count = u32_at(chunk, 8)? as usize;
+    let utf8 = u32_at(chunk, 16)? & UTF8_FLAG != 0;
+    let strings_start = u32_at(chunk, 20)? as usize;
+    (0..count)
+        .map(|i| {
+            let mut at = strings_start + u32_at(chunk, header_size + i * 4)? as usize;
+            if utf8 {
+                utf8_len(chunk, &mut at)?; // length in characters
+                let bytes = utf8_len(chunk, &mut at)?;
+                return Some(String::from_utf8_lossy(chunk.get(at..at + bytes)?).to_string());
+            }
+            let mut len = u16_at(chunk, at)? as usize;
+            at += 2;
+            if len & 0x8000 != 0 {
+                len = ((len & 0x7fff) << 16) | u16_at(chunk, at)? as usize;
+                at += 2;
+            }
+            let units: Vec<u16> = (0..len).map(|j| u16_at(chunk, at + j * 2)).collect::<Option<_>>()?;
+            Some(String::from_utf16_lossy(&units))
+        })
+        .collect()
+}
+
+/// Start elements of a compiled (AXML) manifest, the format APKs carry
+fn parse_axml(data: &[u8]) -> Option<Vec<Element>> {
+    let mut strings = Vec::new();
+    let mut resource_ids: Vec<u32> = Vec::new();
+    let mut elements = Vec::new();
+    let mut at = u16_at(data, 2)? as usize;
+
+    while at + 8 <= data.len() {
+        let kind = u16_at(data, at)?;
+        let header_size = u16_at(data, at + 2)? as usize;
+        let size = u32_at(data, at + 4)? as usize;
+        let chunk = data.get(at..at + size)?;
+        match kind {
+            RES_STRING_POOL => strings = string_pool(chunk)?,
+            RES_XML_RESOURCE_MAP => resource_ids = (header_size..size).step_by(4).filter_map(|o| u32_at(chunk, o)).collect(),
+            RES_XML_START_ELEMENT => {
+                let string = |index: u32| strings.get(index as usize).cloned();
+                let name = string(u32_at(chunk, 20)?)?;
+                let attribute_start = u16_at(chunk, 24)? as usize;
+                let attribute_size = u16_at(chunk, 26)? as usize;
+                let count = u16_at(chunk, 28)? as usize;
+                let attributes = (0..count)
+                    .filter_map(|i| {
+                        let a = header_size + attribute_start + i * attribute_size;
+                        let name_index = u32_at(chunk, a + 4)?;
+                        let known = resource_ids.get(name_index as usize)
+                            .and_then(|id| ATTRIBUTE_IDS.iter().find(|(known, _)| known == id))
+                            .map(|(_, name)| name.to_string());
+                        let name = known.or_else(|| string(name_index))?;
+                        let raw = u32_at(chunk, a + 8)?;
+                        let data_type = *chunk.get(a + 15)?;
+                        let value = u32_at(chunk, a + 16)?;
+                        let text = match data_type {
+                            _ if raw != NO_STRING => string(raw)?,
+                            0x03 => string(value)?,
+                            0x11 => format!("0x{:x}", value),
+                            0x12 => (value != 0).to_string(),
+                            _ => (value as i32).to_string(),
+                        };
+                        Some((name, text))
+                    })
+                    .collect();
+                elements.push(Element { name, attributes });
+            },
+            _ => {},
+        }
+        at += size.max(8);
+    }
+    Some(elements)
+}
+
+/// One protobuf field: its number and either a varint or a length-delimited payload
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+fn varint(data: &[u8], at: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*at)?;
+        *at += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn proto_fields(data: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+    let mut fields = Vec::new();
+    let mut at = 0;
+    while at < data.len() {
+        let key = varint(data, &mut at)?;
+        let field = match key & 7 {
+            0 => Field::Varint(varint(data, &mut at)?),
+            1 => { at += 8; continue },
+            2 => {
+                let len = varint(data, &mut at)? as usize;
+                let bytes = data.get(at..at + len)?;
+                at += len;
+                Field::Bytes(bytes)
+            },
+            5 => { at += 4; continue },
+            _ => return None,
+        };
+        fields.push((key >> 3, field));
+    }
+    Some(fields)
+}
+
+fn proto_string(fields: &[(u64, Field)], number: u64) -> Option<String> {
+    fields.iter().find_map(|(n, f)| match f {
+        Field::Bytes(b) if *n == number => Some(String::from_utf8_lossy(b).to_string()),
+        _ => None,
+    })
+}
+
+fn proto_message<'a>(fields: &[(u64, Field<'a>)], number: u64) -> Option<Vec<(u64, Field<'a>)>> {
+    fields.iter().find_map(|(n, f)| match f {
+        Field::Bytes(b) if *n == number => proto_fields(b),
+        _ => None,
+    })
+}
+
+/// Elements of an aapt2 protobuf `XmlNode`, the format AABs carry: `element` is field 1,
+/// with `name` 3, `attribute` 4 and `child` 5. Attributes keep their text in `value` (3),
+/// or only a compiled primitive (6 → 7) for integers.
+fn parse_proto_xml(data: &[u8], elements: &mut Vec<Element>) -> Option<()> {
+    let node = proto_fields(data)?;
+    let Some(element) = proto_message(&node, 1) else { return Some(()) };
+    let attributes = element.iter()
+        .filter_map(|(n, f)| match f {
+            Field::Bytes(b) if *n == 4 => proto_fields(b),
+            _ => None,
+        })
+        .filter_map(|attribute| {
+            let name = proto_string(&attribute, 2)?;
+            let text = proto_string(&attribute, 3).filter(|v| !v.is_empty()).or_else(|| {
+                let primitive = proto_message(&proto_message(&attribute, 6)?, 7)?;
+                primitive.iter().find_map(|(_, f)| match f {
+                    Field::Varint(v) => Some((*v as i32).to_string()),
+                    Field::Bytes(_) => None,
+                })
+            })?;
+            Some((name, text))
+        })
+        .collect();
+    elements.push(Element { name: proto_string(&element, 3).unwrap_or_default(), attributes });
+    for (n, f) in &element {
+        if let (5, Field::Bytes(child)) = (n, f) {
+            parse_proto_xml(child, elements)?;
+        }
+    }
+    Some(())
+}
+
+/// Package, version and SDK levels from an APK's or AAB's manifest
+pub fn read(artifact: &Path) -> Result<ManifestInfo, HyperZenithError> {
+    let file = std::fs::File::open(artifact).map_err(|e| HyperZenithError::io_at(artifact, e))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| HyperZenithError::InvalidInput(format!("'{}' is not a valid APK/AAB: {}", artifact.display(), e)))?;
+    let mut read_entry = |name: &str| -> Option<Vec<u8>> {
+        let mut entry = zip.by_name(name).ok()?;
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).ok()?;
+        Some(data)
+    };
+
+    let elements = if let Some(data) = read_entry("AndroidManifest.xml") {
+        parse_axml(&data)
+    } else if let Some(data) = read_entry("base/manifest/AndroidManifest.xml") {
+        let mut elements = Vec::new();
+        parse_proto_xml(&data, &mut elements).map(|_| elements)
+    } else {
+        return Err(HyperZenithError::InvalidInput(format!("No AndroidManifest.xml in '{}'", artifact.display())));
+    };
+    let elements = elements.ok_or_else(|| HyperZenithError::InvalidInput(format!("Unreadable manifest in '{}'", artifact.display())))?;
+    Ok(ManifestInfo::from_elements(&elements))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Name, raw value, data type and data, the first two as string indexes
+    type Attribute = (u32, u32, u8, u32);
+
+    /// A minimal AXML file: a UTF-16 string pool, a resource map and start elements
+    fn axml(strings: &[&str], elements: &[(u32, &[Attribute])]) -> Vec<u8> {
+        let push16 = |out: &mut Vec<u8>, v: u16| out.extend_from_slice(&v.to_le_bytes());
+        let push32 = |out: &mut Vec<u8>, v: u32| out.extend_from_slice(&v.to_le_bytes());
+
+        let mut data = Vec::new();
+        let mut offsets = Vec::new();
+        for s in strings {
+            offsets.push(data.len() as u32);
+            let units: Vec<u16> = s.encode_utf16().collect();
+            push16(&mut data, units.len() as u16);
+            units.iter().for_each(|u| push16(&mut data, *u));
+            push16(&mut data, 0);
+        }
+        let mut pool = Vec::new();
+        let strings_start = 28 + 4 * strings.len() as u32;
+        push16(&mut pool, RES_STRING_POOL);
+        push16(&mut pool, 28);
+        push32(&mut pool, strings_start + data.len() as u32);
+        push32(&mut pool, strings.len() as u32);
+        push32(&mut pool, 0);
+        push32(&mut pool, 0);
+        push32(&mut pool, strings_start);
+        push32(&mut pool, 0);
+        offsets.iter().for_each(|o| push32(&mut pool, *o));
+        pool.extend(data);
+
+        let mut body = pool;
+        // versionCode's name index maps to its resource id
+        push16(&mut body, RES_XML_RESOURCE_MAP);
+        push16(&mut body, 8);
+        push32(&mut body, 16);
+        push32(&mut body, 0);
+        push32(&mut body, 0x0101_021b);
+        for (name, attributes) in elements {
+            push16(&mut body, RES_XML_START_ELEMENT);
+            push16(&mut body, 16);
+            push32(&mut body, 36 + 20 * attributes.len() as u32);
+            push32(&mut body, 1);
+            push32(&mut body, NO_STRING);
+            push32(&mut body, NO_STRING);
+            push32(&mut body, *name);
+            push16(&mut body, 20);
+            push16(&mut body, 20);
+            push16(&mut body, attributes.len() as u16);
+            push16(&mut body, 0);
+            push16(&mut body, 0);
+            push16(&mut body, 0);
+            for (name, raw, data_type, value) in attributes.iter() {
+                push32(&mut body, NO_STRING);
+                push32(&mut body, *name);
+                push32(&mut body, *raw);
+                push16(&mut body, 8);
+                body.push(0);
+                body.push(*data_type);
+                push32(&mut body, *value);
+            }
+        }
+        let mut file = Vec::new();
+        push16(&mut file, 0x0003);
+        push16(&mut file, 8);
+        push32(&mut file, 8 + body.len() as u32);
+        file.extend(body);
+        file
+    }
+
+    #[test]
+    fn test_parse_manifests() {
+        // An obfuscated name at index 1, recognized by its resource id
+        let strings = ["", "x", "versionName", "package", "manifest", "uses-sdk", "minSdkVersion", "targetSdkVersion", "com.example.app", "1.4.2"];
+        let data = axml(&strings, &[
+            (4, &[(1, NO_STRING, 0x10, 42), (2, 9, 0x03, 9), (3, 8, 0x03, 8)]),
+            (5, &[(6, NO_STRING, 0x10, 24), (7, NO_STRING, 0x10, 34)]),
+        ]);
+        let elements = parse_axml(&data).unwrap();
+        assert_eq!(ManifestInfo::from_elements(&elements), ManifestInfo {
+            package: Some("com.example.app".to_string()),
+            version_code: Some(42),
+            version_name: Some("1.4.2".to_string()),
+            min_sdk: Some(24),
+            target_sdk: Some(34),
+        });
+
+        // XmlNode { element { name: "manifest", attribute { name: "versionCode", compiled_item { prim { int_decimal_value: 7 } } },
+        //   child { element { name: "uses-sdk", attribute { name: "minSdkVersion", value: "23" } } } } }
+        let proto = b"\x0a\x42\x1a\x08manifest\x22\x13\x12\x0bversionCode\x32\x04\x3a\x02\x30\x07\x2a\x21\x0a\x1f\x1a\x08uses-sdk\x22\x13\x12\x0dminSdkVersion\x1a\x0223";
+        let mut elements = Vec::new();
+        parse_proto_xml(proto, &mut elements).unwrap();
+        let info = ManifestInfo::from_elements(&elements);
+        assert_eq!((info.version_code, info.min_sdk, info.package), (Some(7), Some(23), None));
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::Instant;
+use tauri::Emitter;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+use crate::i18n::{tr, Msg};
+
+/// One cell of a build matrix
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct MatrixEntry {
+    /// Flavor + build type such as `stagingDebug`; `None` builds `debug`
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// `apk` or `aab`
+    pub build_type: String,
+}
+
+impl MatrixEntry {
+    pub fn label(&self) -> String {
+        format!("{} {}", self.variant.as_deref().unwrap_or("debug"), self.build_type.to_uppercase())
+    }
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct MatrixResult {
+    pub entry: MatrixEntry,
+    pub success: bool,
+    pub message: String,
+    pub elapsed_secs: f64,
+    /// Archived copies of what this entry produced
+    pub artifacts: Vec<String>,
+}
+
+/// Sent on `matrix-summary` (and `matrix-summary-{id}`) once every entry has run
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct MatrixSummary {
+    pub id: String,
+    pub success: bool,
+    pub results: Vec<MatrixResult>,
+    pub elapsed_secs: f64,
+    pub report_path: Option<String>,
+}
+
+fn format_elapsed(secs: f64) -> String {
+    let secs = secs.round() as u64;
+    format!("{}m {:02}s", secs / 60, secs % 60)
+}
+
+/// Plain-text report saved next to the build logs
+pub fn render_report(working_dir: &str, results: &[MatrixResult], elapsed_secs: f64) -> String {
+    let mut report = format!("{}\n\n", tr(Msg::MatrixReport, &[("project", &working_dir)]));
+    for result in results {
+        report += &format!(
+            "{} {:<28} {:>8}   {}\n",
+            if result.success { "✅" } else { "❌" },
+            result.entry.label(),
+            format_elapsed(result.elapsed_secs),
+            result.message
+        );
+        for artifact in &result.artifacts {
+            report += &format!("     {}\n", artifact);
+        }
+    }
+    let passed = results.iter().filter(|r| r.success).count();
+    let summary = tr(Msg::MatrixSummary, &[("passed", &passed), ("total", &results.len()), ("elapsed", &format_elapsed(elapsed_secs))]);
+    report += &format!("\n{}\n", summary);
+    report
+}
+
+/// Build each entry in turn on the same channel. They share the Gradle daemon warmed by the
+/// first, and dependency installs and gates only run for the first entry.
+pub async fn run(
+    channel: &BuildChannel,
+    hw: crate::HardwareProfile,
+    working_dir: &str,
+    entries: Vec<MatrixEntry>,
+    base: crate::AndroidBuildOptions,
+    stop_on_failure: bool,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<MatrixSummary, HyperZenithError> {
+    let started = Instant::now();
+    let total = entries.len();
+    let mut results = Vec::new();
+
+    for (i, entry) in entries.into_iter().enumerate() {
+        channel.line(format!("🧮 [{}/{}] Building {}...", i + 1, total, entry.label()));
+        let mut options = base.clone();
+        options.build_type = entry.build_type.clone();
+        options.variant = entry.variant.clone();
+        if i > 0 {
+            options.install_dependencies = false;
+            options.gates = crate::gates::GateOptions::default();
+        }
+
+        let timer = crate::history::BuildTimer::start();
+        let result = crate::run_android_build(channel, hw.clone(), working_dir.to_string(), options, &mut cancel).await;
+        let record = crate::history::BuildRecord {
+            cache_hit_rate: result.as_ref().ok().and_then(|r| r.cache_hit_rate),
+            warnings: result.as_ref().ok().map(|r| r.warnings),
+            phases: channel.take_phases(),
+            ..timer.record(&channel.id, working_dir, "android", &entry.build_type, entry.variant.clone(), &result)
+        };
+        crate::history::record(&channel.app, &record);
+        let elapsed_secs = record.duration_secs;
+        let failed = result.is_err();
+        match result {
+            Err(e @ HyperZenithError::BuildAborted { .. }) => return Err(e),
+            Ok(build) => results.push(MatrixResult {
+                entry,
+                success: true,
+                message: build.message,
+                elapsed_secs,
+                artifacts: build.archived.iter().map(|p| p.display().to_string()).collect(),
+            }),
+            Err(e) => {
+                channel.line(format!("❌ {} failed: {}", entry.label(), e));
+                results.push(MatrixResult { entry, success: false, message: e.to_string(), elapsed_secs, artifacts: Vec::new() });
+            },
+        }
+        if failed && stop_on_failure && i + 1 < total {
+            channel.line(format!("⏹️ Stopping the matrix, {} entr{} skipped", total - i - 1, if total - i - 1 == 1 { "y" } else { "ies" }));
+            break;
+        }
+    }
+
+    let elapsed_secs = started.elapsed().as_secs_f64();
+    let report = render_report(working_dir, &results, elapsed_secs);
+    let report_path = crate::write_build_log(working_dir, "matrix", &report);
+    channel.line(format!("📊 Matrix report saved to: {}", report_path.display()));
+
+    let summary = MatrixSummary {
+        id: channel.id.clone(),
+        success: results.len() == total && results.iter().all(|r| r.success),
+        results,
+        elapsed_secs,
+        report_path: Some(report_path.display().to_string()),
+    };
+    let _ = channel.app.emit(&format!("matrix-summary-{}", channel.id), &summary);
+    let _ = channel.app.emit("matrix-summary", &summary);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_report() {
+        let result = |variant: &str, build_type: &str, success: bool, artifacts: Vec<String>| MatrixResult {
+            entry: MatrixEntry { variant: Some(variant.to_string()), build_type: build_type.to_string() },
+            success,
+            message: if success { tr(Msg::BuildCompleted, &[]) } else { tr(Msg::GradleFailed, &[("code", &1), ("log", &"fail.log")]) },
+            elapsed_secs: 72.4,
+            artifacts,
+        };
+        let results = vec![
+            result("stagingDebug", "apk", true, vec!["C:\\builds\\app-staging-debug_1.apk".to_string()]),
+            result("prodRelease", "aab", false, Vec::new()),
+        ];
+        let report = render_report("C:\\app", &results, 185.0);
+
+        assert!(report.contains("✅ stagingDebug APK"));
+        assert!(report.contains("❌ prodRelease AAB"));
+        assert!(report.contains("     C:\\builds\\app-staging-debug_1.apk\n"));
+        assert!(report.ends_with("1/2 succeeded in 3m 05s\n"));
+    }
+}
@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use crate::error::HyperZenithError;
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Builds whose Gradle daemons and page cache swell vmmem
+const WSL_HEAVY_KINDS: [&str; 3] = ["android", "prewarm", "refresh"];
+/// Free page reporting hands memory back to Windows gradually, so measure again after a bit
+const SETTLE_DELAY: Duration = Duration::from_secs(10);
+
+/// Drop clean page cache (dentries and inodes stay), then compact so free page reporting can
+/// return whole blocks to Windows instead of scattered pages
+const DROP_CACHES_SCRIPT: &str = "sync && echo 1 > /proc/sys/vm/drop_caches && echo 1 > /proc/sys/vm/compact_memory";
+
+/// What to do about WSL's memory once builds stop
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+pub struct MemoryPolicy {
+    /// Drop the page cache after each Android build, prewarm or dependency refresh
+    pub drop_caches: bool,
+    /// Shut WSL down after this many minutes without any build, 0 to leave it running. Every
+    /// distro stops with it, so it's skipped while Docker Desktop runs.
+    pub shutdown_idle_minutes: u64,
+}
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReclaimAction {
+    DropCaches,
+    Shutdown,
+}
+
+/// Sent on `wsl-memory-reclaimed`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct MemoryReclaimed {
+    pub action: ReclaimAction,
+    /// Working set of the WSL VM, `None` when it isn't running
+    pub vmmem_before_bytes: Option<u64>,
+    pub vmmem_after_bytes: Option<u64>,
+    /// Distros a shutdown stopped, not just the one builds use
+    pub stopped_distros: Vec<String>,
+}
+
+/// Decides when to reclaim from which builds are running
+#[derive(Default)]
+struct Reclaimer {
+    heavy_running: bool,
+    /// A WSL build ran since the last shutdown; WSL the user keeps around for other work is left alone
+    wsl_used: bool,
+    idle_since: Option<Instant>,
+}
+
+impl Reclaimer {
+    fn observe(&mut self, running: &[String], now: Instant, policy: &MemoryPolicy) -> Option<ReclaimAction> {
+        let heavy = running.iter().any(|kind| WSL_HEAVY_KINDS.contains(&kind.as_str()));
+        let finished = self.heavy_running && !heavy;
+        self.heavy_running = heavy;
+        self.wsl_used |= heavy;
+        if running.is_empty() {
+            self.idle_since.get_or_insert(now);
+        } else {
+            self.idle_since = None;
+        }
+
+        if finished && policy.drop_caches {
+            return Some(ReclaimAction::DropCaches);
+        }
+        let idle_for = self.idle_since.map(|since| now.duration_since(since)).unwrap_or_default();
+        if policy.shutdown_idle_minutes > 0 && self.wsl_used && idle_for >= Duration::from_secs(policy.shutdown_idle_minutes * 60) {
+            self.wsl_used = false;
+            return Some(ReclaimAction::Shutdown);
+        }
+        None
+    }
+}
+
+/// Memory held by the WSL VM: `vmmem`, or `vmmemWSL` on Windows 11
+pub fn vmmem_bytes(state: &AppState) -> Option<u64> {
+    let mut sys = state.system.lock().ok()?;
+    sys.refresh_processes();
+    let vm = sys.processes().values().filter(|p| p.name().to_lowercase().starts_with("vmmem"));
+    vm.map(|p| p.memory()).reduce(|a, b| a + b)
+}
+
+/// Reclaim now, measuring vmmem before and once it has settled. Caches are dropped in the
+/// distro `project` builds in.
+pub async fn reclaim(app: &tauri::AppHandle, action: ReclaimAction, project: Option<&str>) -> Result<MemoryReclaimed, HyperZenithError> {
+    let state = app.state::<AppState>();
+    let vmmem_before_bytes = vmmem_bytes(&state);
+    let distro = project.and_then(crate::wsl::project_distro);
+    let stopped_distros = tauri::async_runtime::spawn_blocking(move || match action {
+        ReclaimAction::DropCaches => {
+            println!("🧽 [MEMORY] Dropping WSL page cache");
+            crate::wsl::run_as_root_in(distro.as_deref(), DROP_CACHES_SCRIPT).map(|_| Vec::new())
+        },
+        ReclaimAction::Shutdown => {
+            let running = crate::wsl::running_distros()?;
+            println!("🧽 [MEMORY] No builds for a while, shutting WSL down: {}", running.iter().map(|d| d.name.as_str()).collect::<Vec<_>>().join(", "));
+            crate::wsl::shutdown()?;
+            Ok(running.into_iter().map(|d| d.name).collect())
+        },
+    })
+    .await
+    .map_err(|e| HyperZenithError::Other(e.to_string()))??;
+    tokio::time::sleep(SETTLE_DELAY).await;
+    let reclaimed = MemoryReclaimed { action, vmmem_before_bytes, vmmem_after_bytes: vmmem_bytes(&state), stopped_distros };
+    let _ = app.emit("wsl-memory-reclaimed", &reclaimed);
+    Ok(reclaimed)
+}
+
+/// Whether Docker Desktop's distro is up, whose containers a shutdown would stop
+async fn docker_running() -> bool {
+    let distros = tauri::async_runtime::spawn_blocking(crate::wsl::running_distros).await;
+    distros.ok().and_then(Result::ok).is_some_and(|distros| distros.iter().any(|d| d.docker))
+}
+
+/// Watch builds in the background for the lifetime of the app, applying the memory policy
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut reclaimer = Reclaimer::default();
+        // The project the last heavy build was for, whose distro gets its caches dropped
+        let mut last_project: Option<String> = None;
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            let policy = state.memory_policy.lock().map(|p| *p).unwrap_or_default();
+            // A dev server counts too: shutting WSL down would take it with it
+            let builds: Vec<_> = state.builds.active().into_iter().chain(state.builds.services()).collect();
+            if let Some(heavy) = builds.iter().find(|b| WSL_HEAVY_KINDS.contains(&b.platform.as_str())) {
+                last_project = Some(heavy.project.clone());
+            }
+            let running: Vec<String> = builds.into_iter().map(|b| b.platform).collect();
+            let Some(action) = reclaimer.observe(&running, Instant::now(), &policy) else { continue };
+            if action == ReclaimAction::Shutdown && docker_running().await {
+                println!("🧽 [MEMORY] Docker Desktop is running in WSL, not shutting WSL down");
+                continue;
+            }
+            if let Err(e) = reclaim(&app, action, last_project.as_deref()).await {
+                println!("🧽 [MEMORY] ❌ {:?} failed: {}", action, e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reclaimer() {
+        let policy = MemoryPolicy { drop_caches: true, shutdown_idle_minutes: 15 };
+        let start = Instant::now();
+        let at = |mins: u64| start + Duration::from_secs(mins * 60);
+        let android = vec!["android".to_string()];
+        let mut reclaimer = Reclaimer::default();
+
+        // Nothing built yet: WSL is someone else's business
+        assert_eq!(reclaimer.observe(&[], at(0), &policy), None);
+        assert_eq!(reclaimer.observe(&[], at(30), &policy), None);
+
+        assert_eq!(reclaimer.observe(&android, at(31), &policy), None);
+        assert_eq!(reclaimer.observe(&[], at(40), &policy), Some(ReclaimAction::DropCaches));
+        assert_eq!(reclaimer.observe(&["tests".to_string()], at(50), &policy), None);
+        assert_eq!(reclaimer.observe(&[], at(51), &policy), None);
+        assert_eq!(reclaimer.observe(&[], at(66), &policy), Some(ReclaimAction::Shutdown));
+        assert_eq!(reclaimer.observe(&[], at(90), &policy), None);
+
+        let off = MemoryPolicy::default();
+        let mut reclaimer = Reclaimer::default();
+        reclaimer.observe(&android, at(0), &off);
+        assert_eq!(reclaimer.observe(&[], at(1), &off), None);
+        assert_eq!(reclaimer.observe(&[], at(600), &off), None);
+    }
+}
@@ -0,0 +1,461 @@
+use std::path::{Path, PathBuf};
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Npm,
+    /// Yarn 1.x
+    Yarn,
+    /// Yarn 2+ (`.yarnrc.yml`), which renamed `--frozen-lockfile` to `--immutable`
+    YarnBerry,
+    Pnpm,
+    Bun,
+}
+
+impl PackageManager {
+    pub fn binary(self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Yarn | PackageManager::YarnBerry => "yarn",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Bun => "bun",
+        }
+    }
+
+    fn lockfiles(self) -> &'static [&'static str] {
+        match self {
+            PackageManager::Npm => &["package-lock.json", "npm-shrinkwrap.json"],
+            PackageManager::Yarn | PackageManager::YarnBerry => &["yarn.lock"],
+            PackageManager::Pnpm => &["pnpm-lock.yaml"],
+            PackageManager::Bun => &["bun.lockb", "bun.lock"],
+        }
+    }
+
+    /// Install exactly what the lockfile says (`frozen`) or resolve afresh
+    pub fn install_args(self, frozen: bool) -> &'static str {
+        match (self, frozen) {
+            (PackageManager::Npm, true) => "ci --prefer-offline",
+            (PackageManager::Yarn, true) => "install --frozen-lockfile",
+            (PackageManager::YarnBerry, true) => "install --immutable",
+            (PackageManager::Pnpm | PackageManager::Bun, true) => "install --frozen-lockfile",
+            (_, false) => "install",
+        }
+    }
+
+    /// Shell command running a binary from node_modules, like `npx`. Uses `$PM` from `hydrate_script`.
+    pub fn exec_cmd(self, command: &str) -> String {
+        match self {
+            PackageManager::Npm => format!("$PM exec --yes -- {}", command),
+            PackageManager::Yarn | PackageManager::YarnBerry => format!("$PM {}", command),
+            PackageManager::Pnpm => format!("$PM exec {}", command),
+            PackageManager::Bun => format!("$PM x {}", command),
+        }
+    }
+
+    /// Self-contained script running a node_modules binary, setting `$PM` first
+    pub fn run_bin(self, command: &str) -> String {
+        format!("{}{}", runner(self), self.exec_cmd(command))
+    }
+
+    /// Self-contained script running one of package.json's scripts, setting `$PM` first
+    pub fn run_script(self, name: &str) -> String {
+        format!("{}$PM run {}", runner(self), crate::sh_quote(name))
+    }
+
+    /// Yarn and pnpm ship through corepack with modern Node, so they may not be on PATH yet
+    fn via_corepack(self) -> bool {
+        matches!(self, PackageManager::Yarn | PackageManager::YarnBerry | PackageManager::Pnpm)
+    }
+}
+
+/// `packageManager` from package.json (e.g. `pnpm@8.15.0`), which wins over lockfiles
+fn from_package_json(field: &str) -> Option<PackageManager> {
+    let (name, version) = field.split_once('@')?;
+    let major: u32 = version.split('.').next()?.parse().ok()?;
+    match name {
+        "npm" => Some(PackageManager::Npm),
+        "yarn" if major >= 2 => Some(PackageManager::YarnBerry),
+        "yarn" => Some(PackageManager::Yarn),
+        "pnpm" => Some(PackageManager::Pnpm),
+        "bun" => Some(PackageManager::Bun),
+        _ => None,
+    }
+}
+
+/// The project's package manager, from `packageManager` or its lockfile; npm if neither says
+pub fn detect(project_dir: &Path) -> PackageManager {
+    let declared = std::fs::read_to_string(project_dir.join("package.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+        .and_then(|pkg| pkg["packageManager"].as_str().and_then(from_package_json));
+    if let Some(pm) = declared {
+        return pm;
+    }
+
+    let has = |file: &str| project_dir.join(file).exists();
+    if PackageManager::Bun.lockfiles().iter().any(|f| has(f)) {
+        PackageManager::Bun
+    } else if has("pnpm-lock.yaml") {
+        PackageManager::Pnpm
+    } else if has("yarn.lock") {
+        if has(".yarnrc.yml") { PackageManager::YarnBerry } else { PackageManager::Yarn }
+    } else {
+        PackageManager::Npm
+    }
+}
+
+/// A JS monorepo containing the app: dependencies are installed (and hoisted) at `root`
+#[derive(Debug, PartialEq)]
+pub struct Workspace {
+    pub root: PathBuf,
+    /// App directory relative to `root`, with forward slashes
+    pub app_subdir: String,
+}
+
+/// `workspaces` as an array (npm/yarn/bun) or `{ "packages": [...] }` (yarn classic)
+fn declares_workspaces(pkg: &serde_json::Value) -> bool {
+    let workspaces = &pkg["workspaces"];
+    workspaces.is_array() || workspaces["packages"].is_array()
+}
+
+fn is_workspace_root(dir: &Path) -> bool {
+    dir.join("pnpm-workspace.yaml").exists()
+        || dir.join("turbo.json").exists()
+        || std::fs::read_to_string(dir.join("package.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+            .is_some_and(|pkg| declares_workspaces(&pkg))
+}
+
+/// The nearest enclosing workspace root above the app, if the app lives in a monorepo
+pub fn workspace(project_dir: &Path) -> Option<Workspace> {
+    let root = project_dir.ancestors().skip(1).find(|dir| is_workspace_root(dir))?;
+    let app_subdir = project_dir.strip_prefix(root).ok()?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some(Workspace { root: root.to_path_buf(), app_subdir })
+}
+
+/// Where installs run for this project: the workspace root in a monorepo, the project otherwise
+pub fn install_root(project_dir: &Path) -> PathBuf {
+    workspace(project_dir).map(|w| w.root).unwrap_or_else(|| project_dir.to_path_buf())
+}
+
+/// Touched after a successful install so later builds can tell the lockfile changed since
+const HYDRATED_MARKER: &str = ".hyperzenith-hydrated";
+
+/// Why dependencies in `install_root` need (re)installing, `None` if they look current
+pub fn install_reason(install_root: &Path) -> Option<String> {
+    let node_modules = install_root.join("node_modules");
+    if !node_modules.is_dir() {
+        return Some("node_modules missing".to_string());
+    }
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    // Installs done outside HyperZenith leave no marker; the folder's own mtime is the next best thing
+    let installed = modified(&node_modules.join(HYDRATED_MARKER)).or_else(|| modified(&node_modules))?;
+    let pm = detect(install_root);
+    pm.lockfiles().iter()
+        .find_map(|f| modified(&install_root.join(f)).filter(|changed| *changed > installed).map(|_| *f))
+        .map(|lockfile| format!("{} changed since the last install", lockfile))
+}
+
+fn runner(pm: PackageManager) -> String {
+    let bin = pm.binary();
+    if pm.via_corepack() {
+        format!("PM={bin}; command -v {bin} >/dev/null 2>&1 || PM='corepack {bin}'; ", bin = bin)
+    } else {
+        format!("PM={}; ", bin)
+    }
+}
+
+/// Install dependencies unconditionally, strictly from the lockfile if there is one
+fn install_script(pm: PackageManager) -> String {
+    let bin = pm.binary();
+    let lock_test = pm.lockfiles().iter().map(|f| format!("[ -f '{}' ]", f)).collect::<Vec<_>>().join(" || ");
+    format!(
+        "if {lock_test}; then \
+            echo '>> Hydrating with {bin} {frozen} (Strict)...'; \
+            $PM {frozen}; \
+        else \
+            echo '>> Hydrating with {bin} {fresh} (Fallback)...'; \
+            $PM {fresh}; \
+        fi",
+        lock_test = lock_test,
+        bin = bin,
+        frozen = pm.install_args(true),
+        fresh = pm.install_args(false),
+    )
+}
+
+/// Shell snippet run from the install root: sets `$PM` to the package manager command and installs
+/// dependencies when node_modules is missing, strictly from the lockfile if there is one.
+/// Works the same in WSL and on the Mac.
+pub fn hydrate_script(pm: PackageManager) -> String {
+    format!("{}if [ ! -d 'node_modules' ]; then {}; fi", runner(pm), install_script(pm))
+}
+
+/// Full WSL command for the pre-build install step, marking node_modules as current on success
+pub fn install_cmd(wsl_install_root: &str, pm: PackageManager) -> String {
+    format!(
+        "cd {} && {}{{ {}; }} 2>&1 && touch node_modules/{}",
+        crate::sh_quote(wsl_install_root),
+        runner(pm),
+        install_script(pm),
+        HYDRATED_MARKER
+    )
+}
+
+/// Where a project pins its Node version, and the version or range it asks for
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeRequirement {
+    pub spec: String,
+    pub source: &'static str,
+}
+
+impl NodeRequirement {
+    /// What to hand `nvm install`: pin files as written, the lowest major of an `engines` range.
+    /// A range with only an upper bound gets the major below it, `None` when there is none.
+    fn install_target(&self) -> Option<String> {
+        if self.source != "engines.node" {
+            return Some(self.spec.clone());
+        }
+        let comparators = comparators(self.spec.split("||").next()?);
+        let bounds: Vec<(&str, Vec<u64>)> = comparators
+            .iter()
+            .map(|c| split_operator(c))
+            .map(|(op, rest)| Some((op, parse_partial(rest)?)))
+            .collect::<Option<_>>()?;
+        let lowest = bounds.iter().filter(|(op, _)| !op.starts_with('<')).find_map(|(op, parts)| match (*op, parts.as_slice()) {
+            // `>18` leaves out all of 18
+            (">", [major]) => Some(major + 1),
+            (_, parts) => parts.first().copied(),
+        });
+        let major = lowest.or_else(|| bounds.iter().find_map(|(op, parts)| match (*op, parts.as_slice()) {
+            ("<=", [major]) => Some(*major),
+            (_, parts) => parts.first()?.checked_sub(1),
+        }))?;
+        Some(major.to_string())
+    }
+}
+
+/// `.nvmrc`, `.node-version` or `engines.node` from package.json, in that order
+pub fn requirement(project_dir: &Path) -> Option<NodeRequirement> {
+    for file in [".nvmrc", ".node-version"] {
+        if let Ok(text) = std::fs::read_to_string(project_dir.join(file)) {
+            let spec = text.lines().next().unwrap_or("").trim().to_string();
+            if !spec.is_empty() {
+                return Some(NodeRequirement { spec, source: file });
+            }
+        }
+    }
+    let pkg: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(project_dir.join("package.json")).ok()?).ok()?;
+    let spec = pkg["engines"]["node"].as_str()?.trim().to_string();
+    (!spec.is_empty()).then_some(NodeRequirement { spec, source: "engines.node" })
+}
+
+/// Version components up to the first wildcard: `18.x` → [18], `v20.9.0` → [20, 9, 0], `*` → []
+fn parse_partial(s: &str) -> Option<Vec<u64>> {
+    let parts = s.trim().trim_start_matches('v')
+        .split('.')
+        .take_while(|p| !matches!(*p, "x" | "X" | "*" | ""))
+        .map(|p| p.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    (parts.len() <= 3).then_some(parts)
+}
+
+const OPERATORS: [&str; 7] = [">=", "<=", ">", "<", "=", "^", "~"];
+
+/// The comparators of one `||` alternative, each operator joined to its version: `>= 18 <21` is `>=18`, `<21`
+fn comparators(alternative: &str) -> Vec<String> {
+    let mut comparators: Vec<String> = Vec::new();
+    let mut operator: Option<&str> = None;
+    for token in alternative.split_whitespace() {
+        match operator.take() {
+            Some(op) => comparators.push(format!("{}{}", op, token)),
+            None if OPERATORS.contains(&token) => operator = Some(token),
+            None => comparators.push(token.to_string()),
+        }
+    }
+    comparators.extend(operator.map(str::to_string));
+    comparators
+}
+
+fn split_operator(comparator: &str) -> (&str, &str) {
+    OPERATORS.iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", comparator))
+}
+
+fn comparator_matches(v: [u64; 3], comparator: &str) -> Option<bool> {
+    let (op, rest) = split_operator(comparator);
+    let parts = parse_partial(rest)?;
+    let n = parts.len();
+    let mut lower = [0; 3];
+    lower[..n].copy_from_slice(&parts);
+    // Partial versions compare on the components they give: `<=20` allows any 20.x
+    let head = &v[..n];
+    Some(match op {
+        "" | "=" => head == parts.as_slice(),
+        ">=" => v >= lower,
+        ">" => head > parts.as_slice(),
+        "<" => v < lower,
+        "<=" => head <= parts.as_slice(),
+        "^" => v >= lower && (lower[0] == 0 || v[0] == lower[0]),
+        _ => v >= lower && v[..n.clamp(1, 2)] == lower[..n.clamp(1, 2)],
+    })
+}
+
+/// Does `version` (`node --version` output) satisfy a range like `>=18 <21`, `^20.9` or `18.x`?
+/// `None` for specs this can't evaluate, such as nvm aliases (`lts/*`).
+pub fn satisfies(version: &str, spec: &str) -> Option<bool> {
+    let parsed = parse_partial(version)?;
+    let v = [*parsed.first()?, parsed.get(1).copied().unwrap_or(0), parsed.get(2).copied().unwrap_or(0)];
+    let mut any = false;
+    for alternative in spec.split("||") {
+        let mut all = true;
+        for comparator in comparators(alternative) {
+            all &= comparator_matches(v, &comparator)?;
+        }
+        any |= all;
+    }
+    Some(any)
+}
+
+/// Loads nvm when installed so its default Node is on PATH even in non-interactive shells
+pub const LOAD_NVM: &str = "export NVM_DIR=\"${NVM_DIR:-$HOME/.nvm}\"; [ -s \"$NVM_DIR/nvm.sh\" ] && . \"$NVM_DIR/nvm.sh\"; ";
+
+/// How a project's JS tooling should run: package manager and Node version
+#[derive(Clone, Debug)]
+pub struct Toolchain {
+    pub package_manager: PackageManager,
+    pub node: Option<NodeRequirement>,
+    /// Install/switch to the required Node through nvm before building
+    pub use_nvm: bool,
+    /// `export ...;` for the project's build environment variables
+    pub env_exports: String,
+}
+
+impl Toolchain {
+    /// Pin files are looked up in the app first, then at the workspace root
+    pub fn detect(project_dir: &Path, install_root: &Path, use_nvm: bool) -> Self {
+        Toolchain {
+            package_manager: detect(install_root),
+            node: requirement(project_dir).or_else(|| requirement(install_root)),
+            use_nvm,
+            env_exports: String::new(),
+        }
+    }
+
+    pub fn with_env(mut self, env_exports: String) -> Self {
+        self.env_exports = env_exports;
+        self
+    }
+
+    /// Shell prefix for every script that runs node, npm or Gradle (which calls node for autolinking).
+    /// The project's environment variables come first so nvm and the tools see them. nvm is only
+    /// loaded when asked for; otherwise the shell's own Node is used.
+    pub fn env_script(&self) -> String {
+        let nvm = match self.node.as_ref().and_then(|r| r.install_target()) {
+            _ if !self.use_nvm => String::new(),
+            Some(target) => format!(
+                "{}{{ nvm install {t} >/dev/null 2>&1 && nvm use {t} >/dev/null; }} || echo '>> nvm could not switch to Node '{t}; ",
+                LOAD_NVM,
+                t = crate::sh_quote(&target)
+            ),
+            None => LOAD_NVM.to_string(),
+        };
+        format!("{}{}", self.env_exports, nvm)
+    }
+
+    /// Build output line comparing `node --version` from `place` with the project's requirement
+    pub fn check_message(&self, reported: Option<&str>, place: &str) -> Option<String> {
+        let req = self.node.as_ref()?;
+        let wanted = format!("{} ({})", req.spec, req.source);
+        let Some(version) = reported.map(str::trim).filter(|v| !v.is_empty()) else {
+            return Some(format!("⚠️ Node not found in {}, project wants {}", place, wanted));
+        };
+        Some(match satisfies(version, &req.spec) {
+            Some(true) => format!("🟢 Node {} in {} matches {}", version, place, wanted),
+            Some(false) if self.use_nvm => format!("⚠️ Node {} in {} doesn't match {} even after nvm", version, place, wanted),
+            Some(false) => format!("⚠️ Node {} in {} doesn't match {}; enable nvm switching to fix", version, place, wanted),
+            None => format!("ℹ️ Node {} in {}, can't verify {}", version, place, wanted),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_package_json() {
+        assert_eq!(from_package_json("pnpm@8.15.0"), Some(PackageManager::Pnpm));
+        assert_eq!(from_package_json("yarn@1.22.19"), Some(PackageManager::Yarn));
+        assert_eq!(from_package_json("yarn@4.1.0+sha256.abc"), Some(PackageManager::YarnBerry));
+        assert_eq!(from_package_json("bun@1.1.0"), Some(PackageManager::Bun));
+        assert_eq!(from_package_json("deno@1.0.0"), None);
+        assert_eq!(from_package_json("pnpm"), None);
+    }
+
+    #[test]
+    fn test_satisfies() {
+        assert_eq!(satisfies("v18.19.0", "18"), Some(true));
+        assert_eq!(satisfies("v20.11.1", "18.x"), Some(false));
+        assert_eq!(satisfies("v20.11.1", ">=18 <21"), Some(true));
+        assert_eq!(satisfies("v21.0.0", ">=18 <21"), Some(false));
+        assert_eq!(satisfies("v20.8.0", "^20.9.0"), Some(false));
+        assert_eq!(satisfies("v20.9.3", "~20.9"), Some(true));
+        assert_eq!(satisfies("v16.20.2", "^18 || ^20"), Some(false));
+        assert_eq!(satisfies("v20.11.1", "<=20"), Some(true));
+        assert_eq!(satisfies("v20.11.1", "lts/*"), None);
+        assert_eq!(satisfies("v18.19.0", ">= 18"), Some(true));
+        assert_eq!(satisfies("v21.1.0", ">= 18.17 < 21"), Some(false));
+
+        let engines = |spec: &str| NodeRequirement { spec: spec.to_string(), source: "engines.node" }.install_target();
+        assert_eq!(engines(">=18.17 <21").as_deref(), Some("18"));
+        assert_eq!(engines(">= 20").as_deref(), Some("20"));
+        assert_eq!(engines(">18").as_deref(), Some("19"));
+        assert_eq!(engines("<21").as_deref(), Some("20"));
+        assert_eq!(engines("<= 20").as_deref(), Some("20"));
+        assert_eq!(engines("< 0.12"), None);
+        let nvmrc = NodeRequirement { spec: "lts/iron".to_string(), source: ".nvmrc" };
+        assert_eq!(nvmrc.install_target().as_deref(), Some("lts/iron"));
+    }
+
+    #[test]
+    fn test_declares_workspaces() {
+        let yarn: serde_json::Value = serde_json::from_str(r#"{"private": true, "workspaces": ["apps/*", "packages/*"]}"#).unwrap();
+        let classic: serde_json::Value = serde_json::from_str(r#"{"workspaces": {"packages": ["apps/*"], "nohoist": []}}"#).unwrap();
+        let app: serde_json::Value = serde_json::from_str(r#"{"name": "mobile", "dependencies": {"expo": "~51.0.0"}}"#).unwrap();
+
+        assert!(declares_workspaces(&yarn));
+        assert!(declares_workspaces(&classic));
+        assert!(!declares_workspaces(&app));
+    }
+
+    #[test]
+    fn test_hydrate_script() {
+        let npm = hydrate_script(PackageManager::Npm);
+        assert!(npm.contains("[ -f 'package-lock.json' ] || [ -f 'npm-shrinkwrap.json' ]"));
+        assert!(npm.contains("$PM ci --prefer-offline;"));
+        assert!(!npm.contains("corepack"));
+
+        let pnpm = hydrate_script(PackageManager::Pnpm);
+        assert!(pnpm.contains("PM='corepack pnpm'"));
+        assert!(pnpm.contains("$PM install --frozen-lockfile;"));
+        assert!(hydrate_script(PackageManager::YarnBerry).contains("$PM install --immutable;"));
+        assert_eq!(PackageManager::Npm.exec_cmd("expo prebuild"), "$PM exec --yes -- expo prebuild");
+
+        let install = install_cmd("/mnt/c/Dev/My App", PackageManager::Yarn);
+        assert!(install.starts_with("cd '/mnt/c/Dev/My App' && PM=yarn;"));
+        assert!(install.ends_with("touch node_modules/.hyperzenith-hydrated"));
+        assert!(!install.contains("[ ! -d 'node_modules' ]"));
+
+        let mut toolchain = Toolchain { package_manager: PackageManager::Npm, node: None, use_nvm: false, env_exports: String::new() };
+        assert_eq!(toolchain.env_script(), "");
+        toolchain.use_nvm = true;
+        assert_eq!(toolchain.env_script(), LOAD_NVM);
+    }
+}
@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+use crate::error::HyperZenithError;
+
+/// Oldest JDK the Android Gradle Plugin in current React Native templates runs on
+const MIN_JAVA: u32 = 17;
+/// What the SDK step installs when the command-line tools are there to do it
+const SDK_PACKAGES: [&str; 3] = ["platform-tools", "platforms;android-35", "build-tools;35.0.0"];
+
+/// `SDK_PACKAGES` plus the emulator and a system image that runs natively on `arch`;
+/// x86_64 images don't boot on ARM64 hosts
+fn sdk_packages(arch: crate::arch::HostArch) -> Vec<String> {
+    SDK_PACKAGES
+        .iter()
+        .map(|p| p.to_string())
+        .chain(["emulator".to_string(), format!("system-images;android-35;google_apis;{}", arch.system_image_abi())])
+        .collect()
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StepId {
+    Wsl,
+    Java,
+    Node,
+    AndroidSdk,
+    Doctor,
+}
+
+impl StepId {
+    const ALL: [StepId; 5] = [StepId::Wsl, StepId::Java, StepId::Node, StepId::AndroidSdk, StepId::Doctor];
+
+    fn title(self) -> &'static str {
+        match self {
+            StepId::Wsl => "WSL and a Linux distro",
+            StepId::Java => "Java (JDK) in WSL",
+            StepId::Node => "Node.js in WSL",
+            StepId::AndroidSdk => "Android SDK",
+            StepId::Doctor => "Build performance checks",
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Running,
+    Ok,
+    /// Works, but the doctor has advice
+    Warn,
+    Missing,
+    /// Installed just now
+    Installed,
+    /// Needs something an earlier step didn't find
+    Skipped,
+    Failed,
+}
+
+/// One step of the first-run setup, sent on `onboarding-progress` as it starts and ends
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OnboardingStep {
+    pub step: StepId,
+    pub title: String,
+    pub index: usize,
+    pub total: usize,
+    pub status: StepStatus,
+    pub detail: String,
+    /// Passing the step to `initialize_environment` again installs what's missing
+    pub installable: bool,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct OnboardingReport {
+    pub steps: Vec<OnboardingStep>,
+    pub checks: Vec<crate::doctor::DoctorCheck>,
+    /// Nothing is missing; doctor warnings don't hold a build up
+    pub ready: bool,
+}
+
+/// Major version from `java -version`: `openjdk version "17.0.9"` is 17, `java version "1.8.0_392"` is 8
+fn java_major(output: &str) -> Option<u32> {
+    let line = output.lines().find(|l| l.contains(" version \""))?;
+    let version = line.split('"').nth(1)?;
+    let mut parts = version.split(['.', '_', '-', '+']);
+    match parts.next()?.parse().ok()? {
+        1 => parts.next()?.parse().ok(),
+        major => Some(major),
+    }
+}
+
+/// Parts of an SDK folder a build needs that aren't there
+fn sdk_missing(sdk: &Path) -> Vec<&'static str> {
+    [("platform-tools", "platform-tools/adb.exe"), ("build-tools", "build-tools"), ("platforms", "platforms")]
+        .into_iter()
+        .filter(|(_, path)| !sdk.join(path).exists())
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn sdk_dir() -> PathBuf {
+    let local_app_data = std::env::var("LOCALAPPDATA").unwrap_or_else(|_| "C:/Users/Default/AppData/Local".to_string());
+    PathBuf::from(local_app_data).join("Android").join("Sdk")
+}
+
+fn sdkmanager(sdk: &Path) -> PathBuf {
+    sdk.join("cmdline-tools").join("latest").join("bin").join("sdkmanager.bat")
+}
+
+/// Status and detail for a step, and whether it can install what it found missing
+type Outcome = (StepStatus, String, bool);
+
+fn check_wsl(install: bool) -> Outcome {
+    match crate::wsl::installed_distros() {
+        Ok(distros) if !distros.is_empty() => (StepStatus::Ok, distros.join(", "), false),
+        Ok(_) if install => match crate::wsl::install_distro("Ubuntu") {
+            Ok(()) => (StepStatus::Installed, "Ubuntu installed, open it once from the Start menu to create your user".to_string(), false),
+            Err(e) => (StepStatus::Failed, e.to_string(), true),
+        },
+        Ok(_) => (StepStatus::Missing, "WSL is there but has no Linux distro".to_string(), true),
+        Err(HyperZenithError::WslMissing) if install => match crate::run_powershell_elevated("wsl --install --no-launch") {
+            Ok(()) => (StepStatus::Installed, "WSL installed, restart Windows to finish".to_string(), false),
+            Err(e) => (StepStatus::Failed, e.to_string(), true),
+        },
+        Err(HyperZenithError::WslMissing) => (StepStatus::Missing, "WSL isn't installed".to_string(), true),
+        Err(e) => (StepStatus::Failed, e.to_string(), false),
+    }
+}
+
+fn check_java(install: bool) -> Outcome {
+    let found = crate::wsl::run_bash("java -version 2>&1").ok().and_then(|out| java_major(&out));
+    match found {
+        Some(major) if major >= MIN_JAVA => (StepStatus::Ok, format!("JDK {}", major), false),
+        _ if install => {
+            let apt = format!("apt-get update -qq && DEBIAN_FRONTEND=noninteractive apt-get install -y -qq openjdk-{}-jdk-headless", MIN_JAVA);
+            match crate::wsl::run_as_root(&apt) {
+                Ok(_) => (StepStatus::Installed, format!("OpenJDK {} installed", MIN_JAVA), false),
+                Err(e) => (StepStatus::Failed, e.to_string(), true),
+            }
+        },
+        Some(major) => (StepStatus::Missing, format!("JDK {} is too old, Gradle needs {} or newer", major, MIN_JAVA), true),
+        None => (StepStatus::Missing, "No JDK in the default distro".to_string(), true),
+    }
+}
+
+fn check_node() -> Outcome {
+    match crate::wsl::run_bash(&format!("{}node --version", crate::node::LOAD_NVM)) {
+        Ok(version) if !version.trim().is_empty() => (StepStatus::Ok, format!("Node {}", version.trim()), false),
+        // Distro packages lag far behind what Metro needs, nvm is the way to go
+        _ => (StepStatus::Missing, "No Node in the default distro, install it with nvm (github.com/nvm-sh/nvm)".to_string(), false),
+    }
+}
+
+fn check_sdk(install: bool) -> Outcome {
+    let sdk = sdk_dir();
+    let missing = sdk_missing(&sdk);
+    let installable = sdkmanager(&sdk).exists();
+    if missing.is_empty() {
+        return (StepStatus::Ok, sdk.display().to_string(), false);
+    }
+    if install && installable {
+        let packages = sdk_packages(crate::arch::host());
+        // Licenses are prompted for one by one; answer them all
+        let script = format!(
+            "$y = ('y' + [Environment]::NewLine) * 20; $y | & {} --install {} | Out-Null; exit $LASTEXITCODE",
+            crate::ps_quote(&sdkmanager(&sdk).to_string_lossy()),
+            packages.iter().map(|p| crate::ps_quote(p)).collect::<Vec<_>>().join(" ")
+        );
+        return match crate::run_powershell(&script) {
+            Ok(_) if sdk_missing(&sdk).is_empty() => (StepStatus::Installed, packages.join(", "), false),
+            Ok(_) => (StepStatus::Failed, format!("sdkmanager ran but {} is still missing", sdk_missing(&sdk).join(", ")), true),
+            Err(e) => (StepStatus::Failed, e.to_string(), true),
+        };
+    }
+    let detail = if installable {
+        format!("{} missing from {}", missing.join(", "), sdk.display())
+    } else {
+        format!("{} missing from {}; install Android Studio or the command-line tools first", missing.join(", "), sdk.display())
+    };
+    (StepStatus::Missing, detail, installable)
+}
+
+/// Check everything a first build needs, one step at a time with progress on `onboarding-progress`,
+/// installing what's missing for the steps in `install`. Steps that need WSL are skipped without it.
+pub fn run(app: &tauri::AppHandle, working_dir: Option<&str>, install: &[StepId]) -> OnboardingReport {
+    let total = StepId::ALL.len();
+    let mut steps: Vec<OnboardingStep> = Vec::new();
+    let mut checks = Vec::new();
+
+    for (index, id) in StepId::ALL.into_iter().enumerate() {
+        let mut step = OnboardingStep {
+            step: id,
+            title: id.title().to_string(),
+            index,
+            total,
+            status: StepStatus::Running,
+            detail: String::new(),
+            installable: false,
+        };
+        let _ = app.emit("onboarding-progress", &step);
+
+        // Just installed WSL or a distro isn't usable until Windows restarts or the distro is first opened
+        let wsl_status = steps.first().map(|s| s.status);
+        let (status, detail, installable) = match id {
+            StepId::Wsl => check_wsl(install.contains(&id)),
+            StepId::Java | StepId::Node if wsl_status == Some(StepStatus::Installed) => {
+                (StepStatus::Skipped, "Check again once the WSL setup is finished".to_string(), false)
+            },
+            StepId::Java | StepId::Node if wsl_status != Some(StepStatus::Ok) => (StepStatus::Skipped, "Needs WSL".to_string(), false),
+            StepId::Java => check_java(install.contains(&id)),
+            StepId::Node => check_node(),
+            StepId::AndroidSdk => check_sdk(install.contains(&id)),
+            StepId::Doctor => {
+                checks = crate::doctor::run_checks(working_dir);
+                let advice: Vec<&str> = checks.iter().filter(|c| c.status != crate::doctor::CheckStatus::Ok).map(|c| c.title.as_str()).collect();
+                match advice.is_empty() {
+                    true => (StepStatus::Ok, format!("{} check(s) passed", checks.len()), false),
+                    false => (StepStatus::Warn, format!("Worth a look: {}", advice.join(", ")), false),
+                }
+            },
+        };
+        step.status = status;
+        step.detail = detail;
+        step.installable = installable;
+        println!("🧭 [ONBOARDING] {}: {:?} {}", step.title, step.status, step.detail);
+        let _ = app.emit("onboarding-progress", &step);
+        steps.push(step);
+    }
+
+    let ready = steps.iter().all(|s| matches!(s.status, StepStatus::Ok | StepStatus::Warn | StepStatus::Installed));
+    OnboardingReport { steps, checks, ready }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_java_major() {
+        assert_eq!(java_major("openjdk version \"17.0.9\" 2023-10-17\nOpenJDK Runtime Environment"), Some(17));
+        assert_eq!(java_major("java version \"1.8.0_392\"\nJava(TM) SE Runtime Environment"), Some(8));
+        assert_eq!(java_major("openjdk version \"21\" 2023-09-19"), Some(21));
+        assert_eq!(java_major("bash: line 1: java: command not found"), None);
+
+        assert_eq!(sdk_missing(Path::new("/nonexistent/sdk")), vec!["platform-tools", "build-tools", "platforms"]);
+    }
+
+    #[test]
+    fn test_sdk_packages() {
+        use crate::arch::HostArch;
+        assert_eq!(sdk_packages(HostArch::X64).last().map(String::as_str), Some("system-images;android-35;google_apis;x86_64"));
+        assert_eq!(sdk_packages(HostArch::Arm64).last().map(String::as_str), Some("system-images;android-35;google_apis;arm64-v8a"));
+        assert!(sdk_packages(HostArch::Arm64).iter().any(|p| p == "emulator"));
+    }
+}
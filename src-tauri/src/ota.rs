@@ -0,0 +1,202 @@
+use std::io::Write;
+use std::path::Path;
+use tauri::Emitter;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+const HISTORY_FILE: &str = "ota_history.jsonl";
+/// eas-cli through npx when the project doesn't install it; pinned to a major so a release can't change flags under us
+const EAS_CLI: &str = "eas-cli@16";
+
+/// Where and how a JS-only update gets published
+#[derive(serde::Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct OtaOptions {
+    /// EAS branch; wins over `channel`. With neither, `eas update --auto` uses the git branch.
+    pub branch: Option<String>,
+    /// EAS channel, published to whichever branch it points at
+    pub channel: Option<String>,
+    /// Defaults to the last commit message under `--auto`, otherwise a timestamp
+    pub message: Option<String>,
+    /// `android`, `ios` or `all` (default `android`)
+    pub platform: Option<String>,
+    /// `expo export` to `dist/` instead of EAS, then run `upload_command` (with `$DIST_DIR` set)
+    pub self_hosted: bool,
+    pub upload_command: Option<String>,
+}
+
+impl OtaOptions {
+    fn platform(&self) -> &str {
+        self.platform.as_deref().filter(|p| !p.is_empty()).unwrap_or("android")
+    }
+}
+
+/// One published update, appended to `ota_history.jsonl` in the project's logs folder and sent on `ota-published`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct OtaRecord {
+    pub id: String,
+    /// EAS update group; `None` for self-hosted exports
+    pub group_id: Option<String>,
+    pub branch: Option<String>,
+    pub channel: Option<String>,
+    pub message: Option<String>,
+    pub platform: String,
+    pub self_hosted: bool,
+    pub published_at: String,
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+/// `eas update` from the project directory, with the project's own eas-cli when `local_pm` is set
+/// and the pinned `EAS_CLI` through npx otherwise
+pub fn eas_update_cmd(wsl_project_path: &str, options: &OtaOptions, local_pm: Option<crate::node::PackageManager>) -> String {
+    let target = match (non_empty(&options.branch), non_empty(&options.channel)) {
+        (Some(branch), _) => format!("--branch {}", crate::sh_quote(branch)),
+        (None, Some(channel)) => format!("--channel {}", crate::sh_quote(channel)),
+        (None, None) => "--auto".to_string(),
+    };
+    let message = match non_empty(&options.message) {
+        Some(m) => format!(" --message {}", crate::sh_quote(m)),
+        None if target == "--auto" => String::new(),
+        None => format!(" --message {}", crate::sh_quote(&format!("HyperZenith update {}", chrono::Local::now().format("%Y-%m-%d %H:%M")))),
+    };
+    let args = format!("update {}{} --platform {} --non-interactive", target, message, crate::sh_quote(options.platform()));
+    let eas = match local_pm {
+        Some(pm) => pm.run_bin(&format!("eas {}", args)),
+        None => format!("npx --yes {} {}", EAS_CLI, args),
+    };
+    format!("cd {} && {} 2>&1", crate::sh_quote(wsl_project_path), eas)
+}
+
+/// True if eas-cli is installed in the project's (or its workspace root's) node_modules
+pub fn has_local_eas(project_dir: &Path) -> bool {
+    [project_dir.to_path_buf(), crate::node::install_root(project_dir)]
+        .iter()
+        .any(|dir| dir.join("node_modules").join("eas-cli").join("package.json").is_file())
+}
+
+/// `expo export` into `dist/`, followed by the user's upload command when there is one
+pub fn export_cmd(wsl_project_path: &str, options: &OtaOptions, pm: crate::node::PackageManager) -> String {
+    let export = pm.run_bin(&format!("expo export --platform {} --output-dir dist", crate::sh_quote(options.platform())));
+    let upload = non_empty(&options.upload_command)
+        .map(|cmd| format!(" && export DIST_DIR=\"$PWD/dist\" && echo 'Uploading dist/...' && ({}) 2>&1", cmd))
+        .unwrap_or_default();
+    format!("cd {} && rm -rf dist && {} 2>&1{}", crate::sh_quote(wsl_project_path), export, upload)
+}
+
+/// The update group from `eas update` output (`Update group ID  1b2c...`)
+pub fn parse_group_id(log: &str) -> Option<String> {
+    log.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("Update group ID")?;
+        rest.split_whitespace().next().map(str::to_string)
+    })
+}
+
+fn append_history(working_dir: &str, record: &OtaRecord) -> Result<(), HyperZenithError> {
+    let logs_dir = crate::logs::logs_dir(working_dir);
+    std::fs::create_dir_all(&logs_dir).map_err(|e| HyperZenithError::io_at(&logs_dir, e))?;
+    let path = logs_dir.join(HISTORY_FILE);
+    let line = serde_json::to_string(record).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut f| writeln!(f, "{}", line))
+        .map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+/// Publish a JS-only update for an Expo project, streaming output on the channel
+pub async fn publish(
+    channel: &BuildChannel,
+    working_dir: &str,
+    options: OtaOptions,
+    toolchain: &crate::node::Toolchain,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<OtaRecord, HyperZenithError> {
+    let wsl_path = crate::windows_to_wsl_path(working_dir);
+    let script = if options.self_hosted {
+        export_cmd(&wsl_path, &options, toolchain.package_manager)
+    } else {
+        let local_pm = has_local_eas(Path::new(working_dir)).then_some(toolchain.package_manager);
+        eas_update_cmd(&wsl_path, &options, local_pm)
+    };
+    let distro = crate::wsl::project_distro(working_dir);
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &format!("{}{}", toolchain.env_script(), script));
+    if distro.is_none() {
+        cmd.current_dir(working_dir);
+    }
+
+    channel.line(if options.self_hosted {
+        "📡 Exporting update bundle with expo export...".to_string()
+    } else {
+        format!("📡 Publishing OTA update with eas update ({})...", options.platform())
+    });
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    let log_path = crate::write_build_log(working_dir, "ota", &outcome.log);
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+    if !outcome.success {
+        let hint = if !options.self_hosted && outcome.log.contains("not logged in") {
+            format!(" (run `npx {} login` in WSL or set EXPO_TOKEN)", EAS_CLI)
+        } else {
+            String::new()
+        };
+        return Err(HyperZenithError::CommandFailed {
+            command: if options.self_hosted { "expo export" } else { "eas update" }.to_string(),
+            output: format!("exit code {}{}, see {}", outcome.exit_code.unwrap_or(-1), hint, log_path.display()),
+        });
+    }
+
+    let record = OtaRecord {
+        id: channel.id.clone(),
+        group_id: parse_group_id(&outcome.log),
+        branch: options.branch.clone(),
+        channel: options.channel.clone(),
+        message: options.message.clone(),
+        platform: options.platform().to_string(),
+        self_hosted: options.self_hosted,
+        published_at: chrono::Local::now().to_rfc3339(),
+    };
+    if let Err(e) = append_history(working_dir, &record) {
+        channel.line(format!("⚠️ Update history not written: {}", e));
+    }
+    match &record.group_id {
+        Some(group) => channel.line(format!("📡 Update group: {}", group)),
+        None if options.self_hosted => channel.line(format!("📡 Update exported to {}", Path::new(working_dir).join("dist").display())),
+        None => channel.line("⚠️ Published, but no update group ID found in the output"),
+    }
+    let _ = channel.app.emit(&format!("ota-published-{}", channel.id), &record);
+    let _ = channel.app.emit("ota-published", &record);
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_group_id() {
+        let log = "✔ Published!\n\nBranch             production\n\
+                   Runtime version    1.0.0\nPlatform           android\n\
+                   Update group ID    4f2b8c1e-3d5a-4e6f-9a0b-1c2d3e4f5a6b\n\
+                   Android update ID  9a8b7c6d-5e4f-4a3b-2c1d-0e9f8a7b6c5d\n";
+        assert_eq!(parse_group_id(log).as_deref(), Some("4f2b8c1e-3d5a-4e6f-9a0b-1c2d3e4f5a6b"));
+        assert_eq!(parse_group_id("Error: not logged in"), None);
+    }
+
+    #[test]
+    fn test_eas_update_cmd() {
+        let options = OtaOptions { channel: Some("preview".into()), message: Some("fix".into()), ..Default::default() };
+        let npx = eas_update_cmd("/mnt/c/app", &options, None);
+        assert!(npx.contains(&format!("npx --yes {} update --channel 'preview' --message 'fix' --platform 'android'", EAS_CLI)));
+        assert!(!npx.contains("@latest"));
+
+        let local = eas_update_cmd("/mnt/c/app", &options, Some(crate::node::PackageManager::Npm));
+        assert!(local.contains("eas update --channel 'preview'"));
+        assert!(!local.contains(EAS_CLI));
+    }
+}
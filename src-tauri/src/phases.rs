@@ -0,0 +1,126 @@
+/// Stages of a build, in the order they run
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    /// Copying the project to the Mac
+    Sync,
+    Configuration,
+    Compile,
+    Dex,
+    /// Packaging, linking and signing
+    Package,
+    /// Copying artifacts into the archive
+    Archive,
+}
+
+/// How long one phase took, stored with the build's history entry
+#[derive(serde::Serialize, serde::Deserialize, Clone, PartialEq, Debug)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    /// Since the build started
+    pub started_secs: f64,
+    pub duration_secs: f64,
+}
+
+/// Name of a Gradle task from its `> Task :app:compileDebugKotlin` line, lowercased
+fn gradle_task(line: &str) -> Option<String> {
+    let path = line.strip_prefix("> Task ")?.split_whitespace().next()?;
+    Some(path.rsplit(':').next()?.to_lowercase())
+}
+
+/// The phase a line of Gradle or xcodebuild output shows the build is in
+pub fn detect(text: &str) -> Option<Phase> {
+    let line = text.trim();
+    if let Some(task) = gradle_task(line) {
+        return if task.contains("dex") {
+            Some(Phase::Dex)
+        } else if task.starts_with("compile") {
+            Some(Phase::Compile)
+        } else if ["package", "assemble", "sign"].iter().any(|p| task.starts_with(p)) || (task.starts_with("bundle") && !task.contains("js")) {
+            Some(Phase::Package)
+        } else {
+            None
+        };
+    }
+    let lower = line.to_lowercase();
+    if lower.starts_with("> configure project") || lower.starts_with("prepare build") || lower.starts_with("computetargetdependencygraph") {
+        Some(Phase::Configuration)
+    } else if ["compilec ", "compileswift", "swiftcompile ", "compileswiftsources "].iter().any(|p| lower.starts_with(p)) {
+        Some(Phase::Compile)
+    } else if ["ld ", "codesign ", "processproductpackaging "].iter().any(|p| lower.starts_with(p)) {
+        Some(Phase::Package)
+    } else {
+        None
+    }
+}
+
+/// Phases seen so far. Gradle runs module tasks side by side, so a phase only ever moves
+/// forward: a straggling compile task while dexing counts toward dexing.
+#[derive(Default)]
+pub struct PhaseTracker {
+    done: Vec<PhaseTiming>,
+    current: Option<(Phase, f64)>,
+    /// When the build being tracked started, for channels that run several
+    origin: f64,
+}
+
+impl PhaseTracker {
+    pub fn enter(&mut self, phase: Phase, at: f64) {
+        let last = self.current.map(|(p, _)| p).or(self.done.last().map(|t| t.phase));
+        if last.is_some_and(|last| phase <= last) {
+            return;
+        }
+        self.end(at);
+        self.current = Some((phase, at));
+    }
+
+    /// Close the current phase, e.g. once Gradle exits
+    pub fn end(&mut self, at: f64) {
+        if let Some((phase, started)) = self.current.take() {
+            self.done.push(PhaseTiming { phase, started_secs: started, duration_secs: (at - started).max(0.0) });
+        }
+    }
+
+    /// Every phase, the one still running counted up to `at`, and start over for the next build
+    pub fn take(&mut self, at: f64) -> Vec<PhaseTiming> {
+        self.end(at);
+        let origin = std::mem::replace(&mut self.origin, at);
+        std::mem::take(&mut self.done).into_iter().map(|t| PhaseTiming { started_secs: t.started_secs - origin, ..t }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phases() {
+        assert_eq!(detect("> Configure project :app"), Some(Phase::Configuration));
+        assert_eq!(detect("> Task :app:compileDebugKotlin"), Some(Phase::Compile));
+        assert_eq!(detect("> Task :app:dexBuilderDebug"), Some(Phase::Dex));
+        assert_eq!(detect("> Task :app:mergeExtDexDebug UP-TO-DATE"), Some(Phase::Dex));
+        assert_eq!(detect("> Task :app:packageDebug"), Some(Phase::Package));
+        assert_eq!(detect("> Task :app:bundleRelease"), Some(Phase::Package));
+        assert_eq!(detect("> Task :app:createBundleReleaseJsAndAssets"), None);
+        assert_eq!(detect("CompileSwift normal arm64 /Users/dev/app/ios/App/AppDelegate.swift"), Some(Phase::Compile));
+        assert_eq!(detect("Ld /Users/dev/Library/Developer/Xcode/DerivedData/App/Build/Products/App normal"), Some(Phase::Package));
+        assert_eq!(detect("BUILD SUCCESSFUL in 42s"), None);
+
+        let mut tracker = PhaseTracker::default();
+        tracker.enter(Phase::Configuration, 1.0);
+        tracker.enter(Phase::Compile, 5.0);
+        tracker.enter(Phase::Dex, 20.0);
+        tracker.enter(Phase::Compile, 21.0); // another module, still dexing
+        tracker.enter(Phase::Package, 30.0);
+        tracker.end(35.0);
+        tracker.enter(Phase::Archive, 36.0);
+        let timings = tracker.take(38.0);
+        let phases: Vec<(Phase, f64)> = timings.iter().map(|t| (t.phase, t.duration_secs)).collect();
+        assert_eq!(phases, vec![(Phase::Configuration, 4.0), (Phase::Compile, 15.0), (Phase::Dex, 10.0), (Phase::Package, 5.0), (Phase::Archive, 2.0)]);
+        assert_eq!(timings[4].started_secs, 36.0);
+
+        // The next build on the same channel starts from scratch
+        tracker.enter(Phase::Compile, 40.0);
+        assert_eq!(tracker.take(50.0), vec![PhaseTiming { phase: Phase::Compile, started_secs: 2.0, duration_secs: 10.0 }]);
+    }
+}
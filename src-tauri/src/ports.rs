@@ -0,0 +1,180 @@
+use std::os::windows::process::CommandExt;
+use crate::error::HyperZenithError;
+
+/// Ports a debug build and its dev loop expect to have to themselves. The emulator's console/adb
+/// ports aren't here: a running emulator holding them is the normal case, not a conflict.
+pub const DEV_PORTS: [(u16, &str); 2] = [(8081, "Metro"), (8097, "Flipper")];
+
+/// Windows processes that may hold a dev port on someone's machine but must never be killed for it
+const PROTECTED_PROCESSES: [&str; 8] = ["system", "idle", "svchost", "services", "lsass", "wininit", "csrss", "smss"];
+
+/// Forwards WSL's listening ports to Windows; the real owner is found inside WSL
+const WSL_RELAY: &str = "wslrelay";
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum PortSide {
+    Windows,
+    Wsl,
+}
+
+/// A process listening on one of the checked ports
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PortConflict {
+    pub port: u16,
+    pub purpose: String,
+    pub side: PortSide,
+    /// `None` when WSL won't say (another user's process)
+    pub pid: Option<u32>,
+    pub process: String,
+}
+
+/// Parse `port|pid|name` lines from the Get-NetTCPConnection script
+fn parse_windows_listeners(output: &str) -> Vec<(u16, u32, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().splitn(3, '|');
+            let port = parts.next()?.parse().ok()?;
+            let pid = parts.next()?.parse().ok()?;
+            Some((port, pid, parts.next().unwrap_or_default().to_string()))
+        })
+        .collect()
+}
+
+/// Parse `ss -Htlnp` lines, e.g. `LISTEN 0 511 *:8081 *:* users:(("node",pid=4242,fd=21))`
+fn parse_ss(output: &str) -> Vec<(u16, Option<u32>, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let local = line.split_whitespace().nth(3)?;
+            let port = local.rsplit(':').next()?.parse().ok()?;
+            let users = line.split_once("users:((\"").map(|(_, u)| u);
+            let process = users.and_then(|u| u.split('"').next()).unwrap_or("unknown").to_string();
+            let pid = users
+                .and_then(|u| u.split_once("pid="))
+                .and_then(|(_, rest)| rest.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok());
+            Some((port, pid, process))
+        })
+        .collect()
+}
+
+/// Whether `process` in Windows session `session` is part of the OS or a service rather than
+/// something the user started: session 0 is where services run
+fn is_protected(process: &str, session: Option<u32>) -> bool {
+    let name = process.trim().trim_end_matches(".exe");
+    session.is_none_or(|s| s == 0) || PROTECTED_PROCESSES.iter().any(|p| p.eq_ignore_ascii_case(name))
+}
+
+/// The Windows session `pid` runs in, `None` if it's gone or hidden from us
+fn windows_session(pid: u32) -> Option<u32> {
+    crate::run_powershell(&format!("(Get-Process -Id {} -ErrorAction Stop).SessionId", pid)).ok()?.trim().parse().ok()
+}
+
+fn purpose(port: u16) -> String {
+    DEV_PORTS.iter().find(|(p, _)| *p == port).map(|(_, name)| name.to_string()).unwrap_or_else(|| "Custom".to_string())
+}
+
+/// Who's listening on `ports`, on Windows and inside the default WSL distro
+pub fn owners(ports: &[u16]) -> Vec<PortConflict> {
+    let list = ports.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(",");
+    let script = format!(
+        "Get-NetTCPConnection -State Listen -LocalPort {} -ErrorAction SilentlyContinue | \
+         ForEach-Object {{ '{{0}}|{{1}}|{{2}}' -f $_.LocalPort, $_.OwningProcess, (Get-Process -Id $_.OwningProcess -ErrorAction SilentlyContinue).ProcessName }}",
+        list
+    );
+    let windows = crate::run_powershell(&script).map(|out| parse_windows_listeners(&out)).unwrap_or_default();
+    let wsl = crate::wsl::run_bash("ss -Htlnp 2>/dev/null; true").map(|out| parse_ss(&out)).unwrap_or_default();
+
+    let own_pid = std::process::id();
+    // IPv4 and IPv6 listeners of the same process show up twice
+    let mut found: Vec<PortConflict> = Vec::new();
+    let windows = windows
+        .into_iter()
+        .filter(|(_, pid, name)| *pid != own_pid && !name.eq_ignore_ascii_case(WSL_RELAY))
+        .map(|(port, pid, process)| (port, Some(pid), process, PortSide::Windows));
+    let wsl = wsl.into_iter().filter(|(port, ..)| ports.contains(port)).map(|(port, pid, process)| (port, pid, process, PortSide::Wsl));
+    for (port, pid, process, side) in windows.chain(wsl) {
+        if !found.iter().any(|c| c.port == port && c.pid == pid && c.side == side) {
+            found.push(PortConflict { port, purpose: purpose(port), side, pid, process });
+        }
+    }
+    found.sort_by_key(|c| c.port);
+    found
+}
+
+/// Owners of the dev ports
+pub fn check() -> Vec<PortConflict> {
+    owners(&DEV_PORTS.map(|(port, _)| port))
+}
+
+/// Kill whatever listens on dev port `port`, returning what was killed. System and service
+/// processes are left alone.
+pub fn kill(port: u16) -> Result<Vec<PortConflict>, HyperZenithError> {
+    if !DEV_PORTS.iter().any(|(p, _)| *p == port) {
+        return Err(HyperZenithError::InvalidInput(format!("Port {} isn't one of the dev ports", port)));
+    }
+    let owners = owners(&[port]);
+    if owners.is_empty() {
+        return Err(HyperZenithError::InvalidInput(format!("Nothing is listening on port {}", port)));
+    }
+    let mut killed = Vec::new();
+    for owner in owners {
+        // PID 4 is the kernel (http.sys shares ports through it), never a stray dev server
+        let Some(pid) = owner.pid.filter(|pid| *pid > 4) else {
+            println!("🔌 [PORTS] Can't kill {} on port {}: no usable pid", owner.process, port);
+            continue;
+        };
+        println!("🔌 [PORTS] Killing {} (pid {}) on port {}", owner.process, pid, port);
+        match owner.side {
+            PortSide::Windows => {
+                if is_protected(&owner.process, windows_session(pid)) {
+                    println!("🔌 [PORTS] Not killing {} (pid {}) on port {}: it's a system process or service", owner.process, pid, port);
+                    continue;
+                }
+                let output = std::process::Command::new("taskkill")
+                    .args(["/PID", &pid.to_string(), "/T", "/F"])
+                    .creation_flags(crate::CREATE_NO_WINDOW)
+                    .output()
+                    .map_err(|e| HyperZenithError::spawn("taskkill", e))?;
+                if !output.status.success() {
+                    return Err(HyperZenithError::CommandFailed {
+                        command: format!("taskkill /PID {}", pid),
+                        output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                    });
+                }
+            },
+            PortSide::Wsl => {
+                crate::wsl::run_bash(&format!("kill {}", pid))?;
+            },
+        }
+        killed.push(owner);
+    }
+    Ok(killed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_listeners() {
+        let windows = "8081|4242|node\r\n8081|4242|node\r\n5554|9120|qemu-system-x86_64\r\n8097|17|\r\n";
+        assert_eq!(parse_windows_listeners(windows)[2], (5554, 9120, "qemu-system-x86_64".to_string()));
+        assert_eq!(parse_windows_listeners(windows)[3], (8097, 17, String::new()));
+
+        let ss = "LISTEN 0      511          0.0.0.0:8081      0.0.0.0:*    users:((\"node\",pid=4242,fd=21))\n\
+                  LISTEN 0      4096            [::]:8097         [::]:*\n";
+        assert_eq!(parse_ss(ss), vec![(8081, Some(4242), "node".to_string()), (8097, None, "unknown".to_string())]);
+    }
+
+    #[test]
+    fn test_is_protected() {
+        assert!(!is_protected("node", Some(1)));
+        assert!(is_protected("node", Some(0)));
+        assert!(is_protected("node", None));
+        assert!(is_protected("svchost.exe", Some(1)));
+        assert!(is_protected("System", Some(2)));
+        assert!(kill(5555).is_err());
+    }
+}
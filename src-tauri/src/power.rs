@@ -0,0 +1,98 @@
+use crate::events::BuildChannel;
+use crate::HardwareProfile;
+
+/// What to do when a build starts while the laptop is unplugged
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BatteryPolicy {
+    Ignore,
+    /// Build at full speed but say so in the output
+    #[default]
+    Warn,
+    /// Fewer workers and a smaller heap to save battery and heat
+    LowPower,
+}
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+}
+
+/// AC/battery state, `None` on desktops without a battery or when Windows can't tell
+pub fn status() -> Option<PowerStatus> {
+    use winapi::um::winbase::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // SAFETY: SYSTEM_POWER_STATUS is plain data, filled in by the call
+    let mut raw: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut raw) } == 0 {
+        return None;
+    }
+    parse_status(raw.ACLineStatus, raw.BatteryFlag, raw.BatteryLifePercent)
+}
+
+fn parse_status(ac_line: u8, battery_flag: u8, percent: u8) -> Option<PowerStatus> {
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    const UNKNOWN: u8 = 255;
+    if battery_flag == UNKNOWN || battery_flag & NO_SYSTEM_BATTERY != 0 || ac_line == UNKNOWN {
+        return None;
+    }
+    Some(PowerStatus {
+        on_battery: ac_line == 0,
+        battery_percent: (percent <= 100).then_some(percent),
+    })
+}
+
+/// Halve workers and heap, keeping enough to build at all
+pub fn low_power_profile(hw: &HardwareProfile) -> HardwareProfile {
+    HardwareProfile {
+        max_workers: (hw.max_workers / 2).max(2),
+        jvm_heap_gb: (hw.jvm_heap_gb / 2).max(3),
+        ..hw.clone()
+    }
+}
+
+/// Apply the battery policy to a build's profile and report the decision in its output
+pub fn adjust_profile(channel: &BuildChannel, hw: HardwareProfile, policy: BatteryPolicy) -> HardwareProfile {
+    let Some(status) = status().filter(|s| s.on_battery) else { return hw };
+    let charge = status.battery_percent.map(|p| format!(" ({}%)", p)).unwrap_or_default();
+    match policy {
+        BatteryPolicy::Ignore => hw,
+        BatteryPolicy::Warn => {
+            channel.line(format!("🔋 Running on battery{}: full-speed build, expect heavy drain", charge));
+            hw
+        },
+        BatteryPolicy::LowPower => {
+            let low = low_power_profile(&hw);
+            channel.line(format!(
+                "🔋 Running on battery{}: low-power profile, {} → {} workers, {}GB → {}GB heap",
+                charge, hw.max_workers, low.max_workers, hw.jvm_heap_gb, low.jvm_heap_gb
+            ));
+            low
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_status() {
+        assert_eq!(parse_status(0, 1, 57), Some(PowerStatus { on_battery: true, battery_percent: Some(57) }));
+        assert_eq!(parse_status(1, 8, 255), Some(PowerStatus { on_battery: false, battery_percent: None }));
+        assert_eq!(parse_status(1, 128, 255), None); // Desktop
+        assert_eq!(parse_status(255, 255, 255), None);
+    }
+
+    #[test]
+    fn test_low_power_profile() {
+        let hw = HardwareProfile { max_workers: 14, jvm_heap_gb: 16, cpu_cores: 16, performance_cores: 8, efficiency_cores: 0, total_ram_gb: 32, arch: crate::arch::HostArch::X64, idle_cores: 16, available_ram_gb: 24, constrained_by: None, emulator: None };
+        let low = low_power_profile(&hw);
+        assert_eq!((low.max_workers, low.jvm_heap_gb, low.cpu_cores), (7, 8, 16));
+
+        let small = HardwareProfile { max_workers: 4, jvm_heap_gb: 4, cpu_cores: 4, performance_cores: 4, efficiency_cores: 0, total_ram_gb: 8, arch: crate::arch::HostArch::X64, idle_cores: 4, available_ram_gb: 6, constrained_by: None, emulator: None };
+        let low = low_power_profile(&small);
+        assert_eq!((low.max_workers, low.jvm_heap_gb), (2, 3));
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use crate::error::HyperZenithError;
+
+const PRESETS_FILE: &str = "build_presets.json";
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_build_type() -> String {
+    "apk".to_string()
+}
+
+/// A named build configuration for one project, "QA build" or "Store build"
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct BuildPreset {
+    pub name: String,
+    #[serde(default = "default_build_type")]
+    pub build_type: String,
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default = "default_true")]
+    pub turbo_mode: bool,
+    #[serde(default)]
+    pub log_level: crate::gradle::LogLevel,
+    #[serde(default)]
+    pub retry_with_diagnostics: bool,
+    #[serde(default)]
+    pub install_dependencies: bool,
+    #[serde(default)]
+    pub use_nvm: bool,
+    #[serde(default)]
+    pub gates: crate::gates::GateOptions,
+    #[serde(default)]
+    pub engine: crate::gradle::EngineOverrides,
+    #[serde(default)]
+    pub release_notes: bool,
+    #[serde(default)]
+    pub verbosity: crate::events::Verbosity,
+    /// A dotenv file in the project, e.g. `.env.staging`, exported into the build
+    #[serde(default)]
+    pub env_file: Option<String>,
+    /// Archive folder, the default one when `None`
+    #[serde(default)]
+    pub custom_path: Option<String>,
+    /// Run the project's `.hyperzenith/hooks` scripts
+    #[serde(default = "default_true")]
+    pub run_hooks: bool,
+}
+
+impl BuildPreset {
+    pub fn options(&self) -> crate::AndroidBuildOptions {
+        crate::AndroidBuildOptions {
+            build_type: self.build_type.clone(),
+            turbo_mode: self.turbo_mode,
+            custom_path: self.custom_path.clone(),
+            log_level: self.log_level,
+            retry_with_diagnostics: self.retry_with_diagnostics,
+            install_dependencies: self.install_dependencies,
+            use_nvm: self.use_nvm,
+            gates: self.gates,
+            engine: self.engine,
+            variant: self.variant.clone(),
+            release_notes: self.release_notes,
+            env_file: self.env_file.clone(),
+            run_hooks: self.run_hooks,
+        }
+    }
+}
+
+fn presets_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(PRESETS_FILE))
+}
+
+fn load_all(app: &tauri::AppHandle) -> HashMap<String, Vec<BuildPreset>> {
+    presets_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_all(app: &tauri::AppHandle, all: &HashMap<String, Vec<BuildPreset>>) -> Result<(), HyperZenithError> {
+    let path = presets_file(app).ok_or("No app config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(all).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+pub fn load(app: &tauri::AppHandle, working_dir: &str) -> Vec<BuildPreset> {
+    load_all(app).remove(working_dir).unwrap_or_default()
+}
+
+pub fn find(app: &tauri::AppHandle, working_dir: &str, name: &str) -> Result<BuildPreset, HyperZenithError> {
+    load(app, working_dir)
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| HyperZenithError::InvalidInput(format!("No build preset named '{}' for this project", name)))
+}
+
+/// Add the preset, or replace the one with the same name
+pub fn save(app: &tauri::AppHandle, working_dir: &str, preset: BuildPreset) -> Result<Vec<BuildPreset>, HyperZenithError> {
+    if preset.name.trim().is_empty() {
+        return Err(HyperZenithError::InvalidInput("A preset needs a name".to_string()));
+    }
+    if preset.build_type != "apk" && preset.build_type != "aab" {
+        return Err(HyperZenithError::InvalidInput(format!("Unsupported build type: {}", preset.build_type)));
+    }
+    let mut all = load_all(app);
+    let presets = all.entry(working_dir.to_string()).or_default();
+    match presets.iter_mut().find(|p| p.name == preset.name) {
+        Some(existing) => *existing = preset,
+        None => presets.push(preset),
+    }
+    let presets = presets.clone();
+    save_all(app, &all)?;
+    Ok(presets)
+}
+
+pub fn delete(app: &tauri::AppHandle, working_dir: &str, name: &str) -> Result<Vec<BuildPreset>, HyperZenithError> {
+    let mut all = load_all(app);
+    let presets = all.remove(working_dir).unwrap_or_default().into_iter().filter(|p| p.name != name).collect::<Vec<_>>();
+    if !presets.is_empty() {
+        all.insert(working_dir.to_string(), presets.clone());
+    }
+    save_all(app, &all)?;
+    Ok(presets)
+}
+
+/// `KEY=value` pairs from a dotenv file: comments, blank lines and `export ` prefixes are
+/// skipped, and surrounding quotes come off
+fn parse_env_file(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (name, value) = line.strip_prefix("export ").unwrap_or(line).split_once('=')?;
+            let name = name.trim();
+            crate::buildenv::validate_name(name).ok()?;
+            let value = value.trim();
+            let unquoted = ['"', '\'']
+                .iter()
+                .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+                .unwrap_or(value);
+            Some((name.to_string(), unquoted.to_string()))
+        })
+        .collect()
+}
+
+/// The env file's variables as an `export` prefix, with `ENVFILE` pointing react-native-config at it
+pub fn env_file_exports(working_dir: &str, env_file: &str) -> Result<String, HyperZenithError> {
+    let path = Path::new(working_dir).join(env_file);
+    let text = std::fs::read_to_string(&path).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    let mut vars = parse_env_file(&text);
+    vars.push(("ENVFILE".to_string(), env_file.replace('\\', "/")));
+    Ok(crate::buildenv::export_script(&vars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let text = "# Staging\nAPI_URL=https://staging.example.com\nexport FLAG = \"on\"\n\nNAME='QA build'\nnot a variable\n2BAD=x\nEMPTY=\n";
+        assert_eq!(parse_env_file(text), vec![
+            ("API_URL".to_string(), "https://staging.example.com".to_string()),
+            ("FLAG".to_string(), "on".to_string()),
+            ("NAME".to_string(), "QA build".to_string()),
+            ("EMPTY".to_string(), String::new()),
+        ]);
+
+        let preset: BuildPreset = serde_json::from_str(r#"{"name":"Store build","build_type":"aab","variant":"prodRelease"}"#).unwrap();
+        assert!(preset.turbo_mode && preset.run_hooks);
+        assert_eq!(preset.options().variant.as_deref(), Some("prodRelease"));
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt::Write;
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::{mpsc, oneshot};
+use crate::error::HyperZenithError;
+use crate::events::Stream;
+
+pub struct StreamOutcome {
+    pub success: bool,
+    pub cancelled: bool,
+    pub exit_code: Option<i32>,
+    pub log: String,
+}
+
+/// `wsl [-d <distro>] -e bash -c <script>` without a console window.
+/// The distro defaults to WSL's default one; projects hosted inside a distro pass theirs.
+pub fn wsl_command_in(distro: Option<&str>, script: &str) -> Command {
+    let mut cmd = Command::new("wsl");
+    if let Some(d) = distro {
+        cmd.args(["-d", d]);
+    }
+    cmd.args(["-e", "bash", "-c", script])
+        .creation_flags(crate::CREATE_NO_WINDOW);
+    cmd
+}
+
+/// One line of output as text, without its line ending. Tools don't always print valid UTF-8.
+fn decode_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes.strip_suffix(b"\r").unwrap_or(bytes)).into_owned()
+}
+
+/// Cuts stdout and stderr, arriving in chunks of any size, into whole lines per stream, so a
+/// line split across two reads is never emitted as two
+#[derive(Default)]
+pub struct LineSplitter {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl LineSplitter {
+    fn pending(&mut self, stream: Stream) -> &mut Vec<u8> {
+        match stream {
+            Stream::Stderr => &mut self.stderr,
+            _ => &mut self.stdout,
+        }
+    }
+
+    /// The lines `chunk` completes, in order
+    pub fn push(&mut self, stream: Stream, chunk: &[u8]) -> Vec<String> {
+        let pending = self.pending(stream);
+        pending.extend_from_slice(chunk);
+        let Some(last) = pending.iter().rposition(|&b| b == b'\n') else { return Vec::new() };
+        let complete: Vec<u8> = pending.drain(..=last).collect();
+        complete.split_inclusive(|&b| b == b'\n').map(decode_line).collect()
+    }
+
+    /// Whatever is left without a trailing newline once the streams close
+    pub fn finish(&mut self) -> Vec<(Stream, String)> {
+        [Stream::Stdout, Stream::Stderr].into_iter()
+            .filter_map(|stream| {
+                let rest = std::mem::take(self.pending(stream));
+                (!rest.is_empty()).then(|| (stream, decode_line(&rest)))
+            })
+            .collect()
+    }
+}
+
+/// Read whole lines off one of a process's pipes into the shared queue, tagged with the pipe
+async fn forward_lines(reader: impl AsyncRead + Unpin, stream: Stream, lines: mpsc::UnboundedSender<(Stream, String)>) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        match reader.read_until(b'\n', &mut buffer).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => if lines.send((stream, decode_line(&buffer))).is_err() {
+                break;
+            },
+        }
+    }
+}
+
+/// Spawn a process and stream its stdout/stderr lines to the build channel until it exits.
+/// Firing (or dropping) the `cancel` sender kills the process. The receiver is borrowed so a
+/// follow-up run (e.g. a diagnostic retry) can share the same cancel signal.
+pub async fn run_streamed(
+    channel: &crate::events::BuildChannel,
+    cmd: Command,
+    cancel: Option<&mut oneshot::Receiver<()>>,
+) -> Result<StreamOutcome, HyperZenithError> {
+    run_streamed_with_input(channel, cmd, None, cancel).await
+}
+
+/// `run_streamed`, writing `input` to the process's stdin first (then closing it)
+pub async fn run_streamed_with_input(
+    channel: &crate::events::BuildChannel,
+    mut cmd: Command,
+    input: Option<&[u8]>,
+    cancel: Option<&mut oneshot::Receiver<()>>,
+) -> Result<StreamOutcome, HyperZenithError> {
+    if input.is_some() {
+        cmd.stdin(Stdio::piped());
+    }
+    let mut child = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        // A script that never reads stdin closes it early, that's not an error
+        let _ = stdin.write_all(input).await;
+    }
+
+    // Both pipes feed one queue, so lines come out in the order they were read rather than
+    // whichever pipe `select!` happens to poll first
+    let (lines_tx, mut lines) = mpsc::unbounded_channel();
+    tauri::async_runtime::spawn(forward_lines(child.stdout.take().ok_or("stdout not captured")?, Stream::Stdout, lines_tx.clone()));
+    tauri::async_runtime::spawn(forward_lines(child.stderr.take().ok_or("stderr not captured")?, Stream::Stderr, lines_tx));
+
+    let cancelled_signal = async move {
+        match cancel {
+            Some(rx) => { let _ = rx.await; },
+            None => std::future::pending::<()>().await,
+        }
+    };
+    tokio::pin!(cancelled_signal);
+
+    let mut log = String::new();
+    let mut cancelled = false;
+
+    let mut flush_tick = tokio::time::interval(std::time::Duration::from_millis(50));
+
+    loop {
+        let (stream, text) = tokio::select! {
+            l = lines.recv() => match l {
+                Some(l) => l,
+                // Both pipes closed
+                None => break,
+            },
+            _ = &mut cancelled_signal => { cancelled = true; break; }
+            _ = flush_tick.tick() => { channel.flush(); continue; }
+        };
+        let line = channel.output(stream, text);
+        let _ = writeln!(log, "{}", line);
+    }
+
+    if cancelled {
+        let _ = child.kill().await;
+    }
+    channel.flush();
+    let status = child.wait().await.map_err(|e| e.to_string())?;
+
+    Ok(StreamOutcome {
+        success: status.success() && !cancelled,
+        cancelled,
+        exit_code: status.code(),
+        log,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_splitter() {
+        let mut splitter = LineSplitter::default();
+        assert_eq!(splitter.push(Stream::Stdout, b"> Task :app:compi"), Vec::<String>::new());
+        assert_eq!(splitter.push(Stream::Stderr, b"warning: one\r\nwarn"), vec!["warning: one"]);
+        assert_eq!(splitter.push(Stream::Stdout, b"leDebugKotlin\n\nBUILD"), vec!["> Task :app:compileDebugKotlin", ""]);
+        assert_eq!(splitter.push(Stream::Stderr, b"ing: \xff two\n"), vec!["warning: \u{fffd} two"]);
+        assert_eq!(splitter.finish(), vec![(Stream::Stdout, "BUILD".to_string())]);
+        assert_eq!(splitter.finish(), vec![]);
+    }
+}
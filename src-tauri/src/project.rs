@@ -0,0 +1,231 @@
+use std::path::Path;
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct Advisory {
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// What the project is built with, as far as its manifests say
+#[derive(serde::Serialize, Clone, Default, Debug)]
+pub struct ProjectInfo {
+    pub name: Option<String>,
+    /// Expo SDK major, from the `expo` dependency or app.json `sdkVersion`
+    pub expo_sdk: Option<u32>,
+    pub react_native: Option<String>,
+    /// Android Gradle Plugin, only when pinned in android/build.gradle (Expo and RN 0.71+ leave it to RN)
+    pub agp: Option<String>,
+    /// Gradle wrapper version
+    pub gradle: Option<String>,
+    /// Whether the JS runs on Hermes (otherwise JSC)
+    pub hermes: Option<bool>,
+    /// Fabric/TurboModules
+    pub new_architecture: Option<bool>,
+    pub advisories: Vec<Advisory>,
+}
+
+/// `0.74.5`, `~0.74.5`, `^51.0.0` → numeric components, ignoring range operators and tags
+fn version_parts(spec: &str) -> Option<Vec<u32>> {
+    let v = spec.trim().trim_start_matches(['^', '~', '=', '>', '<', 'v', ' ']);
+    let core = v.split(['-', '+', ' ']).next()?;
+    core.split('.').map(|p| p.parse().ok()).collect()
+}
+
+fn at_least(version: Option<&str>, min: &[u32]) -> Option<bool> {
+    Some(version_parts(version?)?.as_slice() >= min)
+}
+
+/// React Native minor each Expo SDK ships with
+fn expected_rn_minor(sdk: u32) -> Option<u32> {
+    match sdk {
+        48 => Some(71),
+        49 => Some(72),
+        50 => Some(73),
+        51 => Some(74),
+        52 => Some(76),
+        53 => Some(79),
+        54 => Some(81),
+        _ => None,
+    }
+}
+
+struct KnownIssue {
+    severity: Severity,
+    applies: fn(&ProjectInfo) -> bool,
+    message: &'static str,
+}
+
+const KNOWN_ISSUES: &[KnownIssue] = &[
+    KnownIssue {
+        severity: Severity::Warning,
+        applies: |p| p.expo_sdk.is_some_and(|sdk| sdk < 50),
+        message: "Expo SDK 49 and older target Android API 33, which Google Play no longer accepts for updates; upgrade to SDK 50+",
+    },
+    KnownIssue {
+        severity: Severity::Warning,
+        applies: |p| at_least(p.react_native.as_deref(), &[0, 71]) == Some(false) && at_least(p.agp.as_deref(), &[8]) == Some(true),
+        message: "React Native below 0.71 does not support Android Gradle Plugin 8; pin AGP 7.x or upgrade React Native",
+    },
+    KnownIssue {
+        severity: Severity::Warning,
+        applies: |p| at_least(p.gradle.as_deref(), &[8, 5]) == Some(false),
+        message: "Gradle older than 8.5 cannot run on JDK 21; make sure WSL's default Java is JDK 17",
+    },
+    KnownIssue {
+        severity: Severity::Info,
+        applies: |p| p.expo_sdk.is_some_and(|sdk| sdk >= 52) && at_least(p.gradle.as_deref(), &[8, 10]) == Some(false),
+        message: "Expo SDK 52+ expects Gradle 8.10 or newer; run `npx expo prebuild --clean` to regenerate android/",
+    },
+];
+
+/// Known-issue checks plus the Expo SDK / React Native pairing
+pub fn advisories(info: &ProjectInfo) -> Vec<Advisory> {
+    let mut found: Vec<Advisory> = KNOWN_ISSUES.iter()
+        .filter(|issue| (issue.applies)(info))
+        .map(|issue| Advisory { severity: issue.severity, message: issue.message.to_string() })
+        .collect();
+
+    let rn_minor = info.react_native.as_deref().and_then(version_parts).and_then(|v| v.get(1).copied());
+    if let (Some(sdk), Some(minor)) = (info.expo_sdk, rn_minor) {
+        if let Some(expected) = expected_rn_minor(sdk).filter(|e| *e != minor) {
+            found.push(Advisory {
+                severity: Severity::Warning,
+                message: format!(
+                    "Expo SDK {} ships with React Native 0.{} but the project has 0.{}; run `npx expo install --fix`",
+                    sdk, expected, minor
+                ),
+            });
+        }
+    }
+    found
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// `com.android.tools.build:gradle:8.1.1` from the top-level build.gradle
+fn parse_agp(build_gradle: &str) -> Option<String> {
+    let rest = build_gradle.split("com.android.tools.build:gradle:").nth(1)?;
+    let version: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '.' || *c == '-').collect();
+    (!version.is_empty()).then_some(version)
+}
+
+/// `distributionUrl=https\://services.gradle.org/distributions/gradle-8.8-all.zip` → `8.8`
+fn parse_gradle_wrapper(properties: &str) -> Option<String> {
+    let url = properties.lines().find_map(|l| l.trim().strip_prefix("distributionUrl="))?;
+    let file = url.rsplit('/').next()?.strip_prefix("gradle-")?;
+    let version = file.trim_end_matches(".zip").trim_end_matches("-all").trim_end_matches("-bin");
+    Some(version.to_string())
+}
+
+/// `hermesEnabled=true` from gradle.properties
+fn gradle_property(properties: &str, key: &str) -> Option<bool> {
+    properties.lines().find_map(|l| {
+        let (k, v) = l.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().parse().ok()).flatten()
+    })
+}
+
+/// Engine switches as the build will see them: android/gradle.properties when it exists,
+/// else the Expo config prebuild would write there, else the framework version's default
+fn engine_settings(project_dir: &Path, app_json: &serde_json::Value, info: &ProjectInfo) -> (Option<bool>, Option<bool>) {
+    let properties = std::fs::read_to_string(project_dir.join("android").join("gradle.properties")).unwrap_or_default();
+    let expo = &app_json["expo"];
+    let build_properties = expo["plugins"]
+        .as_array()
+        .and_then(|plugins| plugins.iter().find(|p| p[0] == "expo-build-properties"))
+        .map(|p| &p[1]["android"]);
+
+    let hermes = gradle_property(&properties, "hermesEnabled")
+        .or_else(|| expo["android"]["jsEngine"].as_str().or(expo["jsEngine"].as_str()).map(|e| e == "hermes"))
+        .or_else(|| info.expo_sdk.map(|sdk| sdk >= 48))
+        .or_else(|| at_least(info.react_native.as_deref(), &[0, 70]));
+    let new_architecture = gradle_property(&properties, "newArchEnabled")
+        .or_else(|| build_properties.and_then(|android| android["newArchEnabled"].as_bool()))
+        .or_else(|| expo["android"]["newArchEnabled"].as_bool().or(expo["newArchEnabled"].as_bool()))
+        .or_else(|| info.expo_sdk.map(|sdk| sdk >= 53))
+        .or_else(|| at_least(info.react_native.as_deref(), &[0, 76]));
+    (hermes, new_architecture)
+}
+
+pub fn inspect(project_dir: &Path) -> ProjectInfo {
+    let pkg = read_json(&project_dir.join("package.json")).unwrap_or_default();
+    let app_json = read_json(&project_dir.join("app.json")).unwrap_or_default();
+    let dependency = |name: &str| {
+        ["dependencies", "devDependencies"].iter().find_map(|key| pkg[key][name].as_str().map(str::to_string))
+    };
+
+    let expo_sdk = dependency("expo")
+        .or_else(|| app_json["expo"]["sdkVersion"].as_str().map(str::to_string))
+        .and_then(|v| version_parts(&v))
+        .and_then(|v| v.first().copied());
+
+    let android = project_dir.join("android");
+    let mut info = ProjectInfo {
+        name: app_json["expo"]["name"].as_str().or(pkg["name"].as_str()).map(str::to_string),
+        expo_sdk,
+        react_native: dependency("react-native").map(|v| v.trim_start_matches(['^', '~']).to_string()),
+        agp: std::fs::read_to_string(android.join("build.gradle")).ok().and_then(|t| parse_agp(&t)),
+        gradle: std::fs::read_to_string(android.join("gradle").join("wrapper").join("gradle-wrapper.properties"))
+            .ok()
+            .and_then(|t| parse_gradle_wrapper(&t)),
+        ..Default::default()
+    };
+    (info.hermes, info.new_architecture) = engine_settings(project_dir, &app_json, &info);
+    info.advisories = advisories(&info);
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!(version_parts("~0.74.5"), Some(vec![0, 74, 5]));
+        assert_eq!(version_parts("^51.0.0-canary.1"), Some(vec![51, 0, 0]));
+        assert_eq!(version_parts("latest"), None);
+        assert_eq!(parse_agp("classpath('com.android.tools.build:gradle:7.4.2')").as_deref(), Some("7.4.2"));
+        assert_eq!(
+            parse_gradle_wrapper("distributionBase=GRADLE_USER_HOME\ndistributionUrl=https\\://services.gradle.org/distributions/gradle-8.8-all.zip\n").as_deref(),
+            Some("8.8")
+        );
+    }
+
+    #[test]
+    fn test_engine_settings() {
+        assert_eq!(gradle_property("# comment\nnewArchEnabled=false\nhermesEnabled = true\n", "hermesEnabled"), Some(true));
+        assert_eq!(gradle_property("newArchEnabled=false", "newArchEnabled"), Some(false));
+        assert_eq!(gradle_property("hermesEnabled=maybe", "hermesEnabled"), None);
+
+        let info = ProjectInfo { expo_sdk: Some(51), ..Default::default() };
+        let app_json = serde_json::json!({ "expo": {
+            "jsEngine": "jsc",
+            "plugins": [["expo-build-properties", { "android": { "newArchEnabled": true } }]],
+        }});
+        assert_eq!(engine_settings(Path::new("/nonexistent"), &app_json, &info), (Some(false), Some(true)));
+        assert_eq!(engine_settings(Path::new("/nonexistent"), &serde_json::Value::Null, &info), (Some(true), Some(false)));
+    }
+
+    #[test]
+    fn test_advisories() {
+        let current = ProjectInfo { expo_sdk: Some(51), react_native: Some("0.74.5".into()), gradle: Some("8.8".into()), ..Default::default() };
+        assert!(advisories(&current).is_empty());
+
+        let mismatched = ProjectInfo { expo_sdk: Some(51), react_native: Some("0.73.6".into()), ..Default::default() };
+        assert_eq!(advisories(&mismatched).len(), 1);
+
+        let old = ProjectInfo { expo_sdk: Some(48), react_native: Some("0.70.5".into()), agp: Some("8.1.0".into()), gradle: Some("8.0.1".into()), ..Default::default() };
+        let found = advisories(&old);
+        assert_eq!(found.len(), 4);
+        assert!(found.iter().all(|a| a.severity == Severity::Warning));
+    }
+}
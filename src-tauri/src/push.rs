@@ -0,0 +1,248 @@
+use std::io::Write;
+use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tauri::Manager;
+use crate::error::HyperZenithError;
+use crate::history::{BuildOutcome, BuildRecord};
+
+const PUSH_FILE: &str = "push.json";
+const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+/// Credential Manager entry for the ntfy access token or Pushover API token
+const TOKEN_NAME: &str = "token";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PushProvider {
+    #[default]
+    Off,
+    Ntfy,
+    Pushover,
+}
+
+/// Where finished builds get pushed to a phone. The token lives in Credential Manager.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PushConfig {
+    pub provider: PushProvider,
+    /// ntfy server, `https://ntfy.sh` unless self-hosted
+    pub server: String,
+    pub topic: String,
+    /// Pushover user or group key
+    pub user_key: String,
+    pub on_success: bool,
+    pub on_failure: bool,
+    /// Quick builds end while you're still at the desk
+    pub min_duration_secs: u64,
+    /// Set when loading: whether a token is stored
+    pub has_token: bool,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        PushConfig {
+            provider: PushProvider::Off,
+            server: "https://ntfy.sh".to_string(),
+            topic: String::new(),
+            user_key: String::new(),
+            on_success: true,
+            on_failure: true,
+            min_duration_secs: 120,
+            has_token: false,
+        }
+    }
+}
+
+/// One notification
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    pub failed: bool,
+}
+
+fn push_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(PUSH_FILE))
+}
+
+fn token_target() -> String {
+    crate::credentials::target("push", TOKEN_NAME)
+}
+
+pub fn load(app: &tauri::AppHandle) -> PushConfig {
+    let config: PushConfig = push_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default();
+    PushConfig { has_token: crate::credentials::read(&token_target()).is_some(), ..config }
+}
+
+pub fn validate(config: &PushConfig) -> Result<(), HyperZenithError> {
+    match config.provider {
+        PushProvider::Off => {},
+        PushProvider::Ntfy => {
+            if !config.server.starts_with("https://") && !config.server.starts_with("http://") {
+                return Err(HyperZenithError::InvalidInput(format!("'{}' is not an ntfy server URL", config.server)));
+            }
+            let topic_ok = !config.topic.is_empty() && config.topic.len() <= 64
+                && config.topic.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !topic_ok {
+                return Err(HyperZenithError::InvalidInput(format!("'{}' is not a valid ntfy topic", config.topic)));
+            }
+        },
+        PushProvider::Pushover => {
+            if config.user_key.trim().is_empty() {
+                return Err(HyperZenithError::InvalidInput("Pushover needs a user key".to_string()));
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Save the config. `token`: `None` keeps the stored one, empty removes it.
+pub fn save(app: &tauri::AppHandle, config: PushConfig, token: Option<String>) -> Result<PushConfig, HyperZenithError> {
+    validate(&config)?;
+    match token.as_deref().map(str::trim) {
+        Some("") => crate::credentials::delete(&token_target()),
+        Some(token) => crate::credentials::write(&token_target(), token)?,
+        None => {},
+    }
+    let path = push_file(app).ok_or("No app config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(&PushConfig { has_token: false, ..config }).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    Ok(load(app))
+}
+
+/// A double-quoted curl config value; control characters can't smuggle in another header
+fn quote(value: &str) -> String {
+    let escaped: String = value
+        .chars()
+        .filter_map(|c| match c {
+            '"' => Some("\\\"".to_string()),
+            '\\' => Some("\\\\".to_string()),
+            '\n' => Some("\\n".to_string()),
+            c if c.is_control() => None,
+            c => Some(c.to_string()),
+        })
+        .collect();
+    format!("\"{}\"", escaped)
+}
+
+/// The request as a curl config, fed through stdin so tokens stay off the command line
+pub fn curl_config(config: &PushConfig, token: Option<&str>, message: &PushMessage) -> Result<String, HyperZenithError> {
+    let mut lines = Vec::new();
+    match config.provider {
+        PushProvider::Off => return Err(HyperZenithError::InvalidInput("Phone notifications are off".to_string())),
+        PushProvider::Ntfy => {
+            lines.push(format!("url = {}", quote(&format!("{}/{}", config.server.trim_end_matches('/'), config.topic))));
+            // Header values are sent as-is, so the title stays on one line
+            lines.push(format!("header = {}", quote(&format!("Title: {}", message.title.replace('\n', " ")))));
+            let (tags, priority) = if message.failed { ("rotating_light", "high") } else { ("white_check_mark", "default") };
+            lines.push(format!("header = {}", quote(&format!("Tags: {}", tags))));
+            lines.push(format!("header = {}", quote(&format!("Priority: {}", priority))));
+            if let Some(token) = token {
+                lines.push(format!("header = {}", quote(&format!("Authorization: Bearer {}", token))));
+            }
+            lines.push(format!("data-binary = {}", quote(&message.body)));
+        },
+        PushProvider::Pushover => {
+            let token = token.ok_or_else(|| HyperZenithError::InvalidInput("Pushover needs an API token".to_string()))?;
+            lines.push(format!("url = {}", quote(PUSHOVER_URL)));
+            for (field, value) in [("token", token), ("user", config.user_key.trim()), ("title", &message.title), ("message", &message.body)] {
+                lines.push(format!("form-string = {}", quote(&format!("{}={}", field, value))));
+            }
+            if message.failed {
+                lines.push(format!("form-string = {}", quote("priority=1")));
+            }
+        },
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Send one notification with `curl.exe`
+pub fn send(config: &PushConfig, message: &PushMessage) -> Result<(), HyperZenithError> {
+    let token = crate::credentials::read(&token_target());
+    let request = curl_config(config, token.as_deref(), message)?;
+    let mut child = Command::new("curl")
+        .args(["-sSf", "--max-time", "20", "-o", "NUL", "-K", "-"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| HyperZenithError::spawn("curl", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(request.as_bytes()).map_err(|e| HyperZenithError::Io(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| HyperZenithError::Io(e.to_string()))?;
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "Push notification".to_string(),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(())
+}
+
+pub fn build_message(record: &BuildRecord, status: &str) -> PushMessage {
+    let project = std::path::Path::new(&record.project.replace('\\', "/"))
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| record.project.clone());
+    let failed = record.outcome != BuildOutcome::Success;
+    let kind = record.variant.clone().unwrap_or_else(|| record.build_type.clone());
+    let secs = record.duration_secs.round() as u64;
+    PushMessage {
+        title: format!("{}: {} {} build {}", project, record.platform, kind, if failed { "failed" } else { "finished" }),
+        body: format!("{} ({}m {:02}s)", status, secs / 60, secs % 60),
+        failed,
+    }
+}
+
+/// Push a finished build in the background when the config asks for it. Aborts never are:
+/// whoever aborted is at the desk.
+pub fn notify_build(app: &tauri::AppHandle, record: &BuildRecord, status: &str) {
+    let config = load(app);
+    let wanted = match record.outcome {
+        BuildOutcome::Success => config.on_success,
+        BuildOutcome::Failed => config.on_failure,
+        BuildOutcome::Aborted => false,
+    };
+    if config.provider == PushProvider::Off || !wanted || record.duration_secs < config.min_duration_secs as f64 {
+        return;
+    }
+    let message = build_message(record, status);
+    std::thread::spawn(move || {
+        if let Err(e) = send(&config, &message) {
+            println!("📱 [PUSH] ⚠️ Notification not sent: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curl_config() {
+        let message = PushMessage { title: "app: android release build failed".to_string(), body: "Gradle \"failed\"\nsee log".to_string(), failed: true };
+        let ntfy = PushConfig { provider: PushProvider::Ntfy, topic: "hz-builds".to_string(), ..Default::default() };
+        assert_eq!(
+            curl_config(&ntfy, None, &message).unwrap(),
+            "url = \"https://ntfy.sh/hz-builds\"\n\
+             header = \"Title: app: android release build failed\"\n\
+             header = \"Tags: rotating_light\"\n\
+             header = \"Priority: high\"\n\
+             data-binary = \"Gradle \\\"failed\\\"\\nsee log\"\n"
+        );
+
+        let pushover = PushConfig { provider: PushProvider::Pushover, user_key: "ukey".to_string(), ..Default::default() };
+        assert!(curl_config(&pushover, None, &message).is_err());
+        let config = curl_config(&pushover, Some("atoken"), &message).unwrap();
+        assert!(config.contains("form-string = \"token=atoken\"\nform-string = \"user=ukey\"\n"));
+        assert!(config.ends_with("form-string = \"priority=1\"\n"));
+
+        assert!(validate(&PushConfig { provider: PushProvider::Ntfy, topic: "a/b".to_string(), ..Default::default() }).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+use crate::error::HyperZenithError;
+use crate::history::{BuildOutcome, BuildRecord};
+
+const RECENTS_FILE: &str = "recent_projects.json";
+/// Unpinned projects kept; pinned ones never drop off
+const MAX_RECENT: usize = 15;
+
+/// Builds finishing together would otherwise overwrite each other's update
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A project built recently or pinned as a favorite
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct RecentProject {
+    pub path: String,
+    #[serde(default)]
+    pub pinned: bool,
+    pub last_built_at: Option<String>,
+    pub last_platform: Option<String>,
+    pub last_outcome: Option<BuildOutcome>,
+    /// Checked when listing; a moved or deleted project stays listed until unpinned
+    #[serde(skip_deserializing)]
+    pub exists: bool,
+}
+
+impl RecentProject {
+    fn new(path: &str) -> Self {
+        RecentProject { path: path.to_string(), pinned: false, last_built_at: None, last_platform: None, last_outcome: None, exists: true }
+    }
+}
+
+fn recents_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(RECENTS_FILE))
+}
+
+fn read(app: &tauri::AppHandle) -> Vec<RecentProject> {
+    recents_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn write(app: &tauri::AppHandle, projects: &[RecentProject]) -> Result<(), HyperZenithError> {
+    let path = recents_file(app).ok_or("No app config directory")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(projects).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+/// Pinned first, then most recently built. RFC 3339 times from one machine sort as text.
+fn sort(projects: &mut [RecentProject]) {
+    projects.sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| b.last_built_at.cmp(&a.last_built_at)));
+}
+
+/// Move a finished build's project to the top, dropping the oldest unpinned ones past the limit
+fn note(mut projects: Vec<RecentProject>, record: &BuildRecord) -> Vec<RecentProject> {
+    let index = match projects.iter().position(|p| p.path.eq_ignore_ascii_case(&record.project)) {
+        Some(index) => index,
+        None => {
+            projects.push(RecentProject::new(&record.project));
+            projects.len() - 1
+        },
+    };
+    let entry = &mut projects[index];
+    entry.last_built_at = Some(record.started_at.clone());
+    entry.last_platform = Some(record.platform.clone());
+    entry.last_outcome = Some(record.outcome);
+    sort(&mut projects);
+
+    let mut unpinned = 0;
+    projects.retain(|p| {
+        unpinned += usize::from(!p.pinned);
+        p.pinned || unpinned <= MAX_RECENT
+    });
+    projects
+}
+
+/// The same folder however it's written: `C:/Dev/App/` is `c:\dev\app`
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| p.replace('/', "\\").trim_end_matches('\\').to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+pub fn list(app: &tauri::AppHandle) -> Vec<RecentProject> {
+    let mut projects = read(app);
+    for project in &mut projects {
+        project.exists = std::path::Path::new(&project.path).is_dir();
+    }
+    sort(&mut projects);
+    projects
+}
+
+/// The built or pinned project at `path`, with the path as it was remembered
+pub fn find(app: &tauri::AppHandle, path: &str) -> Option<RecentProject> {
+    list(app).into_iter().find(|p| same_path(&p.path, path))
+}
+
+/// Remember a finished build; best effort like the history itself
+pub fn record_build(app: &tauri::AppHandle, record: &BuildRecord) {
+    let _guard = FILE_LOCK.lock();
+    if let Err(e) = write(app, &note(read(app), record)) {
+        println!("🕘 [RECENTS] ⚠️ {}", e);
+    }
+}
+
+/// Pin a project as a favorite, or unpin it. Unpinned projects that never built are forgotten.
+pub fn pin(app: &tauri::AppHandle, path: &str, pinned: bool) -> Result<Vec<RecentProject>, HyperZenithError> {
+    let guard = FILE_LOCK.lock();
+    let mut projects = read(app);
+    match projects.iter_mut().find(|p| p.path.eq_ignore_ascii_case(path)) {
+        Some(project) => project.pinned = pinned,
+        None if pinned => projects.push(RecentProject { pinned: true, ..RecentProject::new(path) }),
+        None => {},
+    }
+    projects.retain(|p| p.pinned || p.last_built_at.is_some());
+    write(app, &projects)?;
+    drop(guard);
+    Ok(list(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_build() {
+        let record = |project: &str, started_at: &str| BuildRecord {
+            id: String::new(),
+            project: project.to_string(),
+            platform: "android".to_string(),
+            build_type: "apk".to_string(),
+            variant: None,
+            started_at: started_at.to_string(),
+            duration_secs: 60.0,
+            outcome: BuildOutcome::Success,
+            cache_hit_rate: None,
+            warnings: None,
+            phases: Vec::new(),
+        };
+        let pinned = RecentProject { pinned: true, ..RecentProject::new("C:\\fav") };
+        let mut projects = vec![pinned];
+        for i in 0..MAX_RECENT + 3 {
+            projects = note(projects, &record(&format!("C:\\app{}", i), &format!("2024-05-01T10:{:02}:00+02:00", i)));
+        }
+        assert_eq!(projects.len(), MAX_RECENT + 1);
+        assert_eq!(projects[0].path, "C:\\fav");
+        assert_eq!(projects[1].path, format!("C:\\app{}", MAX_RECENT + 2));
+        assert!(!projects.iter().any(|p| p.path == "C:\\app0"));
+
+        // Same project, different case: updated in place
+        let projects = note(projects, &record("c:\\APP5", "2024-05-02T09:00:00+02:00"));
+        assert_eq!(projects[1].path, "C:\\app5");
+        assert_eq!(projects.len(), MAX_RECENT + 1);
+
+        assert!(same_path("C:\\Dev\\App", "c:/dev/app/"));
+        assert!(!same_path("C:\\Dev\\App", "C:\\Dev\\App2"));
+    }
+}
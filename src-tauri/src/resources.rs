@@ -0,0 +1,142 @@
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use crate::state::AppState;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The processes a build runs as, from Windows' side
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Consumer {
+    /// The WSL VM, and with it Gradle, Kotlin and everything else the build runs in Linux
+    Wsl,
+    /// Gradle and Kotlin daemons running on Windows
+    Java,
+    /// Metro, Expo and npm
+    Node,
+    Emulator,
+}
+
+impl Consumer {
+    /// Which consumer a process belongs to, by its executable name
+    fn of(process_name: &str) -> Option<Self> {
+        let name = process_name.to_lowercase();
+        let name = name.trim_end_matches(".exe");
+        if name.starts_with("vmmem") {
+            Some(Consumer::Wsl)
+        } else if matches!(name, "java" | "javaw" | "gradle" | "kotlin-daemon") {
+            Some(Consumer::Java)
+        } else if matches!(name, "node" | "bun") {
+            Some(Consumer::Node)
+        } else if name.starts_with("qemu-system") || name == "emulator" {
+            Some(Consumer::Emulator)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+pub struct ConsumerUsage {
+    pub consumer: Consumer,
+    pub processes: usize,
+    /// Share of the whole machine, 0-100
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// Sent on `build-resources` every couple of seconds while something builds
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+pub struct BuildResources {
+    /// Heaviest first
+    pub consumers: Vec<ConsumerUsage>,
+    /// Everything else running on the machine
+    pub other_cpu_percent: f32,
+    pub other_memory_bytes: u64,
+}
+
+/// Split the machine's load between the build's processes and the rest.
+/// `processes` are `(name, cpu % of one core, memory)` as sysinfo reports them.
+fn attribute(processes: &[(String, f32, u64)], cpu_count: usize, total_cpu_percent: f32, used_memory: u64) -> BuildResources {
+    let cpus = cpu_count.max(1) as f32;
+    let mut consumers: Vec<ConsumerUsage> = Vec::new();
+    for (name, cpu, memory) in processes {
+        let Some(consumer) = Consumer::of(name) else { continue };
+        let usage = match consumers.iter_mut().find(|c| c.consumer == consumer) {
+            Some(usage) => usage,
+            None => {
+                consumers.push(ConsumerUsage { consumer, processes: 0, cpu_percent: 0.0, memory_bytes: 0 });
+                consumers.last_mut().unwrap()
+            },
+        };
+        usage.processes += 1;
+        usage.cpu_percent += cpu / cpus;
+        usage.memory_bytes += memory;
+    }
+    consumers.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent).then(b.memory_bytes.cmp(&a.memory_bytes)));
+
+    let build_cpu: f32 = consumers.iter().map(|c| c.cpu_percent).sum();
+    let build_memory: u64 = consumers.iter().map(|c| c.memory_bytes).sum();
+    BuildResources {
+        consumers,
+        other_cpu_percent: (total_cpu_percent - build_cpu).max(0.0),
+        other_memory_bytes: used_memory.saturating_sub(build_memory),
+    }
+}
+
+/// What the build's processes are using right now
+fn sample(state: &AppState) -> Option<BuildResources> {
+    let mut sys = state.system.lock().ok()?;
+    sys.refresh_cpu();
+    sys.refresh_memory();
+    sys.refresh_processes();
+    let processes: Vec<(String, f32, u64)> = sys.processes().values()
+        .map(|p| (p.name().to_string(), p.cpu_usage(), p.memory()))
+        .collect();
+    Some(attribute(&processes, sys.cpus().len(), sys.global_cpu_info().cpu_usage(), sys.used_memory()))
+}
+
+/// Sample while builds run for the lifetime of the app. Process CPU is measured between two
+/// refreshes, so the first sample of a build reads low.
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            if state.builds.active().is_empty() {
+                continue;
+            }
+            if let Some(resources) = sample(&state) {
+                let _ = app.emit("build-resources", &resources);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attribute() {
+        let gigabyte = 1024 * 1024 * 1024;
+        let processes = vec![
+            ("VmmemWSL".to_string(), 600.0, 10 * gigabyte),
+            ("node.exe".to_string(), 50.0, gigabyte),
+            ("node.exe".to_string(), 30.0, gigabyte / 2),
+            ("chrome.exe".to_string(), 200.0, 4 * gigabyte),
+            ("java.exe".to_string(), 0.0, 2 * gigabyte),
+        ];
+        let resources = attribute(&processes, 8, 90.0, 20 * gigabyte);
+        let consumers: Vec<(Consumer, usize)> = resources.consumers.iter().map(|c| (c.consumer, c.processes)).collect();
+        assert_eq!(consumers, vec![(Consumer::Wsl, 1), (Consumer::Node, 2), (Consumer::Java, 1)]);
+        assert_eq!(resources.consumers[0].cpu_percent, 75.0);
+        assert_eq!(resources.consumers[1].cpu_percent, 10.0);
+        assert_eq!(resources.other_cpu_percent, 5.0);
+        assert_eq!(resources.other_memory_bytes, 20 * gigabyte - 13 * gigabyte - gigabyte / 2);
+
+        assert_eq!(Consumer::of("qemu-system-x86_64.exe"), Some(Consumer::Emulator));
+        assert_eq!(Consumer::of("javascript.exe"), None);
+    }
+}
@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager};
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+const KNOWN_PROJECTS_FILE: &str = "known_projects.json";
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProjectKind {
+    /// `create-expo-app`
+    Expo,
+    /// `@react-native-community/cli init`
+    ReactNative,
+}
+
+pub struct ScaffoldRequest {
+    pub parent_dir: String,
+    pub name: String,
+    pub kind: ProjectKind,
+    /// create-expo-app template such as `blank-typescript`; ignored for React Native
+    pub template: Option<String>,
+    pub install_dependencies: bool,
+}
+
+/// Sent on `project-created` once the new app is on disk
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ProjectCreated {
+    pub id: String,
+    pub path: String,
+    pub kind: ProjectKind,
+}
+
+/// Folder names each CLI accepts: React Native also uses the name as the Java/Kotlin app name
+pub fn validate_name(kind: ProjectKind, name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = match kind {
+        ProjectKind::Expo => !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
+        ProjectKind::ReactNative => chars.next().is_some_and(|c| c.is_ascii_alphabetic()) && chars.all(|c| c.is_ascii_alphanumeric()),
+    };
+    if valid {
+        Ok(())
+    } else if kind == ProjectKind::ReactNative {
+        Err(format!("'{}' is not a valid React Native app name (letters and digits, starting with a letter)", name))
+    } else {
+        Err(format!("'{}' is not a valid project name (letters, digits, '-' and '_')", name))
+    }
+}
+
+/// The CLI invocation, run from the parent directory. Both CLIs come through npx at their latest version.
+pub fn scaffold_cmd(wsl_parent_dir: &str, name: &str, kind: ProjectKind, template: Option<&str>, install_dependencies: bool) -> String {
+    let create = match kind {
+        ProjectKind::Expo => format!(
+            "npx --yes create-expo-app@latest {} --template {} --yes{}",
+            crate::sh_quote(name),
+            crate::sh_quote(template.map(str::trim).filter(|t| !t.is_empty()).unwrap_or("default")),
+            if install_dependencies { "" } else { " --no-install" }
+        ),
+        ProjectKind::ReactNative => format!(
+            "npx --yes @react-native-community/cli@latest init {} --install-pods false{}",
+            crate::sh_quote(name),
+            if install_dependencies { "" } else { " --skip-install" }
+        ),
+    };
+    format!("export CI=1 && cd {} && {} 2>&1", crate::sh_quote(wsl_parent_dir), create)
+}
+
+/// `https://github.com/acme/shop-app.git` or `git@github.com:acme/shop-app.git` → `shop-app`
+pub fn repo_name(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let is_git_url = ["https://", "http://", "ssh://", "git://", "git@"].iter().any(|p| url.starts_with(p));
+    let last = url.rsplit(['/', ':']).next()?;
+    let name = last.strip_suffix(".git").unwrap_or(last);
+    (is_git_url && !name.is_empty() && !name.contains('@')).then(|| name.to_string())
+}
+
+/// The app inside a fresh clone: the repo root, or for monorepos the first buildable package under it
+pub fn find_app_dir(repo_dir: &Path) -> Option<PathBuf> {
+    if crate::expo::is_android_buildable(repo_dir) {
+        return Some(repo_dir.to_path_buf());
+    }
+    walkdir::WalkDir::new(repo_dir)
+        .max_depth(3)
+        .into_iter()
+        .filter_entry(|e| !matches!(e.file_name().to_str(), Some("node_modules" | ".git")))
+        .filter_map(|e| e.ok())
+        .find(|e| e.file_type().is_dir() && e.path().join("package.json").exists() && crate::expo::is_android_buildable(e.path()))
+        .map(|e| e.into_path())
+}
+
+fn known_projects_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|dir| dir.join(KNOWN_PROJECTS_FILE))
+}
+
+/// Projects created here, which the scanner lists wherever they live
+pub fn known_projects(app: &tauri::AppHandle) -> Vec<String> {
+    known_projects_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn remember_project(app: &tauri::AppHandle, project_dir: &str) -> Result<(), HyperZenithError> {
+    let path = known_projects_file(app).ok_or("No app config directory")?;
+    let mut projects = known_projects(app);
+    if !projects.iter().any(|p| p == project_dir) {
+        projects.push(project_dir.to_string());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| HyperZenithError::io_at(dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(&projects).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(&path, json).map_err(|e| HyperZenithError::io_at(&path, e))
+}
+
+/// Run one setup step in WSL from the project, saving its log there if it fails
+async fn run_step(
+    channel: &BuildChannel,
+    working_dir: &str,
+    script: &str,
+    step: &str,
+    cancel: &mut oneshot::Receiver<()>,
+) -> Result<(), HyperZenithError> {
+    let distro = crate::wsl::project_distro(working_dir);
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), script);
+    if distro.is_none() {
+        cmd.current_dir(working_dir);
+    }
+    let outcome = crate::process::run_streamed(channel, cmd, Some(cancel)).await?;
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: None });
+    }
+    if !outcome.success {
+        let log_path = crate::write_build_log(working_dir, &step.replace(' ', "_"), &outcome.log);
+        return Err(HyperZenithError::CommandFailed {
+            command: step.to_string(),
+            output: format!("exit code {}, see {}", outcome.exit_code.unwrap_or(-1), log_path.display()),
+        });
+    }
+    Ok(())
+}
+
+/// Clone a repository in WSL, install its dependencies, prebuild managed Expo apps and register
+/// the result with the scanner, leaving a project that is ready to build
+pub async fn clone(
+    channel: &BuildChannel,
+    url: &str,
+    repo_dir: PathBuf,
+    use_nvm: bool,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<ProjectCreated, HyperZenithError> {
+    let parent = repo_dir.parent().ok_or_else(|| HyperZenithError::InvalidInput(format!("{} has no parent folder", repo_dir.display())))?;
+    let parent_dir = parent.display().to_string();
+    let folder = repo_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+    channel.line(format!("📥 Cloning {} into {}...", url, repo_dir.display()));
+    let script = format!(
+        "cd {} && git clone --progress {} {} 2>&1",
+        crate::sh_quote(&crate::windows_to_wsl_path(&parent_dir)),
+        crate::sh_quote(url),
+        crate::sh_quote(&folder)
+    );
+    run_step(channel, &parent_dir, &script, "git clone", &mut cancel).await?;
+
+    let app_dir = find_app_dir(&repo_dir).ok_or_else(|| HyperZenithError::InvalidInput(
+        format!("Cloned, but no React Native or Expo app was found in {}", repo_dir.display())
+    ))?;
+    let working_dir = app_dir.display().to_string();
+    if app_dir != repo_dir {
+        channel.line(format!("📁 App found at {}", working_dir));
+    }
+
+    let install_root = crate::node::install_root(&app_dir);
+    let wsl_install_root = crate::windows_to_wsl_path(&install_root.to_string_lossy());
+    let toolchain = crate::node::Toolchain::detect(&app_dir, &install_root, use_nvm);
+    let node_env = toolchain.env_script();
+
+    channel.line(format!("📦 Installing dependencies with {:?}...", toolchain.package_manager));
+    let script = format!("{}{}", node_env, crate::node::install_cmd(&wsl_install_root, toolchain.package_manager));
+    run_step(channel, &working_dir, &script, "dependency install", &mut cancel).await?;
+
+    if crate::expo::needs_prebuild(&app_dir) {
+        channel.line("📐 Managed Expo project, running expo prebuild...");
+        let script = format!(
+            "{}{}",
+            node_env,
+            crate::expo::prebuild_cmd(&crate::windows_to_wsl_path(&working_dir), &wsl_install_root, toolchain.package_manager)
+        );
+        run_step(channel, &working_dir, &script, "expo prebuild", &mut cancel).await?;
+    }
+
+    if let Err(e) = remember_project(&channel.app, &working_dir) {
+        channel.line(format!("⚠️ Project not added to the scanner list: {}", e));
+    }
+    channel.line(format!("🚀 Ready to build: {}", working_dir));
+    let kind = if crate::expo::is_expo_project(&app_dir) { ProjectKind::Expo } else { ProjectKind::ReactNative };
+    let created = ProjectCreated { id: channel.id.clone(), path: working_dir, kind };
+    let _ = channel.app.emit(&format!("project-created-{}", channel.id), &created);
+    let _ = channel.app.emit("project-created", &created);
+    Ok(created)
+}
+
+/// Scaffold a new app in WSL, streaming the CLI's output, and register it with the scanner
+pub async fn create(
+    channel: &BuildChannel,
+    request: ScaffoldRequest,
+    toolchain: &crate::node::Toolchain,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<ProjectCreated, HyperZenithError> {
+    let ScaffoldRequest { parent_dir, name, kind, template, install_dependencies } = request;
+    let project_dir = Path::new(&parent_dir).join(&name);
+    let script = format!(
+        "{}{}",
+        toolchain.env_script(),
+        scaffold_cmd(&crate::windows_to_wsl_path(&parent_dir), &name, kind, template.as_deref(), install_dependencies)
+    );
+    let distro = crate::wsl::project_distro(&parent_dir);
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &script);
+    if distro.is_none() {
+        cmd.current_dir(&parent_dir);
+    }
+
+    channel.line(format!("🌱 Creating {:?} project {} in {}...", kind, name, parent_dir));
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: None });
+    }
+    if !outcome.success || !project_dir.join("package.json").exists() {
+        // The project folder may not exist, so the log goes next to it
+        let log_path = crate::write_build_log(&parent_dir, "create_project", &outcome.log);
+        return Err(HyperZenithError::CommandFailed {
+            command: "create project".to_string(),
+            output: format!("exit code {}, see {}", outcome.exit_code.unwrap_or(-1), log_path.display()),
+        });
+    }
+
+    let path = project_dir.display().to_string();
+    if let Err(e) = remember_project(&channel.app, &path) {
+        channel.line(format!("⚠️ Project not added to the scanner list: {}", e));
+    }
+    channel.line(format!("🌱 Project ready: {}", path));
+    let created = ProjectCreated { id: channel.id.clone(), path, kind };
+    let _ = channel.app.emit(&format!("project-created-{}", channel.id), &created);
+    let _ = channel.app.emit("project-created", &created);
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name(ProjectKind::Expo, "my-app_2").is_ok());
+        assert!(validate_name(ProjectKind::Expo, "my app").is_err());
+        assert!(validate_name(ProjectKind::Expo, "").is_err());
+        assert!(validate_name(ProjectKind::ReactNative, "MyApp2").is_ok());
+        assert!(validate_name(ProjectKind::ReactNative, "my-app").is_err());
+        assert!(validate_name(ProjectKind::ReactNative, "2App").is_err());
+    }
+
+    #[test]
+    fn test_repo_name() {
+        assert_eq!(repo_name("https://github.com/acme/shop-app.git").as_deref(), Some("shop-app"));
+        assert_eq!(repo_name("https://gitlab.com/acme/mobile/").as_deref(), Some("mobile"));
+        assert_eq!(repo_name("git@github.com:acme/shop-app.git").as_deref(), Some("shop-app"));
+        assert_eq!(repo_name("C:\\Dev\\app"), None);
+        assert_eq!(repo_name("git@github.com"), None);
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager};
+
+const SCAN_CACHE_FILE: &str = "project_scan.json";
+/// Levels searched below each root
+const MAX_DEPTH: usize = 3;
+/// Never hold a project root, and can hold tens of thousands of folders
+const SKIP_DIRS: [&str; 9] = ["node_modules", "build", "dist", "Pods", "DerivedData", "vendor", "bin", "obj", "target"];
+/// OneDrive/cloud-files placeholders: listing their contents downloads them
+const FILE_ATTRIBUTE_OFFLINE: u32 = 0x1000;
+const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x40000;
+const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x400000;
+
+/// One scan at a time; asking again while it runs just returns the cached list
+static SCANNING: AtomicBool = AtomicBool::new(false);
+
+/// Subdirectories of a visited directory, valid while its modification time is unchanged
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default, PartialEq)]
+struct CachedDir {
+    mtime: u64,
+    subdirs: Vec<String>,
+    /// Whether the folder has a `.gitignore`, read again on each visit since editing it doesn't touch the mtime
+    gitignore: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+struct ScanCache {
+    dirs: HashMap<String, CachedDir>,
+    /// What the last scan found
+    projects: Vec<String>,
+}
+
+/// A project and the build paths it supports
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct DiscoveredProject {
+    pub path: String,
+    pub android: bool,
+    pub ios: bool,
+}
+
+/// Sent on `projects-discovered` for each match, then once with the full list and `done`
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct ProjectsDiscovered {
+    pub projects: Vec<DiscoveredProject>,
+    pub done: bool,
+}
+
+pub fn is_android_project(path: &Path) -> bool {
+    path.join("android").join("build.gradle").exists() || // Standard
+    path.join("android").join("settings.gradle").exists() || // Alternative
+    (path.join("app.json").exists() && crate::expo::needs_prebuild(path)) // Managed Expo, android/ not generated yet
+}
+
+/// CocoaPods or an Xcode workspace under ios/. The iOS pipeline doesn't prebuild, so managed
+/// Expo apps only count once ios/ exists.
+pub fn is_ios_project(path: &Path) -> bool {
+    let ios = path.join("ios");
+    ios.join("Podfile").exists()
+        || std::fs::read_dir(&ios)
+            .map(|entries| entries.filter_map(|e| e.ok()).any(|e| e.path().extension().is_some_and(|ext| ext == "xcworkspace")))
+            .unwrap_or(false)
+}
+
+fn is_project(path: &Path) -> bool {
+    is_android_project(path) || is_ios_project(path)
+}
+
+/// What a project path can build, `None` when it no longer builds at all
+pub fn capabilities(path: &str) -> Option<DiscoveredProject> {
+    let dir = Path::new(path);
+    let project = DiscoveredProject {
+        path: path.to_string(),
+        android: is_android_project(dir) || crate::expo::is_android_buildable(dir),
+        ios: is_ios_project(dir),
+    };
+    (project.android || project.ios).then_some(project)
+}
+
+/// The given path and its parent, the default scratch workspace and Documents
+fn roots(start_path: &str) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    let start = Path::new(start_path);
+    if start.exists() {
+        roots.push(start.to_path_buf());
+        if let Some(parent) = start.parent() {
+            roots.push(parent.to_path_buf());
+        }
+    }
+    if let Ok(home) = std::env::var("USERPROFILE") {
+        let home = Path::new(&home);
+        let scratch = home.join(".gemini").join("antigravity").join("scratch");
+        let docs = home.join("Documents");
+        roots.extend([scratch, docs].into_iter().filter(|dir| dir.exists()));
+    }
+    roots
+}
+
+fn mtime(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_nanos() as u64)
+}
+
+/// `.git`, `.gradle` and other dot folders, build outputs and dependency trees
+fn is_junk(name: &str) -> bool {
+    name.starts_with('.') || SKIP_DIRS.iter().any(|skip| name.eq_ignore_ascii_case(skip))
+}
+
+/// A folder whose contents are still in the cloud
+fn is_placeholder(attributes: u32) -> bool {
+    attributes & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS) != 0
+}
+
+/// Subfolders worth walking into, and whether there's a `.gitignore`
+fn list_subdirs(dir: &Path) -> (Vec<String>, bool) {
+    use std::os::windows::fs::MetadataExt;
+
+    let Ok(entries) = std::fs::read_dir(dir) else { return (Vec::new(), false) };
+    let mut subdirs = Vec::new();
+    let mut gitignore = false;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        // DirEntry::file_type doesn't follow links, so junctions and symlinks are skipped
+        let Ok(file_type) = entry.file_type() else { continue };
+        if !file_type.is_dir() {
+            gitignore |= name == ".gitignore";
+            continue;
+        }
+        // Attributes come with the directory listing, no extra request to OneDrive
+        if is_junk(&name) || entry.metadata().is_ok_and(|m| is_placeholder(m.file_attributes())) {
+            continue;
+        }
+        subdirs.push(name);
+    }
+    (subdirs, gitignore)
+}
+
+/// Depth-limited walk that lists a directory again only when its mtime moved
+struct Walker<'a, F: FnMut(&Path) -> bool> {
+    old: &'a HashMap<String, CachedDir>,
+    dirs: HashMap<String, CachedDir>,
+    /// Shallowest depth each directory was visited at, so overlapping roots aren't walked twice
+    visited: HashMap<PathBuf, usize>,
+    found: BTreeSet<String>,
+    /// `.gitignore` files of the folders above the current one, innermost last
+    ignores: Vec<crate::gitignore::Gitignore>,
+    is_project: F,
+}
+
+impl<'a, F: FnMut(&Path) -> bool> Walker<'a, F> {
+    fn new(old: &'a HashMap<String, CachedDir>, is_project: F) -> Self {
+        Walker { old, dirs: HashMap::new(), visited: HashMap::new(), found: BTreeSet::new(), ignores: Vec::new(), is_project }
+    }
+
+    fn visit(&mut self, dir: &Path, depth: usize, on_found: &mut impl FnMut(&str)) {
+        if self.visited.get(dir).is_some_and(|&d| d <= depth) {
+            return;
+        }
+        self.visited.insert(dir.to_path_buf(), depth);
+        let key = dir.display().to_string();
+        if (self.is_project)(dir) && self.found.insert(key.clone()) {
+            on_found(&key);
+        }
+        if depth >= MAX_DEPTH {
+            return;
+        }
+        let Some(mtime) = mtime(dir) else { return };
+        let (subdirs, gitignore) = match self.old.get(&key) {
+            Some(cached) if cached.mtime == mtime => (cached.subdirs.clone(), cached.gitignore),
+            _ => list_subdirs(dir),
+        };
+        self.dirs.insert(key, CachedDir { mtime, subdirs: subdirs.clone(), gitignore });
+
+        let pushed = match gitignore.then(|| crate::gitignore::Gitignore::load(dir)).flatten() {
+            Some(ignore) => {
+                self.ignores.push(ignore);
+                true
+            },
+            None => false,
+        };
+        for name in subdirs {
+            let child = dir.join(name);
+            // The innermost file that mentions the folder decides
+            if self.ignores.iter().rev().find_map(|ignore| ignore.ignores(&child)) == Some(true) {
+                continue;
+            }
+            self.visit(&child, depth + 1, on_found);
+        }
+        if pushed {
+            self.ignores.pop();
+        }
+    }
+}
+
+fn cache_file(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_cache_dir().ok().map(|dir| dir.join(SCAN_CACHE_FILE))
+}
+
+fn load_cache(app: &tauri::AppHandle) -> ScanCache {
+    cache_file(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(app: &tauri::AppHandle, cache: &ScanCache) {
+    let Some(path) = cache_file(app) else { return };
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(json) = serde_json::to_string(cache) {
+        let _ = std::fs::write(path, json);
+    }
+}
+
+/// Projects created through HyperZenith, wherever they were put
+fn known_projects(app: &tauri::AppHandle) -> impl Iterator<Item = String> {
+    crate::scaffold::known_projects(app).into_iter()
+}
+
+fn scan(app: &tauri::AppHandle, roots: Vec<PathBuf>, cache: ScanCache) -> Vec<DiscoveredProject> {
+    let mut walker = Walker::new(&cache.dirs, is_project);
+    let mut on_found = |path: &str| {
+        let Some(project) = capabilities(path) else { return };
+        let _ = app.emit("projects-discovered", ProjectsDiscovered { projects: vec![project], done: false });
+    };
+    for root in &roots {
+        walker.visit(root, 0, &mut on_found);
+    }
+    let mut found = std::mem::take(&mut walker.found);
+    found.extend(known_projects(app));
+    let projects: Vec<DiscoveredProject> = found.iter().filter_map(|p| capabilities(p)).collect();
+    save_cache(app, &ScanCache { dirs: walker.dirs, projects: projects.iter().map(|p| p.path.clone()).collect() });
+    projects
+}
+
+/// What the last scan found, still there. A fresh scan starts in the background and reports
+/// on `projects-discovered` as it goes.
+pub fn start(app: &tauri::AppHandle, start_path: &str) -> Vec<DiscoveredProject> {
+    let cache = load_cache(app);
+    let mut previous: BTreeSet<String> = cache.projects.iter().cloned().collect();
+    previous.extend(known_projects(app));
+
+    if !SCANNING.swap(true, Ordering::SeqCst) {
+        let app = app.clone();
+        let roots = roots(start_path);
+        tauri::async_runtime::spawn_blocking(move || {
+            let started = std::time::Instant::now();
+            let projects = scan(&app, roots, cache);
+            SCANNING.store(false, Ordering::SeqCst);
+            println!("🔍 [SCAN] {} project(s) in {:.1}s", projects.len(), started.elapsed().as_secs_f64());
+            let _ = app.emit("projects-discovered", ProjectsDiscovered { projects, done: true });
+        });
+    }
+    previous.iter().filter_map(|p| capabilities(p)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walker_reuses_unchanged_dirs() {
+        let root = std::env::temp_dir().join(format!("hz_scan_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("work").join("app").join("android")).unwrap();
+        std::fs::create_dir_all(root.join("a").join("b").join("c").join("too_deep").join("android")).unwrap();
+        std::fs::create_dir_all(root.join("work").join("node_modules").join("pkg").join("android")).unwrap();
+        std::fs::create_dir_all(root.join("work").join("scratch").join("android")).unwrap();
+        std::fs::write(root.join("work").join(".gitignore"), "scratch/\n").unwrap();
+        let is_project = |p: &Path| p.join("android").is_dir();
+
+        let empty = HashMap::new();
+        let mut found = Vec::new();
+        let mut walker = Walker::new(&empty, is_project);
+        walker.visit(&root, 0, &mut |p| found.push(p.to_string()));
+        walker.visit(&root.join("work"), 0, &mut |p| found.push(p.to_string()));
+        assert_eq!(found, vec![root.join("work").join("app").display().to_string()]);
+
+        // An unchanged directory's cached listing stands in for read_dir
+        let root_key = root.display().to_string();
+        let mut cached = walker.dirs;
+        cached.get_mut(&root_key).unwrap().subdirs = vec!["a".to_string()];
+        let mut walker = Walker::new(&cached, is_project);
+        walker.visit(&root, 0, &mut |_| {});
+        assert!(walker.found.is_empty());
+
+        // Once its mtime moves it's listed again
+        cached.get_mut(&root_key).unwrap().mtime = 0;
+        std::fs::create_dir_all(root.join("work").join("new_app").join("android")).unwrap();
+        let mut walker = Walker::new(&cached, is_project);
+        walker.visit(&root, 0, &mut |_| {});
+        assert_eq!(walker.found.len(), 2);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let root = std::env::temp_dir().join(format!("hz_caps_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("both").join("android")).unwrap();
+        std::fs::write(root.join("both").join("android").join("settings.gradle"), "").unwrap();
+        std::fs::create_dir_all(root.join("both").join("ios").join("App.xcworkspace")).unwrap();
+        std::fs::create_dir_all(root.join("pods").join("ios")).unwrap();
+        std::fs::write(root.join("pods").join("ios").join("Podfile"), "").unwrap();
+        std::fs::create_dir_all(root.join("plain")).unwrap();
+        let caps = |name: &str| capabilities(&root.join(name).display().to_string()).map(|p| (p.android, p.ios));
+
+        assert_eq!(caps("both"), Some((true, true)));
+        assert_eq!(caps("pods"), Some((false, true)));
+        assert_eq!(caps("plain"), None);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,110 @@
+use std::path::Path;
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+/// What a command starts that keeps running until stopped, rather than doing a job and exiting
+const LONG_RUNNING: [&str; 8] = ["expo start", "react-native start", "react-native run-", "expo run:", "metro", "--watch", "nodemon", " serve"];
+
+/// One entry of package.json's `scripts`
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct NpmScript {
+    pub name: String,
+    pub command: String,
+    /// Starts a dev server or watcher: it runs until aborted and holds the project's script slot
+    pub long_running: bool,
+}
+
+/// Scripts declared in a package.json, sorted by name
+pub fn parse_scripts(package_json: &str) -> Result<Vec<NpmScript>, HyperZenithError> {
+    let pkg: serde_json::Value = serde_json::from_str(package_json)
+        .map_err(|e| HyperZenithError::InvalidInput(format!("package.json is not valid JSON: {}", e)))?;
+    let Some(scripts) = pkg["scripts"].as_object() else { return Ok(Vec::new()) };
+    let mut scripts: Vec<NpmScript> = scripts
+        .iter()
+        .filter_map(|(name, command)| {
+            let command = command.as_str()?;
+            let lower = command.to_lowercase();
+            Some(NpmScript {
+                name: name.clone(),
+                command: command.to_string(),
+                long_running: LONG_RUNNING.iter().any(|p| lower.contains(p)),
+            })
+        })
+        .collect();
+    scripts.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(scripts)
+}
+
+pub fn scripts(project_dir: &Path) -> Result<Vec<NpmScript>, HyperZenithError> {
+    let path = project_dir.join("package.json");
+    let text = std::fs::read_to_string(&path).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    parse_scripts(&text)
+}
+
+/// Only names package.json declares get run, so nothing but the project's own scripts reaches the shell
+pub fn find(project_dir: &Path, name: &str) -> Result<NpmScript, HyperZenithError> {
+    let scripts = scripts(project_dir)?;
+    scripts.iter().find(|s| s.name == name).cloned().ok_or_else(|| {
+        let names: Vec<&str> = scripts.iter().map(|s| s.name.as_str()).collect();
+        HyperZenithError::InvalidInput(format!("No script '{}' in package.json (available: {})", name, names.join(", ")))
+    })
+}
+
+/// Run the script in WSL from the project folder, with the same Node and environment builds get
+pub async fn run(
+    channel: &BuildChannel,
+    working_dir: &str,
+    script: &NpmScript,
+    toolchain: &crate::node::Toolchain,
+    mut cancel: oneshot::Receiver<()>,
+) -> Result<String, HyperZenithError> {
+    let wsl_dir = crate::windows_to_wsl_path(working_dir);
+    let command = format!("{}cd {} && {} 2>&1", toolchain.env_script(), crate::sh_quote(&wsl_dir), toolchain.package_manager.run_script(&script.name));
+    let distro = crate::wsl::project_distro(working_dir);
+    let mut cmd = crate::process::wsl_command_in(distro.as_deref(), &command);
+    if distro.is_none() {
+        cmd.current_dir(working_dir);
+    }
+
+    channel.line(format!("📜 {} run {}: {}", toolchain.package_manager.binary(), script.name, script.command));
+    if script.long_running {
+        channel.line("ℹ️ This script keeps running until you abort it".to_string());
+    }
+    let outcome = crate::process::run_streamed(channel, cmd, Some(&mut cancel)).await?;
+    let log_path = crate::write_build_log(working_dir, "script", &outcome.log);
+    channel.line(format!("📄 Log saved to: {}", log_path.display()));
+    if outcome.cancelled {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+    if !outcome.success {
+        return Err(HyperZenithError::CommandFailed { command: format!("run {}", script.name), output: format!("See {}", log_path.display()) });
+    }
+    Ok(format!("Script '{}' finished", script.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scripts() {
+        let pkg = r#"{
+            "name": "app",
+            "scripts": {
+                "start": "expo start",
+                "codegen": "graphql-codegen --config codegen.yml",
+                "postinstall": "patch-package",
+                "test:watch": "jest --watch",
+                "broken": 42
+            }
+        }"#;
+        let scripts = parse_scripts(pkg).unwrap();
+        let names: Vec<(&str, bool)> = scripts.iter().map(|s| (s.name.as_str(), s.long_running)).collect();
+        assert_eq!(names, vec![("codegen", false), ("postinstall", false), ("start", true), ("test:watch", true)]);
+        assert_eq!(scripts[1].command, "patch-package");
+
+        assert!(parse_scripts(r#"{"name": "no-scripts"}"#).unwrap().is_empty());
+        assert!(parse_scripts("{ not json").is_err());
+    }
+}
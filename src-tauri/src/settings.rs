@@ -0,0 +1,125 @@
+use serde_json::{Map, Value};
+use std::path::Path;
+use crate::error::HyperZenithError;
+
+const FORMAT: &str = "hyperzenith-settings";
+const FORMAT_VERSION: u32 = 1;
+/// Frontend settings are localStorage entries with this prefix
+const KEY_PREFIX: &str = "hyperzenith_";
+/// Never leave this machine: Mac and jump host passwords, tokens
+const SECRET_KEYS: [&str; 4] = ["password", "passphrase", "token", "secret"];
+
+/// Everything needed to set up HyperZenith on another machine
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct SettingsBundle {
+    pub format: String,
+    pub version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    /// Frontend settings by localStorage key: Mac profile, turbo defaults, gates, iOS paths...
+    pub settings: Map<String, Value>,
+    /// Projects created or cloned here
+    #[serde(default)]
+    pub projects: Vec<String>,
+}
+
+/// Blank out secret fields at any depth, keeping the keys so the shape stays intact
+fn scrub(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if SECRET_KEYS.iter().any(|s| key.to_lowercase().contains(s)) && value.is_string() {
+                    *value = Value::String(String::new());
+                } else {
+                    scrub(value);
+                }
+            }
+        },
+        Value::Array(items) => items.iter_mut().for_each(scrub),
+        _ => {},
+    }
+}
+
+/// Only HyperZenith's own keys, with secrets removed. Values stored as JSON strings in
+/// localStorage (the Mac profile, gates) are scrubbed inside too.
+pub fn clean_settings(settings: Map<String, Value>) -> Map<String, Value> {
+    settings
+        .into_iter()
+        .filter(|(key, _)| key.starts_with(KEY_PREFIX))
+        .map(|(key, mut value)| {
+            let nested = value.as_str().and_then(|s| serde_json::from_str::<Value>(s).ok()).filter(|v| v.is_object() || v.is_array());
+            match nested {
+                Some(mut nested) => {
+                    scrub(&mut nested);
+                    value = Value::String(nested.to_string());
+                },
+                None => scrub(&mut value),
+            }
+            (key, value)
+        })
+        .collect()
+}
+
+pub fn export(path: &Path, app_version: &str, settings: Map<String, Value>, projects: Vec<String>) -> Result<usize, HyperZenithError> {
+    let bundle = SettingsBundle {
+        format: FORMAT.to_string(),
+        version: FORMAT_VERSION,
+        app_version: app_version.to_string(),
+        exported_at: chrono::Local::now().to_rfc3339(),
+        settings: clean_settings(settings),
+        projects,
+    };
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+    std::fs::write(path, json).map_err(|e| HyperZenithError::io_at(path, e))?;
+    Ok(bundle.settings.len())
+}
+
+pub fn parse(json: &str) -> Result<SettingsBundle, HyperZenithError> {
+    let bundle: SettingsBundle = serde_json::from_str(json)
+        .map_err(|e| HyperZenithError::InvalidInput(format!("Not a HyperZenith settings file: {}", e)))?;
+    if bundle.format != FORMAT {
+        return Err(HyperZenithError::InvalidInput("Not a HyperZenith settings file".to_string()));
+    }
+    if bundle.version > FORMAT_VERSION {
+        return Err(HyperZenithError::InvalidInput(format!(
+            "These settings come from a newer HyperZenith ({}), update first",
+            bundle.app_version
+        )));
+    }
+    // Never trust a file to have been scrubbed, or to stick to our keys
+    Ok(SettingsBundle { settings: clean_settings(bundle.settings), ..bundle })
+}
+
+pub fn import(path: &Path) -> Result<SettingsBundle, HyperZenithError> {
+    let json = std::fs::read_to_string(path).map_err(|e| HyperZenithError::io_at(path, e))?;
+    parse(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_settings() {
+        let mac = r#"{"ip":"mac.local","username":"dev","password":"hunter2","jump":{"ip":"gw","password":"pw2"},"sync":{"checksum":true}}"#;
+        let settings: Map<String, Value> = serde_json::from_value(serde_json::json!({
+            "hyperzenith_mac_config": mac,
+            "hyperzenith_use_nvm": "true",
+            "other_app_token": "abc",
+        }))
+        .unwrap();
+        let cleaned = clean_settings(settings);
+
+        assert_eq!(cleaned.len(), 2);
+        let mac: Value = serde_json::from_str(cleaned["hyperzenith_mac_config"].as_str().unwrap()).unwrap();
+        assert_eq!(mac["password"], "");
+        assert_eq!(mac["jump"]["password"], "");
+        assert_eq!(mac["ip"], "mac.local");
+        assert_eq!(mac["sync"]["checksum"], true);
+        assert_eq!(cleaned["hyperzenith_use_nvm"], "true");
+
+        let newer = r#"{"format":"hyperzenith-settings","version":9,"app_version":"9.0.0","exported_at":"","settings":{}}"#;
+        assert!(parse(newer).is_err());
+        assert!(parse(r#"{"hello":"world"}"#).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+use ssh2::{FileStat, Session, Sftp};
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use crate::error::HyperZenithError;
+
+/// Never uploaded: reinstalled, rebuilt or machine specific on the Mac. Patterns without a `/`
+/// match a name at any depth, like rsync's `--exclude`.
+pub const SYNC_EXCLUDES: [&str; 7] = [
+    "node_modules",
+    ".git",
+    "android",
+    "ios/Pods",        // Save bandwidth: let remote 'pod install' handle this
+    "ios/build",       // Don't sync local build artifacts
+    "ios/DerivedData", // Don't sync intermediate build files
+    "ios/.xcode.env.local", // Machine-specific config
+];
+
+pub fn is_excluded(rel: &str) -> bool {
+    SYNC_EXCLUDES.iter().any(|pattern| {
+        if pattern.contains('/') {
+            rel == *pattern || rel.ends_with(&format!("/{}", pattern))
+        } else {
+            rel.split('/').any(|part| part == *pattern)
+        }
+    })
+}
+
+/// SFTP has no `~`, but relative paths already start in the home folder
+fn sftp_path(remote_path: &str) -> PathBuf {
+    let path = remote_path.trim_end_matches('/');
+    PathBuf::from(path.strip_prefix("~/").unwrap_or(if path == "~" { "." } else { path }))
+}
+
+fn sftp_error(action: &str, path: &Path, err: ssh2::Error) -> HyperZenithError {
+    HyperZenithError::SyncFailed { reason: format!("SFTP {} '{}' failed: {}", action, path.display(), err) }
+}
+
+pub struct LocalFile {
+    pub rel: String,
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub mtime: u64,
+    /// Committed with the executable bit; Windows doesn't keep one, git does
+    pub executable: bool,
+}
+
+pub struct SyncPlan {
+    /// With `true` for files the Mac doesn't have yet
+    pub upload: Vec<(LocalFile, bool)>,
+    pub delete: Vec<String>,
+}
+
+/// Same size and modification time counts as unchanged, like rsync without `--checksum`
+pub fn diff(local: Vec<LocalFile>, remote: &HashMap<String, (u64, u64)>, delete: bool) -> SyncPlan {
+    let local_names: HashSet<String> = local.iter().map(|f| f.rel.clone()).collect();
+    let mut delete: Vec<String> = if delete {
+        remote.keys().filter(|rel| !local_names.contains(*rel)).cloned().collect()
+    } else {
+        Vec::new()
+    };
+    delete.sort();
+    let upload = local
+        .into_iter()
+        .filter_map(|file| match remote.get(&file.rel) {
+            Some(&(bytes, mtime)) if bytes == file.bytes && mtime == file.mtime => None,
+            found => Some((file, found.is_none())),
+        })
+        .collect();
+    SyncPlan { upload, delete }
+}
+
+/// Paths (relative to the folder it ran in) that `git ls-files -s` lists with mode 100755
+fn parse_executables(ls_files: &str) -> HashSet<String> {
+    ls_files
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(meta, _)| meta.starts_with("100755 "))
+        .map(|(_, path)| path.to_string())
+        .collect()
+}
+
+/// Executable files under `local_root` as git records them, none when it isn't a repository or
+/// there's no git for Windows
+fn git_executables(local_root: &Path) -> HashSet<String> {
+    std::process::Command::new("git")
+        .arg("-C")
+        .arg(local_root)
+        .args(["-c", "core.quotePath=false", "ls-files", "-s"])
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_executables(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+fn local_files(local_root: &Path) -> Vec<LocalFile> {
+    let executables = git_executables(local_root);
+    let rel = |path: &Path| path.strip_prefix(local_root).ok().map(|p| p.to_string_lossy().replace('\\', "/"));
+    walkdir::WalkDir::new(local_root)
+        .into_iter()
+        .filter_entry(|e| rel(e.path()).is_none_or(|r| !is_excluded(&r)))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            let mtime = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_secs();
+            let rel = rel(e.path())?;
+            let executable = executables.contains(&rel);
+            Some(LocalFile { rel, path: e.path().to_path_buf(), bytes: meta.len(), mtime, executable })
+        })
+        .collect()
+}
+
+/// Size and mtime of every file already on the Mac, one `readdir` per folder
+fn remote_files(sftp: &Sftp, root: &Path) -> HashMap<String, (u64, u64)> {
+    let mut files = HashMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        // A missing root is just a first sync
+        for (path, stat) in sftp.readdir(&dir).unwrap_or_default() {
+            let Some(rel) = path.strip_prefix(root).ok().map(|p| p.to_string_lossy().replace('\\', "/")) else { continue };
+            if is_excluded(&rel) {
+                continue;
+            }
+            if stat.is_dir() {
+                pending.push(path);
+            } else {
+                files.insert(rel, (stat.size.unwrap_or(0), stat.mtime.unwrap_or(0)));
+            }
+        }
+    }
+    files
+}
+
+pub fn plan(sess: &Session, local_root: &Path, remote_path: &str, delete: bool) -> Result<SyncPlan, HyperZenithError> {
+    let sftp = sess.sftp().map_err(|e| HyperZenithError::SyncFailed { reason: format!("SFTP unavailable: {}", e) })?;
+    let remote = remote_files(&sftp, &sftp_path(remote_path));
+    Ok(diff(local_files(local_root), &remote, delete))
+}
+
+/// Copy in chunks, sleeping as needed to stay under `bwlimit_kbps`
+fn upload(sftp: &Sftp, file: &LocalFile, remote: &Path, bwlimit_kbps: Option<u32>) -> Result<(), HyperZenithError> {
+    let mut source = std::fs::File::open(&file.path).map_err(|e| HyperZenithError::io_at(&file.path, e))?;
+    let mut target = sftp.create(remote).map_err(|e| sftp_error("create", remote, e))?;
+    let started = Instant::now();
+    let mut sent = 0u64;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = source.read(&mut buffer).map_err(|e| HyperZenithError::io_at(&file.path, e))?;
+        if read == 0 {
+            break;
+        }
+        target.write_all(&buffer[..read]).map_err(|e| HyperZenithError::SyncFailed { reason: format!("Upload of '{}' failed: {}", file.rel, e) })?;
+        sent += read as u64;
+        if let Some(limit) = bwlimit_kbps.filter(|l| *l > 0) {
+            let due = Duration::from_secs_f64(sent as f64 / (limit as f64 * 1024.0));
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                std::thread::sleep(wait);
+            }
+        }
+    }
+    drop(target);
+    // Keep the mtime so the next sync sees the file as unchanged, and scripts runnable
+    let perm = if file.executable { 0o755 } else { 0o644 };
+    let stat = FileStat { size: None, uid: None, gid: None, perm: Some(perm), atime: Some(file.mtime), mtime: Some(file.mtime) };
+    sftp.setstat(remote, stat).map_err(|e| sftp_error("setstat", remote, e))
+}
+
+/// Carry out a plan, creating remote folders as needed. `--checksum` has no SFTP equivalent,
+/// size and mtime decide what changed.
+pub fn apply(sess: &Session, plan: &SyncPlan, remote_path: &str, settings: &crate::ios::SyncSettings) -> Result<(), HyperZenithError> {
+    let sftp = sess.sftp().map_err(|e| HyperZenithError::SyncFailed { reason: format!("SFTP unavailable: {}", e) })?;
+    let root = sftp_path(remote_path);
+    let mut created = HashSet::new();
+
+    for (file, _) in &plan.upload {
+        let remote = root.join(&file.rel);
+        let mut dir = PathBuf::new();
+        for part in remote.parent().into_iter().flat_map(|p| p.components()) {
+            dir.push(part);
+            // Already existing folders fail to mkdir, which is fine
+            if created.insert(dir.clone()) {
+                let _ = sftp.mkdir(&dir, 0o755);
+            }
+        }
+        upload(&sftp, file, &remote, settings.bwlimit_kbps)?;
+    }
+    for rel in &plan.delete {
+        let remote = root.join(rel);
+        sftp.unlink(&remote).map_err(|e| sftp_error("delete", &remote, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let file = |rel: &str, bytes: u64, mtime: u64| LocalFile { rel: rel.to_string(), path: PathBuf::from(rel), bytes, mtime, executable: false };
+        let local = vec![file("App.tsx", 100, 10), file("package.json", 50, 20), file("assets/logo.png", 900, 30)];
+        let remote = HashMap::from([
+            ("App.tsx".to_string(), (100, 10)),
+            ("package.json".to_string(), (48, 5)),
+            ("src/Old.tsx".to_string(), (10, 1)),
+        ]);
+
+        let plan = diff(local, &remote, true);
+        let uploads: Vec<(&str, bool)> = plan.upload.iter().map(|(f, new)| (f.rel.as_str(), *new)).collect();
+        assert_eq!(uploads, vec![("package.json", false), ("assets/logo.png", true)]);
+        assert_eq!(plan.delete, vec!["src/Old.tsx".to_string()]);
+        assert!(diff(Vec::new(), &remote, false).delete.is_empty());
+
+        assert!(is_excluded("node_modules/react/index.js"));
+        assert!(is_excluded("apps/mobile/ios/Pods"));
+        assert!(!is_excluded("ios/Podfile"));
+        assert_eq!(sftp_path("~/hyperzenith_builds/project/"), PathBuf::from("hyperzenith_builds/project"));
+
+        let ls_files = "100644 8f1c2a0e9d1b3c4d5e6f708192a3b4c5d6e7f801 0\tpackage.json\n\
+                        100755 1a2b3c4d5e6f708192a3b4c5d6e7f8011a2b3c4d 0\tscripts/build phase.sh\n\
+                        120000 2b3c4d5e6f708192a3b4c5d6e7f8011a2b3c4d5e 0\tlink\n";
+        assert_eq!(parse_executables(ls_files), HashSet::from(["scripts/build phase.sh".to_string()]));
+    }
+}
@@ -0,0 +1,129 @@
+use tauri::Emitter;
+use crate::error::HyperZenithError;
+use crate::ios::MacConfig;
+
+/// Longest recording, `simctl io` files grow quickly
+pub const MAX_RECORDING_SECS: u64 = 60;
+const NO_APP: &str = "HZ_NO_APP";
+const NO_SIMULATOR: &str = "HZ_NO_SIMULATOR";
+
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureKind {
+    Screenshot,
+    Recording,
+}
+
+impl CaptureKind {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Screenshot => "png",
+            Self::Recording => "mp4",
+        }
+    }
+}
+
+/// Sent on `simulator-capture` once the file is on this machine
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SimulatorCapture {
+    pub kind: String,
+    pub path: String,
+}
+
+/// Boot the simulator, install the newest simulator build of `scheme` from DerivedData and
+/// launch it, giving it a few seconds to draw its first screen
+fn launch_cmd(scheme: &str, simulator: &str) -> String {
+    let sim = crate::sh_quote(simulator);
+    format!(
+        "app=$(ls -td ~/Library/Developer/Xcode/DerivedData/*/Build/Products/Debug-iphonesimulator/{app} 2>/dev/null | head -1); \
+        [ -n \"$app\" ] || {{ echo {no_app}; exit 0; }}; \
+        xcrun simctl boot {sim} >/dev/null 2>&1; \
+        xcrun simctl bootstatus {sim} -b >/dev/null 2>&1 || {{ echo {no_sim}; exit 0; }}; \
+        xcrun simctl install booted \"$app\" && \
+        xcrun simctl launch booted \"$(/usr/libexec/PlistBuddy -c 'Print :CFBundleIdentifier' \"$app/Info.plist\")\" && \
+        sleep 3",
+        app = crate::sh_quote(&format!("{}.app", scheme)),
+        sim = sim,
+        no_app = NO_APP,
+        no_sim = NO_SIMULATOR
+    )
+}
+
+/// Capture into `remote_file` from the booted simulator. A recording stops on SIGINT so
+/// `recordVideo` can finish writing the file.
+fn capture_cmd(kind: CaptureKind, remote_file: &str, duration_secs: u64) -> String {
+    match kind {
+        CaptureKind::Screenshot => format!("xcrun simctl io booted screenshot {} >/dev/null", remote_file),
+        CaptureKind::Recording => format!(
+            "xcrun simctl io booted recordVideo --codec=h264 --force {file} >/dev/null 2>&1 & rec=$!; \
+            sleep {secs}; kill -INT $rec; wait $rec",
+            file = remote_file,
+            secs = duration_secs.clamp(1, MAX_RECORDING_SECS)
+        ),
+    }
+}
+
+/// Screenshot or record the simulator on the Mac into `captures` in the project's logs folder.
+/// With `launch`, the last simulator build of `scheme` is installed and started first.
+#[allow(clippy::too_many_arguments)]
+pub fn capture(
+    app: &tauri::AppHandle,
+    config: &MacConfig,
+    working_dir: &str,
+    scheme: &str,
+    simulator: &str,
+    kind: CaptureKind,
+    duration_secs: u64,
+    launch: bool,
+) -> Result<SimulatorCapture, HyperZenithError> {
+    let sess = crate::ios::create_session(config)?;
+
+    if launch {
+        let output = crate::ios::run_remote_capture(&sess, &launch_cmd(scheme, simulator))?;
+        if output.contains(NO_APP) {
+            return Err(HyperZenithError::InvalidInput(format!("No simulator build of {} on the Mac yet. Build it for the simulator first", scheme)));
+        }
+        if output.contains(NO_SIMULATOR) {
+            return Err(HyperZenithError::InvalidInput(format!("The '{}' simulator could not be booted", simulator)));
+        }
+    }
+
+    let remote_file = format!("{}/capture.{}", crate::ios::DETACHED_DIR, kind.extension());
+    let booted = crate::ios::run_remote_capture(&sess, "xcrun simctl list devices booted | grep -c Booted")?;
+    if booted.trim().parse::<u32>().unwrap_or(0) == 0 {
+        return Err(HyperZenithError::InvalidInput("No simulator is running on the Mac".to_string()));
+    }
+    crate::ios::run_remote_capture(&sess, &format!("mkdir -p {} && {}", crate::ios::DETACHED_DIR, capture_cmd(kind, &remote_file, duration_secs)))?;
+
+    let local_dir = crate::logs::logs_dir(working_dir).join("captures");
+    std::fs::create_dir_all(&local_dir).map_err(|e| HyperZenithError::io_at(&local_dir, e))?;
+    let local_file = local_dir.join(format!("{}_{}.{}", scheme, chrono::Local::now().format("%Y-%m-%d_%H-%M-%S"), kind.extension()));
+    crate::ios::download_remote(&sess, &format!("cat {}", remote_file), &local_file)?;
+
+    let capture = SimulatorCapture {
+        kind: kind.extension().to_string(),
+        path: local_file.display().to_string(),
+    };
+    let _ = app.emit("simulator-capture", &capture);
+    Ok(capture)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_cmd() {
+        assert_eq!(
+            capture_cmd(CaptureKind::Screenshot, "~/.hyperzenith/capture.png", 0),
+            "xcrun simctl io booted screenshot ~/.hyperzenith/capture.png >/dev/null"
+        );
+        let recording = capture_cmd(CaptureKind::Recording, "~/.hyperzenith/capture.mp4", 600);
+        assert!(recording.contains("recordVideo --codec=h264 --force ~/.hyperzenith/capture.mp4"));
+        assert!(recording.contains("sleep 60; kill -INT $rec; wait $rec"));
+
+        let launch = launch_cmd("My App", "iPhone 15");
+        assert!(launch.contains("Debug-iphonesimulator/'My App.app'"));
+        assert!(launch.contains("xcrun simctl boot 'iPhone 15'"));
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use crate::error::HyperZenithError;
+
+/// Everything the commands share, registered once via `tauri::Builder::manage`
+pub struct AppState {
+    pub builds: BuildRegistry,
+    pub system: Mutex<sysinfo::System>,
+    pub last_cleared: Mutex<Vec<PathBuf>>,
+    /// Opt-in localhost control API, `None` until started
+    pub control: Mutex<Option<crate::control::ControlServer>>,
+    /// Most recent Android build request, repeated by the tray's "Build last project"
+    pub last_build: Mutex<Option<LastBuild>>,
+    /// Prewarm or build waiting for the machine to go idle
+    pub idle_job: Mutex<Option<crate::idle::IdleJob>>,
+    pub battery_policy: Mutex<crate::power::BatteryPolicy>,
+    pub memory_policy: Mutex<crate::memory::MemoryPolicy>,
+    pub stall_policy: Mutex<crate::watchdog::StallPolicy>,
+    pub failure_cleanup: Mutex<crate::cache::FailureCleanupPolicy>,
+    pub mac_queues: MacQueues,
+    pub ios_pipelines: IosPipelines,
+}
+
+#[derive(Clone)]
+pub struct LastBuild {
+    pub working_dir: String,
+    pub options: crate::AndroidBuildOptions,
+    pub verbosity: crate::events::Verbosity,
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        AppState {
+            builds: BuildRegistry::default(),
+            system: Mutex::new(sysinfo::System::new_all()),
+            last_cleared: Mutex::new(Vec::new()),
+            control: Mutex::new(None),
+            last_build: Mutex::new(None),
+            idle_job: Mutex::new(None),
+            battery_policy: Mutex::new(crate::power::BatteryPolicy::default()),
+            memory_policy: Mutex::new(crate::memory::MemoryPolicy::default()),
+            stall_policy: Mutex::new(crate::watchdog::StallPolicy::default()),
+            failure_cleanup: Mutex::new(crate::cache::FailureCleanupPolicy::default()),
+            mac_queues: MacQueues::default(),
+            ios_pipelines: IosPipelines::default(),
+        }
+    }
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct BuildInfo {
+    pub id: String,
+    pub project: String,
+    pub platform: String,
+    pub started_at: String,
+}
+
+struct BuildEntry {
+    info: BuildInfo,
+    /// Something meant to keep running until stopped (Metro, a watcher) rather than a build:
+    /// the stall watchdog and resource sampler leave it out
+    service: bool,
+    cancel: oneshot::Sender<()>,
+    /// When the build last printed anything, and what
+    heard_at: Instant,
+    last_line: Option<String>,
+}
+
+/// How long a running build has been quiet, for the stall watchdog
+pub struct Silence {
+    pub info: BuildInfo,
+    pub silent: Duration,
+    pub last_line: Option<String>,
+}
+
+/// Running builds keyed by id. Dropping an entry fires its cancel signal
+#[derive(Default)]
+pub struct BuildRegistry {
+    builds: Mutex<HashMap<String, BuildEntry>>,
+}
+
+impl BuildRegistry {
+    /// Track a new build and return its id plus the receiver that fires on abort.
+    /// A still-running build of the same project is an orphan and gets cancelled.
+    pub fn register(&self, project: &str, platform: &str) -> (String, oneshot::Receiver<()>) {
+        self.insert(project, platform, false)
+    }
+
+    /// Like `register`, for a run that only ends when aborted
+    pub fn register_service(&self, project: &str, platform: &str) -> (String, oneshot::Receiver<()>) {
+        self.insert(project, platform, true)
+    }
+
+    fn insert(&self, project: &str, platform: &str, service: bool) -> (String, oneshot::Receiver<()>) {
+        let (tx, rx) = oneshot::channel();
+        let id = uuid::Uuid::new_v4().to_string();
+
+        let mut builds = self.builds.lock().unwrap();
+        builds.retain(|_, b| !(b.info.project == project && b.info.platform == platform));
+        builds.insert(id.clone(), BuildEntry {
+            info: BuildInfo {
+                id: id.clone(),
+                project: project.to_string(),
+                platform: platform.to_string(),
+                started_at: chrono::Local::now().to_rfc3339(),
+            },
+            service,
+            cancel: tx,
+            heard_at: Instant::now(),
+            last_line: None,
+        });
+        (id, rx)
+    }
+
+    /// Forget a build that ended on its own
+    pub fn finish(&self, id: &str) {
+        // Removing without sending: the receiver side has already completed
+        self.builds.lock().unwrap().remove(id);
+    }
+
+    pub fn abort(&self, id: &str) -> bool {
+        match self.builds.lock().unwrap().remove(id) {
+            Some(entry) => entry.cancel.send(()).is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn abort_all(&self) -> usize {
+        let drained: Vec<BuildEntry> = self.builds.lock().unwrap().drain().map(|(_, b)| b).collect();
+        drained.into_iter().map(|b| b.cancel.send(())).filter(Result::is_ok).count()
+    }
+
+    /// Running builds, without services
+    pub fn active(&self) -> Vec<BuildInfo> {
+        self.builds.lock().unwrap().values().filter(|b| !b.service).map(|b| b.info.clone()).collect()
+    }
+
+    /// Running services
+    pub fn services(&self) -> Vec<BuildInfo> {
+        self.builds.lock().unwrap().values().filter(|b| b.service).map(|b| b.info.clone()).collect()
+    }
+
+    /// The build printed `line` just now
+    pub fn heard(&self, id: &str, line: &str) {
+        if let Some(build) = self.builds.lock().unwrap().get_mut(id) {
+            build.heard_at = Instant::now();
+            if !line.trim().is_empty() {
+                build.last_line = Some(line.to_string());
+            }
+        }
+    }
+
+    pub fn silences(&self) -> Vec<Silence> {
+        self.builds.lock().unwrap().values()
+            .filter(|b| !b.service)
+            .map(|b| Silence { info: b.info.clone(), silent: b.heard_at.elapsed(), last_line: b.last_line.clone() })
+            .collect()
+    }
+}
+
+/// iOS builds lined up per Mac, so two never fight over its CPU and DerivedData.
+/// The front of each queue is the build currently using that Mac.
+#[derive(Default)]
+pub struct MacQueues {
+    queues: Mutex<HashMap<String, VecDeque<String>>>,
+    changed: Condvar,
+}
+
+/// A build's turn on a Mac, passed to the next in line when dropped
+pub struct MacTurn<'a> {
+    queues: &'a MacQueues,
+    mac: String,
+    id: String,
+}
+
+impl Drop for MacTurn<'_> {
+    fn drop(&mut self) {
+        let mut queues = self.queues.queues.lock().unwrap();
+        if let Some(queue) = queues.get_mut(&self.mac) {
+            queue.retain(|b| *b != self.id);
+            if queue.is_empty() {
+                queues.remove(&self.mac);
+            }
+        }
+        self.queues.changed.notify_all();
+    }
+}
+
+impl MacQueues {
+    /// Join the queue for `mac` and block until it's this build's turn. `on_wait` hears the
+    /// number of builds ahead each time that changes, and not at all if the Mac is free.
+    /// `None` if the build left the queue while waiting.
+    pub fn wait_turn(&self, mac: &str, id: &str, mut on_wait: impl FnMut(usize)) -> Option<MacTurn<'_>> {
+        let mut queues = self.queues.lock().unwrap();
+        queues.entry(mac.to_string()).or_default().push_back(id.to_string());
+        let mut last = None;
+        loop {
+            let ahead = queues.get(mac)?.iter().position(|b| b == id)?;
+            if ahead == 0 {
+                break;
+            }
+            if last != Some(ahead) {
+                on_wait(ahead);
+                last = Some(ahead);
+            }
+            queues = self.changed.wait(queues).unwrap();
+        }
+        Some(MacTurn { queues: self, mac: mac.to_string(), id: id.to_string() })
+    }
+
+    /// Take a waiting build out of the queue for `mac`
+    pub fn leave(&self, mac: &str, id: &str) {
+        drop(MacTurn { queues: self, mac: mac.to_string(), id: id.to_string() });
+    }
+}
+
+/// iOS builds in flight and the stage each is at, `None` once cancelled
+#[derive(Default)]
+pub struct IosPipelines {
+    running: Mutex<HashMap<String, Option<crate::ios::IosStage>>>,
+}
+
+/// A tracked iOS build, forgotten here and in the build registry when dropped
+pub struct IosPipeline<'a> {
+    pipelines: &'a IosPipelines,
+    builds: &'a BuildRegistry,
+    pub id: String,
+}
+
+impl Drop for IosPipeline<'_> {
+    fn drop(&mut self) {
+        self.pipelines.running.lock().unwrap().remove(&self.id);
+        self.builds.finish(&self.id);
+    }
+}
+
+impl IosPipelines {
+    /// Track a build from the moment it queues for `mac`
+    pub fn track<'a>(&'a self, builds: &'a BuildRegistry, id: &str, mac: &str) -> IosPipeline<'a> {
+        let queued = crate::ios::IosStage::Queued { mac: mac.to_string() };
+        self.running.lock().unwrap().insert(id.to_string(), Some(queued));
+        IosPipeline { pipelines: self, builds, id: id.to_string() }
+    }
+
+    /// Mark a build cancelled and hand back the stage it was at, for the caller to stop.
+    /// `None` if it isn't running or was already cancelled.
+    pub fn cancel(&self, id: &str) -> Option<crate::ios::IosStage> {
+        self.running.lock().unwrap().get_mut(id)?.take()
+    }
+}
+
+impl IosPipeline<'_> {
+    /// Move on to `stage`. A build cancelled meanwhile gets the stage stopped right away
+    /// and `BuildAborted` back.
+    pub fn enter(&self, stage: crate::ios::IosStage) -> Result<(), HyperZenithError> {
+        let mut running = self.pipelines.running.lock().unwrap();
+        match running.get_mut(&self.id) {
+            Some(slot @ Some(_)) => {
+                *slot = Some(stage);
+                Ok(())
+            },
+            _ => {
+                drop(running);
+                let _ = stage.stop();
+                Err(HyperZenithError::BuildAborted { log_path: None })
+            },
+        }
+    }
+
+    pub fn cancelled(&self) -> bool {
+        matches!(self.pipelines.running.lock().unwrap().get(&self.id), Some(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orphan_build_is_cancelled() {
+        let registry = BuildRegistry::default();
+        let (first, mut first_rx) = registry.register("C:\\Dev\\App", "android");
+        let (second, _second_rx) = registry.register("C:\\Dev\\App", "android");
+
+        assert_ne!(first, second);
+        assert!(matches!(first_rx.try_recv(), Err(oneshot::error::TryRecvError::Closed))); // Sender dropped -> orphan cancelled
+        assert_eq!(registry.active().len(), 1);
+    }
+
+    #[test]
+    fn test_abort_and_finish() {
+        let registry = BuildRegistry::default();
+        let (a, mut a_rx) = registry.register("C:\\Dev\\A", "android");
+        let (b, _b_rx) = registry.register("C:\\Dev\\B", "android");
+        assert_eq!(registry.active().len(), 2);
+
+        assert!(registry.abort(&a));
+        assert_eq!(a_rx.try_recv(), Ok(()));
+        assert!(!registry.abort(&a));
+
+        registry.finish(&b);
+        assert!(registry.active().is_empty());
+    }
+
+    #[test]
+    fn test_services_are_not_builds() {
+        let registry = BuildRegistry::default();
+        let (metro, _metro_rx) = registry.register_service("C:\\Dev\\App", "script:start");
+        let (_lint, _lint_rx) = registry.register("C:\\Dev\\App", "script:lint");
+        assert_eq!(registry.active().len(), 1);
+        assert_eq!(registry.silences().len(), 1);
+        assert_eq!(registry.services()[0].id, metro);
+        assert!(registry.abort(&metro));
+        assert!(registry.services().is_empty());
+    }
+
+    #[test]
+    fn test_mac_queue() {
+        let queues = std::sync::Arc::new(MacQueues::default());
+        let first = queues.wait_turn("dev@studio", "a", |_| panic!("the Mac was free")).unwrap();
+        let _other_mac = queues.wait_turn("dev@mini", "c", |_| panic!("a different Mac"));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let waiting = std::sync::Arc::clone(&queues);
+        let handle = std::thread::spawn(move || {
+            let _turn = waiting.wait_turn("dev@studio", "b", |ahead| tx.send(ahead).unwrap());
+        });
+        assert_eq!(rx.recv().unwrap(), 1);
+        drop(first);
+        handle.join().unwrap();
+        assert!(!queues.queues.lock().unwrap().contains_key("dev@studio"));
+
+        // A build cancelled while waiting leaves without getting a turn
+        let _first = queues.wait_turn("dev@studio", "a", |_| {}).unwrap();
+        let waiting = std::sync::Arc::clone(&queues);
+        let handle = std::thread::spawn(move || waiting.wait_turn("dev@studio", "b", |_| {}).is_none());
+        while queues.queues.lock().unwrap()["dev@studio"].len() < 2 {
+            std::thread::yield_now();
+        }
+        queues.leave("dev@studio", "b");
+        assert!(handle.join().unwrap());
+    }
+}
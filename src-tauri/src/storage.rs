@@ -0,0 +1,288 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+
+/// Artifacts wait here, inside the project's own archive, while the configured target is offline
+const PENDING_DIR: &str = "pending";
+const NETWORK_ATTEMPTS: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Win32 errors a share or removable drive returns while it's briefly gone: bad/deleted
+/// net name, network busy or unreachable, semaphore timeout, device not ready
+const TRANSIENT_OS_ERRORS: [i32; 9] = [21, 53, 54, 55, 59, 64, 67, 121, 1231];
+
+#[derive(serde::Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    Local,
+    /// `\\server\share` or a mapped network drive
+    NetworkShare,
+    /// USB stick or SD card
+    Removable,
+}
+
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct StorageStatus {
+    pub root: String,
+    pub kind: StorageKind,
+    pub available: bool,
+    /// Artifacts held locally until the target is reachable again
+    pub pending: usize,
+}
+
+/// Where archived artifacts go: the project's `hyperzenith_builds`, or a custom folder that may
+/// be a network share or a removable drive
+pub struct ArchiveStorage {
+    pub root: PathBuf,
+    pub kind: StorageKind,
+    /// `<project>/hyperzenith_builds`, where pending artifacts are held
+    local: PathBuf,
+}
+
+/// `\\server\share\...`, not counting the `\\wsl$` / `\\wsl.localhost` views of WSL distros
+pub fn is_unc_share(path: &str) -> bool {
+    let normalized = path.replace('/', "\\");
+    let normalized = normalized.strip_prefix(r"\\?\UNC\").map(|p| format!(r"\\{}", p)).unwrap_or(normalized);
+    let Some(rest) = normalized.strip_prefix(r"\\") else { return false };
+    let host = rest.split('\\').next().unwrap_or_default().to_lowercase();
+    !host.is_empty() && host != "?" && host != "." && host != "wsl$" && host != "wsl.localhost"
+}
+
+/// Drive type of a `X:\` path via GetDriveTypeW
+fn drive_kind(path: &Path) -> StorageKind {
+    use winapi::um::fileapi::GetDriveTypeW;
+    use winapi::um::winbase::{DRIVE_REMOTE, DRIVE_REMOVABLE};
+
+    let Some(root) = path.components().next().map(|c| c.as_os_str().to_string_lossy().to_string()) else {
+        return StorageKind::Local;
+    };
+    if !root.ends_with(':') {
+        return StorageKind::Local;
+    }
+    let root: Vec<u16> = format!("{}\\", root).encode_utf16().chain(std::iter::once(0)).collect();
+    // SAFETY: `root` is a NUL-terminated UTF-16 string that outlives the call
+    match unsafe { GetDriveTypeW(root.as_ptr()) } {
+        DRIVE_REMOTE => StorageKind::NetworkShare,
+        DRIVE_REMOVABLE => StorageKind::Removable,
+        _ => StorageKind::Local,
+    }
+}
+
+pub fn is_transient(error: &std::io::Error) -> bool {
+    matches!(error.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted)
+        || error.raw_os_error().is_some_and(|code| TRANSIENT_OS_ERRORS.contains(&code))
+}
+
+impl ArchiveStorage {
+    pub fn resolve(working_dir: &str, custom_path: Option<String>) -> Self {
+        let root = crate::archive::archive_dir(working_dir, custom_path);
+        let kind = if is_unc_share(&root.to_string_lossy()) { StorageKind::NetworkShare } else { drive_kind(&root) };
+        ArchiveStorage { root, kind, local: crate::archive::archive_dir(working_dir, None) }
+    }
+
+    fn pending_dir(&self) -> PathBuf {
+        self.local.join(PENDING_DIR)
+    }
+
+    /// Whether the target folder exists or can be created. A removable drive that's unplugged
+    /// isn't recreated as a folder on whatever takes its letter next: its root has to be there.
+    pub fn available(&self) -> bool {
+        if self.kind == StorageKind::Removable && !self.root.ancestors().last().is_some_and(|drive| drive.exists()) {
+            return false;
+        }
+        std::fs::create_dir_all(&self.root).is_ok()
+    }
+
+    /// Copy with a few retries on a share, where a dropped SMB session is common and short-lived
+    pub fn copy(&self, source: &Path, dest: &Path) -> Result<u64, HyperZenithError> {
+        let attempts = if self.kind == StorageKind::NetworkShare { NETWORK_ATTEMPTS } else { 1 };
+        let mut attempt = 1;
+        loop {
+            match std::fs::copy(source, dest) {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) if attempt < attempts && is_transient(&e) => {
+                    println!("📦 [ARCHIVE] ⚠️ {} (attempt {}/{}), retrying...", e, attempt, attempts);
+                    std::thread::sleep(RETRY_DELAY * attempt);
+                    attempt += 1;
+                },
+                Err(e) => return Err(HyperZenithError::io_at(dest, e)),
+            }
+        }
+    }
+
+    /// Copy an artifact to `dest`. If a share or drive drops out mid-archive the artifact is
+    /// held as pending instead, and the path it actually went to is returned.
+    pub fn store(&self, source: &Path, dest: &Path) -> Result<PathBuf, HyperZenithError> {
+        match self.copy(source, dest) {
+            Ok(_) => Ok(dest.to_path_buf()),
+            Err(e) if self.root != self.local && !dest.starts_with(self.pending_dir()) => {
+                println!("📦 [ARCHIVE] ⚠️ {}, holding the artifact locally", e);
+                let pending = self.pending_dir().join(dest.file_name().ok_or("Artifact without a file name")?);
+                std::fs::create_dir_all(self.pending_dir()).map_err(|e| HyperZenithError::io_at(&self.pending_dir(), e))?;
+                std::fs::copy(source, &pending).map_err(|e| HyperZenithError::io_at(&pending, e))?;
+                Ok(pending)
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn pending(&self) -> Vec<PathBuf> {
+        std::fs::read_dir(self.pending_dir())
+            .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| crate::archive::is_artifact(p)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Move artifacts held while the target was offline into it, sidecars included.
+    /// Returns how many made it; the rest stay pending for next time.
+    pub fn flush_pending(&self) -> usize {
+        if self.root == self.local || !self.available() {
+            return 0;
+        }
+        let mut moved = 0;
+        for artifact in self.pending() {
+            let Some(name) = artifact.file_name() else { continue };
+            let dest = self.root.join(name);
+            if self.copy(&artifact, &dest).is_err() {
+                continue;
+            }
+            let sidecar = crate::archive::sidecar_path(&artifact);
+            if sidecar.exists() && self.move_sidecar(&artifact, &dest) {
+                let _ = std::fs::remove_file(&sidecar);
+            }
+            let _ = std::fs::remove_file(&artifact);
+            moved += 1;
+        }
+        // Symbol folders follow their artifacts
+        let pending_symbols = self.pending_dir().join(crate::symbols::SYMBOLS_DIR);
+        for build in std::fs::read_dir(&pending_symbols).into_iter().flatten().filter_map(|e| e.ok()) {
+            if self.move_tree(&build.path(), &self.root.join(crate::symbols::SYMBOLS_DIR).join(build.file_name())) {
+                let _ = std::fs::remove_dir_all(build.path());
+            }
+        }
+        moved
+    }
+
+    /// Copy `artifact`'s sidecar next to `dest`, with its symbols folder pointing where
+    /// `flush_pending` moves it. True if it made it.
+    fn move_sidecar(&self, artifact: &Path, dest: &Path) -> bool {
+        match crate::archive::read_metadata(artifact) {
+            Some(mut metadata) => {
+                metadata.symbols = metadata.symbols.map(|dir| match Path::new(&dir).strip_prefix(self.pending_dir()) {
+                    Ok(relative) => self.root.join(relative).display().to_string(),
+                    Err(_) => dir,
+                });
+                crate::archive::write_metadata(dest, &metadata).is_ok()
+            },
+            None => self.copy(&crate::archive::sidecar_path(artifact), &crate::archive::sidecar_path(dest)).is_ok(),
+        }
+    }
+
+    /// Copy every file under `from` to the same place under `to`, true if all made it
+    fn move_tree(&self, from: &Path, to: &Path) -> bool {
+        walkdir::WalkDir::new(from)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .all(|e| {
+                let dest = to.join(e.path().strip_prefix(from).unwrap_or(e.path()));
+                dest.parent().is_some_and(|dir| std::fs::create_dir_all(dir).is_ok()) && self.copy(e.path(), &dest).is_ok()
+            })
+    }
+
+    /// The folder to archive into for this build: the target once pending artifacts have caught
+    /// up, or the local pending folder when the target is unreachable
+    pub fn prepare(&self, channel: &BuildChannel) -> PathBuf {
+        match self.select_dir() {
+            (dir, Some(moved)) => {
+                if moved > 0 {
+                    channel.line(format!("📦 Moved {} pending artifact(s) to {}", moved, self.root.display()));
+                }
+                dir
+            },
+            (pending, None) => {
+                channel.line(format!(
+                    "⚠️ Archive {} is unavailable, keeping artifacts in {} until it's back",
+                    self.root.display(),
+                    pending.display()
+                ));
+                pending
+            },
+        }
+    }
+
+    /// `prepare` without the messages: the folder, and how many pending artifacts were flushed
+    /// into it, `None` when it's the pending folder
+    fn select_dir(&self) -> (PathBuf, Option<usize>) {
+        if self.available() {
+            return (self.root.clone(), Some(self.flush_pending()));
+        }
+        let pending = self.pending_dir();
+        let _ = std::fs::create_dir_all(&pending);
+        (pending, None)
+    }
+
+    pub fn status(&self) -> StorageStatus {
+        StorageStatus {
+            root: self.root.display().to_string(),
+            kind: self.kind,
+            available: self.available(),
+            pending: self.pending().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_unc_share() {
+        assert!(is_unc_share(r"\\nas\builds\app"));
+        assert!(is_unc_share(r"\\?\UNC\nas.local\builds"));
+        assert!(is_unc_share("//nas/builds"));
+        assert!(!is_unc_share(r"\\wsl$\Ubuntu\home\dev\app"));
+        assert!(!is_unc_share(r"\\wsl.localhost\Ubuntu\home\dev\app"));
+        assert!(!is_unc_share(r"\\?\C:\builds"));
+        assert!(!is_unc_share(r"D:\builds"));
+
+        assert!(is_transient(&std::io::Error::from_raw_os_error(64)));
+        assert!(!is_transient(&std::io::Error::from(std::io::ErrorKind::PermissionDenied)));
+    }
+
+    #[test]
+    fn test_pending_until_target_is_back() {
+        let base = std::env::temp_dir().join(format!("hz_storage_{}", std::process::id()));
+        let local = base.join("project").join("hyperzenith_builds");
+        // A file where the target's parent folder should be keeps the target from being created
+        let blocker = base.join("share");
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::write(&blocker, b"offline").unwrap();
+        let source = base.join("app-release.apk");
+        std::fs::write(&source, b"apk").unwrap();
+        let storage = ArchiveStorage { root: blocker.join("builds"), kind: StorageKind::Local, local: local.clone() };
+
+        let (dir, moved) = storage.select_dir();
+        assert_eq!((dir, moved), (local.join(PENDING_DIR), None));
+        let held = storage.store(&source, &storage.root.join("app-release_1.apk")).unwrap();
+        assert_eq!(held, local.join(PENDING_DIR).join("app-release_1.apk"));
+        let symbols = local.join(PENDING_DIR).join(crate::symbols::SYMBOLS_DIR).join("b1");
+        let metadata = crate::archive::ArtifactMetadata { symbols: Some(symbols.display().to_string()), ..Default::default() };
+        crate::archive::write_metadata(&held, &metadata).unwrap();
+        std::fs::create_dir_all(&symbols).unwrap();
+        std::fs::write(symbols.join("mapping.txt"), b"map").unwrap();
+        assert_eq!(storage.pending(), vec![held.clone()]);
+        assert_eq!(storage.flush_pending(), 0);
+
+        std::fs::remove_file(&blocker).unwrap();
+        assert_eq!(storage.select_dir(), (storage.root.clone(), Some(1)));
+        assert!(storage.root.join("app-release_1.apk").exists());
+        let flushed = crate::archive::read_metadata(&storage.root.join("app-release_1.apk")).unwrap();
+        let flushed_symbols = storage.root.join(crate::symbols::SYMBOLS_DIR).join("b1");
+        assert_eq!(flushed.symbols, Some(flushed_symbols.display().to_string()));
+        assert!(flushed_symbols.join("mapping.txt").exists());
+        assert!(storage.pending().is_empty() && !held.exists() && !symbols.exists());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+}
@@ -0,0 +1,200 @@
+use std::io::Write;
+use std::os::windows::process::CommandExt;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use serde_json::{Map, Value};
+use crate::error::HyperZenithError;
+use crate::history::BuildRecord;
+use crate::logs::LogFile;
+
+/// Takes plain text and answers with the paste's URL
+const PASTE_URL: &str = "https://paste.rs/";
+/// The end of the log is where builds fail; the whole log is in the zip
+const PASTE_LOG_LINES: usize = 1500;
+/// Shorter secrets would blank out unrelated text
+const MIN_SECRET_LEN: usize = 4;
+
+/// A zip of everything needed to look into a failed build, saved next to the project's logs
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct SupportBundle {
+    pub path: String,
+    /// Names of the files inside
+    pub files: Vec<String>,
+    pub bytes: u64,
+    /// Where the text report was pasted, when asked to upload
+    pub upload_url: Option<String>,
+}
+
+/// What goes in besides the build's own files
+pub struct SupportInfo {
+    pub app_version: String,
+    pub hardware: String,
+    pub settings: Map<String, Value>,
+}
+
+/// The log a build wrote: the first one for its platform saved after it started
+fn find_log<'a>(logs: &'a [LogFile], record: &BuildRecord) -> Option<&'a LogFile> {
+    let started = chrono::DateTime::parse_from_rfc3339(&record.started_at).ok()?;
+    logs.iter()
+        .filter(|log| log.platform.as_deref() == Some(record.platform.as_str()))
+        .filter_map(|log| Some((chrono::DateTime::parse_from_rfc3339(log.modified.as_deref()?).ok()?, log)))
+        .filter(|(modified, _)| *modified >= started)
+        .min_by_key(|(modified, _)| *modified)
+        .map(|(_, log)| log)
+}
+
+/// Replace the values of the project's secret variables wherever a build printed them
+fn redact(text: &str, secrets: &[(String, String)]) -> String {
+    secrets
+        .iter()
+        .filter(|(_, value)| value.len() >= MIN_SECRET_LEN)
+        .fold(text.to_string(), |text, (name, value)| text.replace(value.as_str(), &format!("[{} redacted]", name)))
+}
+
+fn secrets(app: &tauri::AppHandle, working_dir: &str) -> Vec<(String, String)> {
+    crate::buildenv::load(app, working_dir)
+        .into_iter()
+        .filter(|var| var.secret)
+        .filter_map(|var| {
+            let value = crate::credentials::read(&crate::credentials::target(working_dir, &var.name))?;
+            Some((var.name, value))
+        })
+        .collect()
+}
+
+fn system_report(info: &SupportInfo) -> String {
+    let distros = crate::wsl::installed_distros().map(|d| d.join(", ")).unwrap_or_else(|e| e.to_string());
+    format!(
+        "HyperZenith {}\n{}\n{}\nWSL distros: {}\n",
+        info.app_version,
+        sysinfo::System::long_os_version().unwrap_or_else(|| "Windows".to_string()),
+        info.hardware,
+        distros
+    )
+}
+
+fn pretty<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+fn doctor_report(checks: &[crate::doctor::DoctorCheck]) -> String {
+    checks.iter().map(|c| format!("[{:?}] {}: {}\n", c.status, c.title, c.detail)).collect()
+}
+
+/// Paste the report and the end of the log, returning the paste's URL
+fn paste(text: &str) -> Result<String, HyperZenithError> {
+    let mut child = Command::new("curl")
+        .args(["-sSf", "--max-time", "60", "--data-binary", "@-", PASTE_URL])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| HyperZenithError::spawn("curl", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(|e| HyperZenithError::Io(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| HyperZenithError::Io(e.to_string()))?;
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "Log upload".to_string(),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Zip the build's log and record, a doctor run, the settings without secrets and a system
+/// summary into the project's logs folder, pasting a text version too when `upload` is set
+pub fn create(app: &tauri::AppHandle, build_id: &str, info: SupportInfo, upload: bool) -> Result<SupportBundle, HyperZenithError> {
+    let record = crate::history::load(app)
+        .into_iter()
+        .find(|r| r.id == build_id)
+        .ok_or_else(|| HyperZenithError::InvalidInput(format!("No finished build with id {}", build_id)))?;
+    let secrets = secrets(app, &record.project);
+    let logs = crate::logs::list(&record.project, Some(&record.platform));
+    let log = find_log(&logs, &record)
+        .and_then(|log| std::fs::read_to_string(&log.path).ok())
+        .map(|text| redact(&text, &secrets));
+    let checks = crate::doctor::run_checks(Some(&record.project));
+
+    let system = system_report(&info);
+    let doctor = doctor_report(&checks);
+    let mut files: Vec<(&str, String)> = vec![
+        ("system.txt", system.clone()),
+        ("build.json", pretty(&record)),
+        ("doctor.json", pretty(&checks)),
+        ("settings.json", pretty(&crate::settings::clean_settings(info.settings))),
+    ];
+    if let Some(log) = &log {
+        files.push(("build.log", log.clone()));
+    }
+
+    let dir = crate::logs::logs_dir(&record.project);
+    std::fs::create_dir_all(&dir).map_err(|e| HyperZenithError::io_at(&dir, e))?;
+    let path: PathBuf = dir.join(format!("support_{}_{}.zip", &record.id[..record.id.len().min(8)], chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")));
+    let file = std::fs::File::create(&path).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in &files {
+        zip.start_file(*name, options).map_err(|e| HyperZenithError::Other(e.to_string()))?;
+        zip.write_all(content.as_bytes()).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    }
+    zip.finish().map_err(|e| HyperZenithError::Other(e.to_string()))?;
+
+    let upload_url = match upload {
+        true => {
+            let lines: Vec<&str> = log.as_deref().unwrap_or("(no log found)").lines().collect();
+            let tail = lines[lines.len().saturating_sub(PASTE_LOG_LINES)..].join("\n");
+            Some(paste(&format!("{}\n{}\n{}\n\n{}", system, pretty(&record), doctor, tail))?)
+        },
+        false => None,
+    };
+    println!("🧰 [SUPPORT] Bundle for {} saved to {}", build_id, path.display());
+    Ok(SupportBundle {
+        bytes: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+        path: path.display().to_string(),
+        files: files.iter().map(|(name, _)| name.to_string()).collect(),
+        upload_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_log() {
+        let record = BuildRecord {
+            id: "b1".to_string(),
+            project: "C:\\Dev\\App".to_string(),
+            platform: "android".to_string(),
+            build_type: "apk".to_string(),
+            variant: None,
+            started_at: "2026-10-15T10:00:00+02:00".to_string(),
+            duration_secs: 90.0,
+            outcome: crate::history::BuildOutcome::Failed,
+            cache_hit_rate: None,
+            warnings: None,
+            phases: Vec::new(),
+        };
+        let log = |name: &str, platform: &str, modified: &str| LogFile {
+            path: name.to_string(),
+            name: name.to_string(),
+            platform: Some(platform.to_string()),
+            success: None,
+            modified: Some(modified.to_string()),
+            bytes: 0,
+        };
+        let logs = vec![
+            log("android_build_fail_later.log", "android", "2026-10-15T10:30:00+02:00"),
+            log("ios_build_fail.log", "ios", "2026-10-15T10:01:00+02:00"),
+            log("android_build_fail.log", "android", "2026-10-15T10:01:30+02:00"),
+            log("android_build_success_before.log", "android", "2026-10-15T09:58:00+02:00"),
+        ];
+        assert_eq!(find_log(&logs, &record).map(|l| l.name.as_str()), Some("android_build_fail.log"));
+
+        let secrets = vec![("API_KEY".to_string(), "sk-12345".to_string()), ("PIN".to_string(), "42".to_string())];
+        assert_eq!(redact("curl -H 'key: sk-12345' took 42s", &secrets), "curl -H 'key: [API_KEY redacted]' took 42s");
+    }
+}
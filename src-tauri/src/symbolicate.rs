@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::error::HyperZenithError;
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct Symbolication {
+    /// `android` or `ios`
+    pub platform: String,
+    pub frames: usize,
+    pub resolved: usize,
+    /// The pasted text with every frame we could resolve rewritten
+    pub text: String,
+}
+
+/// One obfuscated method: R8 writes `start:end:` for the obfuscated lines and
+/// `:origStart[:origEnd]` for where they came from
+#[derive(Clone, Debug, PartialEq)]
+struct MemberMapping {
+    original: String,
+    obfuscated_lines: Option<(u32, u32)>,
+    original_lines: Option<(u32, u32)>,
+}
+
+/// The parts of an R8/ProGuard `mapping.txt` a stack trace needs
+#[derive(Default, Debug)]
+pub struct Mapping {
+    /// Obfuscated class → original class
+    classes: HashMap<String, String>,
+    /// (obfuscated class, obfuscated method) → candidates
+    methods: HashMap<(String, String), Vec<MemberMapping>>,
+    /// Obfuscated class → the source file R8 recorded for it, `MainActivity.kt`
+    source_files: HashMap<String, String>,
+}
+
+/// R8's `# {"id":"sourceFile","fileName":"MainActivity.kt"}` under a class
+fn parse_source_file(line: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(line.trim().strip_prefix('#')?.trim()).ok()?;
+    (json.get("id")?.as_str()? == "sourceFile").then(|| json.get("fileName")?.as_str().map(str::to_string))?
+}
+
+/// `1:5:void onCreate(android.os.Bundle):42:46 -> a`; fields have no parentheses and are skipped
+fn parse_member(line: &str) -> Option<(MemberMapping, String)> {
+    let (left, obfuscated) = line.trim().rsplit_once(" -> ")?;
+    let open = left.find('(')?;
+    let close = left.rfind(')')?;
+    let head = &left[..open];
+    let mut parts = head.splitn(3, ':');
+    let (obfuscated_lines, head) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(start), Some(end), Some(rest)) => match (start.parse::<u32>(), end.parse::<u32>()) {
+            (Ok(start), Ok(end)) => (Some((start, end)), rest),
+            _ => (None, head),
+        },
+        _ => (None, head),
+    };
+    let name = head.rsplit(' ').next()?;
+    let original = name.rsplit('.').next().unwrap_or(name).to_string();
+    let mut tail = left[close + 1..].split(':').filter(|p| !p.is_empty()).map(|p| p.parse::<u32>());
+    let original_lines = match (tail.next(), tail.next()) {
+        (Some(Ok(start)), Some(Ok(end))) => Some((start, end)),
+        (Some(Ok(start)), None) => Some((start, start)),
+        _ => None,
+    };
+    Some((MemberMapping { original, obfuscated_lines, original_lines }, obfuscated.trim().to_string()))
+}
+
+impl Mapping {
+    pub fn parse(text: &str) -> Self {
+        let mut mapping = Mapping::default();
+        let mut class: Option<String> = None;
+        for line in text.lines() {
+            if line.trim_start().starts_with('#') {
+                if let (Some(class), Some(file)) = (&class, parse_source_file(line)) {
+                    mapping.source_files.insert(class.clone(), file);
+                }
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !line.starts_with(char::is_whitespace) {
+                class = line.trim_end().strip_suffix(':').and_then(|l| l.split_once(" -> ")).map(|(original, obfuscated)| {
+                    mapping.classes.insert(obfuscated.to_string(), original.to_string());
+                    obfuscated.to_string()
+                });
+                continue;
+            }
+            if let (Some(class), Some((member, obfuscated))) = (&class, parse_member(line)) {
+                mapping.methods.entry((class.clone(), obfuscated)).or_default().push(member);
+            }
+        }
+        mapping
+    }
+
+    fn class(&self, obfuscated: &str) -> Option<&str> {
+        self.classes.get(obfuscated).map(String::as_str)
+    }
+
+    /// Original method name(s) and line for an obfuscated frame. Overloads R8 merged
+    /// under one name and can't be told apart come back as `a|b`.
+    fn method(&self, class: &str, method: &str, line: Option<u32>) -> Option<(String, Option<u32>)> {
+        let candidates = self.methods.get(&(class.to_string(), method.to_string()))?;
+        let in_range: Vec<&MemberMapping> = match line {
+            Some(l) => candidates.iter().filter(|m| m.obfuscated_lines.is_none_or(|(s, e)| (s..=e).contains(&l))).collect(),
+            None => candidates.iter().collect(),
+        };
+        let matches = if in_range.is_empty() { candidates.iter().collect() } else { in_range };
+        let mut names: Vec<&str> = matches.iter().map(|m| m.original.as_str()).collect();
+        names.dedup();
+        let original_line = match (matches.as_slice(), line) {
+            ([only], Some(l)) => match (only.obfuscated_lines, only.original_lines) {
+                (Some((start, _)), Some((orig_start, orig_end))) if orig_end > orig_start => Some(orig_start + l.saturating_sub(start)),
+                (_, Some((orig_start, _))) => Some(orig_start),
+                (None, None) => Some(l),
+                (Some(_), None) => None,
+            },
+            _ => None,
+        };
+        Some((names.join("|"), original_line))
+    }
+
+    /// Rewrite `at a.b.c(SourceFile:12)` frames and `a.b: message` exception lines
+    pub fn retrace(&self, stacktrace: &str) -> Symbolication {
+        let (mut frames, mut resolved) = (0, 0);
+        let lines: Vec<String> = stacktrace
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start();
+                let indent = &line[..line.len() - trimmed.len()];
+                if let Some(frame) = trimmed.strip_prefix("at ") {
+                    frames += 1;
+                    let Some((qualified, location)) = frame.split_once('(') else { return line.to_string() };
+                    let Some((class, method)) = qualified.rsplit_once('.') else { return line.to_string() };
+                    let obf_line = location.trim_end_matches(')').rsplit_once(':').and_then(|(_, l)| l.parse::<u32>().ok());
+                    let Some(original_class) = self.class(class) else { return line.to_string() };
+                    resolved += 1;
+                    let (method, original_line) = self.method(class, method, obf_line).unwrap_or((method.to_string(), obf_line));
+                    // Without R8's sourceFile the language isn't known, so no extension is made up
+                    let file = match self.source_files.get(class) {
+                        Some(file) => file.clone(),
+                        None => original_class.rsplit('.').next().unwrap_or(original_class).split('$').next().unwrap_or_default().to_string(),
+                    };
+                    let location = match original_line {
+                        Some(l) => format!("{}:{}", file, l),
+                        None => file,
+                    };
+                    return format!("{}at {}.{}({})", indent, original_class, method, location);
+                }
+                // `Caused by: a.b: message` or a bare `a.b: message` header
+                let (prefix, rest) = match trimmed.strip_prefix("Caused by: ") {
+                    Some(rest) => ("Caused by: ", rest),
+                    None => ("", trimmed),
+                };
+                let class = rest.split(':').next().unwrap_or(rest).trim();
+                match self.class(class) {
+                    Some(original) => format!("{}{}{}{}", indent, prefix, original, &rest[class.len()..]),
+                    None => line.to_string(),
+                }
+            })
+            .collect();
+        Symbolication { platform: "android".to_string(), frames, resolved, text: lines.join("\n") }
+    }
+}
+
+/// `3   MyApp   0x0000000100a8c2f4 0x100a80000 + 49908` in an Apple crash report
+#[derive(Clone, Debug, PartialEq)]
+pub struct IosFrame {
+    pub line: usize,
+    pub binary: String,
+    pub address: String,
+    pub load_address: String,
+}
+
+pub fn parse_ios_frames(report: &str) -> Vec<IosFrame> {
+    report
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let (index, binary, address, load_address) = (parts.first()?, parts.get(1)?, parts.get(2)?, parts.get(3)?);
+            let is_hex = |s: &str| s.starts_with("0x") && s.len() > 2 && s[2..].chars().all(|c| c.is_ascii_hexdigit());
+            (index.parse::<u32>().is_ok() && is_hex(address) && is_hex(load_address)).then(|| IosFrame {
+                line: i,
+                binary: binary.to_string(),
+                address: address.to_string(),
+                load_address: load_address.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// atos architecture from the report's `Code Type:` line, arm64 unless it says otherwise
+pub fn ios_arch(report: &str) -> &'static str {
+    let code_type = report.lines().find_map(|l| l.trim().strip_prefix("Code Type:")).unwrap_or_default();
+    if code_type.contains("X86-64") { "x86_64" } else { "arm64" }
+}
+
+pub fn atos_cmd(dwarf: &str, arch: &str, load_address: &str, addresses: &[&str]) -> String {
+    format!(
+        "atos -arch {} -o {} -l {} {}",
+        arch,
+        crate::sh_quote_path(dwarf),
+        crate::sh_quote(load_address),
+        addresses.iter().map(|a| crate::sh_quote(a)).collect::<Vec<_>>().join(" ")
+    )
+}
+
+/// Frames of `binary` rewritten with the symbols atos printed, one line per address
+pub fn apply_atos(report: &str, frames: &[&IosFrame], symbols: &str) -> (String, usize) {
+    let mut lines: Vec<String> = report.lines().map(str::to_string).collect();
+    let mut resolved = 0;
+    for (frame, symbol) in frames.iter().zip(symbols.lines()) {
+        let symbol = symbol.trim();
+        // atos echoes the address back when it has nothing better
+        if symbol.is_empty() || symbol.starts_with("0x") {
+            continue;
+        }
+        if let Some(line) = lines.get_mut(frame.line) {
+            if let Some(at) = line.find(&frame.address) {
+                *line = format!("{}{} {}", &line[..at], frame.address, symbol);
+                resolved += 1;
+            }
+        }
+    }
+    (lines.join("\n"), resolved)
+}
+
+/// dSYM bundles in a folder, by the binary they describe (`MyApp.app.dSYM` → `MyApp`)
+pub fn find_dsyms(dir: &Path) -> HashMap<String, PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_dir() && e.file_name().to_string_lossy().ends_with(".dSYM"))
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            let binary = name.trim_end_matches(".dSYM").trim_end_matches(".app").trim_end_matches(".framework").to_string();
+            e.path().join("Contents").join("Resources").join("DWARF").join(&binary).is_file().then(|| (binary, e.into_path()))
+        })
+        .collect()
+}
+
+/// De-obfuscate an Android stack trace with the build's archived `mapping.txt`
+pub fn android(symbols_dir: &Path, stacktrace: &str) -> Result<Symbolication, HyperZenithError> {
+    let path = symbols_dir.join("mapping.txt");
+    let text = std::fs::read_to_string(&path).map_err(|e| HyperZenithError::io_at(&path, e))?;
+    Ok(Mapping::parse(&text).retrace(stacktrace))
+}
+
+/// Symbolicate an Apple crash report: the dSYMs go to the Mac, where atos resolves each
+/// binary's frames
+pub fn ios(config: &crate::ios::MacConfig, dsym_dir: &Path, build_id: &str, report: &str) -> Result<Symbolication, HyperZenithError> {
+    let frames = parse_ios_frames(report);
+    let dsyms = find_dsyms(dsym_dir);
+    if dsyms.is_empty() {
+        return Err(HyperZenithError::NotFound { path: format!("{} (*.dSYM)", dsym_dir.display()) });
+    }
+
+    let sess = crate::ios::create_session(config)?;
+    let remote = format!("{}/dsyms/{}", crate::ios::DETACHED_DIR, build_id);
+    let plan = crate::sftp::plan(&sess, dsym_dir, &remote, false)?;
+    crate::sftp::apply(&sess, &plan, &remote, &crate::ios::SyncSettings::default())?;
+
+    let arch = ios_arch(report);
+    let symbolicated = (|| {
+        let mut text = report.to_string();
+        let mut resolved = 0;
+        for (binary, dsym) in &dsyms {
+            let binary_frames: Vec<&IosFrame> = frames.iter().filter(|f| &f.binary == binary).collect();
+            let Some(load_address) = binary_frames.first().map(|f| f.load_address.clone()) else { continue };
+            let relative = dsym.strip_prefix(dsym_dir).unwrap_or(dsym).to_string_lossy().replace('\\', "/");
+            let dwarf = format!("{}/{}/Contents/Resources/DWARF/{}", remote, relative, binary);
+            let addresses: Vec<&str> = binary_frames.iter().map(|f| f.address.as_str()).collect();
+            let symbols = crate::ios::run_remote_capture(&sess, &atos_cmd(&dwarf, arch, &load_address, &addresses))?;
+            let (rewritten, count) = apply_atos(&text, &binary_frames, &symbols);
+            text = rewritten;
+            resolved += count;
+        }
+        Ok(Symbolication { platform: "ios".to_string(), frames: frames.len(), resolved, text })
+    })();
+    // dSYMs run to hundreds of MB; they're uploaded again for the next crash
+    let _ = crate::ios::run_remote_capture(&sess, &format!("rm -rf {}", crate::sh_quote_path(&remote)));
+    symbolicated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retrace() {
+        let mapping = Mapping::parse(
+            "# compiler: R8\n\
+             com.example.app.MainActivity -> a.a:\n\
+             # {\"id\":\"sourceFile\",\"fileName\":\"MainActivity.kt\"}\n\
+             \x20   android.os.Handler handler -> a\n\
+             \x20   1:3:void onCreate(android.os.Bundle):42:44 -> b\n\
+             \x20   4:4:void loadFeed():60 -> b\n\
+             com.example.app.FeedException -> a.b:\n\
+             \x20   1:1:void report():12 -> a\n",
+        );
+        let trace = "a.b: Feed failed\n\tat a.a.b(SourceFile:2)\n\tat a.a.b(SourceFile:4)\n\tat a.b.a(SourceFile:1)\n\tat android.os.Looper.loop(Looper.java:223)";
+        let result = mapping.retrace(trace);
+        assert_eq!(
+            result.text,
+            "com.example.app.FeedException: Feed failed\n\
+             \tat com.example.app.MainActivity.onCreate(MainActivity.kt:43)\n\
+             \tat com.example.app.MainActivity.loadFeed(MainActivity.kt:60)\n\
+             \tat com.example.app.FeedException.report(FeedException:12)\n\
+             \tat android.os.Looper.loop(Looper.java:223)"
+        );
+        assert_eq!((result.frames, result.resolved), (4, 3));
+    }
+
+    #[test]
+    fn test_ios_frames() {
+        let report = "Code Type:       ARM-64 (Native)\n\
+            Thread 0 Crashed:\n\
+            0   libsystem_kernel.dylib        \t0x00000001b2d5e1f0 0x1b2d39000 + 152048\n\
+            1   MyApp                         \t0x0000000100a8c2f4 0x100a80000 + 49908\n";
+        let frames = parse_ios_frames(report);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[1], IosFrame { line: 3, binary: "MyApp".to_string(), address: "0x0000000100a8c2f4".to_string(), load_address: "0x100a80000".to_string() });
+        assert_eq!(ios_arch(report), "arm64");
+        assert_eq!(
+            atos_cmd("~/.hyperzenith/dsyms/b1/MyApp.app.dSYM/Contents/Resources/DWARF/MyApp", "arm64", "0x100a80000", &["0x0000000100a8c2f4"]),
+            "atos -arch arm64 -o ~/'.hyperzenith/dsyms/b1/MyApp.app.dSYM/Contents/Resources/DWARF/MyApp' -l '0x100a80000' '0x0000000100a8c2f4'"
+        );
+
+        let mine: Vec<&IosFrame> = frames.iter().filter(|f| f.binary == "MyApp").collect();
+        let (text, resolved) = apply_atos(report, &mine, "-[FeedViewController reload] (in MyApp) (FeedViewController.m:88)\n");
+        assert_eq!(resolved, 1);
+        assert!(text.ends_with("0x0000000100a8c2f4 -[FeedViewController reload] (in MyApp) (FeedViewController.m:88)"));
+    }
+}
@@ -0,0 +1,114 @@
+use std::path::{Path, PathBuf};
+use crate::error::HyperZenithError;
+use crate::gradle::TaskOutcome;
+
+/// Per-build folders of crash symbols inside the archive
+pub const SYMBOLS_DIR: &str = "symbols";
+/// Unstripped libraries as packaged; `stripped_native_libs` holds what actually ships
+const NATIVE_LIBS_DIR: &str = "merged_native_libs";
+
+/// `<archive>/symbols/<build id>`
+pub fn symbols_dir(builds_dir: &Path, build_id: &str) -> PathBuf {
+    builds_dir.join(SYMBOLS_DIR).join(build_id)
+}
+
+/// Where a symbol file goes inside the build's symbols folder. R8 mappings become
+/// `mapping.txt`, libraries `native/<abi>/<lib>.so`; modules other than `app` get their own folder.
+pub fn relative_dest(module: &str, path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?;
+    let base = if module == "app" { PathBuf::new() } else { PathBuf::from(module) };
+    if name == "mapping.txt" {
+        return Some(base.join(name));
+    }
+    let is_library = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("so"));
+    let abi = path.parent()?.file_name()?;
+    is_library.then(|| base.join("native").join(abi).join(name))
+}
+
+fn files_under(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+}
+
+/// Symbols are only kept for builds that ship: release variants, and any variant whose log shows
+/// an R8/ProGuard `minify` task. Debug builds keep their symbols in the APK anyway.
+pub fn wanted(variant: &str, outcomes: &[(String, TaskOutcome)]) -> bool {
+    let minified = outcomes.iter().any(|(path, outcome)| {
+        path.rsplit(':').next().is_some_and(|name| name.starts_with("minify")) && *outcome != TaskOutcome::Skipped
+    });
+    minified || crate::archive::camel_words(variant).iter().any(|word| word == "release")
+}
+
+/// R8 mappings and unstripped native libraries the variant's build left in each module,
+/// as (source, destination relative to the symbols folder)
+pub fn find(android_dir: &Path, variant: &str) -> Vec<(PathBuf, PathBuf)> {
+    let Ok(modules) = std::fs::read_dir(android_dir) else { return Vec::new() };
+    let mut found = Vec::new();
+    for module in modules.filter_map(|e| e.ok()).map(|e| e.path()).filter(|p| p.join("build").is_dir()) {
+        let name = module.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let build = module.join("build");
+        let candidates = files_under(&build.join("outputs").join("mapping"))
+            .filter(|p| p.file_name().is_some_and(|n| n == "mapping.txt"))
+            .chain(files_under(&build.join("intermediates").join(NATIVE_LIBS_DIR)));
+        for path in candidates {
+            let relative = path.strip_prefix(&build).unwrap_or(&path);
+            if !crate::archive::matches_variant(relative, variant) {
+                continue;
+            }
+            if let Some(dest) = relative_dest(&name, &path) {
+                found.push((path, dest));
+            }
+        }
+    }
+    found
+}
+
+/// Copy a build's symbol files into `<archive>/symbols/<build id>`, returning the folder
+pub fn archive(storage: &crate::storage::ArchiveStorage, builds_dir: &Path, build_id: &str, files: &[(PathBuf, PathBuf)]) -> Result<PathBuf, HyperZenithError> {
+    let dir = symbols_dir(builds_dir, build_id);
+    for (source, relative) in files {
+        let dest = dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| HyperZenithError::io_at(parent, e))?;
+        }
+        storage.copy(source, &dest)?;
+    }
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_dest() {
+        assert_eq!(
+            relative_dest("app", Path::new("build/outputs/mapping/release/mapping.txt")),
+            Some(PathBuf::from("mapping.txt"))
+        );
+        assert_eq!(
+            relative_dest("app", Path::new("build/intermediates/merged_native_libs/release/out/lib/arm64-v8a/libhermes.so")),
+            Some(Path::new("native").join("arm64-v8a").join("libhermes.so"))
+        );
+        assert_eq!(
+            relative_dest("wear", Path::new("build/outputs/mapping/release/mapping.txt")),
+            Some(Path::new("wear").join("mapping.txt"))
+        );
+        assert_eq!(relative_dest("app", Path::new("build/outputs/mapping/release/seeds.txt")), None);
+    }
+
+    #[test]
+    fn test_wanted() {
+        assert!(wanted("release", &[]));
+        assert!(wanted("prodRelease", &[]));
+        assert!(!wanted("debug", &[]));
+        let minified = vec![(":app:minifyStagingWithR8".to_string(), TaskOutcome::UpToDate)];
+        assert!(wanted("staging", &minified));
+        let skipped = vec![(":app:minifyStagingWithR8".to_string(), TaskOutcome::Skipped)];
+        assert!(!wanted("staging", &skipped));
+    }
+}
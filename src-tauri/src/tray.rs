@@ -0,0 +1,159 @@
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{Listener, Manager};
+use crate::state::AppState;
+
+const TRAY_ID: &str = "main";
+const ICON_SIZE: u32 = 32;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TrayStatus {
+    Idle,
+    Building,
+    Success,
+    Failure,
+}
+
+impl TrayStatus {
+    fn tooltip(self) -> &'static str {
+        match self {
+            TrayStatus::Idle => "HyperZenith - Idle",
+            TrayStatus::Building => "HyperZenith - Building...",
+            TrayStatus::Success => "HyperZenith - Build succeeded",
+            TrayStatus::Failure => "HyperZenith - Build failed",
+        }
+    }
+
+    /// Status dot colour, `None` keeps the app icon
+    fn color(self) -> Option<[u8; 3]> {
+        match self {
+            TrayStatus::Idle => None,
+            TrayStatus::Building => Some([245, 158, 11]),
+            TrayStatus::Success => Some([16, 185, 129]),
+            TrayStatus::Failure => Some([239, 68, 68]),
+        }
+    }
+
+    fn from_build_state(state: &str) -> Self {
+        match state {
+            "started" => TrayStatus::Building,
+            "finished" => TrayStatus::Success,
+            "failed" | "aborted" => TrayStatus::Failure,
+            _ => TrayStatus::Idle,
+        }
+    }
+}
+
+/// A filled circle in the given colour on a transparent background
+fn status_dot(rgb: [u8; 3]) -> Image<'static> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let radius = ICON_SIZE as f32 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let dist = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let alpha = if dist <= radius { 255 } else { 0 };
+            rgba.extend_from_slice(&[rgb[0], rgb[1], rgb[2], alpha]);
+        }
+    }
+    Image::new_owned(rgba, ICON_SIZE, ICON_SIZE)
+}
+
+fn set_status(app: &tauri::AppHandle, status: TrayStatus) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    let icon = match status.color() {
+        Some(rgb) => Some(status_dot(rgb)),
+        None => app.default_window_icon().cloned(),
+    };
+    let _ = tray.set_icon(icon);
+    let _ = tray.set_tooltip(Some(status.tooltip()));
+}
+
+fn show_main_window(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+fn on_menu(app: &tauri::AppHandle, id: &str) {
+    let state = app.state::<AppState>();
+    let last = state.last_build.lock().ok().and_then(|l| l.clone());
+    match id {
+        "build_last" => match last {
+            Some(last) => {
+                println!("🖥️ [TRAY] Rebuilding {}", last.working_dir);
+                crate::start_android_build(app, &state, last.working_dir, last.options, last.verbosity);
+            },
+            None => println!("🖥️ [TRAY] No previous build to repeat"),
+        },
+        "abort" => {
+            state.builds.abort_all();
+        },
+        "open_archive" => {
+            if let Some(last) = last {
+                if let Err(e) = crate::open_build_archive(last.working_dir, last.options.custom_path) {
+                    println!("🖥️ [TRAY] ❌ {}", e);
+                }
+            }
+        },
+        "show" => show_main_window(app),
+        "quit" => {
+            state.builds.abort_all();
+            app.exit(0);
+        },
+        _ => {},
+    }
+}
+
+/// Create the tray icon and keep it in sync with every build's lifecycle
+pub fn init(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let menu = Menu::with_items(app, &[
+        &MenuItem::with_id(app, "build_last", "Build last project", true, None::<&str>)?,
+        &MenuItem::with_id(app, "abort", "Abort", true, None::<&str>)?,
+        &MenuItem::with_id(app, "open_archive", "Open archive", true, None::<&str>)?,
+        &PredefinedMenuItem::separator(app)?,
+        &MenuItem::with_id(app, "show", "Show HyperZenith", true, None::<&str>)?,
+        &MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?,
+    ])?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(TrayStatus::Idle.tooltip())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| on_menu(app, event.id().as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+
+    let handle = app.clone();
+    app.listen_any("build-state", move |event| {
+        let payload: serde_json::Value = serde_json::from_str(event.payload()).unwrap_or_default();
+        let status = TrayStatus::from_build_state(payload["state"].as_str().unwrap_or(""));
+        // Another build may still be running when one ends
+        let still_building = handle.state::<AppState>().builds.active().iter().any(|b| b.platform != "prewarm");
+        set_status(&handle, if status != TrayStatus::Building && still_building { TrayStatus::Building } else { status });
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_mapping() {
+        assert_eq!(TrayStatus::from_build_state("started"), TrayStatus::Building);
+        assert_eq!(TrayStatus::from_build_state("aborted"), TrayStatus::Failure);
+        assert!(TrayStatus::Idle.color().is_none());
+        assert_eq!(status_dot([1, 2, 3]).rgba().len(), (ICON_SIZE * ICON_SIZE * 4) as usize);
+    }
+}
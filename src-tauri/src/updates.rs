@@ -0,0 +1,210 @@
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::arch::HostArch;
+use crate::error::HyperZenithError;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/MrHickaru/hyperzenith/releases/latest";
+/// Where this repo's release assets live, the only place an installer is downloaded from
+const RELEASE_DOWNLOAD_PREFIX: &str = "https://github.com/MrHickaru/hyperzenith/releases/download/";
+/// Common name on the code-signing certificate release installers are signed with
+const INSTALLER_PUBLISHER: &str = "MrHickaru";
+
+#[derive(serde::Serialize, Clone, Debug, PartialEq)]
+pub struct UpdateInfo {
+    pub current: String,
+    pub latest: String,
+    pub available: bool,
+    /// Release notes (markdown)
+    pub notes: String,
+    pub release_url: String,
+    /// The release's Windows installer for this machine's architecture, NSIS preferred over MSI
+    pub installer_url: Option<String>,
+    pub installer_name: Option<String>,
+}
+
+/// `v1.4.6`, `1.4.6` or `1.5.0-beta.2` as comparable numbers. A pre-release sorts before its release.
+fn parse_version(tag: &str) -> Option<(u64, u64, u64, bool)> {
+    let tag = tag.trim().trim_start_matches('v');
+    let (core, pre) = match tag.split_once('-') {
+        Some((core, _)) => (core, true),
+        None => (tag, false),
+    };
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch, !pre))
+}
+
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// How well an installer suits the host: its own build, then one that doesn't say, then on
+/// ARM the x64 build Windows can emulate. `None` for an ARM build on an x64 machine.
+fn arch_rank(name: &str, arch: HostArch) -> Option<u8> {
+    let tagged = |arch: HostArch| arch.asset_tags().iter().any(|tag| name.contains(tag));
+    let other = if arch == HostArch::X64 { HostArch::Arm64 } else { HostArch::X64 };
+    if tagged(arch) {
+        Some(0)
+    } else if !tagged(other) {
+        Some(1)
+    } else {
+        (arch == HostArch::Arm64).then_some(2)
+    }
+}
+
+/// Read the GitHub "latest release" response
+pub fn parse_release(json: &str, current: &str, arch: HostArch) -> Result<UpdateInfo, HyperZenithError> {
+    let release: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("Unexpected release data: {}", e))?;
+    let latest = release["tag_name"].as_str().ok_or("The latest release has no tag")?.trim_start_matches('v').to_string();
+    let assets = release["assets"].as_array().cloned().unwrap_or_default();
+    let installer = assets.iter()
+        .filter_map(|a| {
+            let name = a["name"].as_str()?.to_lowercase();
+            let kind = ["-setup.exe", ".msi"].iter().position(|suffix| name.ends_with(suffix))?;
+            Some(((arch_rank(&name, arch)?, kind), a))
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, a)| a);
+    Ok(UpdateInfo {
+        current: current.to_string(),
+        available: is_newer(&latest, current),
+        latest,
+        notes: release["body"].as_str().unwrap_or_default().to_string(),
+        release_url: release["html_url"].as_str().unwrap_or_default().to_string(),
+        installer_url: installer.and_then(|a| a["browser_download_url"].as_str()).map(str::to_string),
+        installer_name: installer.and_then(|a| a["name"].as_str()).map(str::to_string),
+    })
+}
+
+/// `curl.exe` ships with Windows 10+, which saves bundling a TLS stack just for this
+fn curl(args: &[&str]) -> Result<Vec<u8>, HyperZenithError> {
+    let output = Command::new("curl")
+        .args(["-sSfL", "--max-time", "300", "-H", "User-Agent: HyperZenith", "-H", "Accept: application/vnd.github+json"])
+        .args(args)
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("curl", e))?;
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "Update download".to_string(),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+pub fn check(current: &str) -> Result<UpdateInfo, HyperZenithError> {
+    let json = curl(&[LATEST_RELEASE_URL])?;
+    parse_release(&String::from_utf8_lossy(&json), current, crate::arch::host())
+}
+
+/// The installer to download, if the release offers `installer_name` from this repo's releases
+fn installer_url(info: &UpdateInfo, installer_name: &str) -> Result<String, HyperZenithError> {
+    let url = info.installer_url.as_deref()
+        .filter(|_| info.installer_name.as_deref() == Some(installer_name))
+        .ok_or_else(|| HyperZenithError::InvalidInput(format!("{} is not the installer of the latest release", installer_name)))?;
+    if !url.starts_with(RELEASE_DOWNLOAD_PREFIX) {
+        return Err(HyperZenithError::InvalidInput(format!("Refusing to download an update from {}", url)));
+    }
+    Ok(url.to_string())
+}
+
+/// Check `Get-AuthenticodeSignature` output (status, then signer subject, one per line):
+/// the signature must be valid and made with the release certificate
+fn check_signature(output: &str) -> Result<(), HyperZenithError> {
+    let mut lines = output.lines().map(str::trim);
+    let status = lines.next().unwrap_or_default();
+    let subject = lines.next().unwrap_or_default();
+    if status != "Valid" {
+        return Err(HyperZenithError::InvalidInput(format!("The installer's signature is not valid ({}), not running it", if status.is_empty() { "unsigned" } else { status })));
+    }
+    let signer = subject.split(',').map(str::trim).find_map(|part| part.strip_prefix("CN=")).unwrap_or_default();
+    if !signer.trim_matches('"').eq_ignore_ascii_case(INSTALLER_PUBLISHER) {
+        return Err(HyperZenithError::InvalidInput(format!("The installer is signed by '{}', not {}, not running it", signer, INSTALLER_PUBLISHER)));
+    }
+    Ok(())
+}
+
+fn verify_signature(path: &Path) -> Result<(), HyperZenithError> {
+    let script = format!(
+        "$s = Get-AuthenticodeSignature -LiteralPath {}; Write-Output \"$($s.Status)\"; Write-Output \"$($s.SignerCertificate.Subject)\"",
+        crate::ps_quote(&path.to_string_lossy())
+    );
+    check_signature(&crate::run_powershell(&script)?)
+}
+
+/// Download the installer into the temp folder and start it. The caller exits the app right
+/// after so the installer can replace the running executable. The release is looked up again
+/// here, the frontend only names the installer it showed, and nothing runs unless it carries a
+/// valid signature from the release certificate.
+pub fn download_and_launch(current: &str, installer_name: &str) -> Result<PathBuf, HyperZenithError> {
+    let url = installer_url(&check(current)?, installer_name)?;
+    // Only the file name, never a path from the release data
+    let file_name = std::path::Path::new(installer_name).file_name().ok_or("Invalid installer name")?;
+    let target = std::env::temp_dir().join(file_name);
+    curl(&["-o", &target.to_string_lossy(), &url])?;
+    if let Err(e) = verify_signature(&target) {
+        let _ = std::fs::remove_file(&target);
+        return Err(e);
+    }
+
+    let mut cmd = if target.extension().is_some_and(|e| e.eq_ignore_ascii_case("msi")) {
+        let mut cmd = Command::new("msiexec");
+        cmd.arg("/i").arg(&target);
+        cmd
+    } else {
+        Command::new(&target)
+    };
+    cmd.spawn().map_err(|e| HyperZenithError::spawn(&target.display().to_string(), e))?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release() {
+        let json = r#"{"tag_name":"v1.5.0","html_url":"https://github.com/MrHickaru/hyperzenith/releases/tag/v1.5.0","body":"- Faster syncs",
+            "assets":[
+                {"name":"HyperZenith_1.5.0_x64_en-US.msi","browser_download_url":"https://github.com/MrHickaru/hyperzenith/releases/download/v1.5.0/HyperZenith_1.5.0_x64_en-US.msi"},
+                {"name":"HyperZenith_1.5.0_x64-setup.exe","browser_download_url":"https://github.com/MrHickaru/hyperzenith/releases/download/v1.5.0/HyperZenith_1.5.0_x64-setup.exe"}
+            ]}"#;
+        let info = parse_release(json, "1.4.6", HostArch::X64).unwrap();
+        assert!(info.available);
+        assert_eq!(info.latest, "1.5.0");
+        assert_eq!(info.installer_name.as_deref(), Some("HyperZenith_1.5.0_x64-setup.exe"));
+        assert!(!parse_release(json, "1.5.0", HostArch::X64).unwrap().available);
+        // No ARM build yet: ARM machines get the x64 one, which Windows emulates
+        assert_eq!(parse_release(json, "1.4.6", HostArch::Arm64).unwrap().installer_name.as_deref(), Some("HyperZenith_1.5.0_x64-setup.exe"));
+
+        let both = json.replace("\"assets\":[", r#""assets":[{"name":"HyperZenith_1.5.0_arm64-setup.exe","browser_download_url":"https://github.com/MrHickaru/hyperzenith/releases/download/v1.5.0/HyperZenith_1.5.0_arm64-setup.exe"},"#);
+        assert_eq!(parse_release(&both, "1.4.6", HostArch::Arm64).unwrap().installer_name.as_deref(), Some("HyperZenith_1.5.0_arm64-setup.exe"));
+        assert_eq!(parse_release(&both, "1.4.6", HostArch::X64).unwrap().installer_name.as_deref(), Some("HyperZenith_1.5.0_x64-setup.exe"));
+
+        assert!(is_newer("1.10.0", "1.9.3"));
+        assert!(is_newer("1.5.0", "1.5.0-beta.2"));
+        assert!(!is_newer("1.5.0-beta.2", "1.5.0"));
+        assert!(!is_newer("nightly", "1.4.6"));
+
+        assert_eq!(installer_url(&info, "HyperZenith_1.5.0_x64-setup.exe").unwrap(), "https://github.com/MrHickaru/hyperzenith/releases/download/v1.5.0/HyperZenith_1.5.0_x64-setup.exe");
+        assert!(installer_url(&info, "HyperZenith_1.5.0_x64_en-US.msi").is_err());
+        let elsewhere = parse_release(&json.replace("https://github.com/MrHickaru/hyperzenith/releases/download/", "https://github.com/someone/fork/releases/download/"), "1.4.6", HostArch::X64).unwrap();
+        assert!(installer_url(&elsewhere, "HyperZenith_1.5.0_x64-setup.exe").is_err());
+    }
+
+    #[test]
+    fn test_check_signature() {
+        assert!(check_signature("Valid\nCN=MrHickaru, O=MrHickaru, C=US\n").is_ok());
+        assert!(check_signature("Valid\nCN=Someone Else, O=Evil\n").is_err());
+        assert!(check_signature("HashMismatch\nCN=MrHickaru\n").is_err());
+        assert!(check_signature("NotSigned\n\n").is_err());
+        assert!(check_signature("").is_err());
+    }
+}
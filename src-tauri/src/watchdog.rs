@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// When a build that has gone quiet counts as stuck
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct StallPolicy {
+    /// Warn after this many minutes without output, 0 to never warn
+    pub warn_minutes: u64,
+    /// Abort after this many minutes without output, 0 to let it sit
+    pub abort_minutes: u64,
+}
+
+impl Default for StallPolicy {
+    fn default() -> Self {
+        StallPolicy { warn_minutes: 10, abort_minutes: 0 }
+    }
+}
+
+/// Sent on `build-stalled` (and `build-stalled-{id}`)
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct BuildStalled {
+    pub build_id: String,
+    pub silent_secs: u64,
+    /// The last thing the build printed, usually the task it's stuck in
+    pub last_line: Option<String>,
+    /// The build is being aborted rather than just warned about
+    pub aborting: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Verdict {
+    Warn,
+    Abort,
+}
+
+/// Warns once per quiet spell, so a build that stays stuck isn't reported every poll
+#[derive(Default)]
+struct Watchdog {
+    warned: HashSet<String>,
+}
+
+impl Watchdog {
+    fn observe(&mut self, id: &str, silent: Duration, policy: &StallPolicy) -> Option<Verdict> {
+        let past = |minutes: u64| minutes > 0 && silent >= Duration::from_secs(minutes * 60);
+        if past(policy.abort_minutes) {
+            self.warned.remove(id);
+            return Some(Verdict::Abort);
+        }
+        if !past(policy.warn_minutes) {
+            // Output resumed, a later stall is news again
+            self.warned.remove(id);
+            return None;
+        }
+        self.warned.insert(id.to_string()).then_some(Verdict::Warn)
+    }
+
+    /// Forget builds that have ended
+    fn retain(&mut self, running: &[String]) {
+        self.warned.retain(|id| running.contains(id));
+    }
+}
+
+/// A hung Kotlin compile daemon outlives the build it hung, and would hang the next one too.
+/// Only the project's own daemons and sessions are stopped, other builds in the distro keep theirs.
+async fn stop_kotlin_daemon(project: &str) {
+    let distro = crate::wsl::project_distro(project);
+    let android_dir = format!("{}/android", crate::windows_to_wsl_path(project));
+    let _ = crate::process::wsl_command_in(distro.as_deref(), &crate::gradle::stop_daemons_script(&android_dir)).output().await;
+}
+
+/// Check running builds for silence for the lifetime of the app
+pub fn spawn_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watchdog = Watchdog::default();
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            let policy = state.stall_policy.lock().map(|p| *p).unwrap_or_default();
+            let silences = state.builds.silences();
+            watchdog.retain(&silences.iter().map(|s| s.info.id.clone()).collect::<Vec<_>>());
+
+            for silence in silences {
+                let Some(verdict) = watchdog.observe(&silence.info.id, silence.silent, &policy) else { continue };
+                let stalled = BuildStalled {
+                    build_id: silence.info.id.clone(),
+                    silent_secs: silence.silent.as_secs(),
+                    last_line: silence.last_line,
+                    aborting: verdict == Verdict::Abort,
+                };
+                println!("⏳ [WATCHDOG] Build {} silent for {}s{}", stalled.build_id, stalled.silent_secs, if stalled.aborting { ", aborting" } else { "" });
+                let _ = app.emit(&format!("build-stalled-{}", stalled.build_id), &stalled);
+                let _ = app.emit("build-stalled", &stalled);
+                if verdict == Verdict::Abort {
+                    state.builds.abort(&silence.info.id);
+                    if silence.info.platform == "android" {
+                        stop_kotlin_daemon(&silence.info.project).await;
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog() {
+        let policy = StallPolicy { warn_minutes: 5, abort_minutes: 20 };
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        let mut watchdog = Watchdog::default();
+
+        assert_eq!(watchdog.observe("a", minutes(1), &policy), None);
+        assert_eq!(watchdog.observe("a", minutes(6), &policy), Some(Verdict::Warn));
+        assert_eq!(watchdog.observe("a", minutes(7), &policy), None);
+        assert_eq!(watchdog.observe("a", minutes(20), &policy), Some(Verdict::Abort));
+
+        // Output in between makes the next stall worth a warning again
+        assert_eq!(watchdog.observe("b", minutes(6), &policy), Some(Verdict::Warn));
+        assert_eq!(watchdog.observe("b", Duration::from_secs(3), &policy), None);
+        assert_eq!(watchdog.observe("b", minutes(5), &policy), Some(Verdict::Warn));
+
+        let off = StallPolicy { warn_minutes: 0, abort_minutes: 0 };
+        assert_eq!(watchdog.observe("c", minutes(600), &off), None);
+        watchdog.retain(&[]);
+        assert!(watchdog.warned.is_empty());
+    }
+}
@@ -0,0 +1,410 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use std::process::Command;
+use std::os::windows::process::CommandExt;
+use crate::error::HyperZenithError;
+
+#[derive(serde::Serialize, Clone)]
+pub struct WslDisk {
+    pub distro: String,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct WslDiskReport {
+    pub disks: Vec<WslDisk>,
+    pub gradle_usage: Vec<(String, u64)>, // (path inside WSL, bytes)
+    pub gradle_total_bytes: u64,
+}
+
+#[derive(serde::Serialize, Clone)]
+pub struct CompactionResult {
+    pub path: String,
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+    pub method: String,
+    /// What came of it, e.g. "No space reclaimed"
+    pub message: String,
+}
+
+/// A distro running when WSL is about to be purged, and what stopping it would take down
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+pub struct RunningDistro {
+    pub name: String,
+    /// Where plain `wsl` commands, and so builds, run
+    pub default: bool,
+    /// Resident memory of the distro's processes; WSL 2 distros share one VM, so this is the
+    /// distro's share of vmmem rather than something Windows can see directly
+    pub memory_bytes: Option<u64>,
+    /// Docker Desktop's own distros, whose containers stop with them
+    pub docker: bool,
+}
+
+/// Sent back by `purge_wsl_impact` for the user to confirm before anything is stopped
+#[derive(serde::Serialize, Clone, Debug)]
+pub struct PurgeImpact {
+    pub distros: Vec<RunningDistro>,
+    pub vmmem_bytes: Option<u64>,
+    /// Builds that would fail if WSL went away under them
+    pub active_builds: usize,
+}
+
+/// wsl.exe writes its own messages as UTF-16 unless `WSL_UTF8` is set, which older releases ignore
+fn decode_wsl_output(bytes: &[u8]) -> String {
+    // ASCII text in UTF-16LE has a zero every second byte, starting with the first character's
+    if bytes.starts_with(&[0xff, 0xfe]) || bytes.get(1) == Some(&0) {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        String::from_utf16_lossy(&units).trim_start_matches('\u{feff}').to_string()
+    } else {
+        String::from_utf8_lossy(bytes).to_string()
+    }
+}
+
+/// `(name, default)` for each running distro in `wsl --list --verbose` output
+fn parse_running_distros(output: &str) -> Vec<(String, bool)> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let default = line.trim_start().starts_with('*');
+            let mut fields = line.trim_start().trim_start_matches('*').split_whitespace();
+            let (name, state) = (fields.next()?, fields.next()?);
+            state.eq_ignore_ascii_case("running").then(|| (name.to_string(), default))
+        })
+        .collect()
+}
+
+fn wsl_exe(args: &[&str]) -> Result<Vec<u8>, HyperZenithError> {
+    let output = Command::new("wsl")
+        .args(args)
+        .env("WSL_UTF8", "1")
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("wsl", e))?;
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: format!("wsl {}", args.join(" ")),
+            output: decode_wsl_output(&output.stdout).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Running distros with what their processes hold. Stopped ones are left alone: querying them
+/// would start them.
+pub fn running_distros() -> Result<Vec<RunningDistro>, HyperZenithError> {
+    let list = decode_wsl_output(&wsl_exe(&["--list", "--verbose"])?);
+    Ok(parse_running_distros(&list)
+        .into_iter()
+        .map(|(name, default)| RunningDistro {
+            memory_bytes: run_bash_in(Some(&name), "ps -eo rss= | awk '{ s += $1 } END { print s * 1024 }'")
+                .ok()
+                .and_then(|out| out.trim().parse().ok()),
+            docker: name.to_lowercase().starts_with("docker-desktop"),
+            default,
+            name,
+        })
+        .collect())
+}
+
+/// Every registered distro, running or not. Empty when WSL is there but has none yet, which
+/// `wsl --list` reports as a failure. A fresh Windows only has a `wsl.exe` stub offering to
+/// install WSL, which fails `--status` too; that's `WslMissing`.
+pub fn installed_distros() -> Result<Vec<String>, HyperZenithError> {
+    match wsl_exe(&["--list", "--quiet"]) {
+        Ok(out) => Ok(decode_wsl_output(&out).lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect()),
+        Err(HyperZenithError::CommandFailed { .. }) if wsl_exe(&["--status"]).is_err() => Err(HyperZenithError::WslMissing),
+        Err(HyperZenithError::CommandFailed { .. }) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Install a distro from the Store without opening it; its user is created on first launch
+pub fn install_distro(name: &str) -> Result<(), HyperZenithError> {
+    wsl_exe(&["--install", "-d", name, "--no-launch"]).map(|_| ())
+}
+
+/// Stop the whole WSL VM, every distro with it
+pub fn shutdown() -> Result<(), HyperZenithError> {
+    wsl_exe(&["--shutdown"]).map(|_| ())
+}
+
+/// Stop one distro, leaving the others (and the VM) running
+pub fn terminate(distro: &str) -> Result<(), HyperZenithError> {
+    wsl_exe(&["--terminate", distro]).map(|_| ())
+}
+
+/// Run a bash command inside the default WSL distro and return stdout
+pub fn run_bash(cmd: &str) -> Result<String, HyperZenithError> {
+    run_bash_in(None, cmd)
+}
+
+/// Run a bash command inside a specific distro (or the default one) and return stdout
+pub fn run_bash_in(distro: Option<&str>, cmd: &str) -> Result<String, HyperZenithError> {
+    let mut command = Command::new("wsl");
+    if let Some(d) = distro {
+        command.args(["-d", d]);
+    }
+    let output = command
+        .args(["-e", "bash", "-c", cmd])
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("wsl", e))?;
+
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "WSL command".to_string(),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Run a bash command as root in the default distro, for kernel knobs under /proc/sys
+pub fn run_as_root(cmd: &str) -> Result<String, HyperZenithError> {
+    run_as_root_in(None, cmd)
+}
+
+/// Like `run_as_root`, in a specific distro (or the default one)
+pub fn run_as_root_in(distro: Option<&str>, cmd: &str) -> Result<String, HyperZenithError> {
+    let mut command = Command::new("wsl");
+    if let Some(d) = distro {
+        command.args(["-d", d]);
+    }
+    let output = command
+        .args(["-u", "root", "-e", "bash", "-c", cmd])
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("wsl", e))?;
+
+    if !output.status.success() {
+        return Err(HyperZenithError::CommandFailed {
+            command: "WSL command (root)".to_string(),
+            output: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// The distro hosting a project opened through `\\wsl$\<distro>\...` or `\\wsl.localhost\<distro>\...`.
+/// Such projects are built in place instead of through `/mnt/<drive>`.
+pub fn project_distro(win_path: &str) -> Option<String> {
+    let normalized = win_path.replace('\\', "/");
+    let normalized = normalized.strip_prefix("//?/UNC/").map(|p| format!("//{}", p)).unwrap_or(normalized);
+    ["//wsl$/", "//wsl.localhost/"].iter().find_map(|host| {
+        let rest = normalized.get(..host.len()).filter(|h| h.eq_ignore_ascii_case(host)).map(|_| &normalized[host.len()..])?;
+        let distro = rest.split('/').next().filter(|d| !d.is_empty())?;
+        Some(distro.to_string())
+    })
+}
+
+/// Parse `find -printf '%T@\t%p\n'` output into Windows paths with modification times
+fn parse_find_outputs(output: &str, distro: &str) -> Vec<(PathBuf, SystemTime)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (mtime, path) = line.split_once('\t')?;
+            let secs: f64 = mtime.trim().parse().ok()?;
+            let modified = SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs);
+            Some((PathBuf::from(crate::wsl_to_windows_path(path.trim(), Some(distro))), modified))
+        })
+        .collect()
+}
+
+/// `archive::find_build_outputs` for a project inside a distro: walking `\\wsl$` from Windows
+/// is slow, so search with `find` on the Linux side and translate the results back
+pub fn find_build_outputs(distro: &str, linux_android_dir: &str) -> Vec<(PathBuf, SystemTime)> {
+    let prune = crate::archive::SKIP_DIRS.iter()
+        .map(|d| format!("-name {}", crate::sh_quote(d)))
+        .collect::<Vec<_>>()
+        .join(" -o ");
+    let cmd = format!(
+        "find {} \\( {} \\) -prune -o -path '*/build/outputs/*' \\( -name '*.apk' -o -name '*.aab' \\) -printf '%T@\\t%p\\n' 2>/dev/null; true",
+        crate::sh_quote(linux_android_dir),
+        prune
+    );
+    run_bash_in(Some(distro), &cmd)
+        .map(|out| parse_find_outputs(&out, distro))
+        .unwrap_or_default()
+}
+
+/// Parse `du -sb` output ("<bytes>\t<path>" per line)
+pub fn parse_du(output: &str) -> Vec<(String, u64)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (size, path) = line.split_once('\t')?;
+            Some((path.trim().to_string(), size.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Distro name and ext4.vhdx path for every registered WSL distro (via the Lxss registry key)
+pub fn registered_disks() -> Vec<(String, PathBuf)> {
+    let script = "Get-ChildItem 'HKCU:\\Software\\Microsoft\\Windows\\CurrentVersion\\Lxss' -ErrorAction SilentlyContinue | \
+        ForEach-Object { '{0}|{1}' -f $_.GetValue('DistributionName'), $_.GetValue('BasePath') }";
+
+    let output = match crate::run_powershell(script) {
+        Ok(out) => out,
+        Err(e) => {
+            println!("💽 [WSL] ❌ Failed to query distro registry: {}", e);
+            return Vec::new();
+        }
+    };
+
+    output
+        .lines()
+        .filter_map(|l| l.trim().split_once('|'))
+        .map(|(name, base)| {
+            let vhdx = PathBuf::from(base.trim_start_matches("\\\\?\\")).join("ext4.vhdx");
+            (name.to_string(), vhdx)
+        })
+        .filter(|(_, p)| p.exists())
+        .collect()
+}
+
+pub fn vhdx_paths() -> Vec<PathBuf> {
+    registered_disks().into_iter().map(|(_, p)| p).collect()
+}
+
+fn file_size(path: &std::path::Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+pub fn disk_report() -> WslDiskReport {
+    let disks: Vec<WslDisk> = registered_disks()
+        .into_iter()
+        .map(|(distro, path)| WslDisk {
+            distro,
+            size_bytes: file_size(&path),
+            path: path.to_string_lossy().to_string(),
+        })
+        .collect();
+
+    let gradle_usage = run_bash("du -sb ~/.gradle/caches ~/.gradle/wrapper ~/.gradle/daemon ~/.gradle/native 2>/dev/null; true")
+        .map(|out| parse_du(&out))
+        .unwrap_or_default();
+    let gradle_total_bytes = gradle_usage.iter().map(|(_, b)| b).sum();
+
+    WslDiskReport { disks, gradle_usage, gradle_total_bytes }
+}
+
+/// Shrink a WSL vhdx: trim free blocks, shut WSL down, then Optimize-VHD (diskpart as fallback)
+pub fn compact_disk(vhdx_path: &str) -> Result<CompactionResult, HyperZenithError> {
+    let path = PathBuf::from(vhdx_path);
+    let distro = registered_disks()
+        .into_iter()
+        .find(|(_, p)| p == &path)
+        .map(|(name, _)| name)
+        .ok_or_else(|| HyperZenithError::InvalidInput(format!("'{}' is not a registered WSL disk", vhdx_path)))?;
+
+    let before_bytes = file_size(&path);
+    println!("💽 [WSL] Compacting {} ({} bytes)", vhdx_path, before_bytes);
+
+    // Release unused ext4 blocks so the compaction has something to reclaim
+    let _ = Command::new("wsl")
+        .args(["-d", &distro, "-u", "root", "-e", "fstrim", "/"])
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output();
+
+    Command::new("wsl").args(["--shutdown"])
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| HyperZenithError::spawn("wsl", e))?;
+
+    let quoted = crate::ps_quote(vhdx_path);
+    let optimize = format!("Import-Module Hyper-V -ErrorAction Stop; Optimize-VHD -Path {} -Mode Full", quoted);
+
+    let method = match crate::run_powershell_elevated(&optimize) {
+        Ok(_) => "optimize-vhd",
+        Err(HyperZenithError::ElevationDeclined) => return Err(HyperZenithError::ElevationDeclined),
+        Err(e) => {
+            // Hyper-V module is missing on Home editions; diskpart can compact too
+            println!("💽 [WSL] Optimize-VHD failed ({}), falling back to diskpart", e);
+            crate::run_powershell_elevated(&diskpart_script(vhdx_path))?;
+            "diskpart"
+        }
+    };
+
+    let after_bytes = file_size(&path);
+    let message = compaction_message(before_bytes, after_bytes);
+    println!("💽 [WSL] ✅ Compacted via {}: {} -> {} bytes ({})", method, before_bytes, after_bytes, message);
+
+    Ok(CompactionResult {
+        path: vhdx_path.to_string(),
+        before_bytes,
+        after_bytes,
+        method: method.to_string(),
+        message,
+    })
+}
+
+/// PowerShell that runs diskpart's compaction on `vhdx_path` and exits with diskpart's code
+fn diskpart_script(vhdx_path: &str) -> String {
+    format!(
+        "$f = New-TemporaryFile; @({}, 'attach vdisk readonly', 'compact vdisk', 'detach vdisk') | Set-Content $f; \
+         diskpart /s $f; $code = $LASTEXITCODE; Remove-Item $f; exit $code",
+        crate::ps_quote(&format!("select vdisk file=\"{}\"", vhdx_path))
+    )
+}
+
+fn compaction_message(before_bytes: u64, after_bytes: u64) -> String {
+    match before_bytes.checked_sub(after_bytes) {
+        Some(reclaimed) if reclaimed > 0 => format!("Reclaimed {} MB", reclaimed / (1024 * 1024)),
+        _ => "No space reclaimed".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compaction_reporting() {
+        assert!(diskpart_script(r"C:\Users\O'Neil\ext4.vhdx").contains(r#"'select vdisk file="C:\Users\O''Neil\ext4.vhdx"'"#));
+        assert_eq!(compaction_message(10 << 20, 10 << 20), "No space reclaimed");
+        assert_eq!(compaction_message(10 << 20, 11 << 20), "No space reclaimed");
+        assert_eq!(compaction_message(10 << 20, 4 << 20), "Reclaimed 6 MB");
+    }
+
+    #[test]
+    fn test_parse_du() {
+        let out = "1048576\t/home/dev/.gradle/caches\n2048\t/home/dev/.gradle/wrapper\ngarbage line\n";
+        let parsed = parse_du(out);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0], ("/home/dev/.gradle/caches".to_string(), 1048576));
+        assert_eq!(parsed[1].1, 2048);
+    }
+
+    #[test]
+    fn test_running_distros() {
+        let list = "  NAME                   STATE           VERSION\r\n\
+            * Ubuntu-22.04           Running         2\r\n  \
+            docker-desktop         Running         2\r\n  \
+            docker-desktop-data    Stopped         2\r\n";
+        assert_eq!(parse_running_distros(list), vec![("Ubuntu-22.04".to_string(), true), ("docker-desktop".to_string(), false)]);
+
+        let utf16: Vec<u8> = "\u{feff}  NAME  STATE\r\n* Debian  Running  2\r\n".encode_utf16().flat_map(u16::to_le_bytes).collect();
+        assert_eq!(parse_running_distros(&decode_wsl_output(&utf16)), vec![("Debian".to_string(), true)]);
+        assert_eq!(decode_wsl_output(b"plain"), "plain");
+    }
+
+    #[test]
+    fn test_project_distro() {
+        assert_eq!(project_distro("\\\\wsl$\\Ubuntu\\home\\dev\\app").as_deref(), Some("Ubuntu"));
+        assert_eq!(project_distro("\\\\wsl.localhost\\Ubuntu-22.04\\home\\dev").as_deref(), Some("Ubuntu-22.04"));
+        assert_eq!(project_distro("\\\\?\\UNC\\wsl$\\Debian\\srv").as_deref(), Some("Debian"));
+        assert_eq!(project_distro("C:\\Dev\\App"), None);
+        assert_eq!(project_distro("\\\\fileserver\\share\\app"), None);
+    }
+
+    #[test]
+    fn test_parse_find_outputs() {
+        let out = "1735000000.5000000000\t/home/dev/app/android/app/build/outputs/apk/debug/app-debug.apk\n";
+        let parsed = parse_find_outputs(out, "Ubuntu");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].0, PathBuf::from("\\\\wsl$\\Ubuntu\\home\\dev\\app\\android\\app\\build\\outputs\\apk\\debug\\app-debug.apk"));
+        assert_eq!(parsed[0].1, SystemTime::UNIX_EPOCH + Duration::from_millis(1_735_000_000_500));
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::Emitter;
+use crate::error::HyperZenithError;
+use crate::events::BuildChannel;
+use crate::ios::MacConfig;
+
+/// Simulator used when none is chosen, same as simulator builds
+pub const DEFAULT_SIMULATOR: &str = "iPhone 15";
+const RESULT_BUNDLE: &str = "test_results.xcresult";
+
+/// Sent on `ios-test-summary` (and `ios-test-summary-{id}`) once an `xcodebuild test` run ends
+#[derive(serde::Serialize, Clone, Default, Debug, PartialEq)]
+pub struct XcTestSummary {
+    pub id: String,
+    pub success: bool,
+    pub tests_passed: u32,
+    pub tests_failed: u32,
+    pub tests_skipped: u32,
+    pub tests_total: u32,
+    /// `Target.Class/testName`
+    pub failed_tests: Vec<String>,
+    pub elapsed_secs: f64,
+    /// Counts came from the result bundle rather than the console log
+    pub from_xcresult: bool,
+    /// Local copy of the `.xcresult` bundle, tarred
+    pub result_bundle: Option<String>,
+    pub log_path: Option<String>,
+}
+
+/// `-[AppTests.LoginTests testSignIn]` or `AppTests.LoginTests/testSignIn()` as `AppTests.LoginTests/testSignIn`
+fn test_name(raw: &str) -> String {
+    raw.trim_start_matches("-[")
+        .trim_end_matches(']')
+        .trim_end_matches("()")
+        .replacen(' ', "/", 1)
+}
+
+/// Count `Test Case '...' passed|failed|skipped` lines. Xcode 15's parallel runs print
+/// `Test case '...' failed on 'Clone 1 of iPhone 15'` instead, both are understood.
+pub fn parse_log(log: &str) -> XcTestSummary {
+    let mut summary = XcTestSummary::default();
+    for line in log.lines() {
+        let Some(start) = line.find("Test Case '").or_else(|| line.find("Test case '")) else { continue };
+        let rest = &line[start + "Test Case '".len()..];
+        let Some((raw, outcome)) = rest.split_once('\'') else { continue };
+        let name = test_name(raw);
+        match outcome.split_whitespace().next() {
+            Some("passed") => summary.tests_passed += 1,
+            Some("failed") => {
+                summary.tests_failed += 1;
+                if !summary.failed_tests.contains(&name) {
+                    summary.failed_tests.push(name);
+                }
+            },
+            Some("skipped") => summary.tests_skipped += 1,
+            _ => continue,
+        }
+        summary.tests_total += 1;
+    }
+    summary
+}
+
+/// Counts from `xcresulttool get test-results summary` (Xcode 16+), which sees retries and
+/// expected failures the console log doesn't. `None` on older Xcode.
+pub fn parse_xcresult(json: &str) -> Option<XcTestSummary> {
+    let json: serde_json::Value = serde_json::from_str(json.trim()).ok()?;
+    let count = |key: &str| json[key].as_u64().map(|n| n as u32);
+    Some(XcTestSummary {
+        tests_passed: count("passedTests")?,
+        tests_failed: count("failedTests")?,
+        tests_skipped: count("skippedTests").unwrap_or(0),
+        tests_total: count("totalTestCount")?,
+        failed_tests: json["testFailures"].as_array().map(|failures| failures.iter().filter_map(|f| {
+            let test = test_name(f["testName"].as_str()?);
+            Some(match f["targetName"].as_str() {
+                Some(target) => format!("{}/{}", target, test),
+                None => test,
+            })
+        }).collect()).unwrap_or_default(),
+        from_xcresult: true,
+        ..Default::default()
+    })
+}
+
+/// Run the scheme's tests on a simulator on the Mac, then bring the result bundle home
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    channel: &BuildChannel,
+    config: &MacConfig,
+    working_dir: &str,
+    remote_path: &str,
+    app_subdir: &str,
+    scheme: &str,
+    simulator: Option<String>,
+    toolchain: &crate::node::Toolchain,
+    pipeline: &crate::state::IosPipeline,
+) -> Result<XcTestSummary, HyperZenithError> {
+    let started = Instant::now();
+    let (sess, _) = crate::ios::tracked_session(config, pipeline)?;
+    let simulator = simulator.filter(|s| !s.trim().is_empty()).unwrap_or_else(|| DEFAULT_SIMULATOR.to_string());
+    let remote_bundle = format!("{}/{}", crate::ios::DETACHED_DIR, RESULT_BUNDLE);
+
+    // Clear the last run's bundle first, so a failed prepare can't report its results
+    let test_cmd = format!(
+        "mkdir -p {dir} && rm -rf {bundle} && {prepare} && \
+        xcodebuild test -workspace {scheme}.xcworkspace \
+        -scheme {scheme} \
+        -destination {destination} \
+        -resultBundlePath {bundle} \
+        COMPILER_INDEX_STORE_ENABLE=NO 2>&1",
+        prepare = crate::ios::prepare_cmd(remote_path, app_subdir, toolchain),
+        dir = crate::ios::DETACHED_DIR,
+        bundle = remote_bundle,
+        scheme = crate::sh_quote(scheme),
+        destination = crate::sh_quote(&format!("platform=iOS Simulator,name={}", simulator))
+    );
+    channel.line(format!("🧪 Running {} tests on {} ({})...", scheme, simulator, config.ip));
+    let log_buffer = Arc::new(Mutex::new(String::new()));
+    // xcodebuild exits 65 when tests fail, the summary says what happened
+    let outcome = crate::ios::run_remote_command(&sess, &crate::ios::keep_awake(&test_cmd), channel, Some(&log_buffer));
+    let log = log_buffer.lock().map(|l| l.clone()).unwrap_or_default();
+    let log_path = crate::write_build_log(working_dir, "ios_tests", &log);
+    if pipeline.cancelled() {
+        return Err(HyperZenithError::BuildAborted { log_path: Some(log_path.display().to_string()) });
+    }
+
+    let xcresult = crate::ios::run_remote_capture(&sess, &format!("xcrun xcresulttool get test-results summary --path {} --compact 2>/dev/null", remote_bundle))
+        .ok()
+        .and_then(|json| parse_xcresult(&json));
+    let mut summary = xcresult.unwrap_or_else(|| parse_log(&log));
+    if summary.tests_total == 0 {
+        // Nothing ran: the build failed, or the scheme has no test target
+        outcome?;
+        return Err(HyperZenithError::InvalidInput(format!("The {} scheme ran no tests. Does it have a test target?", scheme)));
+    }
+
+    let local_dir: PathBuf = crate::logs::logs_dir(working_dir).join("ios_tests");
+    std::fs::create_dir_all(&local_dir).map_err(|e| HyperZenithError::io_at(&local_dir, e))?;
+    let local_bundle = local_dir.join(format!("{}_{}.xcresult.tar.gz", scheme, chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")));
+    let tar_cmd = format!("tar -czf - -C {} {}", crate::ios::DETACHED_DIR, RESULT_BUNDLE);
+    match crate::ios::download_remote(&sess, &tar_cmd, &local_bundle) {
+        Ok(()) => {
+            channel.line(format!("📦 Result bundle saved to: {}", local_bundle.display()));
+            summary.result_bundle = Some(local_bundle.display().to_string());
+        },
+        Err(e) => channel.line(format!("⚠️ Could not download the result bundle: {}", e)),
+    }
+
+    summary.id = channel.id.clone();
+    summary.success = outcome.is_ok() && summary.tests_failed == 0;
+    summary.elapsed_secs = started.elapsed().as_secs_f64();
+    summary.log_path = Some(log_path.display().to_string());
+    channel.line(format!(
+        "{} {} passed, {} failed, {} skipped",
+        if summary.success { "✅" } else { "❌" },
+        summary.tests_passed,
+        summary.tests_failed,
+        summary.tests_skipped
+    ));
+    for test in &summary.failed_tests {
+        channel.line(format!("   ✗ {}", test));
+    }
+    let _ = channel.app.emit(&format!("ios-test-summary-{}", channel.id), &summary);
+    let _ = channel.app.emit("ios-test-summary", &summary);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let log = "[10:00:01 +1.0s] Test Suite 'LoginTests' started at 2026-01-01 10:00:01.000\n\
+            [10:00:01 +1.1s] Test Case '-[AppTests.LoginTests testSignIn]' passed (0.012 seconds).\n\
+            [10:00:01 +1.2s] Test Case '-[AppTests.LoginTests testSignOut]' failed (0.020 seconds).\n\
+            [10:00:02 +2.0s] Test case 'AppUITests.OnboardingTests/testSkip()' skipped on 'Clone 1 of iPhone 15' (0.000 seconds)\n\
+            [10:00:02 +2.1s] Test case 'AppUITests.OnboardingTests/testFinish()' failed on 'Clone 1 of iPhone 15' (1.250 seconds)\n\
+            [10:00:03 +3.0s] Executed 4 tests, with 2 failures (0 unexpected) in 1.282 (1.300) seconds\n";
+        let summary = parse_log(log);
+
+        assert_eq!((summary.tests_passed, summary.tests_failed, summary.tests_skipped, summary.tests_total), (1, 2, 1, 4));
+        assert_eq!(summary.failed_tests, vec!["AppTests.LoginTests/testSignOut".to_string(), "AppUITests.OnboardingTests/testFinish".to_string()]);
+        assert!(!summary.from_xcresult);
+    }
+
+    #[test]
+    fn test_parse_xcresult() {
+        let json = r#"{"title":"Test - App","result":"Failed","totalTestCount":12,"passedTests":10,"failedTests":1,"skippedTests":1,
+            "expectedFailures":0,"testFailures":[{"testName":"testSignOut()","targetName":"AppTests","failureText":"XCTAssertTrue failed"}]}"#;
+        let summary = parse_xcresult(json).unwrap();
+
+        assert_eq!((summary.tests_passed, summary.tests_failed, summary.tests_total), (10, 1, 12));
+        assert_eq!(summary.failed_tests, vec!["AppTests/testSignOut".to_string()]);
+        assert!(parse_xcresult("Error: unknown subcommand 'test-results'").is_none());
+    }
+}
@@ -1,7 +1,8 @@
-import { useState, useEffect, useCallback, useRef } from 'react';
+import { useState, useEffect, useRef } from 'react';
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { open } from "@tauri-apps/plugin-dialog";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import { openUrl } from "@tauri-apps/plugin-opener";
 import './App.css';
 
 interface SystemStats {
@@ -12,11 +13,292 @@ interface SystemStats {
   cpu_count: number;
 }
 
+interface BuildResources {
+  consumers: { consumer: 'wsl' | 'java' | 'node' | 'emulator'; processes: number; cpu_percent: number; memory_bytes: number }[];
+  other_cpu_percent: number;
+  other_memory_bytes: number;
+}
+
+const CONSUMER_LABELS: Record<BuildResources['consumers'][number]['consumer'], string> = {
+  wsl: '🐧 WSL',
+  java: '☕ Java',
+  node: '🟩 Node',
+  emulator: '📱 Emulator',
+};
+
+// Serialized HyperZenithError: `kind` identifies the failure, extra fields carry details
+interface CommandError {
+  kind: string;
+  message: string;
+  [detail: string]: unknown;
+}
+
+type AnsiColor = 'black' | 'red' | 'green' | 'yellow' | 'blue' | 'magenta' | 'cyan' | 'white';
+
+interface OutputLine {
+  timestamp: string;
+  elapsed: number;
+  stream: 'stdout' | 'stderr' | 'system';
+  text: string;
+  color?: AnsiColor;
+}
+
+interface BuildIssue {
+  severity: 'error' | 'warning' | 'note';
+  message: string;
+  location: string | null;
+  file: string | null;
+  line: number | null;
+  column: number | null;
+  text: string;
+}
+
+interface LogLine {
+  text: string;
+  color?: AnsiColor;
+}
+
+const ANSI_CLASSES: Record<AnsiColor, string> = {
+  black: 'text-slate-600',
+  red: 'text-red-400',
+  green: 'text-emerald-400',
+  yellow: 'text-amber-400',
+  blue: 'text-sky-400',
+  magenta: 'text-fuchsia-400',
+  cyan: 'text-cyan-400',
+  white: 'text-slate-300',
+};
+
+// `sync-started`, `sync-complete`, `build-started`, and `build-finished` with its status
+interface LifecycleEvent {
+  build_id: string;
+  status: 'finished' | 'failed' | 'aborted' | null;
+  message: string | null;
+  error: CommandError | null;
+}
+
+interface BuildProgressEvent {
+  build_id: string;
+  percent: number;
+}
+
+const errorMessage = (err: unknown): string =>
+  typeof err === 'object' && err !== null && 'message' in err ? String((err as CommandError).message) : String(err);
+
+interface DeepLinkEvent {
+  url: string;
+  action: 'build' | 'open' | null;
+  working_dir: string | null;
+  build_type: 'apk' | 'aab' | null;
+  error: CommandError | null;
+}
+
+interface IdleJobInfo {
+  working_dir: string;
+  kind: 'prewarm' | 'build';
+  build_type: string | null;
+  settings: { idle_minutes: number; max_cpu_percent: number };
+  queued_at: string;
+}
+
+interface ProjectInfo {
+  name: string | null;
+  expo_sdk: number | null;
+  react_native: string | null;
+  agp: string | null;
+  gradle: string | null;
+  hermes: boolean | null;
+  new_architecture: boolean | null;
+  advisories: { severity: 'info' | 'warning'; message: string }[];
+}
+
+interface BundleReport {
+  id: string;
+  bundle_bytes: number;
+  bytecode_bytes: number | null;
+  modules: { name: string; bytes: number; files: number }[];
+  output_dir: string;
+}
+
+interface DependencyReport {
+  audit: { vulnerabilities: { name: string; severity: string; is_direct: boolean; fix_available: boolean; fix_is_breaking: boolean }[]; critical: number; high: number; moderate: number; low: number } | null;
+  auditError: string | null;
+  outdated: { name: string; current: string | null; latest: string; major_behind: boolean }[];
+}
+
+interface OnboardingStep {
+  step: 'wsl' | 'java' | 'node' | 'android_sdk' | 'doctor';
+  title: string;
+  index: number;
+  total: number;
+  status: 'running' | 'ok' | 'warn' | 'missing' | 'installed' | 'skipped' | 'failed';
+  detail: string;
+  installable: boolean;
+}
+
+const ONBOARDING_ICONS: Record<OnboardingStep['status'], string> = {
+  running: '⏳', ok: '✅', warn: '⚠️', missing: '❌', installed: '📦', skipped: '⏭️', failed: '❌',
+};
+
+interface RunningDistro {
+  name: string;
+  default: boolean;
+  memory_bytes: number | null;
+  docker: boolean;
+}
+
+interface PurgeImpact {
+  distros: RunningDistro[];
+  vmmem_bytes: number | null;
+  active_builds: number;
+}
+
+interface BuildPhase {
+  phase: 'sync' | 'configuration' | 'compile' | 'dex' | 'package' | 'archive';
+  started_secs: number;
+  duration_secs: number;
+}
+
+const PHASE_COLORS: Record<BuildPhase['phase'], string> = {
+  sync: 'bg-violet-500/70',
+  configuration: 'bg-slate-500/70',
+  compile: 'bg-cyan-500/70',
+  dex: 'bg-emerald-500/70',
+  package: 'bg-amber-500/70',
+  archive: 'bg-pink-500/70',
+};
+
+interface BuildPreset {
+  name: string;
+  build_type: 'apk' | 'aab';
+  variant: string | null;
+  turbo_mode: boolean;
+  log_level: string;
+  retry_with_diagnostics: boolean;
+  install_dependencies: boolean;
+  use_nvm: boolean;
+  gates: { typecheck: boolean; lint: boolean; block_on_errors: boolean };
+  engine: { hermes: boolean | null; new_architecture: boolean | null };
+  release_notes: boolean;
+  verbosity: 'all' | 'compact' | 'problems';
+  env_file: string | null;
+  custom_path: string | null;
+  run_hooks: boolean;
+}
+
+interface ProjectAnalytics {
+  project: string;
+  builds: number;
+  successes: number;
+  failures: number;
+  success_rate: number | null;
+  avg_duration_secs: number | null;
+  median_duration_secs: number | null;
+  avg_cache_hit_rate: number | null;
+  recent_cache_hit_rate: number | null;
+  latest_warnings: { deprecations: number; kotlin: number; java: number } | null;
+  recent_warnings: number | null;
+  series: { started_at: string; duration_secs: number; outcome: 'success' | 'failed' | 'aborted'; cache_hit_rate: number | null; warnings: number | null; phases: BuildPhase[] }[];
+}
+
+interface UpdateInfo {
+  current: string;
+  latest: string;
+  available: boolean;
+  notes: string;
+  release_url: string;
+  installer_url: string | null;
+  installer_name: string | null;
+}
+
+interface CacheEntry {
+  id: string;
+  label: string;
+  path: string;
+  size_bytes: number;
+  exists: boolean;
+}
+
+interface SyncPreview {
+  files: { path: string; bytes: number; is_new: boolean }[];
+  folders: { path: string; bytes: number; files: number }[];
+  total_bytes: number;
+  deleted: string[];
+}
+
+interface PortConflict {
+  port: number;
+  purpose: string;
+  side: 'windows' | 'wsl';
+  pid: number | null;
+  process: string;
+}
+
+interface LongPathStatus {
+  enabled: boolean | null;
+  deepest_path: string | null;
+  deepest_len: number;
+  limit: number;
+  warning: string | null;
+  truncated: boolean;
+}
+
+interface PushConfig {
+  provider: 'off' | 'ntfy' | 'pushover';
+  server: string;
+  topic: string;
+  user_key: string;
+  on_success: boolean;
+  on_failure: boolean;
+  min_duration_secs: number;
+  has_token: boolean;
+}
+
+interface LogFile {
+  path: string;
+  name: string;
+  platform: 'android' | 'ios' | null;
+  success: boolean | null;
+  modified: string | null;
+  bytes: number;
+}
+
+interface DiscoveredProject {
+  path: string;
+  android: boolean;
+  ios: boolean;
+}
+
+interface RecentProject {
+  path: string;
+  pinned: boolean;
+  last_built_at: string | null;
+  last_platform: string | null;
+  last_outcome: 'success' | 'failed' | 'aborted' | null;
+  exists: boolean;
+}
+
 interface HardwareProfile {
   max_workers: number;
   jvm_heap_gb: number;
   cpu_cores: number;
+  performance_cores: number;
+  efficiency_cores: number;
   total_ram_gb: number;
+  arch: 'x64' | 'arm64';
+  idle_cores: number;
+  available_ram_gb: number;
+  constrained_by: string | null;
+  emulator?: EmulatorSupport;
+}
+
+interface EmulatorSupport {
+  gpus: { name: string; driver_version: string | null }[];
+  accelerator: 'whpx' | 'aehd' | null;
+  hypervisor_running: boolean;
+  software_rendering: boolean;
+  system_image_abi: string;
+  warnings: string[];
 }
 
 export default function App() {
@@ -27,13 +309,73 @@ export default function App() {
   const [engineStatus, setEngineStatus] = useState("Select Project");
   const [stats, setStats] = useState<SystemStats | null>(null);
   const [hardware, setHardware] = useState<HardwareProfile | null>(null);
-  const [logs, setLogs] = useState<string[]>(["🚀 HyperZenith V1.4.3"]);
+  const [logs, setLogs] = useState<LogLine[]>([{ text: "🚀 HyperZenith V1.4.3" }]);
   const [buildProgress, setBuildProgress] = useState(0);
   const [buildStartTime, setBuildStartTime] = useState<number | null>(null);
   const [showMaintenance, setShowMaintenance] = useState(false);
-  const [scanResults, setScanResults] = useState<string[]>([]);
+  // First-run setup steps as they complete; null once dismissed
+  const [onboarding, setOnboarding] = useState<OnboardingStep[] | null>(null);
+  const [onboardingBusy, setOnboardingBusy] = useState(false);
+  // What purging WSL would stop, shown for confirmation, and the distros picked to stop on their own
+  const [purgeImpact, setPurgeImpact] = useState<PurgeImpact | null>(null);
+  const [purgeSelection, setPurgeSelection] = useState<string[]>([]);
+  const [scanResults, setScanResults] = useState<DiscoveredProject[]>([]);
   const [showScanResults, setShowScanResults] = useState(false);
+  const [isScanning, setIsScanning] = useState(false);
+  const [recentProjects, setRecentProjects] = useState<RecentProject[] | null>(null);
   const [customArchivePath, setCustomArchivePath] = useState(() => localStorage.getItem('hyperzenith_archive_path') || '');
+  const [gradleLogLevel, setGradleLogLevel] = useState(() => localStorage.getItem('hyperzenith_gradle_log_level') || 'lifecycle');
+  const [gates, setGates] = useState<{ typecheck: boolean; lint: boolean; block_on_errors: boolean }>(() => {
+    const saved = localStorage.getItem('hyperzenith_gates');
+    return saved ? JSON.parse(saved) : { typecheck: false, lint: false, block_on_errors: true };
+  });
+  const [nukeJsTargets, setNukeJsTargets] = useState<string[]>(() => {
+    const saved = localStorage.getItem('hyperzenith_nuke_js');
+    return saved ? JSON.parse(saved) : [];
+  });
+  const [useNvm, setUseNvm] = useState(() => localStorage.getItem('hyperzenith_use_nvm') === 'true');
+  const [autoReleaseNotes, setAutoReleaseNotes] = useState(() => localStorage.getItem('hyperzenith_release_notes') === 'true');
+  const [lastAndroidBuildId, setLastAndroidBuildId] = useState<string | null>(null);
+  const [failedBuildId, setFailedBuildId] = useState<string | null>(null);
+  const [releaseNotes, setReleaseNotes] = useState<string | null>(null);
+  const [portConflicts, setPortConflicts] = useState<PortConflict[] | null>(null);
+  const [longPaths, setLongPaths] = useState<LongPathStatus | null>(null);
+  const [buildResources, setBuildResources] = useState<BuildResources | null>(null);
+  const [logsFolder, setLogsFolder] = useState('');
+  const [savedLogs, setSavedLogs] = useState<{ platform: 'android' | 'ios' | null; logs: LogFile[] } | null>(null);
+  const [pushConfig, setPushConfig] = useState<(PushConfig & { token: string }) | null>(null);
+  const [crash, setCrash] = useState<{ buildId: string; text: string; dsymPath: string; result: string | null } | null>(null);
+  const [installDependencies, setInstallDependencies] = useState(() => localStorage.getItem('hyperzenith_install_deps') === 'true');
+  const [retryWithDiagnostics, setRetryWithDiagnostics] = useState(() => localStorage.getItem('hyperzenith_retry_diagnostics') === 'true');
+  const [verbosity, setVerbosity] = useState<'all' | 'compact' | 'problems'>(() => (localStorage.getItem('hyperzenith_verbosity') as 'all' | 'compact' | 'problems') || 'all');
+  const [controlUrl, setControlUrl] = useState<string | null>(null);
+  const [locale, setLocale] = useState(() => localStorage.getItem('hyperzenith_locale') || navigator.language);
+  const [memoryPolicy, setMemoryPolicy] = useState<{ drop_caches: boolean; shutdown_idle_minutes: number }>(() => {
+    const saved = localStorage.getItem('hyperzenith_memory_policy');
+    return saved ? JSON.parse(saved) : { drop_caches: false, shutdown_idle_minutes: 0 };
+  });
+  const [stallPolicy, setStallPolicy] = useState<{ warn_minutes: number; abort_minutes: number }>(() => {
+    const saved = localStorage.getItem('hyperzenith_stall_policy');
+    return saved ? JSON.parse(saved) : { warn_minutes: 10, abort_minutes: 0 };
+  });
+  const [failureCleanup, setFailureCleanup] = useState<{ clean_intermediates: boolean; min_mb: number }>(() => {
+    const saved = localStorage.getItem('hyperzenith_failure_cleanup');
+    return saved ? JSON.parse(saved) : { clean_intermediates: false, min_mb: 0 };
+  });
+  const [batteryPolicy, setBatteryPolicy] = useState<'ignore' | 'warn' | 'lowpower'>(() => (localStorage.getItem('hyperzenith_battery_policy') as 'ignore' | 'warn' | 'lowpower') || 'warn');
+  const [projectInfo, setProjectInfo] = useState<ProjectInfo | null>(null);
+  const [idleJob, setIdleJob] = useState<IdleJobInfo | null>(null);
+  // One-off hermesEnabled/newArchEnabled overrides; cleared once a build has used them
+  const [engineOverrides, setEngineOverrides] = useState<{ hermes: boolean | null; new_architecture: boolean | null }>({ hermes: null, new_architecture: null });
+  const [newProject, setNewProject] = useState<{ name: string; kind: 'expo' | 'react-native' | 'git' } | null>(null);
+  const [otaBranch, setOtaBranch] = useState(() => localStorage.getItem('hyperzenith_ota_branch') || '');
+  const [archiveStorage, setArchiveStorage] = useState<{ root: string; kind: 'local' | 'network_share' | 'removable'; available: boolean; pending: number } | null>(null);
+  const [compareCommits, setCompareCommits] = useState({ a: 'HEAD~1', b: 'HEAD' });
+  const [matrixSpec, setMatrixSpec] = useState(() => localStorage.getItem('hyperzenith_matrix') || 'debug:apk, release:apk, release:aab');
+  const [depReport, setDepReport] = useState<DependencyReport | null>(null);
+  const [checkingDeps, setCheckingDeps] = useState(false);
+  const [analytics, setAnalytics] = useState<ProjectAnalytics | null>(null);
+  const [bundleReport, setBundleReport] = useState<BundleReport | null>(null);
   const hasPrewarmed = useRef(false);
 
 
@@ -45,27 +387,277 @@ export default function App() {
   });
   const [iosRemotePath, setIosRemotePath] = useState(() => localStorage.getItem('hyperzenith_ios_remote_path') || '~/hyperzenith_builds/project');
   const [iosScheme, setIosScheme] = useState(() => localStorage.getItem('hyperzenith_ios_scheme') || 'App');
+  const [iosTestSimulator, setIosTestSimulator] = useState(() => localStorage.getItem('hyperzenith_ios_test_simulator') || 'iPhone 15');
+  const [iosPullBack, setIosPullBack] = useState(() => localStorage.getItem('hyperzenith_ios_pull_back') ?? 'ios/Podfile.lock');
   const [showIosSettings, setShowIosSettings] = useState(false);
+  const [syncPreview, setSyncPreview] = useState<SyncPreview | null>(null);
+  const [macCaches, setMacCaches] = useState<CacheEntry[] | null>(null);
+  const [fastlaneLanes, setFastlaneLanes] = useState<{ platform: string | null; name: string }[] | null>(null);
+  const [queuedBehind, setQueuedBehind] = useState<number | null>(null);
+  const [buildStage, setBuildStage] = useState<'syncing' | 'building' | null>(null);
+  const [buildIssues, setBuildIssues] = useState<BuildIssue[]>([]);
+  const [lowMacDisk, setLowMacDisk] = useState<CommandError | null>(null);
+  const [update, setUpdate] = useState<UpdateInfo | null>(null);
+  const [buildEnv, setBuildEnv] = useState<{ name: string; value: string; secret: boolean }[] | null>(null);
+  const [buildPresets, setBuildPresets] = useState<BuildPreset[]>([]);
+  const [presetDraft, setPresetDraft] = useState<{ name: string; variant: string; env_file: string; run_hooks: boolean } | null>(null);
   const unlistenRef = useRef<(() => void) | null>(null);
+  const iosBuildIdRef = useRef<string | null>(null);
 
-  const addLog = (msg: string) => setLogs(prev => [msg, ...prev.slice(0, 150)]);
+  const addLog = (msg: string) => setLogs(prev => [{ text: msg }, ...prev.slice(0, 150)]);
 
   // Fetch hardware profile on mount
   useEffect(() => {
     invoke<HardwareProfile>('get_hardware_profile').then(setHardware).catch(console.error);
   }, []);
 
+  // Quietly look for a newer release once per launch
+  useEffect(() => {
+    invoke<UpdateInfo>('check_for_updates').then(info => { if (info.available) setUpdate(info); }).catch(console.error);
+  }, []);
+
+  // Build statuses and errors from the backend follow the app language
+  useEffect(() => {
+    invoke<string>('set_locale', { locale }).catch(console.error);
+  }, [locale]);
+
+  // The backend applies the battery policy to every build, whoever starts it
+  useEffect(() => {
+    localStorage.setItem('hyperzenith_battery_policy', batteryPolicy);
+    invoke('set_battery_policy', { policy: batteryPolicy }).catch(console.error);
+  }, [batteryPolicy]);
+
+  // Versions and advisories for the selected project
+  useEffect(() => {
+    setDepReport(null);
+    if (!projectPath) { setProjectInfo(null); return; }
+    const timer = setTimeout(() => {
+      invoke<ProjectInfo>('get_project_info', { workingDir: projectPath }).then(setProjectInfo).catch(() => setProjectInfo(null));
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [projectPath]);
+
   // Pre-warm when project is selected
   useEffect(() => {
     if (!projectPath || hasPrewarmed.current) return;
     hasPrewarmed.current = true;
     setEngineStatus("Pre-heating...");
-    invoke('prewarm_engine', { workingDir: projectPath })
-      .then(() => setTimeout(() => setEngineStatus("Ready"), 2000))
-      .catch(() => setEngineStatus("Ready"));
+
+    let prewarmId: string | null = null;
+    const progress = listen<{ id: string; message: string }>('prewarm-progress', (event) => {
+      if (!prewarmId || event.payload.id === prewarmId) setEngineStatus(event.payload.message);
+    });
+    const complete = listen<{ id: string; success: boolean; elapsed_secs: number; message: string }>('prewarm-complete', (event) => {
+      const { id, success, elapsed_secs, message } = event.payload;
+      if (prewarmId && id !== prewarmId) return;
+      setEngineStatus("Ready");
+      addLog(`${success ? '🔥' : '⚠️'} ${message} (${elapsed_secs.toFixed(1)}s)`);
+      progress.then(f => f());
+      complete.then(f => f());
+    });
+
+    invoke<string>('prewarm_engine', { workingDir: projectPath })
+      .then(id => { prewarmId = id; })
+      .catch(() => {
+        setEngineStatus("Ready");
+        progress.then(f => f());
+        complete.then(f => f());
+      });
   }, [projectPath]);
 
 
+  // hyperzenith:// links are handled in the backend, which reports what it did here
+  useEffect(() => {
+    const unlisten = listen<DeepLinkEvent>('deep-link', async (event) => {
+      const { action, working_dir, build_type, error } = event.payload;
+      if (error) {
+        addLog(`🔗 ❌ ${error.message}`);
+        return;
+      }
+      if (action === 'build' && working_dir && build_type) {
+        // Any page or app can open a link; the build runs the project's scripts, so the user decides
+        if (!window.confirm(`A link asks to build this project (${build_type.toUpperCase()}):\n\n${working_dir}\n\nThis runs its Gradle scripts and hooks. Build it?`)) {
+          addLog(`🔗 Build link ignored: ${working_dir}`);
+          return;
+        }
+        setProjectPath(working_dir);
+        addLog(`🔗 Build triggered by link: ${working_dir}`);
+        unlistenRef.current?.();
+        setPlatform('android');
+        setIsBuilding(true);
+        setBuildProgress(0);
+        const startTime = Date.now();
+        setBuildStartTime(startTime);
+        let buildId: string | null = null;
+        const cleanup = await watchBuild(() => buildId, startTime);
+        try {
+          buildId = await invoke<string>('execute_build', { workingDir: working_dir, buildType: build_type, turboMode: true });
+        } catch (err) {
+          addLog(`❌ ${errorMessage(err)}`);
+          setIsBuilding(false);
+          cleanup();
+        }
+      } else {
+        if (working_dir) setProjectPath(working_dir);
+        addLog(`🔗 Opened project from link: ${working_dir}`);
+      }
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // vmmem keeps what a turbo build touched; the backend hands it back to Windows per this policy
+  useEffect(() => {
+    localStorage.setItem('hyperzenith_memory_policy', JSON.stringify(memoryPolicy));
+    invoke('set_memory_policy', { policy: memoryPolicy }).catch(console.error);
+  }, [memoryPolicy]);
+
+  useEffect(() => {
+    const mb = (bytes: number | null) => bytes === null ? '?' : `${(bytes / 1_048_576).toFixed(0)} MB`;
+    const unlisten = listen<{ action: 'drop_caches' | 'shutdown'; vmmem_before_bytes: number | null; vmmem_after_bytes: number | null; stopped_distros: string[] }>('wsl-memory-reclaimed', (event) => {
+      const { action, vmmem_before_bytes, vmmem_after_bytes, stopped_distros } = event.payload;
+      const stopped = stopped_distros.length > 0 ? ` (stopped ${stopped_distros.join(', ')})` : '';
+      addLog(`🧽 ${action === 'shutdown' ? 'WSL shut down after idle' : 'WSL page cache dropped'}: vmmem ${mb(vmmem_before_bytes)} → ${mb(vmmem_after_bytes)}${stopped}`);
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // Failed builds leave app/build/intermediates behind; the backend deletes it per this policy
+  useEffect(() => {
+    localStorage.setItem('hyperzenith_failure_cleanup', JSON.stringify(failureCleanup));
+    invoke('set_failure_cleanup_policy', { policy: failureCleanup }).catch(console.error);
+  }, [failureCleanup]);
+
+  useEffect(() => {
+    const unlisten = listen<{ build_id: string; path: string; files: number; freed_bytes: number }>('failed-build-cleaned', (event) => {
+      const { files, freed_bytes } = event.payload;
+      addLog(`🧹 Failed build's intermediates cleaned: ${files} file(s), ${(freed_bytes / 1_048_576).toFixed(1)} MB recovered`);
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // What the build's processes use next to everything else, sampled while builds run
+  useEffect(() => {
+    const unlisten = listen<BuildResources>('build-resources', (event) => setBuildResources(event.payload));
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  useEffect(() => {
+    if (!isBuilding) setBuildResources(null);
+  }, [isBuilding]);
+
+  // Builds that go quiet (a hung kotlin-daemon, a stuck download) are flagged, or aborted, by the backend
+  useEffect(() => {
+    localStorage.setItem('hyperzenith_stall_policy', JSON.stringify(stallPolicy));
+    invoke('set_stall_policy', { policy: stallPolicy }).catch(console.error);
+  }, [stallPolicy]);
+
+  useEffect(() => {
+    const unlisten = listen<{ build_id: string; silent_secs: number; last_line: string | null; aborting: boolean }>('build-stalled', (event) => {
+      const { silent_secs, last_line, aborting } = event.payload;
+      const minutes = Math.round(silent_secs / 60);
+      addLog(`⏳ No output for ${minutes} min${last_line ? `, last: ${last_line.slice(0, 80)}` : ''}${aborting ? ' — aborting' : ''}`);
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // Debug builds report who holds the dev ports; offer to free them
+  useEffect(() => {
+    const unlisten = listen<PortConflict[]>('port-conflicts', (event) => setPortConflicts(event.payload));
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // Hook scripts only run once approved; a build skips a new or edited one and asks here
+  useEffect(() => {
+    const unlisten = listen<{ project: string; hook: string; path: string; sha256: string }>('hook-untrusted', (event) => {
+      const { project, hook, path, sha256 } = event.payload;
+      const name = path.split(/[\\/]/).pop() ?? path;
+      if (window.confirm(`The ${hook} hook of this project was skipped because it is new or changed since you last approved it:\n\n${path}\n\nHooks run with your user's rights. Trust this version for ${project}? It runs from the next build on.`)) {
+        invoke('trust_hook', { workingDir: project, name, sha256 })
+          .then(() => addLog(`🔓 ${name} approved, it runs from the next build`))
+          .catch(err => addLog(`❌ ${errorMessage(err)}`));
+      }
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // Builds warn when node_modules gets too deep for Windows without long paths
+  useEffect(() => {
+    const unlisten = listen<LongPathStatus>('long-paths', (event) => setLongPaths(event.payload));
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  useEffect(() => {
+    const unlisten = listen<{ projects: DiscoveredProject[]; done: boolean }>('projects-discovered', (event) => {
+      const { projects, done } = event.payload;
+      if (!done) {
+        setScanResults(prev => prev.some(p => p.path === projects[0].path) ? prev : [...prev, projects[0]]);
+        return;
+      }
+      setIsScanning(false);
+      setScanResults(projects);
+      if (projects.length === 0) {
+        addLog("🔍 No Android or iOS projects found nearby.");
+        setShowScanResults(false);
+      } else if (projects.length === 1) {
+        setProjectPath(projects[0].path);
+        setShowScanResults(false);
+        addLog("✨ Auto-detected project!");
+      } else {
+        addLog(`🔍 Found ${projects.length} projects.`);
+      }
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
+  // Reopen the favorite or last built project instead of making the user scan for it
+  useEffect(() => {
+    invoke<RecentProject[]>('get_recent_projects')
+      .then(recents => {
+        const first = recents.find(r => r.exists);
+        if (first) setProjectPath(current => current || first.path);
+      })
+      .catch(console.error);
+  }, []);
+
+  const handleShowRecents = async () => {
+    if (recentProjects) {
+      setRecentProjects(null);
+      return;
+    }
+    try {
+      setRecentProjects(await invoke<RecentProject[]>('get_recent_projects'));
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handlePinProject = async (path: string, pinned: boolean) => {
+    try {
+      setRecentProjects(await invoke<RecentProject[]>('pin_project', { path, pinned }));
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  // Queued idle jobs start in the backend; attach to the build like any other
+  useEffect(() => {
+    invoke<IdleJobInfo | null>('get_idle_job').then(setIdleJob).catch(console.error);
+    const unlisten = listen<{ working_dir: string; kind: 'prewarm' | 'build'; build_id: string }>('idle-job-started', async (event) => {
+      const { working_dir, kind, build_id } = event.payload;
+      setIdleJob(null);
+      addLog(`💤 System idle, started queued ${kind}: ${working_dir}`);
+      if (kind !== 'build') return;
+      unlistenRef.current?.();
+      setPlatform('android');
+      setIsBuilding(true);
+      setBuildProgress(0);
+      const startTime = Date.now();
+      setBuildStartTime(startTime);
+      await watchBuild(() => build_id, startTime);
+    });
+    return () => { unlisten.then(f => f()); };
+  }, []);
+
   // Stats polling
   useEffect(() => {
     const interval = setInterval(async () => {
@@ -94,21 +686,83 @@ export default function App() {
   };
 
 
-  const estimateProgress = useCallback((line: string): number => {
-    const l = line.toLowerCase();
-    if (l.includes('starting') || l.includes('initializing')) return 5;
-    if (l.includes('downloading') || l.includes('resolving')) return 12;
-    if (l.includes('configuring')) return 20;
-    if (l.includes(':prebuild') || l.includes('prebuild')) return 28;
-    if (l.includes(':compile') || l.includes('compiling')) return 45;
-    if (l.includes(':merge') || l.includes('merging')) return 60;
-    if (l.includes(':package') || l.includes('packaging')) return 72;
-    if (l.includes(':assemble') || l.includes('assembling')) return 85;
-    if (l.includes(':bundle') || l.includes('bundling')) return 85; // Handled AAB step
-    if (l.includes('signing') || l.includes(':sign')) return 92;
-    if (l.includes('build successful') || l.includes('build completed')) return 100;
-    return -1;
-  }, []);
+  // Stream a build's output into the log and settle the UI when its state event arrives.
+  // `getBuildId` may return null until the invoke resolves; events are filtered once it's known.
+  const watchBuild = async (getBuildId: () => string | null, startTime: number) => {
+    // Output arrives in batches of lines; other builds running alongside have theirs on the same event
+    const unlistenOutput = await listen<{ build_id: string; lines: OutputLine[] }>('build-output', (event) => {
+      if (getBuildId() && event.payload.build_id !== getBuildId()) return;
+      const lines = event.payload.lines.filter(line => line.text.trim());
+      if (lines.length === 0) return;
+      // Colour comes from the tool's own escape codes, stripped from the text by the backend
+      const formatted = lines.map(line => ({ text: `[${line.elapsed.toFixed(1)}s] ${line.text}`.slice(0, 120), color: line.color }));
+      setLogs(prev => [...formatted.reverse(), ...prev].slice(0, 151));
+    });
+
+    // Progress is estimated by the backend, which also mirrors it on the taskbar
+    const unlistenProgress = await listen<BuildProgressEvent>('build-progress', (event) => {
+      if (getBuildId() && event.payload.build_id !== getBuildId()) return;
+      setBuildProgress(event.payload.percent);
+    });
+
+    // Errors and warnings picked out of the output by the backend, kept until the next build
+    setBuildIssues([]);
+    const unlistenIssues = await listen<{ build_id: string; issues: BuildIssue[] }>('build-issues', (event) => {
+      if (getBuildId() && event.payload.build_id !== getBuildId()) return;
+      setBuildIssues(prev => [...prev, ...event.payload.issues].slice(-200));
+    });
+
+    // iOS builds may wait for a Mac another build is using
+    const unlistenQueue = await listen<{ build_id: string; mac: string; ahead: number }>('ios-queue', (event) => {
+      if (getBuildId() && event.payload.build_id !== getBuildId()) return;
+      setQueuedBehind(event.payload.ahead || null);
+    });
+
+    // Milestones arrive as their own events, no need to pick them out of the log text
+    const stageListeners = await Promise.all(([
+      ['sync-started', 'syncing'],
+      ['sync-complete', null],
+      ['build-started', 'building'],
+    ] as const).map(([name, stage]) => listen<LifecycleEvent>(name, (event) => {
+      if (getBuildId() && event.payload.build_id !== getBuildId()) return;
+      setBuildStage(stage);
+    })));
+
+    const unlistenState = await listen<LifecycleEvent>('build-finished', (event) => {
+      const { build_id, status: state, message, error } = event.payload;
+      if (getBuildId() && build_id !== getBuildId()) return;
+
+      if (state === 'finished') {
+        setBuildProgress(100);
+        const elapsed = ((Date.now() - startTime) / 1000).toFixed(1);
+        addLog(`✅ BUILD COMPLETE in ${elapsed}s! ${message ?? ''}`);
+        if (platform === 'android' && turboMode) {
+          addLog(`⚡ Direct Engine mode (~3x faster than standard)`);
+        }
+      } else {
+        addLog(`❌ ${message ?? state}`);
+        setBuildProgress(0);
+        if (error?.kind === 'low_disk_space') setLowMacDisk(error);
+        if (state === 'failed') setFailedBuildId(build_id);
+      }
+      setIsBuilding(false);
+      cleanup();
+    });
+
+    const cleanup = () => {
+      unlistenOutput();
+      unlistenProgress();
+      unlistenQueue();
+      unlistenIssues();
+      stageListeners.forEach(unlisten => unlisten());
+      unlistenState();
+      setQueuedBehind(null);
+      setBuildStage(null);
+      unlistenRef.current = null;
+    };
+    unlistenRef.current = cleanup;
+    return cleanup;
+  };
 
   const handleBuild = async () => {
     // Safety: Ensure no zombie listeners exist from previous runs
@@ -122,60 +776,111 @@ export default function App() {
     setBuildStartTime(startTime);
     addLog(`⚡ TURBO BUILD: ${hardware?.max_workers} workers, ${hardware?.jvm_heap_gb}GB heap (${buildType.toUpperCase()})`);
 
-    const unlisten = await listen<string>('build-output', (event) => {
-      const line = event.payload;
-      if (line.trim()) addLog(line.slice(0, 120));
-
-      const est = estimateProgress(line);
-      if (est > 0) setBuildProgress(prev => Math.max(prev, est));
-      else setBuildProgress(prev => Math.min(prev + 0.08, 95));
-
-      // Handle iOS Completion/Error signals
-      if (platform === 'ios') {
-        if (line.includes('✅') || line.includes('❌')) {
-          if (line.includes('✅')) setBuildProgress(100);
-          else setBuildProgress(0);
-          setIsBuilding(false);
-          unlisten();
-          unlistenRef.current = null; // Clear ref on clean exit
-        }
-      }
-    });
-    unlistenRef.current = unlisten;
+    let buildId: string | null = null;
+    const cleanup = await watchBuild(() => buildId, startTime);
 
     try {
       if (platform === 'android') {
-        await invoke("execute_build", { workingDir: projectPath, buildType, turboMode, customPath: customArchivePath || null });
-        setBuildProgress(100);
-        const elapsed = ((Date.now() - startTime) / 1000).toFixed(1);
-        addLog(`✅ BUILD COMPLETE in ${elapsed}s!`);
-        if (turboMode) {
-          addLog(`⚡ Direct Engine mode (~3x faster than standard)`);
-        }
+        buildId = await invoke<string>("execute_build", { workingDir: projectPath, buildType, turboMode, customPath: customArchivePath || null, verbosity, logLevel: gradleLogLevel, retryWithDiagnostics, installDependencies, useNvm, gates, engine: engineOverrides, releaseNotes: autoReleaseNotes });
+        setLastAndroidBuildId(buildId);
+        setEngineOverrides({ hermes: null, new_architecture: null });
       } else {
         addLog(`🍎 Connecting to Satellite: ${macConfig.ip}...`);
-        await invoke("start_ios_build", {
+        buildId = await invoke<string>("start_ios_build", {
           workingDir: projectPath,
           macConfig,
           remotePath: iosRemotePath,
           scheme: iosScheme,
-          buildType
+          buildType,
+          verbosity,
+          useNvm,
+          pullBack: iosPullBack.split(',').map(f => f.trim()).filter(Boolean)
         });
+        iosBuildIdRef.current = buildId;
         addLog(`📡 Sync & Build command sent.`);
       }
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setBuildProgress(0);
+      setIsBuilding(false);
+      cleanup();
+    }
+  };
+
+  const handleBuildPreset = async (name: string) => {
+    unlistenRef.current?.();
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+    addLog(`📋 Building with preset "${name}"`);
 
+    let buildId: string | null = null;
+    const cleanup = await watchBuild(() => buildId, startTime);
+    try {
+      buildId = await invoke<string>("build_with_preset", { workingDir: projectPath, name });
+      setLastAndroidBuildId(buildId);
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
       setBuildProgress(0);
       setIsBuilding(false);
-      unlisten();
-      unlistenRef.current = null;
-    } finally {
-      if (platform === 'android') {
-        unlisten();
-        unlistenRef.current = null;
-        setIsBuilding(false);
-      }
+      cleanup();
+    }
+  };
+
+  // The build settings as they are now, plus what only a preset sets
+  const handleSavePreset = async () => {
+    if (!presetDraft || buildType === 'simulator' || buildType === 'device') return;
+    const preset: BuildPreset = {
+      name: presetDraft.name.trim(),
+      build_type: buildType,
+      variant: presetDraft.variant.trim() || null,
+      turbo_mode: turboMode,
+      log_level: gradleLogLevel,
+      retry_with_diagnostics: retryWithDiagnostics,
+      install_dependencies: installDependencies,
+      use_nvm: useNvm,
+      gates,
+      engine: engineOverrides,
+      release_notes: autoReleaseNotes,
+      verbosity,
+      env_file: presetDraft.env_file.trim() || null,
+      custom_path: customArchivePath || null,
+      run_hooks: presetDraft.run_hooks,
+    };
+    try {
+      setBuildPresets(await invoke<BuildPreset[]>("save_build_preset", { workingDir: projectPath, preset }));
+      addLog(`📋 Saved preset "${preset.name}"`);
+      setPresetDraft(null);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleDeletePreset = async (name: string) => {
+    try {
+      setBuildPresets(await invoke<BuildPreset[]>("delete_build_preset", { workingDir: projectPath, name }));
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleReattachIos = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let buildId: string | null = null;
+    const cleanup = await watchBuild(() => buildId, startTime);
+    try {
+      buildId = await invoke<string>("reattach_ios_build", { macConfig, verbosity });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
     }
   };
 
@@ -185,70 +890,833 @@ export default function App() {
       await invoke("trigger_nuke_ios", { macConfig, remotePath: iosRemotePath });
       addLog("✅ Nuke sequence ignited.");
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
     }
   };
 
-  const handleAbort = async () => {
-    await invoke("abort_build").catch(() => { });
-    addLog("🛑 Build aborted.");
-    setIsBuilding(false);
+  // Space-hungry caches only: the project's Pods stay, so the next build doesn't start from scratch
+  const handleMacSelectiveClean = async () => {
+    const targets = ['derived_data', 'simulators', 'rn_temp'];
+    setLowMacDisk(null);
+    try {
+      const preview = await invoke<{ message: string }>("trigger_nuke_ios", { macConfig, remotePath: iosRemotePath, dryRun: true, targets });
+      addLog(`🧹 ${preview.message}`);
+      await invoke("trigger_nuke_ios", { macConfig, remotePath: iosRemotePath, targets });
+      addLog("✅ Selective clean started.");
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
   };
 
-  const handleNuke = async () => {
-    addLog("🧨 Nuking build directories...");
+  const handlePreviewSync = async () => {
+    setShowMaintenance(false);
+    addLog(`🔍 Checking what would be sent to ${macConfig.ip}...`);
     try {
-      const msg: string = await invoke("nuke_build", { workingDir: projectPath });
-      addLog(`✅ ${msg}`);
+      setSyncPreview(await invoke<SyncPreview>("preview_sync", { workingDir: projectPath, macConfig, remotePath: iosRemotePath }));
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
     }
-    setShowMaintenance(false);
   };
 
-  const handlePurge = async () => {
-    addLog("🔥 Shutting down WSL...");
+  const handleMacCacheReport = async () => {
+    setShowMaintenance(false);
+    addLog(`📏 Measuring Xcode caches on ${macConfig.ip}...`);
     try {
-      await invoke("purge_wsl");
-      addLog("✅ WSL shutdown.");
+      setMacCaches(await invoke<CacheEntry[]>("get_mac_cache_report", { macConfig }));
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
     }
-    setShowMaintenance(false);
   };
 
-  const handleOpenOutput = async () => {
+  const handleListLanes = async () => {
+    setShowMaintenance(false);
     try {
-      await invoke("open_build_archive", { workingDir: projectPath, customPath: customArchivePath || null });
-      addLog("📂 Opening APK folder...");
+      const lanes = await invoke<{ platform: string | null; name: string }[]>("list_fastlane_lanes", { workingDir: projectPath, platform });
+      if (lanes.length === 0) addLog(`🏁 No ${platform} lanes in the Fastfile`);
+      setFastlaneLanes(lanes);
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
     }
   };
 
-  const handleClearArchive = async () => {
-    addLog("🗑️ Clearing APK archive...");
+  const handleRunLane = async (lane: string) => {
+    setFastlaneLanes(null);
+    unlistenRef.current?.();
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let laneId: string | null = null;
+    const cleanup = await watchBuild(() => laneId, startTime);
     try {
-      const msg: string = await invoke("clear_archive", { workingDir: projectPath, customPath: customArchivePath || null });
-      addLog(`✅ ${msg}`);
+      laneId = await invoke<string>("run_fastlane", {
+        workingDir: projectPath,
+        lane,
+        platform,
+        macConfig: platform === 'ios' ? macConfig : null,
+        remotePath: platform === 'ios' ? iosRemotePath : null,
+        verbosity,
+        useNvm
+      });
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
     }
+  };
+
+  const handleEditReleaseNotes = async () => {
     setShowMaintenance(false);
+    try {
+      setReleaseNotes(await invoke<string>("generate_release_notes", { workingDir: projectPath, platform: 'android' }));
+    } catch (err) {
+      addLog(`⚠️ No git log: ${errorMessage(err)}`);
+      setReleaseNotes('');
+    }
   };
 
-  const handleArchivePathChange = (newPath: string) => {
+  const handleSaveReleaseNotes = async () => {
+    if (releaseNotes === null || !lastAndroidBuildId) return;
+    try {
+      const msg = await invoke<string>("set_release_notes", { workingDir: projectPath, customPath: customArchivePath || null, buildId: lastAndroidBuildId, text: releaseNotes });
+      addLog(`📝 ${msg}`);
+      setReleaseNotes(null);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleSymbolicate = async () => {
+    if (!crash || !crash.buildId.trim() || !crash.text.trim()) return;
+    try {
+      const result = await invoke<{ platform: string; frames: number; resolved: number; text: string }>("symbolicate", {
+        workingDir: projectPath,
+        customPath: customArchivePath || null,
+        buildId: crash.buildId.trim(),
+        stacktraceText: crash.text,
+        macConfig: platform === 'ios' ? macConfig : null,
+        dsymPath: platform === 'ios' ? crash.dsymPath || null : null,
+      });
+      addLog(`🧩 Symbolicated ${result.resolved}/${result.frames} frame(s) (${result.platform})`);
+      setCrash({ ...crash, result: result.text });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleEditBuildEnv = async () => {
+    setShowMaintenance(false);
+    try {
+      setBuildEnv(await invoke<{ name: string; value: string; secret: boolean }[]>("get_build_env", { workingDir: projectPath }));
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleSaveBuildEnv = async () => {
+    if (!buildEnv) return;
+    const vars = buildEnv.filter(v => v.name.trim()).map(v => ({ ...v, name: v.name.trim() }));
+    try {
+      await invoke("set_build_env", { workingDir: projectPath, vars });
+      addLog(`🔑 Saved ${vars.length} build variable(s)${vars.some(v => v.secret) ? ', secrets in Credential Manager' : ''}`);
+      setBuildEnv(null);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleEditPush = async () => {
+    setShowMaintenance(false);
+    try {
+      setPushConfig({ ...await invoke<PushConfig>("get_push_config"), token: '' });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleSavePush = async (test: boolean) => {
+    if (!pushConfig) return;
+    const { token, ...config } = pushConfig;
+    try {
+      const saved = await invoke<PushConfig>("set_push_config", { config, token: token || null });
+      setPushConfig({ ...saved, token: '' });
+      if (test) {
+        await invoke("test_push_notification");
+        addLog("📱 Test notification sent");
+      } else {
+        addLog(saved.provider === 'off' ? "📱 Phone notifications off" : `📱 Builds will be pushed via ${saved.provider}`);
+        setPushConfig(null);
+      }
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleExportSettings = async () => {
+    setShowMaintenance(false);
+    const path = await save({ defaultPath: 'hyperzenith-settings.json', filters: [{ name: 'JSON', extensions: ['json'] }] });
+    if (!path) return;
+    const settings: Record<string, string> = {};
+    for (let i = 0; i < localStorage.length; i++) {
+      const key = localStorage.key(i);
+      if (key?.startsWith('hyperzenith_')) settings[key] = localStorage.getItem(key) ?? '';
+    }
+    try {
+      const count = await invoke<number>("export_settings", { path, settings });
+      addLog(`📤 Exported ${count} setting(s) to ${path} (passwords left out)`);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  // Everything an issue about the last failed build needs, in one zip next to the logs
+  const handleSupportBundle = async () => {
+    setShowMaintenance(false);
+    if (!failedBuildId) return;
+    const upload = window.confirm('Also upload the report and the end of the build log to paste.rs for a shareable link? Anyone with the link can read it.');
+    const settings: Record<string, string> = {};
+    for (let i = 0; i < localStorage.length; i++) {
+      const key = localStorage.key(i);
+      if (key?.startsWith('hyperzenith_')) settings[key] = localStorage.getItem(key) ?? '';
+    }
+    addLog("🧰 Collecting diagnostics...");
+    try {
+      const bundle = await invoke<{ path: string; files: string[]; bytes: number; upload_url: string | null }>("create_support_bundle", { buildId: failedBuildId, settings, upload });
+      addLog(`🧰 Support bundle saved to ${bundle.path} (${bundle.files.join(', ')}, ${(bundle.bytes / 1024).toFixed(0)} KB)`);
+      if (bundle.upload_url) addLog(`🔗 Report uploaded: ${bundle.upload_url}`);
+      if (projectPath) await invoke("open_logs_folder", { workingDir: projectPath });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleImportSettings = async () => {
+    setShowMaintenance(false);
+    const path = await open({ multiple: false, filters: [{ name: 'JSON', extensions: ['json'] }], title: 'Import HyperZenith settings' });
+    if (!path || typeof path !== 'string') return;
+    if (!window.confirm('Replace the current settings with the imported ones?')) return;
+    try {
+      const bundle = await invoke<{ app_version: string; settings: Record<string, unknown>; projects: string[] }>("import_settings", { path });
+      for (const [key, value] of Object.entries(bundle.settings)) {
+        localStorage.setItem(key, typeof value === 'string' ? value : JSON.stringify(value));
+      }
+      // Passwords aren't exported; keep the ones already entered for the same Mac
+      const imported = bundle.settings['hyperzenith_mac_config'];
+      if (typeof imported === 'string' && macConfig.ip) {
+        const next = JSON.parse(imported);
+        if (next.ip === macConfig.ip && !next.password) next.password = macConfig.password;
+        localStorage.setItem('hyperzenith_mac_config', JSON.stringify(next));
+      }
+      addLog(`📥 Imported settings from HyperZenith ${bundle.app_version}, reloading...`);
+      window.location.reload();
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCheckUpdates = async () => {
+    setShowMaintenance(false);
+    try {
+      const info = await invoke<UpdateInfo>("check_for_updates");
+      if (info.available) {
+        setUpdate(info);
+        addLog(`⬆️ HyperZenith ${info.latest} is available (you have ${info.current})`);
+      } else {
+        addLog(`✅ HyperZenith ${info.current} is up to date`);
+      }
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleInstallUpdate = async () => {
+    if (!update?.installer_url || !update.installer_name) {
+      if (update) await openUrl(update.release_url);
+      return;
+    }
+    if (isBuilding && !window.confirm('A build is running. Quit and install the update anyway?')) return;
+    addLog(`⬆️ Downloading ${update.installer_name}...`);
+    try {
+      await invoke("install_update", { installerName: update.installer_name });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleKeepMacAwake = async (minutes: number) => {
+    setShowMaintenance(false);
+    try {
+      const msg = await invoke<string>("keep_mac_awake", { macConfig, durationSecs: minutes * 60 });
+      addLog(`☕ ${msg}`);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCaptureSimulator = async (kind: 'screenshot' | 'recording') => {
+    setShowMaintenance(false);
+    addLog(kind === 'screenshot' ? '📸 Launching the app and taking a screenshot...' : '🎥 Launching the app and recording 15s...');
+    try {
+      const capture = await invoke<{ kind: string; path: string }>("capture_simulator", {
+        workingDir: projectPath,
+        macConfig,
+        scheme: iosScheme,
+        simulator: iosTestSimulator || null,
+        kind,
+        durationSecs: 15
+      });
+      addLog(`✅ Saved to: ${capture.path}`);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleAbort = async () => {
+    // iOS builds can be stopped while queued for the Mac, syncing or building on it
+    if (platform === 'ios' && iosBuildIdRef.current) {
+      await invoke("cancel_ios_pipeline", { buildId: iosBuildIdRef.current }).catch(() => { });
+      iosBuildIdRef.current = null;
+    } else {
+      await invoke("abort_build").catch(() => { });
+    }
+    addLog("🛑 Build aborted.");
+    setIsBuilding(false);
+  };
+
+  const handleNuke = async () => {
+    addLog("🧨 Nuking build directories...");
+    try {
+      const targets = nukeJsTargets.length > 0 ? ['app/build', 'build', '.gradle', ...nukeJsTargets] : null;
+      const report: { message: string } = await invoke("nuke_build", { workingDir: projectPath, targets });
+      addLog(`✅ ${report.message}`);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+    setShowMaintenance(false);
+  };
+
+  const handleCheckPorts = async () => {
+    setShowMaintenance(false);
+    try {
+      const conflicts = await invoke<PortConflict[]>("check_ports");
+      addLog(conflicts.length === 0 ? "🔌 Dev ports are free" : `🔌 ${conflicts.length} dev port(s) in use`);
+      setPortConflicts(conflicts.length === 0 ? null : conflicts);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleKillPort = async (port: number) => {
+    try {
+      const killed = await invoke<PortConflict[]>("kill_port_process", { port });
+      addLog(`🔌 Freed port ${port}: ${killed.map(k => `${k.process} (${k.pid})`).join(', ')}`);
+      setPortConflicts(prev => prev?.filter(c => c.port !== port) ?? null);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCheckLongPaths = async () => {
+    setShowMaintenance(false);
+    addLog("📏 Measuring project paths...");
+    try {
+      const status = await invoke<LongPathStatus>("check_long_paths", { workingDir: projectPath });
+      addLog(status.warning ? `📏 ${status.warning}` : `📏 Deepest path is ${status.deepest_len} characters, long paths ${status.enabled ? 'enabled' : 'off'}`);
+      setLongPaths(status);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleEnableLongPaths = async () => {
+    try {
+      const status = await invoke<LongPathStatus>("enable_long_paths", { workingDir: projectPath });
+      addLog(status.enabled ? "📏 Long paths enabled, restart open terminals and editors to pick it up" : "⚠️ Long paths still look disabled");
+      setLongPaths(status.enabled ? null : status);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleReclaimMemory = async () => {
+    setShowMaintenance(false);
+    addLog("🧽 Dropping WSL page cache...");
+    try {
+      await invoke("reclaim_wsl_memory", { workingDir: projectPath || null });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  // Walk through WSL, JDK, Node, SDK and the doctor; `install` lists the steps allowed to install what's missing
+  const runOnboarding = async (install: OnboardingStep['step'][] = []) => {
+    setOnboarding([]);
+    setOnboardingBusy(true);
+    const unlisten = await listen<OnboardingStep>('onboarding-progress', (event) => {
+      setOnboarding(prev => [...(prev || []).filter(s => s.step !== event.payload.step), event.payload].sort((a, b) => a.index - b.index));
+    });
+    try {
+      const report: { steps: OnboardingStep[]; ready: boolean } = await invoke('initialize_environment', { workingDir: projectPath || null, install });
+      setOnboarding(report.steps);
+      if (report.ready) addLog('🧭 Environment ready to build');
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    } finally {
+      unlisten();
+      setOnboardingBusy(false);
+    }
+  };
+
+  const dismissOnboarding = () => {
+    localStorage.setItem('hyperzenith_onboarded', 'true');
+    setOnboarding(null);
+  };
+
+  useEffect(() => {
+    if (!localStorage.getItem('hyperzenith_onboarded')) runOnboarding();
+  }, []);
+
+  // Show what a purge would take down (Docker included) before stopping anything
+  const handlePurge = async () => {
+    setShowMaintenance(false);
+    try {
+      const impact: PurgeImpact = await invoke("purge_wsl_impact");
+      if (impact.distros.length === 0) {
+        addLog("✅ WSL isn't running, nothing to purge.");
+        return;
+      }
+      setPurgeImpact(impact);
+      setPurgeSelection(impact.distros.filter(d => !d.docker).map(d => d.name));
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  // `distros` null shuts the whole VM down
+  const handleConfirmPurge = async (distros: string[] | null) => {
+    setPurgeImpact(null);
+    addLog(distros ? `🔥 Terminating ${distros.join(', ')}...` : "🔥 Shutting down WSL...");
+    try {
+      const msg: string = await invoke("purge_wsl", { distros });
+      addLog(`✅ ${msg}`);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleOpenOutput = async () => {
+    try {
+      await invoke("open_build_archive", { workingDir: projectPath, customPath: customArchivePath || null });
+      addLog("📂 Opening APK folder...");
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleClearArchive = async () => {
+    addLog("🗑️ Clearing APK archive...");
+    try {
+      const msg: string = await invoke("clear_archive", { workingDir: projectPath, customPath: customArchivePath || null });
+      addLog(`✅ ${msg}`);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+    setShowMaintenance(false);
+  };
+
+  const handleArchivePathChange = (newPath: string) => {
     setCustomArchivePath(newPath);
     localStorage.setItem('hyperzenith_archive_path', newPath);
   };
 
-  const handleOpenLogs = async () => {
+  // Shares and USB drives come and go; re-check whenever the target changes or a build ends
+  useEffect(() => {
+    if (!projectPath) return;
+    invoke<typeof archiveStorage>("get_archive_storage", { workingDir: projectPath, customPath: customArchivePath || null })
+      .then(setArchiveStorage)
+      .catch(() => setArchiveStorage(null));
+  }, [projectPath, customArchivePath, isBuilding]);
+
+  const handleFlushPending = async () => {
+    try {
+      const msg = await invoke<string>("flush_archive_pending", { workingDir: projectPath, customPath: customArchivePath || null });
+      addLog(`📦 ${msg}`);
+    } catch (err) {
+      addLog(`❌ Archive still unavailable: ${errorMessage(err)}`);
+    }
+    setArchiveStorage(await invoke<typeof archiveStorage>("get_archive_storage", { workingDir: projectPath, customPath: customArchivePath || null }));
+  };
+
+  // Android and iOS logs share one folder per project, inside it unless moved
+  useEffect(() => {
+    if (!projectPath) return;
+    invoke<string>("get_logs_folder", { workingDir: projectPath }).then(setLogsFolder).catch(() => setLogsFolder(''));
+  }, [projectPath]);
+
+  useEffect(() => {
+    setPresetDraft(null);
+    if (!projectPath) { setBuildPresets([]); return; }
+    invoke<BuildPreset[]>("list_build_presets", { workingDir: projectPath }).then(setBuildPresets).catch(() => setBuildPresets([]));
+  }, [projectPath]);
+
+  const handleLogsFolderChange = async (folder: string | null) => {
+    try {
+      setLogsFolder(await invoke<string>("set_logs_folder", { workingDir: projectPath, folder }));
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleListLogs = async (platform: 'android' | 'ios' | null) => {
+    setShowMaintenance(false);
+    try {
+      setSavedLogs({ platform, logs: await invoke<LogFile[]>("list_logs", { workingDir: projectPath, platform }) });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleOpenLogs = async () => {
+    try {
+      await invoke("open_logs_folder", { workingDir: projectPath });
+      addLog("📂 Opening logs folder...");
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleOpenIssue = async (issue: BuildIssue) => {
+    if (!issue.file) return;
+    try {
+      await invoke("open_in_editor", { path: issue.file, line: issue.line, column: issue.column });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleToggleControlApi = async () => {
+    try {
+      if (controlUrl) {
+        addLog(`🛰️ ${await invoke<string>("stop_control_server")}`);
+        setControlUrl(null);
+      } else {
+        const info = await invoke<{ port: number; token: string; url: string }>("start_control_server", {});
+        setControlUrl(info.url);
+        addLog(`🛰️ Control API listening: ${info.url}`);
+      }
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleRunTests = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog("🧪 Running Jest...");
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let testId: string | null = null;
+    const cleanup = await watchBuild(() => testId, startTime);
+    const unlistenSummary = await listen<{ id: string; tests_passed: number; tests_failed: number; tests_skipped: number; failed_files: string[] }>('test-summary', (event) => {
+      const { id, tests_passed, tests_failed, tests_skipped, failed_files } = event.payload;
+      if (testId && id !== testId) return;
+      addLog(`🧪 ${tests_passed} passed, ${tests_failed} failed, ${tests_skipped} skipped`);
+      failed_files.forEach(f => addLog(`   ❌ ${f}`));
+      unlistenSummary();
+    });
+    try {
+      testId = await invoke<string>("run_tests", { workingDir: projectPath, pattern: null, verbosity, useNvm });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenSummary();
+    }
+  };
+
+  const handleRunIosTests = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog(`🧪 Running ${iosScheme} tests on ${macConfig.ip}...`);
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let testId: string | null = null;
+    const cleanup = await watchBuild(() => testId, startTime);
+    const unlistenSummary = await listen<{ id: string; tests_passed: number; tests_failed: number; tests_skipped: number; failed_tests: string[]; result_bundle: string | null }>('ios-test-summary', (event) => {
+      const { id, tests_passed, tests_failed, tests_skipped, failed_tests, result_bundle } = event.payload;
+      if (testId && id !== testId) return;
+      addLog(`🧪 ${tests_passed} passed, ${tests_failed} failed, ${tests_skipped} skipped`);
+      failed_tests.forEach(t => addLog(`   ❌ ${t}`));
+      if (result_bundle) addLog(`📦 ${result_bundle}`);
+      unlistenSummary();
+    });
+    try {
+      testId = await invoke<string>("run_ios_tests", {
+        workingDir: projectPath,
+        macConfig,
+        remotePath: iosRemotePath,
+        scheme: iosScheme,
+        simulator: iosTestSimulator || null,
+        verbosity,
+        useNvm
+      });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenSummary();
+    }
+  };
+
+  const handleCreateProject = async () => {
+    if (!newProject) return;
+    const parentDir = await open({ directory: true, multiple: false, title: 'Create the project in...' });
+    if (!parentDir || typeof parentDir !== 'string') return;
+    unlistenRef.current?.();
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    const unlistenCreated = await listen<{ id: string; path: string }>('project-created', (event) => {
+      if (runId && event.payload.id !== runId) return;
+      setProjectPath(event.payload.path);
+      unlistenCreated();
+    });
+    try {
+      runId = newProject.kind === 'git'
+        ? await invoke<string>("clone_project", { url: newProject.name, dest: parentDir, verbosity, useNvm })
+        : await invoke<string>("create_project", { parentDir, name: newProject.name, kind: newProject.kind, verbosity, useNvm });
+      setNewProject(null);
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenCreated();
+    }
+  };
+
+  const handleShowAnalytics = async () => {
+    setShowMaintenance(false);
+    try {
+      const stats = await invoke<ProjectAnalytics[]>('get_build_analytics', { workingDir: projectPath });
+      if (stats.length === 0) {
+        addLog("📊 No builds recorded for this project yet.");
+        setAnalytics(null);
+      } else {
+        setAnalytics(stats[0]);
+      }
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+    }
+  };
+
+  const handleCheckDependencies = async () => {
+    setCheckingDeps(true);
+    const [audit, outdated] = await Promise.allSettled([
+      invoke<DependencyReport['audit']>('audit_dependencies', { workingDir: projectPath, useNvm }),
+      invoke<DependencyReport['outdated']>('outdated_dependencies', { workingDir: projectPath, useNvm }),
+    ]);
+    setDepReport({
+      audit: audit.status === 'fulfilled' ? audit.value : null,
+      auditError: audit.status === 'rejected' ? errorMessage(audit.reason) : null,
+      outdated: outdated.status === 'fulfilled' ? outdated.value : [],
+    });
+    if (outdated.status === 'rejected') addLog(`⚠️ npm outdated: ${errorMessage(outdated.reason)}`);
+    setCheckingDeps(false);
+  };
+
+  const handlePublishOta = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog(`📡 Publishing OTA update${otaBranch ? ` to ${otaBranch}` : ''}...`);
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    try {
+      runId = await invoke<string>("publish_ota", { workingDir: projectPath, options: { branch: otaBranch || null, platform: 'android' }, verbosity, useNvm });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+    }
+  };
+
+  const handleRefreshDependencies = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog(`🔄 Refreshing Gradle dependencies...`);
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    try {
+      runId = await invoke<string>("refresh_dependencies", { workingDir: projectPath, verbosity, useNvm });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+    }
+  };
+
+  const handleCompareCommits = async () => {
+    if (!compareCommits.a.trim() || !compareCommits.b.trim()) return;
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog(`🔀 Comparing ${compareCommits.a} → ${compareCommits.b} (two full builds)`);
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    type CommitBuild = { commit: string; subject: string; success: boolean; duration_secs: number; artifact_bytes: number };
+    const unlistenSummary = await listen<{ id: string; a: CommitBuild; b: CommitBuild }>('commit-comparison', (event) => {
+      if (runId && event.payload.id !== runId) return;
+      [event.payload.a, event.payload.b].forEach(c => addLog(`   ${c.success ? '✅' : '❌'} ${c.commit.slice(0, 8)} ${c.subject} (${Math.round(c.duration_secs)}s, ${(c.artifact_bytes / 1048576).toFixed(2)} MB)`));
+      unlistenSummary();
+    });
+    try {
+      runId = await invoke<string>("compare_commits", { workingDir: projectPath, shaA: compareCommits.a.trim(), shaB: compareCommits.b.trim(), customPath: customArchivePath || null, verbosity, logLevel: gradleLogLevel, useNvm });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenSummary();
+    }
+  };
+
+  const handleBuildMatrix = async () => {
+    // "stagingDebug:apk, prodRelease:aab" → one entry per comma-separated variant:type pair
+    const entries = matrixSpec.split(',').map(s => s.trim()).filter(Boolean).map(s => {
+      const [variant, type] = s.split(':').map(p => p.trim());
+      return { variant: variant || null, build_type: (type || 'apk').toLowerCase() };
+    });
+    if (entries.length === 0) return;
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog(`🧮 Build matrix: ${entries.length} build(s)`);
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    const unlistenSummary = await listen<{ id: string; results: { entry: { variant: string | null; build_type: string }; success: boolean; elapsed_secs: number; artifacts: string[] }[] }>('matrix-summary', (event) => {
+      if (runId && event.payload.id !== runId) return;
+      event.payload.results.forEach(r => addLog(`   ${r.success ? '✅' : '❌'} ${r.entry.variant ?? 'debug'} ${r.entry.build_type.toUpperCase()} (${Math.round(r.elapsed_secs)}s, ${r.artifacts.length} artifact(s))`));
+      unlistenSummary();
+    });
+    try {
+      runId = await invoke<string>("build_matrix", { workingDir: projectPath, entries, customPath: customArchivePath || null, verbosity, logLevel: gradleLogLevel, installDependencies, useNvm, gates });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenSummary();
+    }
+  };
+
+  const handleAnalyzeBundle = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    setBundleReport(null);
+    addLog("📦 Analyzing release JS bundle...");
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    const unlistenReport = await listen<BundleReport>('bundle-report', (event) => {
+      if (runId && event.payload.id !== runId) return;
+      setBundleReport(event.payload);
+      unlistenReport();
+    });
+    try {
+      runId = await invoke<string>("analyze_bundle", { workingDir: projectPath, breakdown: true, verbosity, useNvm });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenReport();
+    }
+  };
+
+  const handleRunE2e = async () => {
+    setShowMaintenance(false);
+    unlistenRef.current?.();
+    addLog("🎬 Running E2E tests on the latest APK...");
+    setIsBuilding(true);
+    setBuildProgress(0);
+    const startTime = Date.now();
+    setBuildStartTime(startTime);
+
+    let runId: string | null = null;
+    const cleanup = await watchBuild(() => runId, startTime);
+    const unlistenSummary = await listen<{ id: string; passed: number; failed: number; failed_flows: string[]; artifacts_dir: string | null }>('e2e-summary', (event) => {
+      const { id, passed, failed, failed_flows, artifacts_dir } = event.payload;
+      if (runId && id !== runId) return;
+      addLog(`🎬 ${passed} passed, ${failed} failed`);
+      failed_flows.forEach(f => addLog(`   ❌ ${f}`));
+      if (artifacts_dir) addLog(`🖼️ Artifacts: ${artifacts_dir}`);
+      unlistenSummary();
+    });
+    try {
+      runId = await invoke<string>("run_e2e", { workingDir: projectPath, customPath: customArchivePath || null, verbosity });
+    } catch (err) {
+      addLog(`❌ ${errorMessage(err)}`);
+      setIsBuilding(false);
+      cleanup();
+      unlistenSummary();
+    }
+  };
+
+  const handleToggleIdleBuild = async () => {
     try {
-      await invoke("open_logs_folder", { workingDir: projectPath });
-      addLog("📂 Opening logs folder...");
+      if (idleJob) {
+        await invoke<boolean>("cancel_idle_job");
+        setIdleJob(null);
+        addLog("💤 Idle build cancelled.");
+      } else {
+        const info = await invoke<IdleJobInfo>("queue_idle_job", {
+          workingDir: projectPath,
+          kind: 'build',
+          buildType,
+          turboMode,
+          customPath: customArchivePath || null,
+          verbosity,
+          logLevel: gradleLogLevel,
+          installDependencies,
+          useNvm,
+          gates
+        });
+        setIdleJob(info);
+        addLog(`💤 ${info.build_type?.toUpperCase()} build queued for when the system is idle (${info.settings.idle_minutes} min).`);
+      }
     } catch (err) {
-      addLog(`❌ ${err}`);
+      addLog(`❌ ${errorMessage(err)}`);
     }
+    setShowMaintenance(false);
   };
 
   const memPercent = stats ? Math.round((stats.used_memory / stats.total_memory) * 100) : 0;
@@ -263,10 +1731,35 @@ export default function App() {
             HYPER<span className="text-cyan-400">ZENITH</span>
           </h1>
           <span className="text-[9px] font-medium px-1.5 py-0.5 bg-cyan-500/20 text-cyan-400 rounded">V1.4.6</span>
+          {update && (
+            <button onClick={handleInstallUpdate} title={update.notes || 'Download and install the new release'} className="text-[9px] font-bold px-1.5 py-0.5 bg-emerald-500/20 text-emerald-400 rounded hover:bg-emerald-500/30">
+              ⬆ UPDATE TO V{update.latest}
+            </button>
+          )}
         </div>
         <div className="flex items-center gap-5 text-[11px]">
           {hardware && (
-            <span className="text-slate-500">{hardware.cpu_cores} cores • {hardware.total_ram_gb}GB</span>
+            <span
+              className="text-slate-500"
+              title={`${hardware.idle_cores} cores idle, ${hardware.available_ram_gb}GB free at launch; builds size themselves to the load when they start`}
+            >
+              {hardware.arch === 'arm64' && 'ARM64 • '}
+              {hardware.cpu_cores} cores{hardware.efficiency_cores > 0 && ` (${hardware.performance_cores}P+${hardware.efficiency_cores}E)`} • {hardware.total_ram_gb}GB
+            </span>
+          )}
+          {hardware?.emulator && (
+            <span
+              className={hardware.emulator.warnings.length ? 'text-amber-400' : 'text-slate-500'}
+              title={[
+                ...hardware.emulator.gpus.map(g => `${g.name}${g.driver_version ? ` (driver ${g.driver_version})` : ''}`),
+                `System images: ${hardware.emulator.system_image_abi}`,
+                ...hardware.emulator.warnings.map(w => `⚠️ ${w}`),
+              ].join('\n')}
+            >
+              {hardware.emulator.warnings.length ? '⚠️ ' : '🎮 '}
+              {hardware.emulator.accelerator ? hardware.emulator.accelerator.toUpperCase() : 'No accel'}
+              {hardware.emulator.software_rendering && ' • SW render'}
+            </span>
           )}
           <span className={engineStatus === 'Ready' ? 'text-emerald-400' : 'text-amber-400'}>● {engineStatus}</span>
         </div>
@@ -316,17 +1809,11 @@ export default function App() {
                 <button
                   onClick={async () => {
                     try {
-                      const found: string[] = await invoke('scan_for_projects', { startPath: projectPath || "C:\\" });
-                      if (found.length === 0) {
-                        addLog("🔍 No Android projects found nearby.");
-                      } else if (found.length === 1) {
-                        setProjectPath(found[0]);
-                        addLog("✨ Auto-detected project!");
-                      } else {
-                        setScanResults(found);
-                        setShowScanResults(true);
-                        addLog(`🔍 Found ${found.length} projects.`);
-                      }
+                      // Last scan's results show at once, the rescan streams in through projects-discovered
+                      const cached: DiscoveredProject[] = await invoke('scan_for_projects', { startPath: projectPath || "C:\\" });
+                      setScanResults(cached);
+                      setIsScanning(true);
+                      setShowScanResults(true);
                     } catch (err) {
                       console.error(err);
                     }
@@ -336,19 +1823,86 @@ export default function App() {
                 >
                   🪄
                 </button>
+                <button
+                  onClick={handleShowRecents}
+                  className="px-2 bg-slate-800 hover:bg-slate-700 active:bg-slate-950 active:scale-95 text-slate-300 text-[10px] font-bold rounded border border-slate-700 hover:border-slate-600 transition-all"
+                  title="Favorites & Recent Projects"
+                >
+                  🕘
+                </button>
+                <button
+                  onClick={() => setNewProject(newProject ? null : { name: '', kind: 'expo' })}
+                  disabled={isBuilding}
+                  className="px-2 bg-slate-800 hover:bg-slate-700 active:bg-slate-950 active:scale-95 text-slate-300 text-[10px] font-bold rounded border border-slate-700 hover:border-slate-600 transition-all disabled:opacity-40"
+                  title="Create New Project"
+                >
+                  🌱
+                </button>
               </div>
 
+              {/* New Project */}
+              {newProject && (
+                <div className="mt-1 p-2 bg-slate-900/60 border border-slate-800 rounded space-y-1">
+                  <input
+                    type="text"
+                    value={newProject.name}
+                    onChange={(e) => setNewProject({ ...newProject, name: e.target.value })}
+                    placeholder={newProject.kind === 'git' ? 'https://github.com/org/app.git' : newProject.kind === 'expo' ? 'my-app' : 'MyApp'}
+                    className="w-full bg-slate-900 border border-slate-700 px-2 py-1 text-[9px] text-slate-300 outline-none rounded placeholder:text-slate-600"
+                  />
+                  <div className="flex gap-1">
+                    <select value={newProject.kind} onChange={e => setNewProject({ ...newProject, kind: e.target.value as 'expo' | 'react-native' | 'git' })}
+                      className="flex-1 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                      <option value="expo">Expo</option>
+                      <option value="react-native">React Native</option>
+                      <option value="git">Clone Git URL</option>
+                    </select>
+                    <button onClick={handleCreateProject} disabled={!newProject.name.trim()} className="px-2 py-0.5 text-[9px] font-bold bg-emerald-600/80 hover:bg-emerald-500 text-black rounded disabled:opacity-40">
+                      Create in...
+                    </button>
+                  </div>
+                </div>
+              )}
+
+              {/* Favorites & Recent Projects */}
+              {recentProjects && (
+                <div className="absolute z-50 mt-1 w-64 bg-slate-900 border border-slate-700 rounded-lg shadow-xl overflow-hidden">
+                  <div className="px-2 py-1 text-[9px] font-bold text-slate-500 bg-slate-950/50 border-b border-slate-800">favorites & recent</div>
+                  {recentProjects.length === 0 && <div className="px-3 py-2 text-[9px] text-slate-600">Nothing built yet</div>}
+                  {recentProjects.map(r => (
+                    <div key={r.path} className="flex items-center hover:bg-cyan-900/20">
+                      <button onClick={() => handlePinProject(r.path, !r.pinned)} title={r.pinned ? 'Unpin' : 'Pin as favorite'}
+                        className={`pl-2 text-[10px] ${r.pinned ? 'text-amber-400' : 'text-slate-600 hover:text-amber-400'}`}>{r.pinned ? '★' : '☆'}</button>
+                      <button
+                        onClick={() => { setProjectPath(r.path); setRecentProjects(null); }}
+                        disabled={!r.exists}
+                        title={r.path}
+                        className="flex-1 min-w-0 text-left px-2 py-2 text-[10px] text-slate-300 hover:text-cyan-400 transition-colors truncate disabled:opacity-40"
+                      >
+                        {r.last_outcome === 'success' ? '✅' : r.last_outcome === 'failed' ? '❌' : r.last_outcome === 'aborted' ? '🛑' : '·'} {r.path.split('\\').pop() || r.path}
+                        {r.last_built_at && <span className="text-slate-600 ml-1 text-[9px]">{r.last_platform} · {new Date(r.last_built_at).toLocaleString()}</span>}
+                      </button>
+                    </div>
+                  ))}
+                  {projectPath && !recentProjects.some(r => r.path === projectPath) && (
+                    <button onClick={() => handlePinProject(projectPath, true)} className="w-full text-center py-1 text-[9px] text-amber-500 hover:text-amber-300 border-t border-slate-800">☆ pin current project</button>
+                  )}
+                  <button onClick={() => setRecentProjects(null)} className="w-full text-center py-1 text-[9px] text-slate-500 hover:text-slate-300 border-t border-slate-800">close</button>
+                </div>
+              )}
+
               {/* Scan Results Dropdown */}
-              {showScanResults && scanResults.length > 0 && (
+              {showScanResults && (scanResults.length > 0 || isScanning) && (
                 <div className="absolute z-50 mt-1 w-64 bg-slate-900 border border-slate-700 rounded-lg shadow-xl overflow-hidden">
-                  <div className="px-2 py-1 text-[9px] font-bold text-slate-500 bg-slate-950/50 border-b border-slate-800">select project</div>
+                  <div className="px-2 py-1 text-[9px] font-bold text-slate-500 bg-slate-950/50 border-b border-slate-800">select project{isScanning && <span className="text-cyan-600 ml-1 animate-pulse">scanning…</span>}</div>
                   {scanResults.map((p, i) => (
                     <button
                       key={i}
-                      onClick={() => { setProjectPath(p); setShowScanResults(false); }}
+                      onClick={() => { setProjectPath(p.path); setShowScanResults(false); }}
                       className="w-full text-left px-3 py-2 text-[10px] text-slate-300 hover:bg-cyan-900/20 hover:text-cyan-400 transition-colors truncate"
+                      title={[p.android && 'Android', p.ios && 'iOS'].filter(Boolean).join(' + ')}
                     >
-                      {p.split('\\').pop() || p} <span className="text-slate-600 ml-1 text-[9px]">({p})</span>
+                      {p.android && '🤖'}{p.ios && '🍎'} {p.path.split('\\').pop() || p.path} <span className="text-slate-600 ml-1 text-[9px]">({p.path})</span>
                     </button>
                   ))}
                   <button onClick={() => setShowScanResults(false)} className="w-full text-center py-1 text-[9px] text-slate-500 hover:text-slate-300 border-t border-slate-800">cancel</button>
@@ -356,6 +1910,197 @@ export default function App() {
               )}
             </div>
 
+            {/* Project Info */}
+            {projectInfo && (projectInfo.expo_sdk || projectInfo.react_native) && (
+              <div className="p-2 bg-slate-900/40 border border-slate-800 rounded space-y-1 text-[9px] text-slate-400">
+                {projectInfo.name && <div className="text-slate-300 font-bold truncate">{projectInfo.name}</div>}
+                {projectInfo.expo_sdk && <div>Expo SDK <span className="text-slate-200">{projectInfo.expo_sdk}</span></div>}
+                {projectInfo.react_native && <div>React Native <span className="text-slate-200">{projectInfo.react_native}</span></div>}
+                {(projectInfo.hermes !== null || projectInfo.new_architecture !== null) && (
+                  <div>
+                    JS engine <span className="text-slate-200">{projectInfo.hermes === null ? '?' : projectInfo.hermes ? 'Hermes' : 'JSC'}</span>
+                    {' · '}New Arch <span className="text-slate-200">{projectInfo.new_architecture === null ? '?' : projectInfo.new_architecture ? 'on' : 'off'}</span>
+                  </div>
+                )}
+                {projectInfo.gradle && <div>Gradle <span className="text-slate-200">{projectInfo.gradle}</span>{projectInfo.agp && <> · AGP <span className="text-slate-200">{projectInfo.agp}</span></>}</div>}
+                {projectInfo.advisories.map((a, i) => (
+                  <div key={i} className={a.severity === 'warning' ? 'text-amber-400' : 'text-cyan-400'}>
+                    {a.severity === 'warning' ? '⚠️' : 'ℹ️'} {a.message}
+                  </div>
+                ))}
+                {depReport ? (
+                  <div className="pt-1 border-t border-slate-800 space-y-0.5">
+                    {depReport.audit && (
+                      <div className={depReport.audit.critical + depReport.audit.high > 0 ? 'text-red-400' : 'text-slate-400'}>
+                        🛡️ {depReport.audit.critical} critical · {depReport.audit.high} high · {depReport.audit.moderate} moderate
+                      </div>
+                    )}
+                    {depReport.auditError && <div className="text-slate-500" title={depReport.auditError}>🛡️ Audit unavailable</div>}
+                    {depReport.audit?.vulnerabilities.filter(v => v.severity === 'critical' || v.severity === 'high').slice(0, 5).map(v => (
+                      <div key={v.name} className="truncate" title={v.fix_available ? (v.fix_is_breaking ? 'Fix needs a major upgrade' : 'npm audit fix') : 'No fix yet'}>
+                        <span className="text-red-400">{v.severity}</span> {v.name}{v.is_direct ? '' : ' (transitive)'}
+                      </div>
+                    ))}
+                    <div className={depReport.outdated.some(p => p.major_behind) ? 'text-amber-400' : 'text-slate-400'}>
+                      📦 {depReport.outdated.length} outdated ({depReport.outdated.filter(p => p.major_behind).length} major)
+                    </div>
+                    {depReport.outdated.filter(p => p.major_behind).slice(0, 5).map(p => (
+                      <div key={p.name} className="truncate">{p.name} <span className="text-slate-500">{p.current ?? '-'} → {p.latest}</span></div>
+                    ))}
+                  </div>
+                ) : (
+                  <button onClick={handleCheckDependencies} disabled={checkingDeps} className="text-cyan-500 hover:text-cyan-400 disabled:opacity-50">
+                    {checkingDeps ? '⏳ Checking dependencies...' : '🛡️ Check dependencies'}
+                  </button>
+                )}
+              </div>
+            )}
+
+            {analytics && (() => {
+              const points = analytics.series.slice(-30);
+              const max = Math.max(...points.map(p => p.duration_secs), 1);
+              const fmt = (secs: number | null) => secs === null ? '-' : `${Math.floor(secs / 60)}m ${Math.round(secs % 60)}s`;
+              const pct = (rate: number | null) => rate === null ? '-' : `${Math.round(rate * 100)}%`;
+              const latest = [...analytics.series].reverse().find(p => p.phases.length > 0);
+              const phaseTotal = latest ? latest.phases.reduce((sum, ph) => sum + ph.duration_secs, 0) || 1 : 1;
+              return (
+                <div className="p-2 bg-slate-900/40 border border-slate-800 rounded space-y-1 text-[9px] text-slate-400">
+                  <div className="flex justify-between">
+                    <span className="text-slate-300 font-bold">📊 Build Stats</span>
+                    <button onClick={() => setAnalytics(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                  </div>
+                  <div>{analytics.builds} builds · success <span className="text-slate-200">{pct(analytics.success_rate)}</span></div>
+                  <div>avg <span className="text-slate-200">{fmt(analytics.avg_duration_secs)}</span> · median <span className="text-slate-200">{fmt(analytics.median_duration_secs)}</span></div>
+                  <div>cache hits <span className="text-slate-200">{pct(analytics.avg_cache_hit_rate)}</span> · recent <span className="text-slate-200">{pct(analytics.recent_cache_hit_rate)}</span></div>
+                  {analytics.latest_warnings && (
+                    <div title="Last build: Gradle deprecations / Kotlin / Java">
+                      warnings <span className="text-amber-300">{analytics.latest_warnings.deprecations} / {analytics.latest_warnings.kotlin} / {analytics.latest_warnings.java}</span>
+                      {analytics.recent_warnings !== null && <> · recent avg <span className="text-slate-200">{analytics.recent_warnings.toFixed(1)}</span></>}
+                    </div>
+                  )}
+                  {latest && (
+                    <div title={`Where the time went, ${new Date(latest.started_at).toLocaleString()}`}>
+                      <div className="flex h-1.5 rounded overflow-hidden">
+                        {latest.phases.map(ph => (
+                          <div key={ph.phase} className={PHASE_COLORS[ph.phase]} style={{ width: `${(ph.duration_secs / phaseTotal) * 100}%` }} />
+                        ))}
+                      </div>
+                      <div className="flex flex-wrap gap-x-2">
+                        {latest.phases.map(ph => (
+                          <span key={ph.phase}>{ph.phase} <span className="text-slate-200">{fmt(ph.duration_secs)}</span></span>
+                        ))}
+                      </div>
+                    </div>
+                  )}
+                  <svg viewBox={`0 0 ${points.length * 4} 24`} className="w-full h-6" preserveAspectRatio="none">
+                    {points.map((p, i) => (
+                      <rect key={i} x={i * 4} y={24 - (p.duration_secs / max) * 24} width={3} height={(p.duration_secs / max) * 24}
+                        className={p.outcome === 'success' ? 'fill-cyan-500/70' : p.outcome === 'failed' ? 'fill-red-500/70' : 'fill-slate-600'}>
+                        <title>{`${new Date(p.started_at).toLocaleString()} · ${fmt(p.duration_secs)}${p.cache_hit_rate !== null ? ` · ${pct(p.cache_hit_rate)} cached` : ''}${p.warnings !== null ? ` · ${p.warnings} warnings` : ''}${p.phases.map(ph => `\n${ph.phase} ${fmt(ph.duration_secs)}`).join('')}`}</title>
+                      </rect>
+                    ))}
+                  </svg>
+                </div>
+              );
+            })()}
+
+            {onboarding && (
+              <div className="p-2 bg-slate-900/40 border border-cyan-900/60 rounded space-y-1 text-[9px] text-slate-400">
+                <div className="flex justify-between">
+                  <span className="text-cyan-300 font-bold">🧭 Environment Setup</span>
+                  <button onClick={dismissOnboarding} disabled={onboardingBusy} className="text-slate-600 hover:text-slate-400 disabled:opacity-40">✕</button>
+                </div>
+                {onboarding.map(step => (
+                  <div key={step.step} title={step.detail}>
+                    <span>{ONBOARDING_ICONS[step.status]} </span>
+                    <span className="text-slate-200">{step.title}</span>
+                    {step.detail && <span> · {step.detail}</span>}
+                  </div>
+                ))}
+                {!onboardingBusy && (() => {
+                  const installable = onboarding.filter(s => s.installable).map(s => s.step);
+                  return (
+                    <div className="flex gap-1 pt-1">
+                      {installable.length > 0 && (
+                        <button onClick={() => runOnboarding(installable)} title="WSL and the SDK may ask for administrator rights"
+                          className="flex-1 py-1 font-semibold uppercase bg-cyan-900/40 text-cyan-300 rounded hover:bg-cyan-900/60">
+                          Install missing
+                        </button>
+                      )}
+                      <button onClick={() => runOnboarding()} className="flex-1 py-1 font-semibold uppercase bg-slate-700/50 text-slate-300 rounded hover:bg-slate-600/50">
+                        Check again
+                      </button>
+                      <button onClick={dismissOnboarding} className="flex-1 py-1 font-semibold uppercase bg-slate-700/50 text-slate-300 rounded hover:bg-slate-600/50">
+                        Done
+                      </button>
+                    </div>
+                  );
+                })()}
+              </div>
+            )}
+
+            {purgeImpact && (() => {
+              const mb = (bytes: number | null) => bytes === null ? '?' : `${(bytes / 1_048_576).toFixed(0)} MB`;
+              return (
+                <div className="p-2 bg-orange-950/30 border border-orange-900/60 rounded space-y-1 text-[9px] text-slate-400">
+                  <div className="flex justify-between">
+                    <span className="text-orange-300 font-bold">🔥 Purge WSL</span>
+                    <button onClick={() => setPurgeImpact(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                  </div>
+                  <div>vmmem <span className="text-slate-200">{mb(purgeImpact.vmmem_bytes)}</span> · running distros:</div>
+                  {purgeImpact.distros.map(d => (
+                    <label key={d.name} className="flex items-center justify-between gap-2 cursor-pointer">
+                      <span className="flex items-center gap-1">
+                        <input type="checkbox" checked={purgeSelection.includes(d.name)}
+                          onChange={e => setPurgeSelection(e.target.checked ? [...purgeSelection, d.name] : purgeSelection.filter(n => n !== d.name))} />
+                        <span className="text-slate-200">{d.name}</span>
+                        {d.default && <span className="text-cyan-400">default</span>}
+                        {d.docker && <span className="text-amber-400" title="Docker Desktop's containers stop with it">docker</span>}
+                      </span>
+                      <span>{mb(d.memory_bytes)}</span>
+                    </label>
+                  ))}
+                  {purgeImpact.active_builds > 0 && (
+                    <div className="text-red-400">⚠️ {purgeImpact.active_builds} build(s) running would fail</div>
+                  )}
+                  <div className="flex gap-1 pt-1">
+                    <button onClick={() => handleConfirmPurge(purgeSelection)} disabled={purgeSelection.length === 0}
+                      className="flex-1 py-1 font-semibold uppercase bg-orange-900/40 text-orange-300 rounded hover:bg-orange-900/60 disabled:opacity-40">
+                      Terminate selected
+                    </button>
+                    <button onClick={() => handleConfirmPurge(null)} title="Stop the whole WSL VM, every distro with it"
+                      className="flex-1 py-1 font-semibold uppercase bg-red-900/40 text-red-300 rounded hover:bg-red-900/60">
+                      Shut down all
+                    </button>
+                  </div>
+                </div>
+              );
+            })()}
+
+            {bundleReport && (
+              <div className="p-2 bg-slate-900/40 border border-slate-800 rounded space-y-1 text-[9px] text-slate-400">
+                <div className="flex justify-between">
+                  <span className="text-slate-300 font-bold">📦 JS Bundle</span>
+                  <button onClick={() => setBundleReport(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                </div>
+                <div>
+                  Bundle <span className="text-slate-200">{(bundleReport.bundle_bytes / 1048576).toFixed(2)} MB</span>
+                  {bundleReport.bytecode_bytes !== null && <> · Hermes <span className="text-slate-200">{(bundleReport.bytecode_bytes / 1048576).toFixed(2)} MB</span></>}
+                </div>
+                {bundleReport.modules.slice(0, 12).map(m => (
+                  <div key={m.name} title={`${m.files} file(s)`}>
+                    <div className="flex justify-between gap-2">
+                      <span className="truncate">{m.name}</span>
+                      <span className="text-slate-300 shrink-0">{(m.bytes / 1024).toFixed(0)} KB</span>
+                    </div>
+                    <div className="h-1 bg-slate-800 rounded">
+                      <div className="h-1 bg-cyan-500/70 rounded" style={{ width: `${Math.max(1, (m.bytes / bundleReport.bundle_bytes) * 100)}%` }} />
+                    </div>
+                  </div>
+                ))}
+              </div>
+            )}
+
             {/* Platform Toggle */}
             <div className="flex bg-slate-900/80 border border-slate-800 rounded p-1 mb-3">
               <button
@@ -490,6 +2235,201 @@ export default function App() {
               )}
             </div>
 
+            {/* Output Verbosity */}
+            <div className="flex items-center justify-between text-[9px] text-slate-400">
+              <span className="uppercase tracking-widest">Output</span>
+              <select value={verbosity} onChange={e => {
+                const v = e.target.value as 'all' | 'compact' | 'problems';
+                setVerbosity(v); localStorage.setItem('hyperzenith_verbosity', v);
+              }} className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                <option value="all">All lines</option>
+                <option value="compact">Compact</option>
+                <option value="problems">Problems only</option>
+              </select>
+            </div>
+
+            {/* Gradle Log Level (direct engine only) */}
+            {platform === 'android' && turboMode && (
+              <div className="flex items-center justify-between text-[9px] text-slate-400">
+                <span className="uppercase tracking-widest">Gradle</span>
+                <div className="flex items-center gap-2">
+                  <label className="flex items-center gap-1" title="Re-run failed builds with --stacktrace --info">
+                    <input type="checkbox" checked={retryWithDiagnostics} onChange={e => {
+                      setRetryWithDiagnostics(e.target.checked); localStorage.setItem('hyperzenith_retry_diagnostics', String(e.target.checked));
+                    }} />
+                    Retry w/ trace
+                  </label>
+                  <select value={gradleLogLevel} onChange={e => {
+                    setGradleLogLevel(e.target.value); localStorage.setItem('hyperzenith_gradle_log_level', e.target.value);
+                  }} className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                    <option value="quiet">Quiet</option>
+                    <option value="lifecycle">Lifecycle</option>
+                    <option value="info">Info</option>
+                    <option value="debug">Debug</option>
+                  </select>
+                </div>
+              </div>
+            )}
+
+            {/* Node version and dependency install */}
+            <div className="flex items-center justify-between text-[9px] text-slate-400">
+              <span className="uppercase tracking-widest">Node</span>
+              <div className="flex items-center gap-2">
+                <label className="flex items-center gap-1" title="Switch to the version from .nvmrc / engines.node via nvm before building">
+                  <input type="checkbox" checked={useNvm} onChange={e => {
+                    setUseNvm(e.target.checked); localStorage.setItem('hyperzenith_use_nvm', String(e.target.checked));
+                  }} />
+                  Use nvm
+                </label>
+                {platform === 'android' && (
+                  <label className="flex items-center gap-1" title="Install node_modules in WSL when missing or the lockfile changed">
+                    <input type="checkbox" checked={installDependencies} onChange={e => {
+                      setInstallDependencies(e.target.checked); localStorage.setItem('hyperzenith_install_deps', String(e.target.checked));
+                    }} />
+                    Auto-install
+                  </label>
+                )}
+                {platform === 'android' && (
+                  <label className="flex items-center gap-1" title="Attach the git log since the last successful build to the archived artifacts">
+                    <input type="checkbox" checked={autoReleaseNotes} onChange={e => {
+                      setAutoReleaseNotes(e.target.checked); localStorage.setItem('hyperzenith_release_notes', String(e.target.checked));
+                    }} />
+                    Notes
+                  </label>
+                )}
+              </div>
+            </div>
+
+            {/* Pre-build Gates */}
+            {platform === 'android' && (
+              <div className="flex items-center justify-between text-[9px] text-slate-400">
+                <span className="uppercase tracking-widest">Gates</span>
+                <div className="flex items-center gap-2">
+                  {([['typecheck', 'tsc', 'Run tsc --noEmit before building'], ['lint', 'lint', 'Run ESLint before building'], ['block_on_errors', 'block', 'Abort the build when a gate finds errors']] as const).map(([key, label, title]) => (
+                    <label key={key} className="flex items-center gap-1" title={title}>
+                      <input type="checkbox" checked={gates[key]} onChange={e => {
+                        const next = { ...gates, [key]: e.target.checked };
+                        setGates(next); localStorage.setItem('hyperzenith_gates', JSON.stringify(next));
+                      }} />
+                      {label}
+                    </label>
+                  ))}
+                </div>
+              </div>
+            )}
+
+            {/* One-off Engine Overrides */}
+            {platform === 'android' && turboMode && (
+              <div className="flex items-center justify-between text-[9px] text-slate-400">
+                <span className="uppercase tracking-widest" title="Override gradle.properties for the next build only">Engine</span>
+                <div className="flex items-center gap-1">
+                  {([['hermes', 'Hermes'], ['new_architecture', 'New Arch']] as const).map(([key, label]) => (
+                    <select key={key} value={engineOverrides[key] === null ? 'project' : String(engineOverrides[key])}
+                      onChange={e => setEngineOverrides({ ...engineOverrides, [key]: e.target.value === 'project' ? null : e.target.value === 'true' })}
+                      title={`${label} for the next build only`}
+                      className={`bg-slate-900 border px-1 py-0.5 text-[9px] rounded outline-none ${engineOverrides[key] === null ? 'border-slate-700 text-slate-400' : 'border-amber-500/60 text-amber-300'}`}>
+                      <option value="project">{label}: project</option>
+                      <option value="true">{label}: on</option>
+                      <option value="false">{label}: off</option>
+                    </select>
+                  ))}
+                </div>
+              </div>
+            )}
+
+            {/* Battery Policy */}
+            {platform === 'android' && (
+              <div className="flex items-center justify-between text-[9px] text-slate-400">
+                <span className="uppercase tracking-widest">On Battery</span>
+                <select value={batteryPolicy} onChange={e => setBatteryPolicy(e.target.value as 'ignore' | 'warn' | 'lowpower')}
+                  title="What to do when a build starts while unplugged"
+                  className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                  <option value="warn">Warn</option>
+                  <option value="lowpower">Low-power profile</option>
+                  <option value="ignore">Ignore</option>
+                </select>
+              </div>
+            )}
+
+            {/* Stalled builds */}
+            <div className="flex items-center justify-between text-[9px] text-slate-400">
+              <span className="uppercase tracking-widest">When Silent</span>
+              <div className="flex items-center gap-2">
+                <select value={stallPolicy.warn_minutes} onChange={e => setStallPolicy({ ...stallPolicy, warn_minutes: Number(e.target.value) })}
+                  title="Warn when a build prints nothing for this long"
+                  className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                  <option value={0}>Never warn</option>
+                  <option value={5}>Warn after 5 min</option>
+                  <option value={10}>Warn after 10 min</option>
+                  <option value={20}>Warn after 20 min</option>
+                </select>
+                <select value={stallPolicy.abort_minutes} onChange={e => setStallPolicy({ ...stallPolicy, abort_minutes: Number(e.target.value) })}
+                  title="Abort a build that prints nothing for this long"
+                  className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                  <option value={0}>Never abort</option>
+                  <option value={15}>Abort after 15 min</option>
+                  <option value={30}>Abort after 30 min</option>
+                  <option value={60}>Abort after 1 h</option>
+                </select>
+              </div>
+            </div>
+
+            {/* WSL Memory */}
+            {platform === 'android' && (
+              <div className="flex items-center justify-between text-[9px] text-slate-400">
+                <span className="uppercase tracking-widest">WSL Memory</span>
+                <div className="flex items-center gap-2">
+                  <label className="flex items-center gap-1 cursor-pointer" title="Drop WSL's page cache after each build so vmmem shrinks">
+                    <input type="checkbox" checked={memoryPolicy.drop_caches} onChange={e => setMemoryPolicy({ ...memoryPolicy, drop_caches: e.target.checked })} />
+                    Drop caches
+                  </label>
+                  <select value={memoryPolicy.shutdown_idle_minutes} onChange={e => setMemoryPolicy({ ...memoryPolicy, shutdown_idle_minutes: Number(e.target.value) })}
+                    title="Shut WSL down once no build has run for a while. This stops every distro, so it is skipped while Docker Desktop is running"
+                    className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                    <option value={0}>Keep running</option>
+                    <option value={15}>Shutdown after 15 min</option>
+                    <option value={30}>Shutdown after 30 min</option>
+                    <option value={60}>Shutdown after 1 h</option>
+                  </select>
+                </div>
+              </div>
+            )}
+
+            {/* Failed Build Cleanup */}
+            {platform === 'android' && (
+              <div className="flex items-center justify-between text-[9px] text-slate-400">
+                <span className="uppercase tracking-widest">Failed Builds</span>
+                <div className="flex items-center gap-2">
+                  <label className="flex items-center gap-1 cursor-pointer" title="Delete android/app/build/intermediates after Gradle fails; caches are kept">
+                    <input type="checkbox" checked={failureCleanup.clean_intermediates} onChange={e => setFailureCleanup({ ...failureCleanup, clean_intermediates: e.target.checked })} />
+                    Clean intermediates
+                  </label>
+                  <select value={failureCleanup.min_mb} onChange={e => setFailureCleanup({ ...failureCleanup, min_mb: Number(e.target.value) })}
+                    disabled={!failureCleanup.clean_intermediates}
+                    title="Only clean when the intermediates are at least this big"
+                    className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200 disabled:opacity-40">
+                    <option value={0}>Always</option>
+                    <option value={500}>Over 500 MB</option>
+                    <option value={1024}>Over 1 GB</option>
+                    <option value={4096}>Over 4 GB</option>
+                  </select>
+                </div>
+              </div>
+            )}
+
+            {/* Language */}
+            <div className="flex items-center justify-between text-[9px] text-slate-400">
+              <span className="uppercase tracking-widest">Language</span>
+              <select value={locale.slice(0, 2)} onChange={e => { setLocale(e.target.value); localStorage.setItem('hyperzenith_locale', e.target.value); }}
+                title="Language of build statuses and error messages"
+                className="bg-slate-900 border border-slate-700 px-1.5 py-0.5 text-[9px] rounded outline-none text-slate-200">
+                <option value="en">English</option>
+                <option value="de">Deutsch</option>
+                <option value="es">Español</option>
+                <option value="fr">Français</option>
+              </select>
+            </div>
+
             {/* iOS Satellite Configuration */}
             {platform === 'ios' && (
               <div className="p-3 bg-cyan-500/10 border border-cyan-500/30 rounded-lg space-y-2 relative">
@@ -503,7 +2443,7 @@ export default function App() {
                       <input type="text" value={macConfig.ip} onChange={e => {
                         const c = { ...macConfig, ip: e.target.value };
                         setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
-                      }} placeholder="IP" className="bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                      }} placeholder="Host or IP[:port]" title="IP, hostname, mac.local or MagicDNS name; IPv6 with a port as [addr]:port" className="bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
                       <input type="text" value={macConfig.username} onChange={e => {
                         const c = { ...macConfig, username: e.target.value };
                         setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
@@ -520,6 +2460,31 @@ export default function App() {
                       }} placeholder="SSH Key Path (e.g., ~/.ssh/id_rsa)" className="w-full bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
                       <span className="text-[7px] text-slate-600 block">For MacinCloud 2FA or key-only auth</span>
                     </div>
+                    <div className="space-y-0.5">
+                      <div className="grid grid-cols-2 gap-1">
+                        <input type="text" value={macConfig.jump?.ip || ''} onChange={e => {
+                          const c = { ...macConfig, jump: { ...macConfig.jump, ip: e.target.value } };
+                          setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                        }} placeholder="Jump host (optional)" className="bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                        <input type="text" value={macConfig.jump?.username || ''} onChange={e => {
+                          const c = { ...macConfig, jump: { ...macConfig.jump, username: e.target.value } };
+                          setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                        }} placeholder="Jump user" className="bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                      </div>
+                      {macConfig.jump?.ip && (
+                        <div className="grid grid-cols-2 gap-1">
+                          <input type="text" value={macConfig.jump?.ssh_key_path || ''} onChange={e => {
+                            const c = { ...macConfig, jump: { ...macConfig.jump, ssh_key_path: e.target.value } };
+                            setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                          }} placeholder="Jump key path" className="bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                          <input type="password" value={macConfig.jump?.password || ''} onChange={e => {
+                            const c = { ...macConfig, jump: { ...macConfig.jump, password: e.target.value } };
+                            setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                          }} placeholder="Jump password" className="bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                        </div>
+                      )}
+                      <span className="text-[7px] text-slate-600 block">For Macs only reachable through a gateway box</span>
+                    </div>
                     <div className="grid grid-cols-3 gap-1">
                       <input type="text" value={iosScheme} onChange={e => {
                         setIosScheme(e.target.value); localStorage.setItem('hyperzenith_ios_scheme', e.target.value);
@@ -528,14 +2493,300 @@ export default function App() {
                         setIosRemotePath(e.target.value); localStorage.setItem('hyperzenith_ios_remote_path', e.target.value);
                       }} placeholder="Path" className="col-span-2 bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
                     </div>
+                    <input type="text" value={iosTestSimulator} onChange={e => {
+                      setIosTestSimulator(e.target.value); localStorage.setItem('hyperzenith_ios_test_simulator', e.target.value);
+                    }} placeholder="Simulator for tests (e.g. iPhone 15)" className="w-full bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                    <div className="space-y-0.5">
+                      <input type="text" value={iosPullBack} onChange={e => {
+                        setIosPullBack(e.target.value); localStorage.setItem('hyperzenith_ios_pull_back', e.target.value);
+                      }} placeholder="Pull back after build (comma separated)" className="w-full bg-slate-900 border border-slate-700 px-1.5 py-1 text-[9px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                      <span className="text-[7px] text-slate-600 block">Generated files copied back from the Mac, e.g. ios/Podfile.lock</span>
+                    </div>
+                    <div className="flex items-center gap-2 text-[8px] text-slate-400">
+                      <label className="flex items-center gap-1" title="Compare contents instead of size and time">
+                        <input type="checkbox" checked={macConfig.sync?.checksum ?? false} onChange={e => {
+                          const c = { ...macConfig, sync: { ...macConfig.sync, checksum: e.target.checked } };
+                          setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                        }} /> Checksum
+                      </label>
+                      <label className="flex items-center gap-1" title="Remove files deleted locally from the Mac's copy">
+                        <input type="checkbox" checked={macConfig.sync?.delete ?? false} onChange={e => {
+                          const c = { ...macConfig, sync: { ...macConfig.sync, delete: e.target.checked } };
+                          setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                        }} /> Delete stale
+                      </label>
+                      <input type="number" min={0} value={macConfig.sync?.bwlimit_kbps ?? ''} onChange={e => {
+                        const c = { ...macConfig, sync: { ...macConfig.sync, bwlimit_kbps: e.target.value ? Number(e.target.value) : null } };
+                        setMacConfig(c); localStorage.setItem('hyperzenith_mac_config', JSON.stringify(c));
+                      }} placeholder="KB/s limit" className="w-16 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none focus:border-cyan-500/50 text-cyan-100" />
+                    </div>
                     <button onClick={() => setShowIosSettings(false)} className="w-full py-1 bg-cyan-500 text-black text-[8px] font-bold rounded">SAVE</button>
                   </div>
                 ) : (
                   <div className="text-[9px] text-slate-400">
-                    <div>{macConfig.ip || 'No IP'} • {macConfig.username || 'No User'}</div>
+                    <div>{macConfig.ip || 'No Host'} • {macConfig.username || 'No User'}{macConfig.jump?.ip ? ` via ${macConfig.jump.ip}` : ''}</div>
                     <div className="truncate opacity-60">Path: {iosRemotePath}</div>
                   </div>
                 )}
+                {syncPreview && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-0.5">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">🔍 Next Sync: {syncPreview.files.length} file(s), {(syncPreview.total_bytes / 1048576).toFixed(1)} MB</span>
+                      <button onClick={() => setSyncPreview(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    {syncPreview.folders.slice(0, 6).map(f => (
+                      <div key={f.path} className="flex justify-between gap-2" title={`${f.files} file(s)`}>
+                        <span className="truncate">{f.path}</span>
+                        <span className={`shrink-0 ${f.bytes > 100 * 1048576 ? 'text-amber-400' : 'text-slate-200'}`}>{(f.bytes / 1048576).toFixed(1)} MB</span>
+                      </div>
+                    ))}
+                    {syncPreview.deleted.length > 0 && (
+                      <div className="text-red-400/80" title={syncPreview.deleted.join('\n')}>🗑️ {syncPreview.deleted.length} stale file(s) removed from the Mac</div>
+                    )}
+                  </div>
+                )}
+                {macCaches && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-0.5">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">📏 Mac Caches</span>
+                      <button onClick={() => setMacCaches(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    {macCaches.filter(c => c.exists).map(c => (
+                      <div key={c.id} className="flex justify-between gap-2" title={c.path}>
+                        <span className="truncate">{c.label}</span>
+                        <span className="text-slate-200 shrink-0">{(c.size_bytes / 1e9).toFixed(2)} GB</span>
+                      </div>
+                    ))}
+                    <div className="text-slate-500">Total {(macCaches.reduce((sum, c) => sum + c.size_bytes, 0) / 1e9).toFixed(1)} GB</div>
+                  </div>
+                )}
+                {portConflicts && portConflicts.length > 0 && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">🔌 Ports In Use</span>
+                      <button onClick={() => setPortConflicts(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    {portConflicts.map(c => (
+                      <div key={`${c.port}-${c.side}-${c.pid}`} className="flex items-center justify-between gap-1">
+                        <span className="truncate" title={`${c.purpose} port, ${c.side === 'wsl' ? 'inside WSL' : 'on Windows'}`}>
+                          {c.port} {c.purpose}: <span className="text-slate-300">{c.process}</span> ({c.pid ?? '?'}{c.side === 'wsl' ? ', WSL' : ''})
+                        </span>
+                        <button onClick={() => handleKillPort(c.port)} disabled={c.pid === null} className="px-1.5 py-0.5 bg-red-900/30 text-red-400 text-[8px] font-bold rounded hover:bg-red-900/50 disabled:opacity-40">KILL</button>
+                      </div>
+                    ))}
+                  </div>
+                )}
+                {longPaths && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">📏 Path Lengths</span>
+                      <button onClick={() => setLongPaths(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    <div className={longPaths.warning ? 'text-amber-300' : ''}>
+                      Deepest path {longPaths.deepest_len}/{longPaths.limit} characters, long paths {longPaths.enabled === null ? 'unknown' : longPaths.enabled ? 'enabled' : 'off'}
+                    </div>
+                    {longPaths.deepest_path && <div className="text-[8px] text-slate-500 font-mono break-all">{longPaths.deepest_path}</div>}
+                    {longPaths.truncated && <div className="text-[8px] text-amber-400">Large project, only part of it was checked; deeper paths may exist</div>}
+                    {longPaths.enabled !== true && (
+                      <button onClick={handleEnableLongPaths} title="Sets LongPathsEnabled and Git's core.longpaths (asks for administrator rights)"
+                        className="w-full py-1 bg-cyan-900/40 text-cyan-300 text-[8px] font-bold rounded hover:bg-cyan-900/60">ENABLE LONG PATHS</button>
+                    )}
+                  </div>
+                )}
+                {savedLogs && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">📄 Recent Logs</span>
+                      <button onClick={() => setSavedLogs(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    <div className="flex gap-1">
+                      {([null, 'android', 'ios'] as const).map(p => (
+                        <button key={p ?? 'all'} onClick={() => handleListLogs(p)} className={`px-1.5 py-0.5 rounded text-[8px] ${savedLogs.platform === p ? 'bg-cyan-900/40 text-cyan-300' : 'bg-slate-800 text-slate-500 hover:text-slate-300'}`}>
+                          {p === null ? 'All' : p === 'android' ? '🤖 Android' : '🍎 iOS'}
+                        </button>
+                      ))}
+                      <button onClick={handleOpenLogs} title={logsFolder} className="ml-auto px-1.5 py-0.5 rounded text-[8px] bg-slate-800 text-slate-500 hover:text-slate-300">📂</button>
+                    </div>
+                    {savedLogs.logs.length === 0 && <div className="text-slate-600">No logs yet</div>}
+                    {savedLogs.logs.slice(0, 20).map(log => (
+                      <div key={log.path} className="flex justify-between gap-1" title={log.path}>
+                        <span className="truncate">
+                          {log.platform === 'ios' ? '🍎' : log.platform === 'android' ? '🤖' : '🧰'}{log.success === true ? ' ✅' : log.success === false ? ' ❌' : ''} {log.name}
+                        </span>
+                        <span className="text-slate-600 shrink-0">{(log.bytes / 1024).toFixed(0)} KB</span>
+                      </div>
+                    ))}
+                  </div>
+                )}
+                {crash && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">🧩 Symbolicate Crash</span>
+                      <button onClick={() => setCrash(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    <input type="text" value={crash.buildId} onChange={e => setCrash({ ...crash, buildId: e.target.value })} placeholder="Build id"
+                      className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100 font-mono" />
+                    {platform === 'ios' && (
+                      <button onClick={async () => {
+                        const selected = await open({ directory: true, multiple: false, title: "Folder with the build's dSYMs" });
+                        if (typeof selected === 'string') setCrash({ ...crash, dsymPath: selected });
+                      }} title="iOS builds here are Debug builds without dSYMs; pick the dSYMs folder of the release's .xcarchive"
+                        className="w-full py-0.5 bg-slate-800 text-slate-400 text-[8px] rounded hover:bg-slate-700 truncate">
+                        {crash.dsymPath || 'Choose dSYMs folder...'}
+                      </button>
+                    )}
+                    <textarea value={crash.result ?? crash.text} onChange={e => setCrash({ ...crash, text: e.target.value, result: null })} rows={6}
+                      placeholder="Paste a stack trace or crash report" className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100 font-mono resize-none" />
+                    <button onClick={handleSymbolicate} disabled={!crash.buildId.trim() || !crash.text.trim() || (platform === 'ios' && !crash.dsymPath)} className="w-full py-1 bg-cyan-900/40 text-cyan-300 text-[8px] font-bold rounded hover:bg-cyan-900/60 disabled:opacity-40">SYMBOLICATE</button>
+                  </div>
+                )}
+                {releaseNotes !== null && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">📝 Release Notes</span>
+                      <button onClick={() => setReleaseNotes(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    <textarea value={releaseNotes} onChange={e => setReleaseNotes(e.target.value)} rows={5} placeholder="What changed in this build"
+                      className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100 font-mono resize-none" />
+                    <button onClick={handleSaveReleaseNotes} className="w-full py-1 bg-cyan-900/40 text-cyan-300 text-[8px] font-bold rounded hover:bg-cyan-900/60">ATTACH TO LAST BUILD</button>
+                  </div>
+                )}
+                {buildEnv && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">🔑 Build Variables</span>
+                      <button onClick={() => setBuildEnv(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    {buildEnv.map((v, i) => (
+                      <div key={i} className="flex gap-1 items-center">
+                        <input type="text" value={v.name} onChange={e => setBuildEnv(buildEnv.map((x, j) => j === i ? { ...x, name: e.target.value.toUpperCase() } : x))}
+                          placeholder="NAME" className="w-1/2 min-w-0 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                        <input type={v.secret ? 'password' : 'text'} value={v.value} onChange={e => setBuildEnv(buildEnv.map((x, j) => j === i ? { ...x, value: e.target.value } : x))}
+                          placeholder={v.secret ? '(stored)' : 'value'} className="w-1/2 min-w-0 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                        <input type="checkbox" checked={v.secret} title="Keep the value in Windows Credential Manager"
+                          onChange={e => setBuildEnv(buildEnv.map((x, j) => j === i ? { ...x, secret: e.target.checked } : x))} />
+                        <button onClick={() => setBuildEnv(buildEnv.filter((_, j) => j !== i))} className="text-slate-600 hover:text-red-400">✕</button>
+                      </div>
+                    ))}
+                    <div className="flex gap-1">
+                      <button onClick={() => setBuildEnv([...buildEnv, { name: '', value: '', secret: false }])} className="flex-1 py-1 bg-slate-800 text-slate-300 text-[8px] font-bold rounded hover:bg-slate-700">+ ADD</button>
+                      <button onClick={handleSaveBuildEnv} className="flex-1 py-1 bg-cyan-900/40 text-cyan-300 text-[8px] font-bold rounded hover:bg-cyan-900/60">SAVE</button>
+                    </div>
+                    <span className="text-[7px] text-slate-600 block">Exported in WSL and on the Mac for this project's builds. 🔒 = secret</span>
+                  </div>
+                )}
+                {pushConfig && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">📱 Phone Notifications</span>
+                      <button onClick={() => setPushConfig(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    <select value={pushConfig.provider} onChange={e => setPushConfig({ ...pushConfig, provider: e.target.value as PushConfig['provider'] })}
+                      className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-slate-200">
+                      <option value="off">Off</option>
+                      <option value="ntfy">ntfy</option>
+                      <option value="pushover">Pushover</option>
+                    </select>
+                    {pushConfig.provider === 'ntfy' && (
+                      <div className="flex gap-1">
+                        <input type="text" value={pushConfig.server} onChange={e => setPushConfig({ ...pushConfig, server: e.target.value })} placeholder="https://ntfy.sh"
+                          className="w-1/2 min-w-0 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                        <input type="text" value={pushConfig.topic} onChange={e => setPushConfig({ ...pushConfig, topic: e.target.value })} placeholder="topic"
+                          className="w-1/2 min-w-0 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                      </div>
+                    )}
+                    {pushConfig.provider === 'pushover' && (
+                      <input type="text" value={pushConfig.user_key} onChange={e => setPushConfig({ ...pushConfig, user_key: e.target.value })} placeholder="User key"
+                        className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                    )}
+                    {pushConfig.provider !== 'off' && (
+                      <>
+                        <input type="password" value={pushConfig.token} onChange={e => setPushConfig({ ...pushConfig, token: e.target.value })}
+                          placeholder={pushConfig.has_token ? '(token stored)' : pushConfig.provider === 'ntfy' ? 'Access token (optional)' : 'API token'}
+                          className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                        <div className="flex items-center gap-2">
+                          <label className="flex items-center gap-1"><input type="checkbox" checked={pushConfig.on_success} onChange={e => setPushConfig({ ...pushConfig, on_success: e.target.checked })} />Success</label>
+                          <label className="flex items-center gap-1"><input type="checkbox" checked={pushConfig.on_failure} onChange={e => setPushConfig({ ...pushConfig, on_failure: e.target.checked })} />Failure</label>
+                          <label className="flex items-center gap-1 ml-auto" title="Only builds that took at least this long">
+                            ≥<input type="number" min={0} value={Math.round(pushConfig.min_duration_secs / 60)} onChange={e => setPushConfig({ ...pushConfig, min_duration_secs: Math.max(0, Number(e.target.value)) * 60 })}
+                              className="w-8 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />min
+                          </label>
+                        </div>
+                      </>
+                    )}
+                    <div className="flex gap-1">
+                      {pushConfig.provider !== 'off' && (
+                        <button onClick={() => handleSavePush(true)} className="flex-1 py-1 bg-slate-800 text-slate-300 text-[8px] font-bold rounded hover:bg-slate-700">SAVE & TEST</button>
+                      )}
+                      <button onClick={() => handleSavePush(false)} className="flex-1 py-1 bg-cyan-900/40 text-cyan-300 text-[8px] font-bold rounded hover:bg-cyan-900/60">SAVE</button>
+                    </div>
+                  </div>
+                )}
+                {fastlaneLanes && fastlaneLanes.length > 0 && (
+                  <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-0.5">
+                    <div className="flex justify-between">
+                      <span className="text-slate-300 font-bold">🏁 Fastlane Lanes</span>
+                      <button onClick={() => setFastlaneLanes(null)} className="text-slate-600 hover:text-slate-400">✕</button>
+                    </div>
+                    {fastlaneLanes.map(l => (
+                      <button key={`${l.platform}-${l.name}`} onClick={() => handleRunLane(l.name)} disabled={isBuilding} className="w-full text-left px-1 py-0.5 rounded hover:bg-slate-800 text-slate-200 disabled:opacity-40">
+                        ▶ {l.platform ? `${l.platform} ` : ''}{l.name}
+                      </button>
+                    ))}
+                  </div>
+                )}
+                {lowMacDisk && (
+                  <div className="p-1.5 bg-red-900/20 border border-red-800/40 rounded text-[9px] text-red-300 space-y-1">
+                    <div>💽 Only {((lowMacDisk.free_bytes as number) / 1e9).toFixed(1)} GB free on the Mac</div>
+                    <div className="flex gap-1">
+                      <button onClick={handleMacSelectiveClean} className="flex-1 py-1 bg-red-900/40 text-red-300 text-[8px] font-bold rounded hover:bg-red-900/60">🧹 SELECTIVE CLEAN</button>
+                      <button onClick={() => setLowMacDisk(null)} className="px-2 text-slate-500 hover:text-slate-300">✕</button>
+                    </div>
+                  </div>
+                )}
+              </div>
+            )}
+
+            {/* Build Presets */}
+            {platform === 'android' && projectPath && (
+              <div className="p-1.5 bg-slate-900/60 border border-slate-800 rounded text-[9px] text-slate-400 space-y-1">
+                <div className="flex justify-between">
+                  <span className="text-slate-300 font-bold">📋 Presets</span>
+                  {!presetDraft && (
+                    <button onClick={() => setPresetDraft({ name: '', variant: '', env_file: '', run_hooks: true })} disabled={buildType !== 'apk' && buildType !== 'aab'}
+                      className="text-cyan-400 hover:text-cyan-300 disabled:opacity-40" title="Save the current build settings as a preset">+ SAVE CURRENT</button>
+                  )}
+                </div>
+                {buildPresets.map(p => (
+                  <div key={p.name} className="flex gap-1 items-center">
+                    <button onClick={() => handleBuildPreset(p.name)} disabled={isBuilding}
+                      title={[p.variant ?? 'debug', p.build_type.toUpperCase(), p.env_file, p.turbo_mode ? 'turbo' : null, p.run_hooks ? null : 'no hooks'].filter(Boolean).join(' • ')}
+                      className="flex-1 text-left px-1 py-0.5 rounded hover:bg-slate-800 text-slate-200 disabled:opacity-40">
+                      ▶ {p.name} <span className="text-slate-500">{p.variant ?? p.build_type.toUpperCase()}</span>
+                    </button>
+                    <button onClick={() => handleDeletePreset(p.name)} className="text-slate-600 hover:text-red-400">✕</button>
+                  </div>
+                ))}
+                {buildPresets.length === 0 && !presetDraft && (
+                  <span className="text-[7px] text-slate-600 block">Save the settings below as a named build, e.g. "QA build"</span>
+                )}
+                {presetDraft && (
+                  <div className="space-y-1">
+                    <input type="text" value={presetDraft.name} onChange={e => setPresetDraft({ ...presetDraft, name: e.target.value })} placeholder="Name, e.g. Store build"
+                      className="w-full bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                    <div className="flex gap-1">
+                      <input type="text" value={presetDraft.variant} onChange={e => setPresetDraft({ ...presetDraft, variant: e.target.value })} placeholder="Variant (prodRelease)"
+                        className="w-1/2 min-w-0 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                      <input type="text" value={presetDraft.env_file} onChange={e => setPresetDraft({ ...presetDraft, env_file: e.target.value })} placeholder="Env file (.env.staging)"
+                        className="w-1/2 min-w-0 bg-slate-900 border border-slate-700 px-1 py-0.5 text-[8px] rounded outline-none text-cyan-100" />
+                    </div>
+                    <label className="flex items-center gap-1"><input type="checkbox" checked={presetDraft.run_hooks} onChange={e => setPresetDraft({ ...presetDraft, run_hooks: e.target.checked })} />Run build hooks</label>
+                    <div className="flex gap-1">
+                      <button onClick={() => setPresetDraft(null)} className="flex-1 py-1 bg-slate-800 text-slate-300 text-[8px] font-bold rounded hover:bg-slate-700">CANCEL</button>
+                      <button onClick={handleSavePreset} disabled={!presetDraft.name.trim()} className="flex-1 py-1 bg-cyan-900/40 text-cyan-300 text-[8px] font-bold rounded hover:bg-cyan-900/60 disabled:opacity-40">SAVE {buildType.toUpperCase()}</button>
+                    </div>
+                  </div>
+                )}
               </div>
             )}
 
@@ -579,6 +2830,20 @@ export default function App() {
                 <div className={`font-bold ${memPercent > 80 ? 'text-orange-400' : 'text-cyan-400'}`}>{memPercent}%</div>
               </div>
             </div>
+            {buildResources && (
+              <div className="p-2 bg-slate-900/50 rounded border border-slate-800/50 text-[9px] font-mono space-y-0.5">
+                {buildResources.consumers.map(c => (
+                  <div key={c.consumer} className="flex justify-between text-slate-400" title={`${c.processes} process(es)`}>
+                    <span>{CONSUMER_LABELS[c.consumer]}</span>
+                    <span>{Math.round(c.cpu_percent)}% • {(c.memory_bytes / 1_073_741_824).toFixed(1)}GB</span>
+                  </div>
+                ))}
+                <div className="flex justify-between text-slate-600">
+                  <span>Everything else</span>
+                  <span>{Math.round(buildResources.other_cpu_percent)}% • {(buildResources.other_memory_bytes / 1_073_741_824).toFixed(1)}GB</span>
+                </div>
+              </div>
+            )}
 
           </div>
 
@@ -594,23 +2859,197 @@ export default function App() {
               <div className="absolute bottom-full left-0 right-0 mx-2 mb-2 p-2 bg-slate-900 border border-slate-800 rounded-lg space-y-1 shadow-2xl z-30">
                 {platform === 'android' ? (
                   <button onClick={handleNuke} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-red-900/30 text-red-400 rounded hover:bg-red-900/50 transition-colors">
-                    🧨 Nuke Gradle Cache
+                    🧨 Nuke Gradle Cache{nukeJsTargets.length > 0 ? ' + JS' : ''}
                   </button>
                 ) : (
                   <button onClick={handleIosNuke} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-red-900/30 text-red-400 rounded hover:bg-red-900/50 transition-colors">
                     ☢️ Nuclear iOS Reset
                   </button>
                 )}
+                {platform === 'android' && (
+                  <div className="flex flex-wrap gap-x-2 px-1 text-[8px] text-slate-500" title="JavaScript-side caches to clear along with the Gradle directories">
+                    {[['node_modules/.cache', 'node_modules/.cache'], ['metro', 'Metro'], ['watchman', 'Watchman'], ['expo', '.expo']].map(([id, label]) => (
+                      <label key={id} className="flex items-center gap-1 cursor-pointer">
+                        <input type="checkbox" checked={nukeJsTargets.includes(id)} onChange={e => {
+                          const next = e.target.checked ? [...nukeJsTargets, id] : nukeJsTargets.filter(t => t !== id);
+                          setNukeJsTargets(next); localStorage.setItem('hyperzenith_nuke_js', JSON.stringify(next));
+                        }} />
+                        {label}
+                      </label>
+                    ))}
+                  </div>
+                )}
+                {platform === 'ios' && (
+                  <button onClick={handleReattachIos} disabled={isBuilding} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-cyan-900/30 text-cyan-400 rounded hover:bg-cyan-900/50 transition-colors disabled:opacity-40">
+                    🔌 Reattach iOS Build
+                  </button>
+                )}
+                {platform === 'ios' && (
+                  <button onClick={handlePreviewSync} disabled={!projectPath} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                    🔍 Preview Sync
+                  </button>
+                )}
+                {platform === 'ios' && (
+                  <button onClick={handleMacCacheReport} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                    📏 Mac Cache Sizes
+                  </button>
+                )}
+                {platform === 'ios' && (
+                  <div className="flex gap-1">
+                    <button onClick={() => handleCaptureSimulator('screenshot')} disabled={!projectPath || !macConfig.ip} title="Launch the last simulator build and screenshot it" className="flex-1 py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                      📸 Sim Screenshot
+                    </button>
+                    <button onClick={() => handleCaptureSimulator('recording')} disabled={!projectPath || !macConfig.ip} title="Launch the last simulator build and record 15 seconds" className="flex-1 py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                      🎥 Record 15s
+                    </button>
+                  </div>
+                )}
+                {platform === 'ios' && (
+                  <div className="flex gap-1">
+                    <button onClick={() => handleKeepMacAwake(60)} className="flex-1 py-1.5 text-[9px] font-semibold uppercase bg-cyan-900/30 text-cyan-400 rounded hover:bg-cyan-900/50 transition-colors">
+                      ☕ Keep Mac Awake 1h
+                    </button>
+                    <button onClick={() => handleKeepMacAwake(0)} title="Let the Mac sleep again" className="px-2 py-1.5 text-[9px] font-semibold bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                      💤
+                    </button>
+                  </div>
+                )}
                 <button onClick={handleClearArchive} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-purple-900/30 text-purple-400 rounded hover:bg-purple-900/50 transition-colors">
                   🗑️ Clear Archive
                 </button>
+                <button onClick={() => { setShowMaintenance(false); runOnboarding(); }} title="Check WSL, Java, Node and the Android SDK, and install what's missing" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                  🧭 Setup Check
+                </button>
+                <button onClick={handleCheckPorts} title="Find processes holding the Metro, Flipper and emulator ports" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                  🔌 Check Dev Ports
+                </button>
+                <button onClick={handleCheckLongPaths} disabled={!projectPath} title="Measure the project's deepest path against Windows' 260 character limit" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  📏 Check Path Lengths
+                </button>
+                <button onClick={handleReclaimMemory} title="Hand memory WSL is only using as cache back to Windows" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                  🧽 Reclaim WSL Memory
+                </button>
                 <button onClick={handlePurge} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-orange-900/30 text-orange-400 rounded hover:bg-orange-900/50 transition-colors">
                   🔥 Purge WSL
                 </button>
+                <div className="flex gap-1">
+                  <button onClick={handleExportSettings} title="Save settings and Mac profiles (without passwords) to a file" className="flex-1 py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                    📤 Export
+                  </button>
+                  <button onClick={handleImportSettings} title="Load settings exported on another machine" className="flex-1 py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                    📥 Import
+                  </button>
+                </div>
+                <button onClick={handleCheckUpdates} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                  ⬆️ Check for Updates
+                </button>
                 <button onClick={handleOpenLogs} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
                   📂 Open Logs Folder
                 </button>
+                <button onClick={() => handleListLogs(null)} disabled={!projectPath} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  📄 Recent Logs
+                </button>
+                <button onClick={handleRunTests} disabled={!projectPath || isBuilding} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  🧪 Run Jest Tests
+                </button>
+                {platform === 'ios' && (
+                  <button onClick={handleRunIosTests} disabled={!projectPath || !macConfig.ip || isBuilding} title="Run the scheme's XCTest targets on a simulator on the Mac" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                    🧪 Run iOS Tests
+                  </button>
+                )}
+                {platform === 'android' && (
+                  <button onClick={handleRefreshDependencies} disabled={!projectPath || isBuilding} title="./gradlew --refresh-dependencies, e.g. after publishing a new library snapshot" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                    🔄 Refresh Dependencies
+                  </button>
+                )}
+                <button onClick={() => { setShowMaintenance(false); setCrash({ buildId: lastAndroidBuildId ?? '', text: '', dsymPath: '', result: null }); }} disabled={!projectPath} title="De-obfuscate a crash with a build's archived mapping.txt, or an iOS build's dSYMs" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  🧩 Symbolicate Crash
+                </button>
+                <button onClick={handleSupportBundle} disabled={!failedBuildId} title="Zip the last failed build's log, a doctor run, settings (no passwords) and system info for a bug report" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  🧰 Support Bundle
+                </button>
+                <button onClick={handleEditReleaseNotes} disabled={!lastAndroidBuildId || isBuilding} title="Attach notes to the last Android build's artifacts, starting from the git log" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  📝 Release Notes
+                </button>
+                <button onClick={handleEditBuildEnv} disabled={!projectPath} title="Environment variables for this project's builds" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  🔑 Build Variables
+                </button>
+                <button onClick={handleEditPush} title="Push finished builds to your phone via ntfy or Pushover" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                  📱 Phone Notifications
+                </button>
+                <button onClick={handleListLanes} disabled={!projectPath || isBuilding} title={platform === 'ios' ? 'Run a lane from the Fastfile on the Mac' : 'Run a lane from the Fastfile in WSL'} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  🏁 Fastlane Lanes
+                </button>
+                <button onClick={handleShowAnalytics} disabled={!projectPath} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  📊 Build Stats
+                </button>
+                <button onClick={handleAnalyzeBundle} disabled={!projectPath || isBuilding} title="Build the release JS bundle and break its size down by package" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                  📦 Analyze JS Bundle
+                </button>
+                {platform === 'android' && (
+                  <button onClick={handleRunE2e} disabled={!projectPath || isBuilding} title="Install the newest archived APK on an emulator and run Maestro flows or Detox tests" className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                    🎬 Run E2E Tests
+                  </button>
+                )}
+                {platform === 'android' && (
+                  <button onClick={handleToggleIdleBuild} disabled={!idleJob && !projectPath} title={idleJob ? `Queued for ${idleJob.working_dir}` : 'Run this build when there is no input and CPU is quiet'} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors disabled:opacity-40">
+                    💤 {idleJob ? 'Cancel Idle Build' : 'Build When Idle'}
+                  </button>
+                )}
+                <button onClick={handleToggleControlApi} title={controlUrl ?? 'Expose build/status/abort/logs over a localhost WebSocket'} className="w-full py-1.5 text-[9px] font-semibold uppercase bg-slate-700/50 text-slate-400 rounded hover:bg-slate-600/50 transition-colors">
+                  🛰️ {controlUrl ? 'Stop Control API' : 'Start Control API'}
+                </button>
                 <div className="mt-2 pt-2 border-t border-slate-700">
+                  {projectInfo?.expo_sdk && (
+                    <>
+                      <label className="text-[8px] text-slate-500 block mb-1">OTA Update Branch (blank = git branch):</label>
+                      <div className="flex gap-1 mb-2">
+                        <input
+                          type="text"
+                          value={otaBranch}
+                          onChange={(e) => { setOtaBranch(e.target.value); localStorage.setItem('hyperzenith_ota_branch', e.target.value); }}
+                          placeholder="production"
+                          className="flex-1 px-2 py-1 text-[9px] bg-slate-800 border border-slate-700 rounded text-slate-300 placeholder-slate-600"
+                        />
+                        <button onClick={handlePublishOta} disabled={!projectPath || isBuilding} title="Publish a JS-only update with eas update, no native build" className="px-2 py-1 text-[9px] bg-slate-700 hover:bg-slate-600 rounded text-slate-300 disabled:opacity-40">
+                          📡
+                        </button>
+                      </div>
+                    </>
+                  )}
+                  {platform === 'android' && (
+                    <>
+                      <label className="text-[8px] text-slate-500 block mb-1">Build Matrix (variant:type, ...):</label>
+                      <div className="flex gap-1 mb-2">
+                        <input
+                          type="text"
+                          value={matrixSpec}
+                          onChange={(e) => { setMatrixSpec(e.target.value); localStorage.setItem('hyperzenith_matrix', e.target.value); }}
+                          placeholder="stagingDebug:apk, prodRelease:aab"
+                          className="flex-1 px-2 py-1 text-[9px] bg-slate-800 border border-slate-700 rounded text-slate-300 placeholder-slate-600"
+                        />
+                        <button onClick={handleBuildMatrix} disabled={!projectPath || isBuilding} title="Build every entry in turn on the warm daemon and archive each" className="px-2 py-1 text-[9px] bg-slate-700 hover:bg-slate-600 rounded text-slate-300 disabled:opacity-40">
+                          🧮
+                        </button>
+                      </div>
+                      <label className="text-[8px] text-slate-500 block mb-1">Compare Commits (build time &amp; size):</label>
+                      <div className="flex gap-1 mb-2">
+                        {(['a', 'b'] as const).map(side => (
+                          <input
+                            key={side}
+                            type="text"
+                            value={compareCommits[side]}
+                            onChange={(e) => setCompareCommits({ ...compareCommits, [side]: e.target.value })}
+                            placeholder={side === 'a' ? 'base commit' : 'new commit'}
+                            className="flex-1 min-w-0 px-2 py-1 text-[9px] bg-slate-800 border border-slate-700 rounded text-slate-300 placeholder-slate-600 font-mono"
+                          />
+                        ))}
+                        <button onClick={handleCompareCommits} disabled={!projectPath || isBuilding} title="Build both commits in temporary git worktrees and compare duration and APK size" className="px-2 py-1 text-[9px] bg-slate-700 hover:bg-slate-600 rounded text-slate-300 disabled:opacity-40">
+                          🔀
+                        </button>
+                      </div>
+                    </>
+                  )}
                   <label className="text-[8px] text-slate-500 block mb-1">Custom Output Path:</label>
                   <div className="flex gap-1">
                     <input
@@ -630,6 +3069,46 @@ export default function App() {
                       ...
                     </button>
                   </div>
+                  <label className="text-[8px] text-slate-500 block mb-1 mt-1">Logs Folder (this project):</label>
+                  <div className="flex gap-1">
+                    <input
+                      type="text"
+                      value={logsFolder}
+                      readOnly
+                      title="Android and iOS build logs for this project"
+                      className="flex-1 px-2 py-1 text-[9px] bg-slate-800 border border-slate-700 rounded text-slate-300 placeholder-slate-600"
+                    />
+                    <button
+                      onClick={async () => {
+                        const selected = await open({ directory: true, multiple: false });
+                        if (selected) handleLogsFolderChange(selected as string);
+                      }}
+                      disabled={!projectPath}
+                      className="px-2 py-1 text-[9px] bg-slate-700 hover:bg-slate-600 rounded text-slate-300 disabled:opacity-40"
+                    >
+                      ...
+                    </button>
+                    <button
+                      onClick={() => handleLogsFolderChange(null)}
+                      disabled={!projectPath}
+                      title="Back to hyperzenith_logs/ in the project"
+                      className="px-2 py-1 text-[9px] bg-slate-700 hover:bg-slate-600 rounded text-slate-300 disabled:opacity-40"
+                    >
+                      ↺
+                    </button>
+                  </div>
+                  {archiveStorage && archiveStorage.kind !== 'local' && (
+                    <div className="flex items-center justify-between mt-1 text-[8px]">
+                      <span className={archiveStorage.available ? 'text-slate-500' : 'text-amber-400'}>
+                        {archiveStorage.kind === 'network_share' ? '🌐 Network share' : '💾 Removable drive'} · {archiveStorage.available ? 'online' : 'unavailable, archiving locally'}
+                      </span>
+                      {archiveStorage.pending > 0 && (
+                        <button onClick={handleFlushPending} disabled={!archiveStorage.available} title="Move artifacts kept locally while the target was offline" className="px-1 text-cyan-400 hover:text-cyan-300 disabled:opacity-40">
+                          ⇪ {archiveStorage.pending} pending
+                        </button>
+                      )}
+                    </div>
+                  )}
                 </div>
               </div>
             )}
@@ -641,14 +3120,35 @@ export default function App() {
           <div className="shrink-0 px-4 py-2 border-b border-slate-800/30 flex justify-between items-center">
             <span className="text-[9px] font-bold text-slate-600 uppercase tracking-widest">Console</span>
             <span className={`text-[9px] ${isBuilding ? 'text-emerald-400 animate-pulse' : 'text-slate-700'}`}>
-              {isBuilding ? '● BUILDING' : '○ IDLE'}
+              {isBuilding ? (queuedBehind ? `⏳ QUEUED (${queuedBehind} AHEAD)` : buildStage === 'syncing' ? '🔄 SYNCING' : '● BUILDING') : '○ IDLE'}
             </span>
           </div>
+          {buildIssues.some(i => i.severity !== 'note') && (
+            <div className="shrink-0 max-h-32 px-4 py-1 overflow-y-auto border-b border-slate-800/30 text-[9px] font-mono custom-scrollbar">
+              <div className="flex justify-between text-slate-500">
+                <span>
+                  ⚠️ {buildIssues.filter(i => i.severity === 'error').length} error(s), {buildIssues.filter(i => i.severity === 'warning').length} warning(s)
+                </span>
+                <button onClick={() => setBuildIssues([])} className="text-slate-600 hover:text-slate-400">✕</button>
+              </div>
+              {buildIssues.filter(i => i.severity !== 'note').map((issue, i) => (
+                <div
+                  key={i}
+                  title={issue.file ? `${issue.text}\n\nClick to open in VS Code` : issue.text}
+                  onClick={() => handleOpenIssue(issue)}
+                  className={`truncate ${issue.severity === 'error' ? 'text-red-400' : 'text-amber-400'} ${issue.file ? 'cursor-pointer hover:underline' : ''}`}
+                >
+                  {issue.severity === 'error' ? '✖' : '▲'} {issue.message}{issue.file && <span className="text-slate-600"> · {issue.file.split(/[\\/]/).pop()}{issue.line ? `:${issue.line}` : ''}</span>}
+                </div>
+              ))}
+            </div>
+          )}
           <div className="flex-1 min-h-0 p-4 overflow-y-auto text-[10px] leading-relaxed font-mono custom-scrollbar">
-            {logs.map((log, i) => (
+            {logs.map(({ text: log, color }, i) => (
               <div
                 key={i}
-                className={`py-px ${log.startsWith('❌') ? 'text-red-400' :
+                className={`py-px ${color ? ANSI_CLASSES[color] :
+                  log.startsWith('❌') ? 'text-red-400' :
                   log.startsWith('✅') ? 'text-emerald-400' :
                     log.startsWith('⚡') || log.startsWith('🚀') ? 'text-cyan-400' :
                       log.startsWith('🧨') || log.startsWith('🔥') ? 'text-orange-400' :